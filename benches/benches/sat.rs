@@ -0,0 +1,40 @@
+//! SAT narrow phase benchmarks
+//!
+//! `cargo bench` in `benches/`, run it on both sides of a change to the shapes to compare
+
+use bevy_physimple::{
+    math::Vec2,
+    physics_components::Transform2D,
+    shapes::{collide, CollisionShape, Polygon, Square, SAT},
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn octagon() -> Polygon {
+    Polygon::new((0..8).map(|i| i as f32 * std::f32::consts::FRAC_PI_4).map(|a| Vec2::new(a.cos(), a.sin())).collect())
+}
+
+fn project(c: &mut Criterion) {
+    let square = Square::new(Vec2::new(2.0, 1.0));
+    let polygon = octagon();
+    let t = Transform2D::new(Vec2::new(1.0, 2.0), 0.3, Vec2::ONE);
+    let normals = [Vec2::X, Vec2::Y, Vec2::new(0.6, 0.8), Vec2::new(-0.8, 0.6)];
+
+    c.bench_function("project square", |b| b.iter(|| square.project(black_box(&t), black_box(normals[2]))));
+    c.bench_function("project4 square", |b| b.iter(|| square.project4(black_box(&t), black_box(normals))));
+    c.bench_function("project polygon", |b| b.iter(|| polygon.project(black_box(&t), black_box(normals[2]))));
+    c.bench_function("project4 polygon", |b| b.iter(|| polygon.project4(black_box(&t), black_box(normals))));
+}
+
+fn sat(c: &mut Criterion) {
+    let square = CollisionShape::Square(Square::default());
+    let polygon = CollisionShape::Polygon(octagon());
+    let ta = Transform2D::new(Vec2::ZERO, 0.3, Vec2::ONE);
+    let tb = Transform2D::new(Vec2::new(1.5, 0.5), 1.1, Vec2::ONE);
+
+    c.bench_function("collide rotated squares", |b| b.iter(|| collide(&square, black_box(&ta), &square, black_box(&tb))));
+    c.bench_function("collide polygons", |b| b.iter(|| collide(&polygon, black_box(&ta), &polygon, black_box(&tb))));
+    c.bench_function("collide square polygon", |b| b.iter(|| collide(&square, black_box(&ta), &polygon, black_box(&tb))));
+}
+
+criterion_group!(benches, project, sat);
+criterion_main!(benches);
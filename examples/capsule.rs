@@ -6,7 +6,7 @@ fn main() {
 
     app
         .add_plugins(DefaultPlugins)
-        .add_plugin(Physics2dPlugin)
+        .add_plugin(Physics2dPlugin::default())
         .add_system(bevy::window::close_on_esc);
 
     app
@@ -50,6 +50,7 @@ fn setup_sys(
         ..Default::default()
     })
     // this is pretty much how you get a non continuous collision kinematic object
+    .insert(KinematicBody::discrete())
     .insert(CollisionShape::Square(Square::size(Vec2::splat(30.0))))
     .insert(CollisionLayer::default())
     .insert(Controller);
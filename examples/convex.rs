@@ -6,7 +6,7 @@ fn main() {
 
     app
         .add_plugins(DefaultPlugins)
-        .add_plugin(Physics2dPlugin)
+        .add_plugin(Physics2dPlugin::default())
         .add_system(bevy::window::close_on_esc);
 
     app
@@ -72,6 +72,13 @@ impl SAT for MyTriangle {
         None
     }
 }
+// `CollisionShape::Convex` needs a `ConvexShape`, on top of `SAT` it knows the outline of the shape
+// (a shape which wraps a `Polygon` can implement `PolygonBacked` and get all of this for free)
+impl ConvexShape for MyTriangle {
+    fn local_vertices(&self) -> Option<Vec<Vec2>> {
+        Some(vec![self.v1, self.v2, self.v3])
+    }
+}
 
 #[derive(Component)]
 struct Controller;
@@ -90,11 +97,11 @@ fn setup_sys(
     })
     .insert_bundle(StaticBundle {
         marker: StaticBody,
-        shape: CollisionShape::Convex(Box::new(MyTriangle {
+        shape: CollisionShape::convex(MyTriangle {
             v1: Vec2::new(-100.0,100.0),
             v2: Vec2::new(100.0,100.0),
             v3: Vec2::new(0.0,-100.0),
-        })),
+        }),
         coll_layer: CollisionLayer::default(),
     });
 
@@ -109,6 +116,7 @@ fn setup_sys(
         ..Default::default()
     })
     // this is pretty much how you get a non continuous collision kinematic object
+    .insert(KinematicBody::discrete())
     .insert(CollisionShape::Square(Square::size(Vec2::splat(30.0))))
     .insert(CollisionLayer::default())
     .insert(Controller);
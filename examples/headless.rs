@@ -0,0 +1,79 @@
+//! Headless(server) usage - no window or rendering, just the physics running at a fixed rate
+//!
+//! A box falls onto the floor, and its position is printed every few frames
+
+use std::time::Duration;
+
+use bevy::{app::ScheduleRunnerSettings, prelude::*};
+use bevy_physimple::prelude::*;
+
+const GRAVITY: f32 = -400.0;
+
+fn main() {
+    App::new()
+        // run the app loop 60 times a second without a window
+        .insert_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f64(1.0 / 60.0)))
+        .add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(Physics2dPlugin::default())
+        .add_startup_system(setup_sys)
+        .add_system(gravity_sys)
+        .add_system(report_sys)
+        .run();
+}
+
+#[derive(Component)]
+struct Falling;
+
+fn setup_sys(mut coms: Commands) {
+    // floor
+    coms.spawn_bundle(StaticBundle {
+        shape: CollisionShape::Square(Square::size(Vec2::new(600.0, 20.0))),
+        ..Default::default()
+    })
+    .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(0.0, -100.0, 0.0)));
+
+    // falling box
+    coms.spawn_bundle(KinematicBundle {
+        shape: CollisionShape::Square(Square::size(Vec2::splat(20.0))),
+        ..Default::default()
+    })
+    .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(0.0, 100.0, 0.0)))
+    .insert(Falling);
+}
+
+fn gravity_sys(
+    time: Res<Time>,
+    mut q: Query<(&mut Vel, &mut Transform), With<Falling>>,
+) {
+    let delta = time.delta_seconds();
+
+    for (mut v, mut t) in q.iter_mut() {
+        v.0.y += GRAVITY * delta;
+        t.translation += v.0.extend(0.0) * delta;
+    }
+}
+
+fn report_sys(
+    mut frame: Local<u32>,
+    mut colls: EventReader<CollisionEvent>,
+    mut q: Query<(&mut Vel, &Transform), With<Falling>>,
+) {
+    *frame += 1;
+
+    for c in colls.iter() {
+        // stop on the floor
+        if let Ok((mut v, _)) = q.get_mut(c.entity_a) {
+            if v.0.dot(c.normal) < 0.0 {
+                v.0 = v.0.slide(c.normal);
+            }
+        }
+    }
+
+    if *frame % 30 == 0 {
+        for (_, t) in q.iter() {
+            println!("frame {}: box at {}", *frame, t.translation.truncate());
+        }
+    }
+}
@@ -14,7 +14,7 @@ fn main() {
 
     app
         .add_plugins(DefaultPlugins)
-        .add_plugin(Physics2dPlugin);
+        .add_plugin(Physics2dPlugin::default());
     
         // FPS in terminal
     app
@@ -24,8 +24,7 @@ fn main() {
 
     app
         .add_startup_system(setup_sys)
-        .add_system(gravity_sys)
-        .add_system(jumpy_sys);
+        .add_system(slide_sys);
 
     app.run();
 }
@@ -55,6 +54,7 @@ fn setup_sys(
     });
 
     const SIZE: f32 = 40.0;
+    const ATTRACTION: f32 = 50_000.0;
 
     let c1 = Color::RED;
     let c2 = Color::GREEN;
@@ -83,32 +83,27 @@ fn setup_sys(
             .insert_bundle(KinematicBundle {
                 shape: shape,
                 ..Default::default()
-            });
+            })
+            // every body pulls every other body
+            .insert(Attractor::new(ATTRACTION))
+            .insert(Acc::default());
         })
     })
 }
 
-fn gravity_sys(
-    time: Res<Time>,
-    mut q: Query<&mut Vel>,
-) {
-    const GRAV: f32 = 420.0;
-
-    for mut v in q.iter_mut() {
-        v.0.y -= GRAV * time.delta_seconds();
-    }
-}
-fn jumpy_sys(
+fn slide_sys(
     mut q: Query<&mut Vel>,
     mut colls: EventReader<CollisionEvent>,
 ) {
-    const BOUNCE: f32 = 100.0;
-
+    // stop moving into whatever we hit, so bodies clump together instead of sinking into each other
     for c in colls.iter() {
-        let e = if c.normal.dot(Vec2::Y) > 0.0 { c.entity_a } else { c.entity_b };
-
-        if let Ok(mut v) = q.get_mut(e) {
-            v.0.y += BOUNCE
+        for e in [c.entity_a, c.entity_b] {
+            if let Ok(mut v) = q.get_mut(e) {
+                let normal = c.normal_for(e);
+                if v.0.dot(normal) < 0.0 {
+                    v.0 = v.0.slide(normal);
+                }
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -18,7 +18,7 @@ fn main() {
             ..Default::default()
         })
         .add_plugins(DefaultPlugins)
-        .add_plugin(Physics2dPlugin)
+        .add_plugin(Physics2dPlugin::default())
         .add_system(bevy::window::close_on_esc)
         ;
     app // startup systems
@@ -219,14 +219,12 @@ pub fn slide_movement_sys(
     mut query: Query<&mut Vel>,
 ) {
     for c in coll_events.iter() {
-        if let Ok(mut v) = query.get_mut(c.entity_a) {
-            if v.0.dot(c.normal) < 0.0 {
-                v.0 = v.0.slide(c.normal);
-            }
-        }
-        if let Ok(mut v) = query.get_mut(c.entity_b) {
-            if v.0.dot(-c.normal) < 0.0 {
-                v.0 = v.0.slide(c.normal);
+        for e in [c.entity_a, c.entity_b] {
+            if let Ok(mut v) = query.get_mut(e) {
+                let normal = c.normal_for(e);
+                if v.0.dot(normal) < 0.0 {
+                    v.0 = v.0.slide(normal);
+                }
             }
         }
     }
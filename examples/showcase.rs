@@ -23,7 +23,7 @@ fn main() {
     // plugins
     app
         .add_plugins(DefaultPlugins)
-        .add_plugin(Physics2dPlugin)
+        .add_plugin(Physics2dPlugin::default())
         ;
 
     // startup systems
@@ -166,10 +166,10 @@ fn setup_sys(
     // Multiple collision shapes in 1!
     coms
         .spawn_bundle(StaticBundle {
-            shape: CollisionShape::Multiple(Vec::from([
-                CollisionShape::Square(Square::size(Vec2::new(50.0, 100.0))),
-                CollisionShape::Square(Square::size(Vec2::splat(50.0)).with_offset(Vec2::new(50.0, 25.0)))
-            ])),
+            shape: CompoundBuilder::new()
+                .add_square(Vec2::new(50.0, 100.0), Vec2::ZERO, 0.0)
+                .add_square(Vec2::splat(50.0), Vec2::new(50.0, 25.0), 0.0)
+                .build(),
             ..Default::default()
         })
         .insert(GlobalTransform::default())
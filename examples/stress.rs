@@ -0,0 +1,123 @@
+//! Stress test - a lot of boxes piling into a pit, no window or rendering
+//!
+//! Runs a fixed number of frames and prints the average frame time and how many heap allocations
+//! were made per frame(counted by a wrapper around the system allocator)
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use bevy_physimple::prelude::*;
+
+const BODIES: usize = 800;
+const FRAMES: usize = 300;
+const GRAVITY: f32 = -400.0;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugin(bevy::core::CorePlugin)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .insert_resource(Time::default())
+        .add_plugin(Physics2dPlugin::default())
+        .add_startup_system(setup_sys)
+        .add_system(gravity_sys);
+
+    // the first few frames spawn everything and warm up the buffers
+    app.update();
+    app.update();
+
+    let dt = Duration::from_secs_f64(1.0 / 60.0);
+    let mut elapsed = Duration::ZERO;
+    let start_allocs = ALLOCATIONS.load(Ordering::Relaxed);
+    let mut collisions = 0;
+    let mut reader = app.world.resource::<Events<CollisionEvent>>().get_reader();
+
+    for _ in 0..FRAMES {
+        {
+            let mut time = app.world.resource_mut::<Time>();
+            let last = time.last_update().unwrap_or_else(|| time.startup());
+            time.update_with_instant(last + dt);
+        }
+        let start = Instant::now();
+        app.update();
+        elapsed += start.elapsed();
+
+        collisions += reader.iter(app.world.resource::<Events<CollisionEvent>>()).count();
+    }
+
+    let allocs = ALLOCATIONS.load(Ordering::Relaxed) - start_allocs;
+    println!("{} bodies, {} frames", BODIES, FRAMES);
+    println!("average frame: {:?}", elapsed / FRAMES as u32);
+    println!("allocations per frame: {}", allocs / FRAMES);
+    println!("collisions per frame: {}", collisions / FRAMES);
+}
+
+#[derive(Component)]
+struct Falling;
+
+fn setup_sys(mut coms: Commands) {
+    // the pit
+    for (size, position) in [
+        (Vec2::new(1200.0, 20.0), Vec2::new(0.0, -300.0)),
+        (Vec2::new(20.0, 1200.0), Vec2::new(-600.0, 300.0)),
+        (Vec2::new(20.0, 1200.0), Vec2::new(600.0, 300.0)),
+    ] {
+        coms.spawn_bundle(StaticBundle {
+            shape: CollisionShape::Square(Square::size(size)),
+            ..Default::default()
+        })
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))));
+    }
+
+    // the boxes, in a loose grid so they settle over the run
+    let columns = 40;
+    for i in 0..BODIES {
+        let position = Vec2::new(
+            (i % columns) as f32 * 25.0 - 500.0,
+            (i / columns) as f32 * 25.0 - 250.0,
+        );
+        coms.spawn_bundle(KinematicBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::splat(20.0))),
+            ..Default::default()
+        })
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))))
+        .insert(Falling);
+    }
+}
+
+fn gravity_sys(
+    time: Res<Time>,
+    mut q: Query<(&mut Vel, &mut Transform), With<Falling>>,
+) {
+    let delta = time.delta_seconds();
+
+    for (mut v, mut t) in q.iter_mut() {
+        v.0.y += GRAVITY * delta;
+        t.translation += v.0.extend(0.0) * delta;
+    }
+}
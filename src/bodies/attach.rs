@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::physics_components::{Transform2D, Vel};
+
+/**
+    # Attach
+
+    Makes a body follow another one(a carried crate, a rider on its mount) without parenting their `Transform`s.
+
+    Each frame, right after the physics step(so before the collision detection), the body is moved to `offset` from `to`
+    (the offset rotates with it, and with `rotate` the body takes its rotation as well) and gets its velocity,
+    so it keeps the momentum once it is let go(throwing the crate).
+    The 2 bodies never collide with each other, the attached body still collides with everything else.
+
+    Remove the component to detach the body, it is removed on its own when `to` is despawned.
+*/
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Attach {
+    pub to: Entity,
+    /// Where the body is held, relative to `to`
+    pub offset: Vec2,
+    /// Whether the body rotates with `to`(defaults to true)
+    pub rotate: bool,
+}
+
+impl Attach {
+    pub fn new(
+        to: Entity,
+        offset: Vec2,
+    ) -> Self {
+        Attach {
+            to,
+            offset,
+            rotate: true,
+        }
+    }
+    /// Keeps the body's own rotation(the offset still rotates with `to`)
+    pub fn without_rotation(mut self) -> Self {
+        self.rotate = false;
+        self
+    }
+
+    /// Whether the pair is made of a body and what it is attached to
+    pub(crate) fn ignores(
+        attached: &Query<&Attach>,
+        a: Entity,
+        b: Entity,
+    ) -> bool {
+        attached.get(a).map_or(false, |at| at.to == b) || attached.get(b).map_or(false, |at| at.to == a)
+    }
+}
+
+/// Moves the attached bodies to what they are attached to, runs at the end of the physics step
+pub fn attach_system(
+    mut coms: Commands,
+    attached: Query<(Entity, &Attach)>,
+    mut trans: Query<&mut Transform2D>,
+    mut vels: Query<&mut Vel>,
+) {
+    for (e, at) in attached.iter() {
+        let (position, rotation) = match trans.get(at.to) {
+            Ok(t) => (t.translation() + t.rot_matrix() * at.offset, t.rotation()),
+            Err(_) => {
+                coms.entity(e).remove::<Attach>();
+                continue;
+            },
+        };
+        if let Ok(mut t) = trans.get_mut(e) {
+            if at.rotate {
+                t.set_rotation(rotation);
+            }
+            t.teleport(position);
+        }
+
+        let vel = vels.get(at.to).map(|v| v.0).unwrap_or_default();
+        if let Ok(mut v) = vels.get_mut(e) {
+            v.0 = vel;
+        }
+    }
+}
+
+#[cfg(test)]
+mod attach_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn carry_and_throw() {
+        let mut app = PhysicsTestApp::new();
+        let player = app.spawn_at(Vec2::ZERO, KinematicBundle::default());
+        app.app.world.entity_mut(player).insert(Acc::default());
+        // held right inside the player, they would push each other apart if they collided
+        let crate_ = app.spawn_at(Vec2::new(5.0, 0.0), KinematicBundle::default());
+        app.app.world.entity_mut(crate_).insert_bundle((Acc::default(), Attach::new(player, Vec2::new(1.0, 0.5))));
+        app.step();
+        app.assert_position(crate_, Vec2::new(1.0, 0.5), 0.0001);
+
+        app.app.world.get_mut::<Vel>(player).unwrap().0 = Vec2::new(60.0, 0.0);
+        app.step_n(3);
+        app.assert_not_collided(player, crate_);
+        let px = app.position(player).x;
+        app.assert_position(crate_, Vec2::new(px + 1.0, 0.5), 0.0001);
+
+        // thrown, keeps flying on its own
+        app.app.world.entity_mut(crate_).remove::<Attach>();
+        app.app.world.get_mut::<Vel>(player).unwrap().0 = Vec2::ZERO;
+        app.step_n(2);
+        assert_eq!(app.app.world.get::<Vel>(crate_).unwrap().0, Vec2::new(60.0, 0.0));
+        assert!(app.position(crate_).x > px + 1.5);
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+use crate::{
+    physics_components::{Acc, CollisionLayer, Transform2D},
+    transform_mode::TransformMode,
+};
+
+use super::{PhysicsDisabled, PlanetWalker, StaticBody};
+
+/**
+    # Attractor
+
+    Pulls every kinematic body with `Acc`(the ones being integrated) towards itself,
+    with an acceleration of `strength / distance^falloff`(so `falloff = 2.0` is regular gravity).
+
+    An attractor can be a body as well(which is how n-body simulations are made),
+    it doesn't pull itself. `PlanetWalker`s aren't pulled either, they fall towards their closest attractor instead.
+*/
+#[derive(Component, Debug, Clone)]
+pub struct Attractor {
+    pub strength: f32,
+    /// Exponent of the distance, `2.0` for inverse square, `0.0` for constant pull
+    pub falloff: f32,
+    /// Added to the distance so bodies right on top of the attractor don't get launched to space
+    pub softening: f32,
+    /// Only pull bodies whose `CollisionLayer` overlaps this(`None` pulls everything)
+    pub layer: Option<CollisionLayer>,
+}
+
+impl Attractor {
+    /// Inverse square attractor
+    pub fn new(strength: f32) -> Self {
+        Attractor {
+            strength,
+            falloff: 2.0,
+            softening: 1.0,
+            layer: None,
+        }
+    }
+    pub fn with_falloff(
+        mut self,
+        falloff: f32,
+    ) -> Self {
+        self.falloff = falloff;
+        self
+    }
+    pub fn with_layer(
+        mut self,
+        layer: CollisionLayer,
+    ) -> Self {
+        self.layer = Some(layer);
+        self
+    }
+
+    /// Acceleration the attractor(at `position`) applies on a body at `body`
+    pub fn acceleration(
+        &self,
+        position: Vec2,
+        body: Vec2,
+    ) -> Vec2 {
+        let d = position - body;
+        let dis = d.length() + self.softening;
+        if dis <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+
+        d.normalize_or_zero() * self.strength / dis.powf(self.falloff)
+    }
+}
+
+/// Adds the acceleration of every attractor to the kinematic bodies, runs before the integration
+#[allow(clippy::type_complexity)]
+pub fn attractor_system(
+    trans_mode: Res<TransformMode>,
+    attractors: Query<(Entity, &Attractor, &GlobalTransform)>,
+    mut bodies: Query<(Entity, &Transform2D, &mut Acc, Option<&CollisionLayer>), (Without<StaticBody>, Without<PhysicsDisabled>, Without<PlanetWalker>)>,
+) {
+    for (ae, a, gt) in attractors.iter() {
+        let pos = trans_mode.get_global_position(gt);
+
+        for (e, t, mut acc, l) in bodies.iter_mut() {
+            if e == ae {
+                continue;
+            }
+            if let Some(al) = a.layer {
+                if !al.overlap(l.unwrap_or(&CollisionLayer::ZERO)) {
+                    continue;
+                }
+            }
+            acc.add(a.acceleration(pos, t.translation()));
+        }
+    }
+}
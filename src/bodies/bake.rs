@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+
+use crate::{
+    math::Mat2,
+    physics_components::{CollisionLayer, Transform2D},
+    shapes::{Aabb, Capsule, Circle, CollisionShape, Polygon, Square, Triangle},
+    transform_mode::TransformMode,
+};
+
+use super::StaticBody;
+
+/// A static body which was baked into a `BakedStatics` entity
+pub struct BakedSource {
+    pub entity: Entity,
+    pub shape: CollisionShape,
+    pub layer: CollisionLayer,
+}
+
+/**
+    # BakedStatics
+
+    A single static body made out of many others, created by `bake_statics` for finished level chunks
+    (one entity and one compound shape instead of hundreds of bodies means less pairs to check in the broad phase).
+
+    The sources lose their `StaticBody`, `CollisionShape` and `CollisionLayer`(everything else, like sprites, stays),
+    and get them back with `unbake_statics`(for editing the chunk).
+*/
+#[derive(Component, Default)]
+pub struct BakedStatics {
+    pub sources: Vec<BakedSource>,
+}
+
+/**
+    Bakes all the static bodies fully inside `region` into `BakedStatics` entities(one for each `CollisionLayer` used),
+    and returns them.
+
+    Bodies with shapes which can't be moved into a compound(rotated capsules and `Convex` shapes) are left alone.
+    Needs the `GlobalTransform` of the bodies to be up to date, so call it from an exclusive system(or a command):
+
+    ```ignore
+    coms.add(move |world: &mut World| {
+        bake_statics(world, chunk);
+    });
+    ```
+*/
+#[allow(clippy::type_complexity)]
+pub fn bake_statics(
+    world: &mut World,
+    region: Aabb,
+) -> Vec<Entity> {
+    let mode = world.get_resource::<TransformMode>().copied().unwrap_or(TransformMode::XY);
+    let (min, max) = region.min_max();
+    let center = region.position;
+
+    // (mask, layer) -> shapes relative to the center of the region, and their sources
+    let mut groups: Vec<((u8, u8), Vec<CollisionShape>, Vec<Entity>)> = Vec::new();
+
+    let mut q = world.query_filtered::<(Entity, &CollisionShape, &CollisionLayer, &GlobalTransform), With<StaticBody>>();
+    for (e, shape, layer, gt) in q.iter(world) {
+        let t: Transform2D = (gt, mode).into();
+
+        let (smin, smax) = shape.aabb(&t).min_max();
+        if smin.cmplt(min).any() || smax.cmpgt(max).any() {
+            continue;
+        }
+        let moved = match move_shape(shape, t.rotation(), t.translation() - center) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let key = (layer.mask, layer.layer);
+        match groups.iter_mut().find(|g| g.0 == key) {
+            Some(g) => {
+                g.1.push(moved);
+                g.2.push(e);
+            }
+            None => groups.push((key, vec![moved], vec![e])),
+        }
+    }
+
+    let mut transform = Transform::default();
+    mode.set_position(&mut transform, center);
+
+    let mut baked = Vec::with_capacity(groups.len());
+    for ((mask, layer), shapes, entities) in groups {
+        let sources = entities
+            .into_iter()
+            .filter_map(|entity| {
+                let mut e = world.entity_mut(entity);
+                e.remove::<StaticBody>();
+                e.remove::<Transform2D>();
+                Some(BakedSource {
+                    entity,
+                    shape: e.remove::<CollisionShape>()?,
+                    layer: e.remove::<CollisionLayer>()?,
+                })
+            })
+            .collect();
+
+        let layer = CollisionLayer { mask, layer };
+        let e = world
+            .spawn()
+            .insert_bundle(super::StaticBundle {
+                shape: CollisionShape::Multiple(shapes),
+                coll_layer: layer,
+                ..Default::default()
+            })
+            .insert_bundle(TransformBundle::from_transform(transform))
+            .insert(BakedStatics { sources })
+            .id();
+        baked.push(e);
+    }
+    baked
+}
+
+/// Gives the sources of a `BakedStatics` entity their bodies back and despawns it, returns the sources which still exist
+pub fn unbake_statics(
+    world: &mut World,
+    baked: Entity,
+) -> Vec<Entity> {
+    let sources = match world.get_entity_mut(baked).and_then(|mut e| e.remove::<BakedStatics>()) {
+        Some(b) => b.sources,
+        None => return Vec::new(),
+    };
+    world.despawn(baked);
+
+    sources
+        .into_iter()
+        .filter_map(|s| {
+            let mut e = world.get_entity_mut(s.entity)?;
+            e.insert_bundle(super::StaticBundle {
+                shape: s.shape,
+                coll_layer: s.layer,
+                ..Default::default()
+            });
+            Some(s.entity)
+        })
+        .collect()
+}
+
+/// Moves(and rotates) the shape into the compound's space, `None` if it can't be done
+fn move_shape(
+    shape: &CollisionShape,
+    rotation: f32,
+    offset: Vec2,
+) -> Option<CollisionShape> {
+    let rot = Mat2::from_angle(rotation);
+    let unrotated = rotation.abs() < f32::EPSILON;
+
+    Some(match shape {
+        CollisionShape::Square(s) if unrotated => CollisionShape::Square(Square::new(s.extents).with_offset(offset + s.offset)),
+        CollisionShape::Square(s) => CollisionShape::Polygon(Polygon::new(
+            [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)]
+                .iter()
+                .map(|v| offset + rot * (s.offset + *v * s.extents))
+                .collect(),
+        )),
+        CollisionShape::Circle(c) => CollisionShape::Circle(Circle::new(c.radius).with_offset(offset + rot * c.offset)),
+        // capsules are always standing
+        CollisionShape::Capsule(c) if unrotated => {
+            CollisionShape::Capsule(Capsule::new(2.0 * c.half_height, c.radius).with_offset(offset + c.offset))
+        }
+        CollisionShape::Triangle(t) => {
+            let v = t.verts().map(|v| offset + rot * v);
+            CollisionShape::Triangle(Triangle::new(v[0], v[1], v[2]))
+        }
+        CollisionShape::Polygon(p) => CollisionShape::Polygon(Polygon::new(p.verts().iter().map(|v| offset + rot * *v).collect())),
+        CollisionShape::Multiple(v) => {
+            CollisionShape::Multiple(v.iter().map(|s| move_shape(s, rotation, offset)).collect::<Option<Vec<_>>>()?)
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod bake_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn bake_and_unbake() {
+        let mut app = PhysicsTestApp::new();
+
+        let floor: Vec<Entity> = (0..3)
+            .map(|i| app.spawn_at(Vec2::new(i as f32 * 2.0, 0.0), StaticBundle::default()))
+            .collect();
+        let outside = app.spawn_at(Vec2::new(20.0, 0.0), StaticBundle::default());
+        app.step();
+
+        let region = Aabb::from_min_max(Vec2::new(-5.0, -5.0), Vec2::new(10.0, 5.0));
+        let baked = bake_statics(&mut app.app.world, region);
+        assert_eq!(baked.len(), 1);
+        assert!(app.app.world.get::<StaticBody>(floor[0]).is_none());
+        assert!(app.app.world.get::<StaticBody>(outside).is_some());
+
+        // the compound still collides like the floor did
+        let body = app.spawn_at(Vec2::new(4.0, 1.5), KinematicBundle::default());
+        app.step();
+        app.assert_collided(body, baked[0]);
+
+        assert_eq!(unbake_statics(&mut app.app.world, baked[0]), floor);
+        assert!(app.app.world.get::<StaticBody>(floor[2]).is_some());
+    }
+}
@@ -0,0 +1,201 @@
+use bevy::prelude::*;
+
+use crate::{common::VecOp, physics_components::Vel, plugin::CollisionEvent};
+
+use super::PhysicsDisabled;
+
+/**
+    # Bouncy
+
+    Makes a body bounce off whatever it hits instead of sliding along it, for grenades and ricocheting bullets.
+
+    Each frame the body hits something its velocity(from before the collision solve) is reflected on the hit's normal,
+    keeping `restitution` of the speed along the normal(the speed along the surface is kept as is),
+    hitting a few things in the same frame(like a corner) counts as a single bounce off all of them.
+
+    Once the body hit something after already bouncing `max_bounces` times, or lived for `lifetime` seconds,
+    a `BouncyExhausted` event is sent and the body is despawned(unless `keep_alive` is used, then it only stops bouncing).
+*/
+#[derive(Component, Debug, Clone)]
+pub struct Bouncy {
+    /// How much of the speed along the normal is kept on each bounce
+    pub restitution: f32,
+    /// How many times the body may bounce
+    pub max_bounces: u32,
+    /// Seconds until the body is exhausted regardless of its bounces, `None` to live forever
+    pub lifetime: Option<f32>,
+    /// Whether to despawn the body once it is exhausted
+    pub despawn: bool,
+    /// How many times the body bounced so far
+    pub bounces: u32,
+    /// Seconds the body has lived for
+    pub age: f32,
+    exhausted: bool,
+    last_vel: Vec2,
+}
+
+impl Bouncy {
+    pub fn new(
+        restitution: f32,
+        max_bounces: u32,
+    ) -> Self {
+        Bouncy {
+            restitution,
+            max_bounces,
+            lifetime: None,
+            despawn: true,
+            bounces: 0,
+            age: 0.0,
+            exhausted: false,
+            last_vel: Vec2::ZERO,
+        }
+    }
+    pub fn with_lifetime(
+        mut self,
+        lifetime: f32,
+    ) -> Self {
+        self.lifetime = Some(lifetime);
+        self
+    }
+    /// Only sends the `BouncyExhausted` event instead of despawning the body
+    pub fn keep_alive(mut self) -> Self {
+        self.despawn = false;
+        self
+    }
+    /// Whether the body ran out of bounces(or time)
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
+/// Why a `Bouncy` body was exhausted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExhaustedBy {
+    /// Hit something after bouncing `max_bounces` times
+    Bounces,
+    /// Lived for `lifetime` seconds
+    Lifetime,
+}
+
+/// Sent when a `Bouncy` body runs out of bounces or time, right before it is despawned
+#[derive(Debug, Clone, Copy)]
+pub struct BouncyExhausted {
+    pub entity: Entity,
+    pub by: ExhaustedBy,
+    /// How many times it bounced
+    pub bounces: u32,
+}
+
+/// Ages the `Bouncy` bodies and records their velocity before the collision solve, runs at the end of the physics step
+pub fn bouncy_step_system(
+    time: Res<Time>,
+    mut q: Query<(&mut Bouncy, Option<&Vel>), Without<PhysicsDisabled>>,
+) {
+    let dt = time.delta_seconds();
+    for (mut b, vel) in q.iter_mut() {
+        b.age += dt;
+        b.last_vel = vel.map(|v| v.0).unwrap_or_default();
+    }
+}
+
+/// Bounces the `Bouncy` bodies off their collisions, and despawns(or reports) the exhausted ones,
+/// runs right after the collision solve
+pub fn bouncy_system(
+    mut coms: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    mut q: Query<(Entity, &mut Bouncy, Option<&mut Vel>), Without<PhysicsDisabled>>,
+    mut writer: EventWriter<BouncyExhausted>,
+    mut normals: Local<Vec<(Entity, Vec2)>>,
+) {
+    normals.clear();
+    for ev in collisions.iter() {
+        if q.contains(ev.entity_a) {
+            normals.push((ev.entity_a, ev.normal));
+        }
+        if q.contains(ev.entity_b) {
+            normals.push((ev.entity_b, -ev.normal));
+        }
+    }
+
+    for (e, mut b, vel) in q.iter_mut() {
+        if b.exhausted {
+            continue;
+        }
+        // the normals the body moved into this frame, joined into one
+        let incoming = normals
+            .iter()
+            .filter(|(ne, n)| *ne == e && b.last_vel.dot(*n) < 0.0)
+            .fold(Vec2::ZERO, |sum, (_, n)| sum + *n)
+            .normalize_or_zero();
+
+        let by = if incoming != Vec2::ZERO && b.bounces >= b.max_bounces {
+            Some(ExhaustedBy::Bounces)
+        }
+        else if b.lifetime.map_or(false, |l| b.age >= l) {
+            Some(ExhaustedBy::Lifetime)
+        }
+        else {
+            None
+        };
+        if let Some(by) = by {
+            b.exhausted = true;
+            writer.send(BouncyExhausted {
+                entity: e,
+                by,
+                bounces: b.bounces,
+            });
+            if b.despawn {
+                coms.entity(e).despawn();
+            }
+            continue;
+        }
+
+        if incoming != Vec2::ZERO {
+            b.bounces += 1;
+            if let Some(mut v) = vel {
+                let reflected = b.last_vel.reflect(incoming);
+                v.0 = reflected.slide(incoming) + reflected.project(incoming) * b.restitution;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod bouncy_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn bounces_until_exhausted() {
+        let mut app = PhysicsTestApp::new();
+        // a corridor, the grenade goes back and forth between the walls
+        app.spawn_at(Vec2::new(-4.0, 0.0), StaticBundle::default());
+        app.spawn_at(Vec2::new(4.0, 0.0), StaticBundle::default());
+
+        let grenade = |app: &mut PhysicsTestApp, y: f32, bouncy: Bouncy| {
+            let e = app.spawn_at(Vec2::new(0.0, y), KinematicBundle {
+                vel: Vel(Vec2::new(60.0, 0.0)),
+                ..Default::default()
+            });
+            app.app.world.entity_mut(e).insert_bundle((Acc::default(), bouncy));
+            e
+        };
+        let shots = grenade(&mut app, 0.0, Bouncy::new(0.5, 2));
+        let timed = grenade(&mut app, 100.0, Bouncy::new(1.0, 2).with_lifetime(0.8).keep_alive());
+
+        let mut reader = app.app.world.resource::<Events<BouncyExhausted>>().get_reader();
+        let mut exhausted = Vec::new();
+        // bounces off the right wall at half the speed, then off the left one
+        app.step_n(5);
+        assert_eq!(app.app.world.get::<Bouncy>(shots).unwrap().bounces, 1);
+        assert!((app.app.world.get::<Vel>(shots).unwrap().0 - Vec2::new(-30.0, 0.0)).length() < 0.001);
+
+        for _ in 0..60 {
+            app.step();
+            exhausted.extend(reader.iter(app.app.world.resource::<Events<BouncyExhausted>>()).map(|ev| (ev.entity, ev.by, ev.bounces)));
+        }
+        assert_eq!(exhausted, vec![(shots, ExhaustedBy::Bounces, 2), (timed, ExhaustedBy::Lifetime, 0)]);
+        assert!(app.app.world.get_entity(shots).is_none());
+        assert!(app.app.world.get::<Bouncy>(timed).unwrap().is_exhausted());
+    }
+}
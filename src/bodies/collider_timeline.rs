@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+
+use crate::shapes::{CollisionShape, ShapeDesc};
+
+/// A single shape of a `ColliderTimeline`
+#[derive(Debug, Clone)]
+pub struct ColliderKeyframe {
+    /// When the keyframe starts, in seconds(or the atlas index it starts at, with `TimelineSync::AtlasIndex`)
+    pub time: f32,
+    pub shape: ShapeDesc,
+    /// Offset of the shape from the `Transform`(rotates with the body)
+    pub offset: Vec2,
+}
+
+/// What drives a `ColliderTimeline`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimelineSync {
+    /// The timeline plays on its own, in seconds
+    #[default]
+    Time,
+    /// The keyframes follow the index of the entity's `TextureAtlasSprite`, so they never drift from the animation
+    AtlasIndex,
+}
+
+/**
+    # ColliderTimeline
+
+    Keyframed shapes for a body whose collider changes with its animation(attack hitboxes, a crouching character),
+    the body's `CollisionShape` is replaced with the shape of the current keyframe every time it changes.
+
+    With `TimelineSync::AtlasIndex` the keyframes' `time` is the atlas index they start at,
+    so a sword swing's hitbox only shows up on the frames the sword is out:
+
+    ```ignore
+    coms.entity(player).insert(ColliderTimeline::new(vec![
+        ColliderKeyframe { time: 0.0, shape: ShapeDesc::Square(Vec2::new(16.0, 32.0)), offset: Vec2::ZERO },
+        ColliderKeyframe { time: 3.0, shape: ShapeDesc::Square(Vec2::new(40.0, 32.0)), offset: Vec2::new(12.0, 0.0) },
+        ColliderKeyframe { time: 6.0, shape: ShapeDesc::Square(Vec2::new(16.0, 32.0)), offset: Vec2::ZERO },
+    ]).synced_to_atlas());
+    ```
+
+    With `interpolate` the shape is morphed between the keyframes(see `CollisionShape::lerp`),
+    keyframes which can't be morphed into each other still switch at once.
+*/
+#[derive(Component, Debug, Clone)]
+pub struct ColliderTimeline {
+    /// Keyframes, sorted by their time
+    pub keyframes: Vec<ColliderKeyframe>,
+    pub sync: TimelineSync,
+    /// Whether to morph the shape between keyframes instead of switching it at once
+    pub interpolate: bool,
+    /// Whether to start over after the last keyframe(`TimelineSync::Time` only), the timeline ends at the last keyframe's time
+    pub looping: bool,
+    /// How far the timeline played, in seconds
+    pub time: f32,
+    current: Option<usize>,
+}
+
+impl ColliderTimeline {
+    pub fn new(mut keyframes: Vec<ColliderKeyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        ColliderTimeline {
+            keyframes,
+            sync: TimelineSync::Time,
+            interpolate: false,
+            looping: true,
+            time: 0.0,
+            current: None,
+        }
+    }
+    /// Follows the entity's `TextureAtlasSprite` index instead of the time
+    pub fn synced_to_atlas(mut self) -> Self {
+        self.sync = TimelineSync::AtlasIndex;
+        self
+    }
+    pub fn interpolated(mut self) -> Self {
+        self.interpolate = true;
+        self
+    }
+    /// Stays on the last keyframe instead of starting over
+    pub fn once(mut self) -> Self {
+        self.looping = false;
+        self
+    }
+    /// Restarts the timeline(`TimelineSync::Time` only)
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+        self.current = None;
+    }
+
+    /// Index of the keyframe at `at`, and how far it is to the next one(0 to 1)
+    fn keyframe_at(
+        &self,
+        at: f32,
+    ) -> Option<(usize, f32)> {
+        let i = self.keyframes.iter().rposition(|k| k.time <= at).unwrap_or(0);
+        let k = self.keyframes.get(i)?;
+        let t = match self.keyframes.get(i + 1) {
+            Some(next) if next.time > k.time => ((at - k.time) / (next.time - k.time)).clamp(0.0, 1.0),
+            _ => 0.0,
+        };
+        Some((i, t))
+    }
+}
+
+/// Builds the shape of a keyframe, with its offset(`None` when its shape can't be built, see `ShapeDesc::to_shape`)
+fn keyframe_shape(k: &ColliderKeyframe) -> Option<CollisionShape> {
+    let shape = match &k.shape {
+        // polygons have no offset of their own, the points are moved instead
+        ShapeDesc::Polygon(points) => ShapeDesc::Polygon(points.iter().map(|p| *p + k.offset).collect()).to_shape()?,
+        desc => match desc.to_shape()? {
+            CollisionShape::Square(s) => CollisionShape::Square(s.with_offset(k.offset)),
+            CollisionShape::Circle(c) => CollisionShape::Circle(c.with_offset(k.offset)),
+            CollisionShape::Capsule(c) => CollisionShape::Capsule(c.with_offset(k.offset)),
+            shape => shape,
+        },
+    };
+    Some(shape)
+}
+
+/// Plays the `ColliderTimeline`s and replaces the shapes of the bodies, runs right before the broad phase
+pub fn collider_timeline_system(
+    time: Res<Time>,
+    mut q: Query<(&mut ColliderTimeline, &mut CollisionShape, Option<&TextureAtlasSprite>)>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut tl, mut shape, atlas) in q.iter_mut() {
+        let at = match (tl.sync, atlas) {
+            (TimelineSync::AtlasIndex, Some(a)) => a.index as f32,
+            (TimelineSync::AtlasIndex, None) => continue,
+            (TimelineSync::Time, _) => {
+                let end = tl.keyframes.last().map_or(0.0, |k| k.time);
+                tl.time += dt;
+                if tl.time > end {
+                    tl.time = if tl.looping && end > 0.0 { tl.time % end } else { end };
+                }
+                tl.time
+            },
+        };
+        let (i, t) = match tl.keyframe_at(at) {
+            Some(k) => k,
+            None => continue,
+        };
+
+        let k = &tl.keyframes[i];
+        let morphed = match tl.keyframes.get(i + 1) {
+            Some(next) if tl.interpolate && t > 0.0 => keyframe_shape(k).zip(keyframe_shape(next)).and_then(|(a, b)| a.lerp(&b, t)),
+            _ => None,
+        };
+        tl.current = match morphed {
+            Some(s) => {
+                *shape = s;
+                // the next frame might be right on the keyframe, which has to be rebuilt then
+                None
+            },
+            None => {
+                // no need to rebuild a shape which didn't change(a keyframe whose shape can't be built keeps the last one)
+                if tl.current != Some(i) {
+                    if let Some(s) = keyframe_shape(k) {
+                        *shape = s;
+                    }
+                }
+                Some(i)
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod collider_timeline_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn hitbox_follows_the_animation() {
+        let mut app = PhysicsTestApp::new();
+        let keyframes = || {
+            vec![
+                ColliderKeyframe { time: 0.0, shape: ShapeDesc::Square(Vec2::splat(2.0)), offset: Vec2::ZERO },
+                ColliderKeyframe { time: 2.0, shape: ShapeDesc::Square(Vec2::splat(2.0)), offset: Vec2::new(3.0, 0.0) },
+                ColliderKeyframe { time: 3.0, shape: ShapeDesc::Circle(1.0), offset: Vec2::ZERO },
+            ]
+        };
+        let synced = app.spawn_at(Vec2::ZERO, SensorBundle::default());
+        app.app.world.entity_mut(synced).insert_bundle((
+            ColliderTimeline::new(keyframes()).synced_to_atlas(),
+            TextureAtlasSprite::new(0),
+        ));
+        let timed = app.spawn_at(Vec2::new(0.0, 20.0), SensorBundle::default());
+        app.app.world.entity_mut(timed).insert(ColliderTimeline::new(keyframes()).interpolated().once());
+        // a dummy in reach of the attack frame only
+        let dummy = app.spawn_at(Vec2::new(4.5, 0.0), KinematicBundle::default());
+
+        let sensor_has = |app: &PhysicsTestApp, e: Entity| app.app.world.get::<Sensor>(e).unwrap().bodies.contains(&dummy);
+        app.step();
+        assert!(!sensor_has(&app, synced));
+
+        app.app.world.get_mut::<TextureAtlasSprite>(synced).unwrap().index = 2;
+        app.step();
+        assert!(sensor_has(&app, synced));
+
+        app.app.world.get_mut::<TextureAtlasSprite>(synced).unwrap().index = 5;
+        app.step();
+        assert!(!sensor_has(&app, synced));
+        assert!(matches!(app.app.world.get::<CollisionShape>(synced).unwrap(), CollisionShape::Circle(_)));
+
+        // a second in, halfway to the offset square
+        app.step_n(60);
+        let shape = app.app.world.get::<CollisionShape>(timed).unwrap();
+        let x = shape.project(&Transform2D::default(), Vec2::X);
+        assert!((x.0 - 0.5).abs() < 0.1 && (x.1 - 2.5).abs() < 0.1, "{:?}", x);
+        // stays on the circle at the end
+        app.step_n(180);
+        assert!(matches!(app.app.world.get::<CollisionShape>(timed).unwrap(), CollisionShape::Circle(_)));
+    }
+}
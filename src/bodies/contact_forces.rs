@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+/// A single contact and the impulse it applied on the body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactImpulse {
+    pub other: Entity,
+    /// Normal of the contact, pointing away from `other`
+    pub normal: Vec2,
+    /// Size of the impulse(mass times the velocity removed along the normal)
+    pub impulse: f32,
+}
+
+/**
+    # ContactForces
+
+    Add it to a body to read back the impulses its contacts applied on it,
+    filled by `narrow_phase_2` each frame(and reset when the body touches nothing).
+
+    Handy for detecting being crushed(a big `magnitude` with a small `total`),
+    fall damage(a strong `strongest`) or breaking joints once a threshold is passed.
+
+    The impulse of a contact is the velocity(relative to the other body) going into it before the solve,
+    times the body's `Mass`(`1.0` when it has none).
+*/
+#[derive(Component, Debug, Clone, Default)]
+pub struct ContactForces {
+    /// Sum of the impulses of all the contacts this frame(opposing contacts cancel each other)
+    pub total: Vec2,
+    /// Sum of the sizes of the impulses of all the contacts this frame
+    pub magnitude: f32,
+    /// The contact which applied the biggest impulse this frame
+    pub strongest: Option<ContactImpulse>,
+}
+
+impl ContactForces {
+    pub(crate) fn clear(&mut self) {
+        *self = ContactForces::default();
+    }
+    pub(crate) fn add(
+        &mut self,
+        contact: ContactImpulse,
+    ) {
+        self.total += contact.normal * contact.impulse;
+        self.magnitude += contact.impulse;
+        if self.strongest.map(|s| s.impulse < contact.impulse).unwrap_or(true) {
+            self.strongest = Some(contact);
+        }
+    }
+}
+
+/**
+    # CollisionStats
+
+    Add it to a kinematic body to keep running counts of its contacts, updated by `narrow_phase_2` each frame
+    (and never reset by the plugin, call `reset` when you need a fresh start).
+
+    Good for achievements("bounced off 100 walls"), debugging,
+    or telling a body is stuck(lots of contacts but it doesn't get anywhere).
+    Impulses are the same as the ones in `ContactForces`.
+*/
+#[derive(Component, Debug, Clone, Default)]
+pub struct CollisionStats {
+    /// Every contact since the stats were added(or reset), a pair touching for 10 frames counts 10 times
+    pub total_contacts: u64,
+    /// Frames in which the body touched anything
+    pub frames_in_contact: u64,
+    /// Contacts of the last frame
+    pub last_frame_contacts: usize,
+    /// When the body last touched anything(in seconds since startup)
+    pub last_contact: Option<f64>,
+    /// The contact which applied the biggest impulse since the stats were added(or reset)
+    pub strongest: Option<ContactImpulse>,
+}
+
+impl CollisionStats {
+    pub fn reset(&mut self) {
+        *self = CollisionStats::default();
+    }
+    pub(crate) fn add_frame(
+        &mut self,
+        now: f64,
+        contacts: impl IntoIterator<Item = ContactImpulse>,
+    ) {
+        self.last_frame_contacts = 0;
+        for c in contacts {
+            self.last_frame_contacts += 1;
+            if self.strongest.map(|s| s.impulse < c.impulse).unwrap_or(true) {
+                self.strongest = Some(c);
+            }
+        }
+        if self.last_frame_contacts > 0 {
+            self.total_contacts += self.last_frame_contacts as u64;
+            self.frames_in_contact += 1;
+            self.last_contact = Some(now);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    physics_components::{CollisionLayer, Transform2D},
+    prelude::CollisionShape,
+};
+
+/// Marker component for static bodies which can be carved by a `DestructionEvent`
+#[derive(Default, Serialize, Deserialize, Clone, Debug, Component)]
+pub struct Destructible;
+
+/**
+    # DestructionEvent
+
+    Send this event to carve `shape`(placed at `transform`) out of every `Destructible` body it overlaps
+    (and whose `CollisionLayer` overlaps `layer`).
+
+    Bodies which are completely removed get despawned,
+    the rest get their `CollisionShape` replaced with what is left of them(see `CollisionShape::subtract`).
+*/
+pub struct DestructionEvent {
+    pub shape: CollisionShape,
+    pub transform: Transform2D,
+    pub layer: CollisionLayer,
+}
+
+pub fn destruction_system(
+    mut coms: Commands,
+    mut events: EventReader<DestructionEvent>,
+    mut bodies: Query<(Entity, &mut CollisionShape, &Transform2D, &CollisionLayer), With<Destructible>>,
+) {
+    // Despawning is deferred, so keep track of what we already destroyed this frame
+    let mut destroyed = Vec::new();
+
+    for ev in events.iter() {
+        let region_aabb = ev.shape.aabb(&ev.transform);
+
+        for (e, mut shape, t, l) in bodies.iter_mut() {
+            if destroyed.contains(&e) || !l.overlap(&ev.layer) || !shape.aabb(t).collides(&region_aabb) {
+                continue;
+            }
+
+            match shape.subtract(t, &ev.shape, &ev.transform) {
+                Some(s) => *shape = s,
+                None => {
+                    coms.entity(e).despawn();
+                    destroyed.push(e);
+                },
+            }
+        }
+    }
+}
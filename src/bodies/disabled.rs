@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+/**
+    # PhysicsDisabled
+
+    Marker which freezes a body without removing its components,
+    useful for cutscenes, inventories and pooled entities.
+
+    While it is present the body is skipped by the integration(and anything adding to its `Acc`), collision detection,
+    sensors and rays, accelerations added to it in the meantime are dropped and `Impulse`s are kept until it is enabled again.
+    A disabled sensor keeps the bodies it had when it was disabled, and joints treat disabled bodies as immovable.
+
+    Remove the marker to bring the body back exactly as it was.
+*/
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct PhysicsDisabled;
+
+#[cfg(test)]
+mod disabled_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn disabled_body_is_frozen() {
+        let mut app = PhysicsTestApp::new();
+        let wall = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        let body = app.spawn_at(Vec2::new(0.0, 1.2), KinematicBundle::default());
+        app.app.world.entity_mut(body).insert_bundle((Acc::default(), PhysicsDisabled));
+
+        for _ in 0..5 {
+            app.app.world.get_mut::<Acc>(body).unwrap().add(Vec2::new(0.0, -10.0));
+            app.step();
+        }
+        app.assert_not_collided(body, wall);
+        app.assert_position(body, Vec2::new(0.0, 1.2), 0.0);
+
+        app.app.world.entity_mut(body).remove::<PhysicsDisabled>();
+        app.step();
+        app.assert_collided(body, wall);
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{
+    physics_components::{Acc, Mass, Transform2D, Vel},
+    shapes::CollisionShape,
+};
+
+use super::PhysicsDisabled;
+
+/**
+    # Drag
+
+    Air resistance for bodies with `Vel` and `Acc`,
+    slows them down with a force of `coefficient * speed^2` against their velocity(divided by their `Mass`, if they have one).
+
+    With `cross_section` the force is also scaled by the width of the `CollisionShape` perpendicular to the motion,
+    so a flat shape falling flat slows down more than when it falls on its side.
+*/
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Drag {
+    pub coefficient: f32,
+    pub cross_section: bool,
+}
+
+impl Drag {
+    pub fn new(coefficient: f32) -> Self {
+        Drag {
+            coefficient,
+            cross_section: false,
+        }
+    }
+    /// Scale the drag by the shape's cross section
+    pub fn with_cross_section(mut self) -> Self {
+        self.cross_section = true;
+        self
+    }
+
+    /// Deceleration of a body moving at `vel`, `width` is its cross section(`1.0` if not used)
+    pub fn acceleration(
+        &self,
+        vel: Vec2,
+        width: f32,
+        inv_mass: f32,
+    ) -> Vec2 {
+        -vel * vel.length() * self.coefficient * width * inv_mass
+    }
+}
+
+/// Adds the drag deceleration to the bodies, runs before the integration
+#[allow(clippy::type_complexity)]
+pub fn drag_system(
+    time: Res<Time>,
+    mut q: Query<(&Drag, &Vel, &mut Acc, &Transform2D, Option<&Mass>, Option<&CollisionShape>), Without<PhysicsDisabled>>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (d, v, mut acc, t, m, s) in q.iter_mut() {
+        let speed = v.0.length();
+        if speed <= f32::EPSILON {
+            continue;
+        }
+
+        let width = match s {
+            Some(s) if d.cross_section => {
+                let (min, max) = s.project(t, v.0.perp() / speed);
+                max - min
+            },
+            _ => 1.0,
+        };
+        let inv_mass = m.map(|m| m.inverse()).unwrap_or(1.0);
+
+        // drag can only stop a body, never push it backwards
+        let a = d.acceleration(v.0, width, inv_mass).clamp_length_max(speed / dt);
+        acc.add(a);
+    }
+}
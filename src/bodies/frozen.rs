@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+/**
+    # Frozen
+
+    Freezes a body along some axes(or all of them) while it keeps colliding,
+    for cutscene actors, carried objects, or a platformer body which may only move up and down.
+
+    Along the frozen axes the body doesn't integrate(its `Vel` is zeroed there) and nothing can push it,
+    the bodies colliding with it are pushed out by the whole penetration instead, as if it was a static body
+    (static bodies don't push it either, so a frozen body may be left inside them).
+    Unlike `PhysicsDisabled` it still sends `CollisionEvent`s, shows up in sensors and blocks rays,
+    and moving it through its `Transform` works as usual.
+*/
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frozen {
+    pub x: bool,
+    pub y: bool,
+}
+
+impl Default for Frozen {
+    fn default() -> Self {
+        Frozen::ALL
+    }
+}
+
+impl Frozen {
+    /// Frozen along both axes
+    pub const ALL: Frozen = Frozen { x: true, y: true };
+    /// Only frozen horizontally
+    pub const X: Frozen = Frozen { x: true, y: false };
+    /// Only frozen vertically
+    pub const Y: Frozen = Frozen { x: false, y: true };
+
+    /// The part of `v` along the axes which aren't frozen
+    pub fn mask(
+        &self,
+        v: Vec2,
+    ) -> Vec2 {
+        Vec2::new(if self.x { 0.0 } else { v.x }, if self.y { 0.0 } else { v.y })
+    }
+}
+
+/// Splits the movement of a solved pair(`m1 - m2` separates them) so the frozen axes of each body don't move,
+/// whatever one body can't move is moved by the other one
+pub(crate) fn frozen_split(
+    m1: Vec2,
+    m2: Vec2,
+    f1: Option<&Frozen>,
+    f2: Option<&Frozen>,
+) -> (Vec2, Vec2) {
+    let mask = |f: Option<&Frozen>, v: Vec2| f.map_or(v, |f| f.mask(v));
+    let (k1, k2) = (mask(f1, m1), mask(f2, m2));
+    let (b1, b2) = (m1 - k1, m2 - k2);
+    (k1 - mask(f1, b2), k2 - mask(f2, b1))
+}
+
+#[cfg(test)]
+mod frozen_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn frozen_blocks_but_collides() {
+        let mut app = PhysicsTestApp::new();
+        // the actor only slides up and down
+        let actor = app.spawn_at(Vec2::ZERO, KinematicBundle {
+            vel: Vel(Vec2::new(30.0, 30.0)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(actor).insert_bundle((Acc::default(), Frozen::X));
+        // overlapping it, gets pushed out entirely
+        let body = app.spawn_at(Vec2::new(1.5, 0.0), KinematicBundle::default());
+        app.step_n(3);
+
+        app.assert_collided(actor, body);
+        assert_eq!(app.position(actor).x, 0.0);
+        assert!(app.position(actor).y > 0.0);
+        assert!(app.position(body).x >= 2.0 - 0.001, "{}", app.position(body));
+        assert_eq!(app.app.world.get::<Vel>(actor).unwrap().0.x, 0.0);
+    }
+}
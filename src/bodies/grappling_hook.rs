@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+
+use crate::{
+    joints::DistanceJoint,
+    normal_coll::{collide_ray, RayTargets},
+    physics_components::{CollisionLayer, Transform2D},
+};
+
+use super::{PhysicsDisabled, RayCast};
+
+/**
+    # GrapplingHook
+
+    A hook which is shot at a direction(`fire`), and once it hits a body ties its entity to the hit point with a rope
+    (a `max_only` `DistanceJoint`, spawned on an entity of its own) which can be reeled in and out.
+
+    The hook is a ray of length `range` going through the same layers as a `RayCast` on the entity would,
+    it can hit kinematic and static bodies(not sensors), and hitting a moving body ties the rope to it.
+    A `HookEvent` is sent when the hook hits, misses or is released.
+
+    ```ignore
+    fn grapple(mouse: Res<Input<MouseButton>>, keys: Res<Input<KeyCode>>, cursor: Res<CursorWorldPosition>, mut q: Query<(&Transform2D, &mut GrapplingHook)>) {
+        let (t, mut hook) = q.single_mut();
+        if mouse.just_pressed(MouseButton::Right) {
+            hook.fire(cursor.0 - t.translation());
+        }
+        if mouse.just_released(MouseButton::Right) {
+            hook.release();
+        }
+        hook.reel = if keys.pressed(KeyCode::W) { 10.0 } else { 0.0 };
+    }
+    ```
+*/
+#[derive(Component, Debug, Clone)]
+pub struct GrapplingHook {
+    /// How far the hook flies, and the longest the rope can be reeled out to
+    pub range: f32,
+    /// The shortest the rope can be reeled in to
+    pub min_length: f32,
+    /// How fast the rope is reeled in(negative to reel out), in units per second
+    pub reel: f32,
+    fire: Option<Vec2>,
+    release: bool,
+    attached: Option<HookAttachment>,
+}
+
+/// Where a `GrapplingHook` is stuck
+#[derive(Debug, Clone, Copy)]
+pub struct HookAttachment {
+    /// The body the hook hit
+    pub entity: Entity,
+    /// Where it hit it(world space, at the time of the hit)
+    pub point: Vec2,
+    /// The rope's `DistanceJoint` entity
+    pub joint: Entity,
+}
+
+impl GrapplingHook {
+    pub fn new(range: f32) -> Self {
+        GrapplingHook {
+            range,
+            min_length: 0.5,
+            reel: 0.0,
+            fire: None,
+            release: false,
+            attached: None,
+        }
+    }
+    pub fn with_min_length(
+        mut self,
+        min_length: f32,
+    ) -> Self {
+        self.min_length = min_length;
+        self
+    }
+    /// Shoots the hook towards `direction`(world space) on the next physics step, releasing it first if it is attached
+    pub fn fire(
+        &mut self,
+        direction: Vec2,
+    ) {
+        self.fire = Some(direction);
+    }
+    /// Releases the hook(and despawns the rope) on the next physics step
+    pub fn release(&mut self) {
+        self.release = true;
+    }
+    /// Where the hook is stuck, `None` when it isn't
+    pub fn attached(&self) -> Option<HookAttachment> {
+        self.attached
+    }
+}
+
+/// Sent by `GrapplingHook`s
+#[derive(Debug, Clone, Copy)]
+pub enum HookEvent {
+    /// The hook hit a body and the rope was tied
+    Attached { hook: Entity, attachment: HookAttachment },
+    /// The hook didn't hit anything within its range
+    Missed { hook: Entity },
+    /// The hook was released(or what it hit was despawned)
+    Released { hook: Entity },
+}
+
+/// Fires, reels and releases the `GrapplingHook`s, runs after the physics step(so the ropes are solved in the same frame)
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn grappling_hook_system(
+    mut coms: Commands,
+    time: Res<Time>,
+    mut hooks: Query<(Entity, &mut GrapplingHook, &Transform2D, Option<&CollisionLayer>), Without<PhysicsDisabled>>,
+    trans: Query<&Transform2D>,
+    targets: RayTargets,
+    mut joints: Query<&mut DistanceJoint>,
+    mut events: EventWriter<HookEvent>,
+) {
+    let dt = time.delta_seconds();
+
+    for (e, mut hook, t, layer) in hooks.iter_mut() {
+        // whatever the hook hit is gone, so is the rope
+        let lost = hook.attached.map_or(false, |a| trans.get(a.entity).is_err() || joints.get(a.joint).is_err());
+        if hook.release || hook.fire.is_some() || lost {
+            hook.release = false;
+            if let Some(a) = hook.attached.take() {
+                if joints.contains(a.joint) {
+                    coms.entity(a.joint).despawn();
+                }
+                events.send(HookEvent::Released { hook: e });
+            }
+        }
+
+        if let Some(direction) = hook.fire.take() {
+            let origin = t.translation();
+            let ray = RayCast::new(direction.normalize_or_zero() * hook.range);
+            let ray_trans = Transform2D::new(origin, 0.0, Vec2::ONE);
+            let layer = layer.copied().unwrap_or_default();
+            let hit = collide_ray(&ray, &ray_trans, targets.candidates(layer, true, false, &[e]));
+
+            match hit.and_then(|h| trans.get(h.entity).ok().map(|ht| (h, ht))) {
+                Some((h, ht)) => {
+                    // the rope is tied to the hit body, in its own space
+                    let anchor = ht.rot_matrix().transpose() * (h.collision_point - ht.translation());
+                    let length = origin.distance(h.collision_point).max(hook.min_length);
+                    let joint = coms
+                        .spawn()
+                        .insert(DistanceJoint::new(e, Some(h.entity), length).with_anchors(Vec2::ZERO, anchor).max_only())
+                        .id();
+
+                    let attachment = HookAttachment {
+                        entity: h.entity,
+                        point: h.collision_point,
+                        joint,
+                    };
+                    hook.attached = Some(attachment);
+                    events.send(HookEvent::Attached { hook: e, attachment });
+                },
+                None => events.send(HookEvent::Missed { hook: e }),
+            }
+            continue;
+        }
+
+        if let Some(a) = hook.attached {
+            if let Ok(mut j) = joints.get_mut(a.joint) {
+                j.length = (j.length - hook.reel * dt).clamp(hook.min_length, hook.range.max(hook.min_length));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod grappling_hook_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn swing_and_reel() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(Gravity(Vec2::new(0.0, -20.0)));
+        let ceiling = app.spawn_at(Vec2::new(0.0, 10.0), StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(20.0, 1.0))),
+            ..Default::default()
+        });
+        let player = app.spawn_at(Vec2::ZERO, KinematicBundle::default());
+        app.app.world.entity_mut(player).insert_bundle((Acc::default(), GrapplingHook::new(15.0)));
+
+        let mut reader = app.app.world.resource::<Events<HookEvent>>().get_reader();
+        let mut read = |app: &PhysicsTestApp| reader.iter(app.app.world.resource::<Events<HookEvent>>()).copied().collect::<Vec<_>>();
+
+        // too far to the side
+        app.app.world.get_mut::<GrapplingHook>(player).unwrap().fire(Vec2::new(1.0, 0.0));
+        app.step();
+        assert!(matches!(read(&app)[..], [HookEvent::Missed { .. }]));
+
+        app.app.world.get_mut::<GrapplingHook>(player).unwrap().fire(Vec2::new(0.0, 1.0));
+        app.step();
+        let attachment = match read(&app)[..] {
+            [HookEvent::Attached { attachment, .. }] => attachment,
+            ref ev => panic!("{:?}", ev),
+        };
+        assert_eq!(attachment.entity, ceiling);
+        assert!((attachment.point - Vec2::new(0.0, 9.5)).length() < 0.001);
+
+        // hangs from the rope instead of falling, then gets pulled up
+        app.step_n(30);
+        assert!(app.position(player).y > -0.5, "{}", app.position(player));
+        app.app.world.get_mut::<GrapplingHook>(player).unwrap().reel = 6.0;
+        app.step_n(60);
+        assert!(app.position(player).y > 4.0, "{}", app.position(player));
+
+        app.app.world.get_mut::<GrapplingHook>(player).unwrap().release();
+        app.step();
+        assert!(matches!(read(&app)[..], [HookEvent::Released { .. }]));
+        assert!(app.app.world.get_entity(attachment.joint).is_none());
+        assert!(app.app.world.get::<GrapplingHook>(player).unwrap().attached().is_none());
+    }
+}
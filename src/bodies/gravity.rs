@@ -0,0 +1,197 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::physics_components::Acc;
+
+use super::{PhysicsDisabled, Sensor, StaticBody};
+
+/**
+    # Gravity
+
+    Acceleration added to every kinematic body with `Acc` each frame, before the integration.
+
+    `Vec2::ZERO` by default, so bodies only fall when you ask them to(adding gravity to `Acc` yourself still works).
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gravity(pub Vec2);
+
+impl Gravity {
+    /// Against the gravity(`Vec2::Y` without gravity)
+    pub fn up(&self) -> Vec2 {
+        up_from_gravity(self.0)
+    }
+}
+
+/**
+    # LocalGravity
+
+    Replaces the global `Gravity` for a single body(the `GravityArea`s it is in still apply on top of it),
+    and gives the body its own up(against its gravity), which is used to tell its floors from its walls and ceilings
+    (`ContactInfo::surface`).
+
+    Walking around a planet is a matter of pointing the gravity at the planet's center every frame:
+
+    ```ignore
+    fn planet_gravity(planet: Query<&Transform2D, With<Planet>>, mut q: Query<(&Transform2D, &mut LocalGravity)>) {
+        let center = planet.single().translation();
+        for (t, mut g) in q.iter_mut() {
+            g.0 = (center - t.translation()).normalize_or_zero() * 20.0;
+        }
+    }
+    ```
+*/
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct LocalGravity(pub Vec2);
+
+impl LocalGravity {
+    /// Against the body's gravity(`Vec2::Y` without gravity)
+    pub fn up(&self) -> Vec2 {
+        up_from_gravity(self.0)
+    }
+}
+
+/// The up of a body falling with `gravity`, `Vec2::Y` when there is no gravity
+pub fn up_from_gravity(gravity: Vec2) -> Vec2 {
+    let up = -gravity.normalize_or_zero();
+    if up == Vec2::ZERO { Vec2::Y } else { up }
+}
+
+/// How a `GravityArea` changes the gravity of the bodies inside it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GravityMode {
+    /// Multiplies the gravity(`0.5` for underwater, `-1.0` for reversed gravity)
+    Scale(f32),
+    /// Replaces the gravity altogether
+    Override(Vec2),
+}
+
+/**
+    # GravityArea
+
+    Changes the `Gravity` of the bodies inside a sensor, put it on an entity with a `SensorBundle`.
+
+    The area uses the sensor's bodies of the last frame(the sensors are filled after the integration),
+    so a body entering the area feels it a frame later.
+
+    When areas overlap they are applied from the lowest `priority` to the highest(ties by entity),
+    each one on top of the gravity left by the previous ones - so an `Override` wipes out everything below it,
+    and a `Scale` above it scales the overridden gravity.
+*/
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GravityArea {
+    pub mode: GravityMode,
+    pub priority: i32,
+}
+
+impl GravityArea {
+    pub fn scale(scale: f32) -> Self {
+        GravityArea {
+            mode: GravityMode::Scale(scale),
+            priority: 0,
+        }
+    }
+    pub fn with_gravity(gravity: Vec2) -> Self {
+        GravityArea {
+            mode: GravityMode::Override(gravity),
+            priority: 0,
+        }
+    }
+    pub fn with_priority(
+        mut self,
+        priority: i32,
+    ) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// The gravity of a body inside the area, given the gravity it had before
+    pub fn apply(
+        &self,
+        gravity: Vec2,
+    ) -> Vec2 {
+        match self.mode {
+            GravityMode::Scale(s) => gravity * s,
+            GravityMode::Override(g) => g,
+        }
+    }
+}
+
+/// Adds the gravity(their `LocalGravity` or the global one, after the `GravityArea`s the body is in) to the kinematic bodies,
+/// runs before the integration
+#[allow(clippy::type_complexity)]
+pub fn gravity_system(
+    gravity: Res<Gravity>,
+    areas: Query<(Entity, &GravityArea, &Sensor), Without<PhysicsDisabled>>,
+    mut bodies: Query<(Entity, &mut Acc, Option<&LocalGravity>), (Without<StaticBody>, Without<PhysicsDisabled>)>,
+    mut sorted: Local<Vec<(i32, Entity)>>,
+    mut in_areas: Local<HashMap<Entity, Vec2>>,
+) {
+    sorted.clear();
+    sorted.extend(areas.iter().map(|(e, a, _)| (a.priority, e)));
+    sorted.sort_unstable();
+
+    in_areas.clear();
+    for (_, e) in sorted.iter() {
+        let (_, area, sensor) = areas.get(*e).unwrap();
+        for b in sensor.bodies.iter() {
+            let g = in_areas
+                .entry(*b)
+                .or_insert_with(|| bodies.get_component::<LocalGravity>(*b).map_or(gravity.0, |l| l.0));
+            *g = area.apply(*g);
+        }
+    }
+
+    for (e, mut acc, local) in bodies.iter_mut() {
+        let g = match in_areas.get(&e) {
+            Some(g) => *g,
+            None => local.map_or(gravity.0, |l| l.0),
+        };
+        if g != Vec2::ZERO {
+            acc.add(g);
+        }
+    }
+}
+
+#[cfg(test)]
+mod gravity_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn areas_in_priority_order() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(Gravity(Vec2::new(0.0, -10.0)));
+
+        let area = |app: &mut PhysicsTestApp, x: f32, area: GravityArea| {
+            let e = app.spawn_at(Vec2::new(x, 0.0), SensorBundle {
+                shape: CollisionShape::Square(Square::new(Vec2::splat(5.0))),
+                ..Default::default()
+            });
+            app.app.world.entity_mut(e).insert(area);
+        };
+        // reversed gravity on the left, on the right a scale spawned before an override but applied after it
+        area(&mut app, -10.0, GravityArea::scale(-1.0));
+        area(&mut app, 10.0, GravityArea::scale(3.0).with_priority(1));
+        area(&mut app, 10.0, GravityArea::with_gravity(Vec2::new(0.0, -1.0)));
+
+        let spawn = |app: &mut PhysicsTestApp, x: f32| {
+            let e = app.spawn_at(Vec2::new(x, 0.0), KinematicBundle::default());
+            app.app.world.entity_mut(e).insert(Acc::default());
+            e
+        };
+        let reversed = spawn(&mut app, -10.0);
+        let outside = spawn(&mut app, 0.0);
+        let stacked = spawn(&mut app, 10.0);
+        // the areas only kick in once the sensors found the bodies
+        app.step_n(2);
+
+        let vel = |app: &PhysicsTestApp, e: Entity| app.app.world.get::<Vel>(e).unwrap().0.y;
+        let before = [reversed, outside, stacked].map(|e| vel(&app, e));
+        app.step();
+        let after = [reversed, outside, stacked].map(|e| vel(&app, e));
+        let dv = [0, 1, 2].map(|i| (after[i] - before[i]) * 60.0);
+
+        assert!((dv[0] - 10.0).abs() < 0.001, "{:?}", dv);
+        assert!((dv[1] + 10.0).abs() < 0.001, "{:?}", dv);
+        assert!((dv[2] + 3.0).abs() < 0.001, "{:?}", dv);
+    }
+}
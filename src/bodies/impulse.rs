@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::physics_components::{Mass, Vel};
+
+use super::PhysicsDisabled;
+
+/**
+    # Impulse
+
+    Impulse accumulator for bodies with `Vel`, add knockbacks, explosions and jumps to it using `Impulse::add`
+    from any system during the frame, instead of writing to `Vel` directly.
+
+    At the start of the physics step the accumulated impulse is added to `Vel`(divided by the body's `Mass`, if it has one)
+    and cleared, so the result doesn't depend on the order of the gameplay systems.
+*/
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Impulse(pub Vec2);
+
+impl Impulse {
+    /// Adds to the impulse applied in the next physics step
+    pub fn add(
+        &mut self,
+        impulse: Vec2,
+    ) {
+        self.0 += impulse;
+    }
+}
+
+/// Applies and clears the accumulated `Impulse`s, runs before the integration
+pub fn impulse_system(mut q: Query<(&mut Impulse, &mut Vel, Option<&Mass>), Without<PhysicsDisabled>>) {
+    for (mut impulse, mut vel, mass) in q.iter_mut() {
+        if impulse.0 == Vec2::ZERO {
+            continue;
+        }
+        let inv_mass = mass.map(|m| m.inverse()).unwrap_or(1.0);
+        vel.0 += impulse.0 * inv_mass;
+        impulse.0 = Vec2::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod impulse_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn impulses_add_up_and_clear() {
+        let mut app = PhysicsTestApp::new();
+        let body = app.spawn_at(Vec2::ZERO, KinematicBundle {
+            collision_layer: CollisionLayer::ZERO,
+            ..Default::default()
+        });
+        app.app.world.entity_mut(body).insert_bundle((Impulse::default(), Mass(2.0)));
+
+        {
+            let mut impulse = app.app.world.get_mut::<Impulse>(body).unwrap();
+            impulse.add(Vec2::new(4.0, 0.0));
+            impulse.add(Vec2::new(0.0, 2.0));
+        }
+        app.step();
+
+        assert_eq!(app.app.world.get::<Vel>(body).unwrap().0, Vec2::new(2.0, 1.0));
+        assert_eq!(app.app.world.get::<Impulse>(body).unwrap().0, Vec2::ZERO);
+    }
+}
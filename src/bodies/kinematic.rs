@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
 use crate::{
     physics_components::{
         Vel,
@@ -10,7 +12,77 @@ use crate::{
 /// Kinematic bodys are practically everything with the `Vel` struct
 #[derive(Bundle, Default)]
 pub struct KinematicBundle {
+    pub marker: KinematicBody,
     pub vel: Vel,
     pub shape: CollisionShape,
     pub collision_layer: CollisionLayer,
 }
+
+/// Marks a kinematic body explicitly
+///
+/// Bodies with a `CollisionShape` which are neither static bodies nor sensors are treated as kinematic bodies anyway,
+/// the marker just makes it explicit(and quiets `validate_bodies_system`).
+///
+/// Kinematic bodies without `Vel` are moved only by their `Transform` and never use continuous collision,
+/// `continuous: false` opts out of it for bodies with `Vel` as well(same as `CcdMode::Never`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Component)]
+pub struct KinematicBody {
+    pub continuous: bool,
+}
+
+impl KinematicBody {
+    /// Kinematic body which doesn't use continuous collision
+    pub fn discrete() -> Self {
+        KinematicBody { continuous: false }
+    }
+}
+
+impl Default for KinematicBody {
+    fn default() -> Self {
+        KinematicBody { continuous: true }
+    }
+}
+
+/// When a body with `Vel` uses continuous collision(consulted by `ccd_system`, see the `ccd` module)
+///
+/// Bodies which don't use it in a given frame are left to the discrete path,
+/// which is cheaper but lets fast and small bodies tunnel through thin walls.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Component)]
+pub enum CcdMode {
+    Always,
+    /// Only when the body is faster than `speed_threshold`(units per second)
+    Auto { speed_threshold: f32 },
+    Never,
+}
+
+impl CcdMode {
+    /// Whether a body moving at `vel` should use continuous collision
+    pub fn is_active(
+        &self,
+        vel: Vec2,
+    ) -> bool {
+        match self {
+            CcdMode::Always => true,
+            CcdMode::Auto { speed_threshold } => vel.length_squared() > speed_threshold * speed_threshold,
+            CcdMode::Never => false,
+        }
+    }
+    /// Whether a body with the given `CcdMode`(the default one when it has none) and `KinematicBody` marker,
+    /// moving at `vel`, should use continuous collision - a marker with `continuous: false` always opts out
+    pub fn resolve(
+        ccd: Option<&CcdMode>,
+        kin: Option<&KinematicBody>,
+        vel: Vec2,
+    ) -> bool {
+        match kin {
+            Some(k) if !k.continuous => false,
+            _ => ccd.copied().unwrap_or_default().is_active(vel),
+        }
+    }
+}
+
+impl Default for CcdMode {
+    fn default() -> Self {
+        CcdMode::Auto { speed_threshold: 500.0 }
+    }
+}
@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+
+use crate::{
+    normal_coll::{collide_ray, RayTargets},
+    physics_components::{CollisionLayer, Transform2D},
+};
+
+use super::{PhysicsDisabled, RayCast};
+
+/**
+    # LineOfSight
+
+    Casts a ray from its entity to `target` every frame(right after the rays, in `PhysicsSystem::Rays`),
+    and keeps whether the target can be seen and what is in the way.
+
+    Static and kinematic bodies whose layer(their `RayLayer` when they have one, like with `RayCast`s) overlaps `layer` block the view,
+    sensors, the entity itself and the target never do.
+    A target further than `max_dist`(or without a `Transform2D`) is never visible, and has no blocker either.
+
+    ```ignore
+    coms.entity(guard).insert(LineOfSight::new(player, 300.0));
+
+    fn chase(guards: Query<&LineOfSight, With<Guard>>) {
+        for los in guards.iter() {
+            if let Some(p) = los.last_seen { /* go to `p` */ }
+        }
+    }
+    ```
+*/
+#[derive(Component, Debug, Clone)]
+pub struct LineOfSight {
+    pub target: Entity,
+    /// How far the target can be seen from
+    pub max_dist: f32,
+    /// Offset of the eyes from the entity's `Transform2D`(rotates with it)
+    pub offset: Vec2,
+    /// Only bodies overlapping this layer block the view
+    pub layer: CollisionLayer,
+    /// Whether the target was seen this frame
+    pub visible: bool,
+    /// The closest body between the entity and the target, when there is one
+    pub blocked_by: Option<Entity>,
+    /// Where the target was the last time it was seen(world space)
+    pub last_seen: Option<Vec2>,
+}
+
+impl LineOfSight {
+    pub fn new(
+        target: Entity,
+        max_dist: f32,
+    ) -> Self {
+        LineOfSight {
+            target,
+            max_dist,
+            offset: Vec2::ZERO,
+            layer: CollisionLayer::default(),
+            visible: false,
+            blocked_by: None,
+            last_seen: None,
+        }
+    }
+    pub fn with_offset(
+        mut self,
+        offset: Vec2,
+    ) -> Self {
+        self.offset = offset;
+        self
+    }
+    pub fn with_layer(
+        mut self,
+        layer: CollisionLayer,
+    ) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+/// Casts the `LineOfSight` rays, runs with the `RayCast`s
+pub fn line_of_sight_system(
+    trans: Query<&Transform2D>,
+    targets: RayTargets,
+    mut sights: Query<(Entity, &mut LineOfSight), Without<PhysicsDisabled>>,
+) {
+    for (e, mut los) in sights.iter_mut() {
+        let (t, target) = match (trans.get(e), trans.get(los.target)) {
+            (Ok(t), Ok(target)) => (t, target.translation()),
+            _ => {
+                los.visible = false;
+                los.blocked_by = None;
+                continue;
+            },
+        };
+        let origin = t.translation() + t.rot_matrix() * los.offset;
+        if origin.distance(target) > los.max_dist {
+            los.visible = false;
+            los.blocked_by = None;
+            continue;
+        }
+
+        // the ray is cast from an unrotated transform at the eyes, so it goes straight at the target
+        let ray = RayCast::new(target - origin);
+        let ray_trans = Transform2D::new(origin, 0.0, Vec2::ONE);
+        let hit = collide_ray(&ray, &ray_trans, targets.candidates(los.layer, true, false, &[e, los.target]));
+
+        los.blocked_by = hit.map(|h| h.entity);
+        los.visible = hit.is_none();
+        if los.visible {
+            los.last_seen = Some(target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod line_of_sight_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn blocked_by_walls() {
+        let mut app = PhysicsTestApp::new();
+        let target = app.spawn_at(Vec2::new(10.0, 0.0), KinematicBundle::default());
+        let wall = app.spawn_at(Vec2::new(5.0, 0.0), StaticBundle::default());
+        // glass stops bodies but not the view
+        let glass = app.spawn_at(Vec2::new(5.0, 10.0), StaticBundle::default());
+        app.app.world.entity_mut(glass).insert(RayLayer(CollisionLayer::ZERO));
+        let behind_glass_target = app.spawn_at(Vec2::new(10.0, 10.0), KinematicBundle::default());
+
+        let looker = |app: &mut PhysicsTestApp, position: Vec2, los: LineOfSight| {
+            let e = app.spawn_at(position, KinematicBundle::default());
+            app.app.world.entity_mut(e).insert(los);
+            e
+        };
+        let behind_wall = looker(&mut app, Vec2::ZERO, LineOfSight::new(target, 100.0));
+        let behind_glass = looker(&mut app, Vec2::new(0.0, 10.0), LineOfSight::new(behind_glass_target, 100.0));
+        let too_far = looker(&mut app, Vec2::new(10.0, 5.0), LineOfSight::new(target, 2.0));
+        app.step();
+
+        let los = |app: &PhysicsTestApp, e: Entity| app.app.world.get::<LineOfSight>(e).unwrap().clone();
+        let l = los(&app, behind_wall);
+        assert!(!l.visible);
+        assert_eq!(l.blocked_by, Some(wall));
+        let l = los(&app, behind_glass);
+        assert!(l.visible);
+        assert_eq!(l.blocked_by, None);
+        assert_eq!(l.last_seen, Some(Vec2::new(10.0, 10.0)));
+        let l = los(&app, too_far);
+        assert!(!l.visible && l.blocked_by.is_none());
+    }
+}
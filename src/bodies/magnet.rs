@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+
+use crate::{
+    physics_components::{Acc, Mass, Transform2D},
+    spatial_hash::SpatialHash,
+    transform_mode::TransformMode,
+};
+
+use super::{PhysicsDisabled, StaticBody};
+
+/// Pole of a `Magnet`, like poles repel and opposite poles attract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    North,
+    South,
+}
+
+/**
+    # Magnet
+
+    Pulls `Magnetic` bodies within `radius` towards itself, and pushes or pulls other magnets by their `Polarity`.
+
+    The pull is `strength` right on top of the magnet and fades linearly to nothing at `radius`,
+    it is divided by the `Mass` of the pulled body(when it has one).
+    Only kinematic bodies with `Acc` are moved, a magnet can be a static body or a kinematic one(and then it is pulled as well).
+*/
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Magnet {
+    pub strength: f32,
+    pub radius: f32,
+    pub polarity: Polarity,
+}
+
+impl Magnet {
+    pub fn new(
+        strength: f32,
+        radius: f32,
+    ) -> Self {
+        Magnet {
+            strength,
+            radius,
+            polarity: Polarity::North,
+        }
+    }
+    pub fn with_polarity(
+        mut self,
+        polarity: Polarity,
+    ) -> Self {
+        self.polarity = polarity;
+        self
+    }
+
+    /// Acceleration(before the mass) the magnet at `position` applies on a body at `body`,
+    /// `attract` is false for a magnet with the same polarity
+    pub fn acceleration(
+        &self,
+        position: Vec2,
+        body: Vec2,
+        attract: bool,
+    ) -> Vec2 {
+        let d = position - body;
+        let dis = d.length();
+        if dis >= self.radius || dis <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+
+        let a = d / dis * self.strength * (1.0 - dis / self.radius);
+        if attract { a } else { -a }
+    }
+}
+
+/// Marks a body as attracted by every `Magnet`(regardless of polarity), like a piece of iron
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Magnetic;
+
+/// Adds the magnets' pull to the `Magnetic` bodies and the kinematic magnets, runs before the integration
+#[allow(clippy::type_complexity)]
+pub fn magnet_system(
+    trans_mode: Res<TransformMode>,
+    magnets: Query<(Entity, &Magnet, &GlobalTransform), Without<PhysicsDisabled>>,
+    mut bodies: Query<
+        (Entity, &Transform2D, &mut Acc, Option<&Magnet>, Option<&Mass>),
+        (Or<(With<Magnetic>, With<Magnet>)>, Without<StaticBody>, Without<PhysicsDisabled>),
+    >,
+    mut found: Local<Vec<(Entity, Magnet, Vec2)>>,
+    mut hash: Local<Option<SpatialHash>>,
+) {
+    found.clear();
+    found.extend(magnets.iter().map(|(e, m, gt)| (e, *m, trans_mode.get_global_position(gt))));
+    if found.is_empty() {
+        return;
+    }
+
+    // every query covers the biggest radius, smaller magnets are filtered by their distance
+    let max_radius = found.iter().map(|(_, m, _)| m.radius).fold(f32::EPSILON, f32::max);
+    let hash = match hash.as_mut() {
+        Some(h) if h.cell_size() == max_radius => {
+            h.clear();
+            h
+        },
+        _ => hash.insert(SpatialHash::new(max_radius)),
+    };
+    for (i, (_, _, p)) in found.iter().enumerate() {
+        hash.insert(i, *p);
+    }
+
+    for (e, t, mut acc, magnet, mass) in bodies.iter_mut() {
+        let pos = t.translation();
+        let inv_mass = mass.map(|m| m.inverse()).unwrap_or(1.0);
+
+        let mut a = Vec2::ZERO;
+        for i in hash.query(pos, max_radius) {
+            let (me, m, mp) = &found[i];
+            if *me == e {
+                continue;
+            }
+            // plain magnetic bodies are always attracted, magnets only by the opposite pole
+            let attract = magnet.map_or(true, |b| b.polarity != m.polarity);
+            a += m.acceleration(*mp, pos, attract);
+        }
+        acc.add(a * inv_mass);
+    }
+}
+
+#[cfg(test)]
+mod magnet_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn attract_and_repel() {
+        let mut app = PhysicsTestApp::new();
+        let magnet = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        app.app.world.entity_mut(magnet).insert(Magnet::new(100.0, 10.0));
+
+        let body = |app: &mut PhysicsTestApp, position: Vec2| {
+            let e = app.spawn_at(position, KinematicBundle::default());
+            app.app.world.entity_mut(e).insert(Acc::default());
+            e
+        };
+        let iron = body(&mut app, Vec2::new(5.0, 0.0));
+        let far = body(&mut app, Vec2::new(-30.0, 0.0));
+        // small magnets above and below the big one
+        let same = body(&mut app, Vec2::new(0.0, 5.0));
+        let opposite = body(&mut app, Vec2::new(0.0, -5.0));
+        app.app.world.entity_mut(iron).insert(Magnetic);
+        app.app.world.entity_mut(far).insert(Magnetic);
+        app.app.world.entity_mut(same).insert(Magnet::new(1.0, 1.0));
+        app.app.world.entity_mut(opposite).insert(Magnet::new(1.0, 1.0).with_polarity(Polarity::South));
+        app.step_n(3);
+
+        let vel = |e: Entity| app.app.world.get::<Vel>(e).unwrap().0;
+        assert!(vel(iron).x < 0.0, "{}", vel(iron));
+        assert_eq!(vel(far), Vec2::ZERO);
+        // pushed up, away from the north pole
+        assert!(vel(same).y > 0.0, "{}", vel(same));
+        // pulled up, towards it
+        assert!(vel(opposite).y > 0.0, "{}", vel(opposite));
+    }
+}
@@ -2,8 +2,54 @@ mod kinematic;
 mod sensor;
 mod staticbody;
 mod raycast;
+mod destructible;
+mod rope;
+mod ragdoll;
+mod attractor;
+mod drag;
+mod validate;
+mod contact_forces;
+mod bake;
+mod vehicle;
+mod impulse;
+mod disabled;
+mod teleport;
+mod gravity;
+mod magnet;
+mod stuck;
+mod line_of_sight;
+mod penetration;
+mod bouncy;
+mod collider_timeline;
+mod frozen;
+mod attach;
+mod grappling_hook;
+mod planet_walker;
 
 pub use kinematic::*;
 pub use sensor::*;
 pub use staticbody::*;
 pub use raycast::*;
+pub use destructible::*;
+pub use rope::*;
+pub use ragdoll::*;
+pub use attractor::*;
+pub use drag::*;
+pub use validate::*;
+pub use contact_forces::*;
+pub use bake::*;
+pub use vehicle::*;
+pub use impulse::*;
+pub use disabled::*;
+pub use teleport::*;
+pub use gravity::*;
+pub use magnet::*;
+pub use stuck::*;
+pub use line_of_sight::*;
+pub use penetration::*;
+pub use bouncy::*;
+pub use collider_timeline::*;
+pub use frozen::*;
+pub use attach::*;
+pub use grappling_hook::*;
+pub use planet_walker::*;
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+
+use super::RayHit;
+
+/**
+    # Penetrability
+
+    How easily projectiles(`RayCast`s with a `Penetration`) go through a body,
+    in units of thickness per unit of energy - a projectile loses `thickness / penetrability` energy going through it.
+
+    Bodies without it(or with `0.0`) stop projectiles right where they hit.
+*/
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Penetrability(pub f32);
+
+/**
+    # Penetration
+
+    Turns a `RayCast` into a projectile which goes through bodies until its `energy` is spent,
+    a `PenetrationHit` is sent for every body it hits(in order, from the closest),
+    and `RayCast::collision` still holds the first one.
+
+    The energy left is a good way to scale damage down for the later hits - `hit.energy / penetration.energy`.
+*/
+#[derive(Component, Debug, Clone)]
+pub struct Penetration {
+    /// Energy the projectile starts with every frame
+    pub energy: f32,
+    /// Energy left at the end of the ray(0 when it was stopped)
+    pub remaining: f32,
+    /// Where the projectile stopped, `None` if it made it to the end of the ray
+    pub stopped_at: Option<Vec2>,
+}
+
+impl Penetration {
+    pub fn new(energy: f32) -> Self {
+        Penetration {
+            energy,
+            remaining: energy,
+            stopped_at: None,
+        }
+    }
+}
+
+/// Sent for every body a `Penetration` projectile hits, in the order they were hit
+#[derive(Debug, Clone, Copy)]
+pub struct PenetrationHit {
+    /// The projectile(the `RayCast` entity)
+    pub ray: Entity,
+    /// The body it hit
+    pub entity: Entity,
+    /// Which hit this is along the ray, starting from 0
+    pub index: usize,
+    /// Where it went into the body
+    pub entry: Vec2,
+    /// Where it went out of the body, or where it stopped inside it
+    pub exit: Vec2,
+    /// Energy it had when it hit the body
+    pub energy: f32,
+    /// Energy left after going through the body(0 when it stopped in it)
+    pub remaining: f32,
+}
+
+impl Penetration {
+    /// Walks the `hits`(ordered from the closest) with the projectile's energy, filling `remaining` and `stopped_at`
+    /// and returning the `PenetrationHit`s
+    pub(crate) fn penetrate(
+        &mut self,
+        ray: Entity,
+        origin: Vec2,
+        cast: Vec2,
+        hits: &[RayHit],
+        penetrability: impl Fn(Entity) -> Option<f32>,
+    ) -> Vec<PenetrationHit> {
+        self.remaining = self.energy;
+        self.stopped_at = None;
+
+        let mut events = Vec::new();
+        for (index, hit) in hits.iter().enumerate() {
+            if self.remaining <= 0.0 {
+                break;
+            }
+            let e = hit.collision.entity;
+            let entry = origin + cast * hit.entry;
+            let exit = origin + cast * hit.exit;
+            let thickness = entry.distance(exit);
+
+            let energy = self.remaining;
+            let p = penetrability(e).unwrap_or(0.0).max(0.0);
+            // how far into the body the energy lasts
+            let reach = energy * p;
+            let (exit, remaining) = if reach > thickness {
+                (exit, energy - thickness / p)
+            }
+            else {
+                let stop = entry + (exit - entry).normalize_or_zero() * reach;
+                self.stopped_at = Some(stop);
+                (stop, 0.0)
+            };
+            self.remaining = remaining;
+
+            events.push(PenetrationHit {
+                ray,
+                entity: e,
+                index,
+                entry,
+                exit,
+                energy,
+                remaining,
+            });
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod penetration_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn through_walls_in_order() {
+        let mut app = PhysicsTestApp::new();
+        let wall = |app: &mut PhysicsTestApp, x: f32, penetrability: Option<f32>| {
+            // 2 units thick
+            let e = app.spawn_at(Vec2::new(x, 0.0), StaticBundle::default());
+            if let Some(p) = penetrability {
+                app.app.world.entity_mut(e).insert(Penetrability(p));
+            }
+            e
+        };
+        // costs 1 energy, then 4, the last one stops it
+        let paper = wall(&mut app, 5.0, Some(2.0));
+        let wood = wall(&mut app, 10.0, Some(0.5));
+        let steel = wall(&mut app, 15.0, None);
+        let behind = wall(&mut app, 20.0, Some(100.0));
+
+        let gun = |app: &mut PhysicsTestApp, energy: f32| {
+            let e = app.app.world.spawn().insert_bundle(RayCastBundle {
+                ray: RayCast::new(Vec2::new(30.0, 0.0)),
+                ..Default::default()
+            }).insert_bundle(SpatialBundle::default()).insert(Penetration::new(energy)).id();
+            e
+        };
+        let strong = gun(&mut app, 10.0);
+        let weak = gun(&mut app, 3.0);
+
+        let mut reader = app.app.world.resource::<Events<PenetrationHit>>().get_reader();
+        app.step();
+        let hits = reader.iter(app.app.world.resource::<Events<PenetrationHit>>()).copied().collect::<Vec<_>>();
+
+        let of = |ray: Entity| hits.iter().filter(|h| h.ray == ray).map(|h| (h.entity, h.index, (h.remaining * 1000.0).round() / 1000.0)).collect::<Vec<_>>();
+        assert_eq!(of(strong), vec![(paper, 0, 9.0), (wood, 1, 5.0), (steel, 2, 0.0)]);
+        assert!(!of(strong).iter().any(|h| h.0 == behind));
+
+        // spends the last of it 1 unit into the wood
+        assert_eq!(of(weak), vec![(paper, 0, 2.0), (wood, 1, 0.0)]);
+        let p = app.app.world.get::<Penetration>(weak).unwrap();
+        assert!(p.stopped_at.unwrap().distance(Vec2::new(10.0, 0.0)) < 0.001, "{:?}", p.stopped_at);
+        assert_eq!(app.app.world.get::<RayCast>(weak).unwrap().collision.unwrap().entity, paper);
+    }
+}
@@ -0,0 +1,187 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+
+use crate::{
+    physics_components::{Acc, Transform2D},
+    shapes::CollisionShape,
+    transform_mode::TransformMode,
+};
+
+use super::{Attractor, LocalGravity, PhysicsDisabled};
+
+/**
+    # PlanetWalker
+
+    Keeps a character's feet towards the closest `Attractor`(a planet, usually a static body with a round shape),
+    for games where the characters walk all the way around small planets.
+
+    Each frame, before the gravity is added, the walker finds the attractor whose surface is the closest
+    (along the line to its center, its center itself for attractors without a `CollisionShape`),
+    points its `LocalGravity` at that attractor's center with a constant `gravity`(instead of being pulled by the attractors themselves),
+    and turns its `Transform2D` so its feet(`-Y`) face the attractor, by up to `turn_speed` radians per second.
+    With the `LocalGravity`, the attractor's surface shows up as the walker's floor in `ContactsPerEntity`.
+
+    Input made for a regular platformer is remapped using `to_world`(and `to_local` to read the velocity back):
+
+    ```ignore
+    fn walk(keys: Res<Input<KeyCode>>, mut q: Query<(&PlanetWalker, &mut Vel)>) {
+        let (walker, mut vel) = q.single_mut();
+        let mut local = walker.to_local(vel.0);
+        local.x = if keys.pressed(KeyCode::Right) { 8.0 } else if keys.pressed(KeyCode::Left) { -8.0 } else { 0.0 };
+        vel.0 = walker.to_world(local);
+    }
+    ```
+*/
+#[derive(Component, Debug, Clone)]
+pub struct PlanetWalker {
+    /// Strength of the walker's gravity towards the attractor
+    pub gravity: f32,
+    /// How fast the walker turns its feet towards the attractor(in radians per second), `f32::INFINITY` to snap
+    pub turn_speed: f32,
+    up: Vec2,
+    planet: Option<Entity>,
+}
+
+impl Default for PlanetWalker {
+    fn default() -> Self {
+        PlanetWalker::new(20.0)
+    }
+}
+
+impl PlanetWalker {
+    pub fn new(gravity: f32) -> Self {
+        PlanetWalker {
+            gravity,
+            turn_speed: f32::INFINITY,
+            up: Vec2::Y,
+            planet: None,
+        }
+    }
+    pub fn with_turn_speed(
+        mut self,
+        turn_speed: f32,
+    ) -> Self {
+        self.turn_speed = turn_speed;
+        self
+    }
+    /// Where the walker's head points
+    pub fn up(&self) -> Vec2 {
+        self.up
+    }
+    /// The walker's right, along the surface it stands on
+    pub fn right(&self) -> Vec2 {
+        Vec2::new(self.up.y, -self.up.x)
+    }
+    /// The attractor the walker stands on(or falls towards), `None` before it found one
+    pub fn planet(&self) -> Option<Entity> {
+        self.planet
+    }
+    /// Turns a vector in the walker's frame(`x` to its right, `y` up) to world space
+    pub fn to_world(
+        &self,
+        local: Vec2,
+    ) -> Vec2 {
+        self.right() * local.x + self.up * local.y
+    }
+    /// Turns a world space vector to the walker's frame(`x` to its right, `y` up)
+    pub fn to_local(
+        &self,
+        world: Vec2,
+    ) -> Vec2 {
+        Vec2::new(world.dot(self.right()), world.dot(self.up))
+    }
+}
+
+/// A `PlanetWalker` with its gravity and `Acc`, add it next to a `KinematicBundle`
+#[derive(Bundle, Default)]
+pub struct PlanetWalkerBundle {
+    pub walker: PlanetWalker,
+    pub gravity: LocalGravity,
+    pub acc: Acc,
+}
+
+/// Points the walkers' gravity and feet at their closest attractor, runs before the gravity is added
+#[allow(clippy::type_complexity)]
+pub fn planet_walker_system(
+    time: Res<Time>,
+    trans_mode: Res<TransformMode>,
+    attractors: Query<(Entity, &GlobalTransform, Option<&CollisionShape>, Option<&Transform2D>), With<Attractor>>,
+    mut walkers: Query<(Entity, &mut PlanetWalker, &mut Transform2D, &mut LocalGravity), (Without<Attractor>, Without<PhysicsDisabled>)>,
+) {
+    let dt = time.delta_seconds();
+
+    for (e, mut walker, mut t, mut gravity) in walkers.iter_mut() {
+        let p = t.translation();
+        let closest = attractors
+            .iter()
+            .filter(|(ae, ..)| *ae != e)
+            .map(|(ae, gt, shape, at)| {
+                let center = at.map_or_else(|| trans_mode.get_global_position(gt), |at| at.translation());
+                let to = center - p;
+                let surface = match (shape, at) {
+                    (Some(s), Some(at)) => s.ray(at, p, to).unwrap_or(1.0),
+                    _ => 1.0,
+                };
+                (ae, center, to.length() * surface)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        let (planet, center) = match closest {
+            Some((ae, center, _)) => (ae, center),
+            None => continue,
+        };
+        walker.planet = Some(planet);
+        let up = (p - center).normalize_or_zero();
+        if up == Vec2::ZERO {
+            continue;
+        }
+        gravity.0 = -up * walker.gravity;
+
+        // `Vec2::Y` turned by the rotation is the walker's up
+        let target = f32::atan2(-up.x, up.y);
+        let diff = (target - t.rotation() + PI).rem_euclid(TAU) - PI;
+        let turn = if walker.turn_speed.is_infinite() { diff } else { diff.clamp(-walker.turn_speed * dt, walker.turn_speed * dt) };
+        if turn != 0.0 {
+            let r = t.rotation();
+            t.set_rotation(r + turn);
+        }
+        walker.up = t.rot_matrix() * Vec2::Y;
+    }
+}
+
+#[cfg(test)]
+mod planet_walker_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn walks_around_the_planet() {
+        let mut app = PhysicsTestApp::new();
+        let planet = app.spawn_at(Vec2::ZERO, StaticBundle {
+            shape: CollisionShape::Circle(Circle::new(10.0)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(planet).insert(Attractor::new(1000.0));
+        // on the planet's right side, so down is to the left
+        let walker = app.spawn_at(Vec2::new(14.0, 0.0), KinematicBundle::default());
+        app.app.world.entity_mut(walker).insert_bundle(PlanetWalkerBundle::default());
+        app.step_n(60);
+
+        assert!((app.position(walker).x - 11.0).abs() < 0.05, "{}", app.position(walker));
+        assert!(app.app.world.resource::<ContactsPerEntity>().on_floor(walker));
+        let w = app.app.world.get::<PlanetWalker>(walker).unwrap();
+        assert_eq!(w.planet(), Some(planet));
+        assert!((w.up() - Vec2::X).length() < 0.001, "{}", w.up());
+        // walking right on this side of the planet goes down
+        assert!((w.to_world(Vec2::X) - Vec2::new(0.0, -1.0)).length() < 0.001);
+
+        app.app.world.get_mut::<Vel>(walker).unwrap().0 = Vec2::new(0.0, -5.0);
+        app.step_n(30);
+        // went around the planet, still standing on it
+        let p = app.position(walker);
+        assert!(p.y < -1.0 && (p.length() - 11.0).abs() < 0.2, "{}", p);
+        let up = app.app.world.get::<PlanetWalker>(walker).unwrap().up();
+        assert!(up.dot(p.normalize()) > 0.99, "{} {}", up, p);
+    }
+}
@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+
+use crate::{
+    joints::RevoluteJoint,
+    physics_components::{Acc, CollisionLayer, Mass},
+    shapes::{Capsule, Circle, CollisionShape},
+};
+
+use super::KinematicBundle;
+
+/**
+    # RagdollBuilder
+
+    Spawns a standard 2D ragdoll - a torso, a head and 2 arms and legs(each made of 2 capsules),
+    connected by `RevoluteJoint`s with limits at the neck, shoulders, elbows, hips and knees.
+
+    The parts are kinematic bodies with `Acc`, so apply gravity to them like you do for the rest of your bodies,
+    and they don't collide with each other(see `RagdollBuilder::with_layer`).
+
+    ```ignore
+    let ragdoll = RagdollBuilder::new(Vec2::new(0.0, 200.0), 80.0).spawn(&mut coms);
+    coms.entity(ragdoll.head).insert(Player);
+    ```
+*/
+#[derive(Debug, Clone)]
+pub struct RagdollBuilder {
+    /// Position of the hips
+    pub position: Vec2,
+    /// Height of the whole ragdoll(standing straight), the parts are proportional to it
+    pub height: f32,
+    /// Radius of the limbs
+    pub radius: f32,
+    /// Mass of the whole ragdoll, split between the parts
+    pub mass: f32,
+    /// Layer of the parts, by default they don't collide with each other but do collide with layer 1
+    pub layer: CollisionLayer,
+}
+
+/// An arm or a leg
+#[derive(Debug, Clone, Copy)]
+pub struct Limb {
+    pub upper: Entity,
+    pub lower: Entity,
+}
+
+/// The parts of a spawned ragdoll(arms and legs are left then right)
+#[derive(Debug, Clone)]
+pub struct Ragdoll {
+    pub torso: Entity,
+    pub head: Entity,
+    pub arms: [Limb; 2],
+    pub legs: [Limb; 2],
+    /// The entities holding the joints
+    pub joints: Vec<Entity>,
+}
+
+impl Ragdoll {
+    /// Every body part
+    pub fn parts(&self) -> impl Iterator<Item = Entity> + '_ {
+        [self.torso, self.head]
+            .into_iter()
+            .chain(self.arms.iter().chain(self.legs.iter()).flat_map(|l| [l.upper, l.lower]))
+    }
+}
+
+impl RagdollBuilder {
+    pub fn new(
+        position: Vec2,
+        height: f32,
+    ) -> Self {
+        RagdollBuilder {
+            position,
+            height,
+            radius: 0.04 * height,
+            mass: 10.0,
+            layer: CollisionLayer::new(0b0100_0000, 0b0000_0001),
+        }
+    }
+    pub fn with_radius(
+        mut self,
+        radius: f32,
+    ) -> Self {
+        self.radius = radius;
+        self
+    }
+    pub fn with_mass(
+        mut self,
+        mass: f32,
+    ) -> Self {
+        self.mass = mass;
+        self
+    }
+    pub fn with_layer(
+        mut self,
+        layer: CollisionLayer,
+    ) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    fn spawn_part(
+        &self,
+        coms: &mut Commands,
+        shape: CollisionShape,
+        position: Vec2,
+        mass: f32,
+    ) -> Entity {
+        coms.spawn_bundle(KinematicBundle {
+            shape,
+            collision_layer: self.layer,
+            ..Default::default()
+        })
+        .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(position.x, position.y, 0.0)))
+        .insert(Acc::default())
+        .insert(Mass(mass * self.mass))
+        .id()
+    }
+    /// A standing capsule of the given length(including its round ends)
+    fn segment(
+        &self,
+        length: f32,
+    ) -> CollisionShape {
+        CollisionShape::Capsule(Capsule::new((length - 2.0 * self.radius).max(0.0), self.radius))
+    }
+
+    /// Spawns the parts and joints
+    pub fn spawn(
+        &self,
+        coms: &mut Commands,
+    ) -> Ragdoll {
+        let h = self.height;
+        let (torso_len, head_radius) = (0.35 * h, 0.08 * h);
+        let (upper_arm, lower_arm) = (0.17 * h, 0.16 * h);
+        let (upper_leg, lower_leg) = (0.25 * h, 0.25 * h);
+        let shoulder = torso_len - 0.03 * h;
+
+        let hips = self.position;
+        let mut joints = Vec::new();
+        let mut joint = |coms: &mut Commands, j: RevoluteJoint| joints.push(coms.spawn().insert(j).id());
+
+        let torso = self.spawn_part(coms, self.segment(torso_len), hips + Vec2::Y * 0.5 * torso_len, 0.4);
+        let head = self.spawn_part(
+            coms,
+            CollisionShape::Circle(Circle::new(head_radius)),
+            hips + Vec2::Y * (torso_len + head_radius),
+            0.1,
+        );
+        joint(
+            coms,
+            RevoluteJoint::new(torso, Some(head), Vec2::Y * 0.5 * torso_len, -Vec2::Y * head_radius).with_limits(-0.5, 0.5),
+        );
+
+        // spawns the 2 parts of a limb hanging down from `top`, attached to the torso at `anchor`
+        let mut limb = |coms: &mut Commands, top: Vec2, anchor: Vec2, upper_len: f32, lower_len: f32, limits: [(f32, f32); 2]| {
+            let upper = self.spawn_part(coms, self.segment(upper_len), top - Vec2::Y * 0.5 * upper_len, 0.0625);
+            let lower = self.spawn_part(coms, self.segment(lower_len), top - Vec2::Y * (upper_len + 0.5 * lower_len), 0.0625);
+
+            joint(
+                coms,
+                RevoluteJoint::new(torso, Some(upper), anchor, Vec2::Y * 0.5 * upper_len).with_limits(limits[0].0, limits[0].1),
+            );
+            joint(
+                coms,
+                RevoluteJoint::new(upper, Some(lower), -Vec2::Y * 0.5 * upper_len, Vec2::Y * 0.5 * lower_len)
+                    .with_limits(limits[1].0, limits[1].1),
+            );
+            Limb { upper, lower }
+        };
+
+        let arm_anchor = Vec2::Y * (shoulder - 0.5 * torso_len);
+        let leg_anchor = -Vec2::Y * 0.5 * torso_len;
+        // shoulders go all around, elbows and knees only bend one way
+        let arm_limits = [(-3.0, 3.0), (-2.5, 0.0)];
+        let leg_limits = [(-1.5, 1.5), (0.0, 2.5)];
+
+        let arms = [
+            limb(coms, hips + Vec2::Y * shoulder, arm_anchor, upper_arm, lower_arm, arm_limits),
+            limb(coms, hips + Vec2::Y * shoulder, arm_anchor, upper_arm, lower_arm, arm_limits),
+        ];
+        let legs = [
+            limb(coms, hips, leg_anchor, upper_leg, lower_leg, leg_limits),
+            limb(coms, hips, leg_anchor, upper_leg, lower_leg, leg_limits),
+        ];
+
+        Ragdoll {
+            torso,
+            head,
+            arms,
+            legs,
+            joints,
+        }
+    }
+}
+
+#[cfg(test)]
+mod ragdoll_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+    use bevy::ecs::system::CommandQueue;
+
+    #[test]
+    fn joints_hold_while_falling() {
+        let mut app = PhysicsTestApp::new();
+
+        let mut queue = CommandQueue::default();
+        let ragdoll = RagdollBuilder::new(Vec2::ZERO, 2.0).spawn(&mut Commands::new(&mut queue, &app.app.world));
+        queue.apply(&mut app.app.world);
+        assert_eq!(ragdoll.parts().count(), 10);
+
+        for _ in 0..30 {
+            for e in ragdoll.parts() {
+                app.app.world.get_mut::<Acc>(e).unwrap().add(Vec2::new(0.0, -10.0));
+            }
+            app.step();
+        }
+
+        let world = &app.app.world;
+        let anchor = |e: Entity, a: Vec2| {
+            let t = world.get::<Transform2D>(e).unwrap();
+            t.translation() + t.rot_matrix() * a
+        };
+        for j in ragdoll.joints.iter() {
+            let j = world.get::<RevoluteJoint>(*j).unwrap();
+            let gap = anchor(j.a, j.anchor_a) - anchor(j.b.unwrap(), j.anchor_b);
+            assert!(gap.length() < 0.05, "joint came apart by {}", gap.length());
+        }
+        assert!(world.get::<Transform2D>(ragdoll.torso).unwrap().translation().y < -0.5);
+    }
+}
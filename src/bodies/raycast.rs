@@ -32,6 +32,10 @@ pub struct RayCast {
     /// Whether to try and collide with static objects as well(defaults to true)
     pub collide_with_static: bool,
 
+    /// Whether to try and collide with sensors as well(defaults to false)
+    #[serde(default)]
+    pub collide_with_sensors: bool,
+
     #[serde(skip_serializing, skip_deserializing)]
     pub collision: Option<RayCastCollision>,
 }
@@ -47,8 +51,42 @@ pub struct RayCastCollision {
     pub collision_point: Vec2,
     /// The entity which the ray collides with
     pub entity: Entity,
-    /// Whether the entity is a statcibody or not - will always be `false` if `Ray.collides_with_static` is false
+    /// Whether the entity is a statcibody or not(same as `kind == BodyKind::Static`)
     pub is_static: bool,
+    /// What kind of body the ray hit
+    #[reflect(ignore)]
+    #[serde(default)]
+    pub kind: BodyKind,
+}
+
+/// A body along a ray, with where the ray goes into it and out of it(as fractions of the ray's `cast`)
+///
+/// `entry` is 0 when the ray starts inside the body, and `exit` is 1 when it ends inside it
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit {
+    pub collision: RayCastCollision,
+    pub entry: f32,
+    pub exit: f32,
+}
+
+/// A body swept through by a shape(`collide_shape_sweep`), `fraction` is how far along the sweep(0 to 1) it was first touched
+#[derive(Debug, Clone, Copy)]
+pub struct SweepHit {
+    pub entity: Entity,
+    pub kind: BodyKind,
+    pub fraction: f32,
+}
+
+/// The category of a body, as the physics sees it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BodyKind {
+    /// Neither a `StaticBody` nor a `Sensor`
+    #[default]
+    Kinematic,
+    /// Has a `StaticBody`
+    Static,
+    /// Has a `Sensor`
+    Sensor,
 }
 
 impl RayCast {
@@ -62,6 +100,7 @@ impl RayCast {
             offset: Vec2::ZERO,
             cast,
             collide_with_static: true,
+            collide_with_sensors: false,
             collision: None,
         }
     }
@@ -81,6 +120,14 @@ impl RayCast {
         self.collide_with_static = collide_with_static;
         self
     }
+    /// Whether to try and collide with sensors as well(defaults to false)
+    pub fn with_sensors(
+        mut self,
+        collide_with_sensors: bool,
+    ) -> Self {
+        self.collide_with_sensors = collide_with_sensors;
+        self
+    }
 
     pub fn get_collision(&self) -> Option<RayCastCollision> {
         self.collision
@@ -0,0 +1,363 @@
+use bevy::prelude::*;
+
+use crate::{
+    joints::DistanceJoint,
+    physics_components::{Acc, CollisionLayer, Mass, Transform2D},
+    shapes::{push_out_circle, Capsule, CollisionShape},
+};
+
+use super::{KinematicBundle, StaticBody};
+
+/// What a rope end is attached to
+#[derive(Debug, Clone, Copy)]
+pub enum RopeAttachment {
+    /// A fixed world position
+    World(Vec2),
+    /// A body, with an anchor relative to it
+    Body(Entity, Vec2),
+}
+
+/**
+    # RopeBuilder
+
+    Spawns a rope made of capsule links(kinematic bodies with `Acc`) connected by `DistanceJoint`s.
+
+    The links don't collide with each other(see `RopeBuilder::with_layer`),
+    but they do collide with everything else and get integrated like any other body with `Acc`,
+    so apply gravity to them the same way you do for the rest of your bodies.
+
+    ```ignore
+    let rope = RopeBuilder::new(Vec2::new(0.0, 200.0), Vec2::new(200.0, 200.0), 10)
+        .attach_start(RopeAttachment::World(Vec2::new(0.0, 200.0)))
+        .spawn(&mut coms);
+
+    // hang something from the other end
+    coms.spawn().insert(DistanceJoint::new(rope.end(), Some(crate_entity), 5.0));
+    ```
+*/
+#[derive(Debug, Clone)]
+pub struct RopeBuilder {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub links: usize,
+    pub radius: f32,
+    pub mass: f32,
+    /// Layer of the links, by default they don't collide with each other but do collide with layer 1
+    pub layer: CollisionLayer,
+    pub start_attachment: Option<RopeAttachment>,
+    pub end_attachment: Option<RopeAttachment>,
+}
+
+impl RopeBuilder {
+    pub fn new(
+        start: Vec2,
+        end: Vec2,
+        links: usize,
+    ) -> Self {
+        RopeBuilder {
+            start,
+            end,
+            links: links.max(1),
+            radius: 2.0,
+            mass: 1.0,
+            layer: CollisionLayer::new(0b1000_0000, 0b0000_0001),
+            start_attachment: None,
+            end_attachment: None,
+        }
+    }
+    pub fn with_radius(
+        mut self,
+        radius: f32,
+    ) -> Self {
+        self.radius = radius;
+        self
+    }
+    /// Mass of each link
+    pub fn with_mass(
+        mut self,
+        mass: f32,
+    ) -> Self {
+        self.mass = mass;
+        self
+    }
+    pub fn with_layer(
+        mut self,
+        layer: CollisionLayer,
+    ) -> Self {
+        self.layer = layer;
+        self
+    }
+    pub fn attach_start(
+        mut self,
+        attachment: RopeAttachment,
+    ) -> Self {
+        self.start_attachment = Some(attachment);
+        self
+    }
+    pub fn attach_end(
+        mut self,
+        attachment: RopeAttachment,
+    ) -> Self {
+        self.end_attachment = Some(attachment);
+        self
+    }
+
+    /// Spawns the links and joints
+    pub fn spawn(
+        &self,
+        coms: &mut Commands,
+    ) -> Rope {
+        let dir = (self.end - self.start).normalize_or_zero();
+        let link_len = (self.end - self.start).length() / self.links as f32;
+        // capsules stand on the y axis, so rotate them to the rope's direction
+        let rotation = dir.y.atan2(dir.x) - std::f32::consts::FRAC_PI_2;
+        let half_height = (0.5 * link_len - self.radius).max(0.0);
+
+        let mut links: Vec<Entity> = Vec::with_capacity(self.links);
+        for i in 0..self.links {
+            let pos = self.start + dir * link_len * (i as f32 + 0.5);
+
+            let e = coms
+                .spawn_bundle(KinematicBundle {
+                    shape: CollisionShape::Capsule(Capsule::new(2.0 * half_height, self.radius)),
+                    collision_layer: self.layer,
+                    ..Default::default()
+                })
+                .insert_bundle(TransformBundle::from_transform(
+                    Transform::from_xyz(pos.x, pos.y, 0.0).with_rotation(Quat::from_rotation_z(rotation)),
+                ))
+                .insert(Acc::default())
+                .insert(Mass(self.mass))
+                .insert(RopeLink {
+                    prev: links.last().copied(),
+                    next: None,
+                })
+                .id();
+
+            if let Some(&prev) = links.last() {
+                coms.entity(prev).insert(RopeLink {
+                    prev: links.len().checked_sub(2).map(|i| links[i]),
+                    next: Some(e),
+                });
+                coms.spawn().insert(DistanceJoint::new(prev, Some(e), link_len));
+            }
+            links.push(e);
+        }
+
+        let rope = Rope { links };
+
+        // attach the ends by half a link, so the links stay evenly spaced
+        for (link, att) in [(rope.start(), self.start_attachment), (rope.end(), self.end_attachment)] {
+            let joint = match att {
+                Some(RopeAttachment::World(p)) => DistanceJoint::new(link, None, 0.5 * link_len).with_anchors(Vec2::ZERO, p),
+                Some(RopeAttachment::Body(b, anchor)) => DistanceJoint::new(link, Some(b), 0.5 * link_len).with_anchors(Vec2::ZERO, anchor),
+                None => continue,
+            };
+            coms.spawn().insert(joint);
+        }
+
+        rope
+    }
+}
+
+/// The links of a spawned rope, from start to end
+#[derive(Debug, Clone)]
+pub struct Rope {
+    pub links: Vec<Entity>,
+}
+
+impl Rope {
+    /// The first link
+    pub fn start(&self) -> Entity {
+        self.links[0]
+    }
+    /// The last link
+    pub fn end(&self) -> Entity {
+        self.links[self.links.len() - 1]
+    }
+}
+
+/// Component of a rope link, pointing to its neighbours
+#[derive(Component, Debug, Clone, Copy)]
+pub struct RopeLink {
+    pub prev: Option<Entity>,
+    pub next: Option<Entity>,
+}
+
+/// Rotates each rope link to face its neighbours(links have no angular velocity of their own)
+pub fn rope_align_system(
+    links: Query<(Entity, &RopeLink)>,
+    mut transforms: Query<&mut Transform2D>,
+) {
+    for (e, link) in links.iter() {
+        let pos = |e: Option<Entity>| e.and_then(|e| transforms.get(e).ok()).map(|t| t.translation());
+
+        let center = match pos(Some(e)) {
+            Some(p) => p,
+            None => continue,
+        };
+        let dir = match (pos(link.prev), pos(link.next)) {
+            (Some(p), Some(n)) => n - p,
+            (Some(p), None) => center - p,
+            (None, Some(n)) => n - center,
+            (None, None) => continue,
+        };
+        if dir.length_squared() < f32::EPSILON {
+            continue;
+        }
+
+        if let Ok(mut t) = transforms.get_mut(e) {
+            t.set_rotation(dir.y.atan2(dir.x) - std::f32::consts::FRAC_PI_2);
+        }
+    }
+}
+
+/**
+    # VerletRope
+
+    A purely visual rope, simulated as a chain of points using verlet integration.
+
+    It doesn't push anything, but the points do collide with static bodies(as circles with `radius`).
+    The points are in world space, so the entity holding the component doesn't need a transform.
+*/
+#[derive(Component, Debug, Clone)]
+pub struct VerletRope {
+    /// Positions of the points(world space), from start to end
+    pub points: Vec<Vec2>,
+    previous: Vec<Vec2>,
+    /// Distance between consecutive points
+    pub segment_length: f32,
+    /// Collision radius of each point
+    pub radius: f32,
+    pub gravity: Vec2,
+    /// How much velocity is kept each step(1.0 keeps everything)
+    pub damping: f32,
+    /// How many times the segment lengths are enforced each step
+    pub iterations: u32,
+    /// Collision layer of the points(only checked against static bodies)
+    pub layer: CollisionLayer,
+    pub start_attachment: Option<RopeAttachment>,
+    pub end_attachment: Option<RopeAttachment>,
+}
+
+impl VerletRope {
+    pub fn new(
+        start: Vec2,
+        end: Vec2,
+        segments: usize,
+    ) -> Self {
+        let segments = segments.max(1);
+        let points: Vec<Vec2> = (0..=segments)
+            .map(|i| start.lerp(end, i as f32 / segments as f32))
+            .collect();
+
+        VerletRope {
+            previous: points.clone(),
+            points,
+            segment_length: (end - start).length() / segments as f32,
+            radius: 1.0,
+            gravity: Vec2::ZERO,
+            damping: 0.99,
+            iterations: 10,
+            layer: CollisionLayer::default(),
+            start_attachment: None,
+            end_attachment: None,
+        }
+    }
+    pub fn with_radius(
+        mut self,
+        radius: f32,
+    ) -> Self {
+        self.radius = radius;
+        self
+    }
+    pub fn with_gravity(
+        mut self,
+        gravity: Vec2,
+    ) -> Self {
+        self.gravity = gravity;
+        self
+    }
+    pub fn attach_start(
+        mut self,
+        attachment: RopeAttachment,
+    ) -> Self {
+        self.start_attachment = Some(attachment);
+        self
+    }
+    pub fn attach_end(
+        mut self,
+        attachment: RopeAttachment,
+    ) -> Self {
+        self.end_attachment = Some(attachment);
+        self
+    }
+}
+
+pub fn verlet_rope_system(
+    time: Res<Time>,
+    mut ropes: Query<&mut VerletRope>,
+    bodies: Query<&Transform2D>,
+    statics: Query<(&CollisionShape, &Transform2D, &CollisionLayer), With<StaticBody>>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    let attachment = |att: Option<RopeAttachment>| match att {
+        Some(RopeAttachment::World(p)) => Some(p),
+        Some(RopeAttachment::Body(e, anchor)) => bodies
+            .get(e)
+            .ok()
+            .map(|t| t.translation() + t.rot_matrix() * anchor),
+        None => None,
+    };
+
+    for mut rope in ropes.iter_mut() {
+        let rope = &mut *rope;
+        let last = rope.points.len() - 1;
+
+        // integrate
+        for (p, prev) in rope.points.iter_mut().zip(rope.previous.iter_mut()) {
+            let v = (*p - *prev) * rope.damping;
+            *prev = *p;
+            *p += v + rope.gravity * dt * dt;
+        }
+
+        let start = attachment(rope.start_attachment);
+        let end = attachment(rope.end_attachment);
+
+        for _ in 0..rope.iterations {
+            // keep the segment lengths
+            for i in 0..last {
+                let d = rope.points[i + 1] - rope.points[i];
+                let len = d.length();
+                if len < f32::EPSILON {
+                    continue;
+                }
+                let correction = d * (0.5 * (len - rope.segment_length) / len);
+                rope.points[i] += correction;
+                rope.points[i + 1] -= correction;
+            }
+
+            if let Some(p) = start {
+                rope.points[0] = p;
+            }
+            if let Some(p) = end {
+                rope.points[last] = p;
+            }
+
+            // push out of static bodies
+            for p in rope.points.iter_mut() {
+                let layer = rope.layer;
+                let shapes = statics
+                    .iter()
+                    .filter(|(_, _, l)| l.overlap(&layer))
+                    .map(|(s, t, _)| (s, t));
+
+                *p = push_out_circle(*p, rope.radius, shapes);
+            }
+        }
+    }
+}
@@ -1,7 +1,15 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::Duration};
 use serde::{Deserialize, Serialize};
 
-use crate::{physics_components::CollisionLayer, prelude::CollisionShape};
+use crate::{
+    bodies::{PhysicsDisabled, StaticBody},
+    lod::BodyShapes,
+    physics_components::{CollisionLayer, Transform2D},
+    plugin::CollisionSettings,
+    prelude::CollisionShape,
+    shapes::collide,
+    world_wrap,
+};
 
 #[derive(Bundle, Default)]
 pub struct SensorBundle {
@@ -17,22 +25,595 @@ pub struct SensorBundle {
     and store their `Entity` in the `Sensor.bodies` Vec.
 
     NOTE: "kinematic entities" qualifies as `Without<StaticBody>, Without<Sensor>`
+
+    Add a `SensorArc` to only detect bodies in some direction.
+
+    A `SensorTriggeredEvent` is sent whenever a body enters the sensor(and a `SensorExitedEvent` once it leaves),
+    and `mode` can make the sensor trigger only once(checkpoints, pickups), ignore bodies re-entering it for a while,
+    or keep its bodies until they actually leave(`SensorMode::Persistent`).
+
+    `bodies` is cleared and filled again in `stage::COLLISION_DETECTION`, so systems in that stage should run
+    `.after(PhysicsSystem::Collision)`, systems in any other stage see the bodies of the last physics step.
+
+    Sensors made `with_parts` also fill `parts` with which parts of the bodies with a `CollisionShape::Multiple`
+    overlap them(which segment of the boss got hit), it costs a collision check per part so it is off by default.
 */
 #[derive(Debug, Clone, Serialize, Deserialize, Reflect, Component)]
 pub struct Sensor {
     /// Holds the entities which overlap with the sensor.
     pub bodies: Vec<Entity>,
+    #[reflect(ignore)]
+    pub mode: SensorMode,
+    /// Whether a `SensorMode::Once` sensor already triggered, set it back to `false` to re-arm the sensor
+    pub triggered: bool,
+    /// Whether to fill `parts`
+    pub report_parts: bool,
+    /// The parts(indices in the `CollisionShape::Multiple`) of the bodies in `bodies` which overlap the sensor,
+    /// bodies with other shapes have no entries
+    #[reflect(ignore)]
+    pub parts: Vec<(Entity, usize)>,
+    /// Bodies which were in the sensor on the last frame
+    #[serde(skip)]
+    #[reflect(ignore)]
+    inside: Vec<Entity>,
+    /// When each body last triggered the sensor(in seconds since startup), for `SensorMode::Cooldown`
+    #[serde(skip)]
+    #[reflect(ignore)]
+    last_triggered: Vec<(Entity, f64)>,
 }
 
 impl Sensor {
     pub fn new() -> Self {
         Sensor {
             bodies: Vec::with_capacity(5),
+            mode: SensorMode::Always,
+            triggered: false,
+            report_parts: false,
+            parts: Vec::new(),
+            inside: Vec::new(),
+            last_triggered: Vec::new(),
         }
     }
+    /// Sensor which deactivates after the first body enters it
+    pub fn once() -> Self {
+        Sensor::new().with_mode(SensorMode::Once)
+    }
+    /// Sensor which ignores a body re-entering it for `cooldown` after it last triggered the sensor
+    pub fn cooldown(cooldown: Duration) -> Self {
+        Sensor::new().with_mode(SensorMode::Cooldown(cooldown))
+    }
+    pub fn with_mode(
+        mut self,
+        mode: SensorMode,
+    ) -> Self {
+        self.mode = mode;
+        self
+    }
+    /// Fills `parts` as well
+    pub fn with_parts(mut self) -> Self {
+        self.report_parts = true;
+        self
+    }
+    /// The parts of `body`'s `CollisionShape::Multiple` which overlap the sensor(needs `with_parts`)
+    pub fn parts_of(
+        &self,
+        body: Entity,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.parts.iter().filter(move |(e, _)| *e == body).map(|(_, i)| *i)
+    }
 }
 impl Default for Sensor {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// When a `Sensor` reports the bodies overlapping it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SensorMode {
+    /// Every body overlapping it, every frame
+    #[default]
+    Always,
+    /// Until the first body enters it, after which it stays empty(until `Sensor::triggered` is reset)
+    Once,
+    /// Bodies entering it within the duration of last triggering it are ignored(until the duration passes)
+    Cooldown(Duration),
+    /// Bodies stay in it until they no longer overlap it, even on frames their pair wasn't checked
+    /// (skipped by the frame budget and such), so it doesn't flicker
+    Persistent,
+}
+
+/// Sent when a body enters a `Sensor`(which accepted it according to its `SensorMode`)
+#[derive(Debug, Clone, Copy)]
+pub struct SensorTriggeredEvent {
+    pub sensor: Entity,
+    pub body: Entity,
+}
+
+/// Sent when a body which was in a `Sensor` no longer is(it left, or the sensor stopped reporting it according to its `SensorMode`)
+#[derive(Debug, Clone, Copy)]
+pub struct SensorExitedEvent {
+    pub sensor: Entity,
+    pub body: Entity,
+}
+
+/// Applies the `SensorMode` of each sensor, runs right after the sensors are filled
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn sensor_mode_system(
+    time: Res<Time>,
+    settings: Res<CollisionSettings>,
+    shapes: BodyShapes,
+    transforms: Query<&Transform2D>,
+    layers: Query<&CollisionLayer>,
+    disabled: Query<(), With<PhysicsDisabled>>,
+    mut sensors: Query<(Entity, &mut Sensor, Option<&SensorArc>), Without<PhysicsDisabled>>,
+    mut events: EventWriter<SensorTriggeredEvent>,
+    mut exits: EventWriter<SensorExitedEvent>,
+) {
+    let now = time.seconds_since_startup();
+
+    for (se, mut sensor, arc) in sensors.iter_mut() {
+        let sensor = &mut *sensor;
+
+        match sensor.mode {
+            SensorMode::Always => {},
+            SensorMode::Persistent => {
+                // bodies from the last frame which weren't found again are checked directly(a body whose layer changed leaves)
+                if let (Ok(ss), Ok(ts)) = (shapes.get(se), transforms.get(se)) {
+                    let sl = layers.get(se).copied().unwrap_or(CollisionLayer::ZERO);
+                    for e in sensor.inside.iter() {
+                        if sensor.bodies.contains(e) || disabled.contains(*e) {
+                            continue;
+                        }
+                        if !layers.get(*e).map_or(false, |l| l.overlap(&sl)) {
+                            continue;
+                        }
+                        let (sb, tb) = match (shapes.get(*e), transforms.get(*e)) {
+                            (Ok(s), Ok(t)) => (s, t),
+                            _ => continue,
+                        };
+                        let tb = world_wrap::nearest_transform(settings.world_wrap, ts, tb);
+                        let inside = collide(sb, &tb, ss, ts).is_some()
+                            && !matches!(arc, Some(a) if !a.contains(ts, tb.translation()));
+                        if inside {
+                            sensor.bodies.push(*e);
+                        }
+                    }
+                }
+            },
+            SensorMode::Once => {
+                if sensor.triggered {
+                    sensor.bodies.clear();
+                }
+                else if !sensor.bodies.is_empty() {
+                    sensor.triggered = true;
+                }
+            },
+            SensorMode::Cooldown(cooldown) => {
+                let cooldown = cooldown.as_secs_f64();
+                sensor.last_triggered.retain(|(_, t)| now - *t < cooldown);
+
+                let (inside, last) = (&sensor.inside, &mut sensor.last_triggered);
+                sensor.bodies.retain(|e| {
+                    if inside.contains(e) {
+                        return true;
+                    }
+                    if last.iter().any(|(l, _)| l == e) {
+                        return false;
+                    }
+                    last.push((*e, now));
+                    true
+                });
+            },
+        }
+
+        if sensor.report_parts {
+            sensor.parts.clear();
+            if let (Ok(ss), Ok(ts)) = (shapes.get(se), transforms.get(se)) {
+                for e in sensor.bodies.iter() {
+                    if let (Ok(sb), Ok(tb)) = (shapes.get(*e), transforms.get(*e)) {
+                        let tb = world_wrap::nearest_transform(settings.world_wrap, ts, tb);
+                        sensor.parts.extend(sb.overlapping_parts(&tb, ss, ts).into_iter().map(|i| (*e, i)));
+                    }
+                }
+            }
+        }
+
+        for e in sensor.bodies.iter().filter(|e| !sensor.inside.contains(e)) {
+            events.send(SensorTriggeredEvent { sensor: se, body: *e });
+        }
+        for e in sensor.inside.iter().filter(|e| !sensor.bodies.contains(e)) {
+            exits.send(SensorExitedEvent { sensor: se, body: *e });
+        }
+        sensor.inside.clone_from(&sensor.bodies);
+    }
+}
+
+/**
+    # SensorOverlap
+
+    Put next to a `Sensor` to estimate how much of each body in it is actually inside(zone capture progress and such),
+    by sampling a `resolution x resolution` grid of points over each body.
+
+    ```ignore
+    coms.spawn_bundle(SensorBundle::default())
+        .insert(SensorOverlap::new(8));
+    ```
+*/
+#[derive(Debug, Clone, Component)]
+pub struct SensorOverlap {
+    /// How many points are sampled along each axis of a body
+    pub resolution: u32,
+    /// Fraction(`0.0..=1.0`) of the area of each body in `Sensor.bodies` which is inside the sensor
+    pub fractions: Vec<(Entity, f32)>,
+}
+
+impl SensorOverlap {
+    pub fn new(resolution: u32) -> Self {
+        SensorOverlap {
+            resolution: resolution.max(1),
+            fractions: Vec::with_capacity(5),
+        }
+    }
+    /// Fraction of `body` inside the sensor, `None` if it isn't in the sensor
+    pub fn fraction(
+        &self,
+        body: Entity,
+    ) -> Option<f32> {
+        self.fractions.iter().find(|(e, _)| *e == body).map(|(_, f)| *f)
+    }
+}
+impl Default for SensorOverlap {
+    fn default() -> Self {
+        Self::new(8)
+    }
+}
+
+/// Approximate fraction of `body`'s area which is inside `sensor`, sampled over a `resolution x resolution` grid
+pub fn overlap_fraction(
+    body: &CollisionShape,
+    body_trans: &Transform2D,
+    sensor: &CollisionShape,
+    sensor_trans: &Transform2D,
+    resolution: u32,
+) -> f32 {
+    let (min, max) = body.aabb(body_trans).min_max();
+    let step = (max - min) / resolution as f32;
+
+    let (mut in_body, mut in_both) = (0u32, 0u32);
+    for x in 0..resolution {
+        for y in 0..resolution {
+            // the center of each cell of the grid
+            let p = min + step * Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            if body.contains_point(body_trans, p) {
+                in_body += 1;
+                if sensor.contains_point(sensor_trans, p) {
+                    in_both += 1;
+                }
+            }
+        }
+    }
+
+    if in_body == 0 {
+        0.0
+    }
+    else {
+        in_both as f32 / in_body as f32
+    }
+}
+
+/// Fills the `SensorOverlap`s, runs after the sensors are filled
+pub fn sensor_overlap_system(
+    settings: Res<CollisionSettings>,
+    shapes: BodyShapes,
+    transforms: Query<&Transform2D>,
+    mut sensors: Query<(Entity, &Sensor, &mut SensorOverlap), Without<PhysicsDisabled>>,
+) {
+    for (se, sensor, mut overlap) in sensors.iter_mut() {
+        overlap.fractions.clear();
+
+        let (ss, ts) = match (shapes.get(se), transforms.get(se)) {
+            (Ok(s), Ok(t)) => (s, t),
+            _ => continue,
+        };
+        for e in sensor.bodies.iter() {
+            let (sb, tb) = match (shapes.get(*e), transforms.get(*e)) {
+                (Ok(s), Ok(t)) => (s, t),
+                _ => continue,
+            };
+            let tb = world_wrap::nearest_transform(settings.world_wrap, ts, tb);
+            let fraction = overlap_fraction(sb, &tb, ss, ts, overlap.resolution);
+            overlap.fractions.push((*e, fraction));
+        }
+    }
+}
+
+/**
+    # SensorArc
+
+    Put next to a `Sensor` to only detect bodies whose center is within an angle range,
+    relative to the direction the sensor is facing(its local +X axis, rotated with it).
+
+    Angles are in radians between -PI and PI, positive is counter clockwise.
+
+    ```ignore
+    // a backstab zone behind the enemy(which faces +X)
+    coms.spawn_bundle(SensorBundle::default())
+        .insert(SensorArc::behind(0.5));
+    ```
+*/
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Reflect, Component)]
+pub struct SensorArc {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl SensorArc {
+    pub fn new(
+        min: f32,
+        max: f32,
+    ) -> Self {
+        SensorArc { min, max }
+    }
+    /// Everything within `half_angle` of the facing direction
+    pub fn facing(half_angle: f32) -> Self {
+        SensorArc::new(-half_angle, half_angle)
+    }
+    /// Everything within `half_angle` of the opposite of the facing direction
+    pub fn behind(half_angle: f32) -> Self {
+        SensorArc::new(std::f32::consts::PI - half_angle, half_angle - std::f32::consts::PI)
+    }
+
+    /// Whether `point` is within the arc of a sensor at `sensor`,
+    /// ranges going over PI(`min > max`) wrap around the back
+    pub fn contains(
+        &self,
+        sensor: &Transform2D,
+        point: Vec2,
+    ) -> bool {
+        let local = sensor.rot_matrix().transpose() * (point - sensor.translation());
+        let bearing = local.y.atan2(local.x);
+
+        if self.min <= self.max {
+            (self.min..=self.max).contains(&bearing)
+        }
+        else {
+            bearing >= self.min || bearing <= self.max
+        }
+    }
+}
+
+/**
+    # SensorShape
+
+    An extra shape on a body which detects the kinematic bodies around it(like a `Sensor` would),
+    without making the body itself a sensor - a detection radius around a solid enemy,
+    without a child entity.
+
+    The body still collides using its `CollisionShape`, while `SensorShape.bodies` holds the bodies overlapping `shape`
+    (never the body itself), using the body's `CollisionLayer`(and its `SensorArc`, if it has one).
+
+    ```ignore
+    coms.spawn_bundle(KinematicBundle::default())
+        .insert(SensorShape::new(CollisionShape::Circle(Circle::new(200.0))));
+    ```
+*/
+#[derive(Component)]
+pub struct SensorShape {
+    pub shape: CollisionShape,
+    /// Holds the entities which overlap with `shape`
+    pub bodies: Vec<Entity>,
+}
+
+impl SensorShape {
+    pub fn new(shape: CollisionShape) -> Self {
+        SensorShape {
+            shape,
+            bodies: Vec::with_capacity(5),
+        }
+    }
+}
+
+/// Fills the `SensorShape`s, runs after the collisions are solved(disabled bodies keep their bodies)
+#[allow(clippy::type_complexity)]
+pub fn sensor_shape_system(
+    settings: Res<CollisionSettings>,
+    shapes: BodyShapes,
+    mut sensors: Query<(Entity, &mut SensorShape, &Transform2D, &CollisionLayer, Option<&SensorArc>), Without<PhysicsDisabled>>,
+    kins: Query<(Entity, &Transform2D, &CollisionLayer), (Without<StaticBody>, Without<Sensor>, Without<PhysicsDisabled>)>,
+) {
+    for (se, mut sensor, ts, ls, arc) in sensors.iter_mut() {
+        sensor.bodies.clear();
+        let aabb = sensor.shape.aabb(ts);
+
+        for (ek, tk, lk) in kins.iter() {
+            if ek == se || !ls.overlap(lk) {
+                continue;
+            }
+            let sk = match shapes.get(ek) {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+
+            let tk = world_wrap::nearest_transform(settings.world_wrap, ts, tk);
+            if !aabb.collides(&sk.aabb(&tk)) || collide(sk, &tk, &sensor.shape, ts).is_none() {
+                continue;
+            }
+            if matches!(arc, Some(a) if !a.contains(ts, tk.translation())) {
+                continue;
+            }
+            sensor.bodies.push(ek);
+        }
+    }
+}
+
+#[cfg(test)]
+mod sensor_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn arc_filters_by_bearing() {
+        let mut app = PhysicsTestApp::new();
+
+        let sensor = app.spawn_at(Vec2::ZERO, SensorBundle {
+            shape: CollisionShape::Circle(Circle::new(5.0)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(sensor).insert(SensorArc::facing(0.5));
+        let ahead = app.spawn_at(Vec2::new(3.0, 0.5), KinematicBundle::default());
+        app.spawn_at(Vec2::new(-3.0, 0.0), KinematicBundle::default());
+        app.spawn_at(Vec2::new(0.0, 3.0), KinematicBundle::default());
+
+        app.step();
+
+        assert_eq!(app.app.world.get::<Sensor>(sensor).unwrap().bodies, vec![ahead]);
+        assert!(SensorArc::behind(0.5).contains(&Transform2D::default(), Vec2::new(-3.0, 0.1)));
+    }
+
+    #[test]
+    fn sensor_shape_on_a_body() {
+        let mut app = PhysicsTestApp::new();
+
+        let enemy = app.spawn_at(Vec2::ZERO, KinematicBundle::default());
+        app.app.world.entity_mut(enemy).insert(SensorShape::new(CollisionShape::Circle(Circle::new(5.0))));
+        let near = app.spawn_at(Vec2::new(4.0, 0.0), KinematicBundle::default());
+        app.spawn_at(Vec2::new(10.0, 0.0), KinematicBundle::default());
+
+        app.step_n(2);
+
+        assert_eq!(app.app.world.get::<SensorShape>(enemy).unwrap().bodies, vec![near]);
+        // the body itself still collides with its own shape
+        app.assert_position(near, Vec2::new(4.0, 0.0), 0.001);
+    }
+
+    #[test]
+    fn once_and_cooldown() {
+        let mut app = PhysicsTestApp::new();
+
+        let once = app.spawn_at(Vec2::ZERO, SensorBundle {
+            sensor: Sensor::once(),
+            ..Default::default()
+        });
+        let cooldown = app.spawn_at(Vec2::new(20.0, 0.0), SensorBundle {
+            sensor: Sensor::cooldown(Duration::from_secs(1)),
+            ..Default::default()
+        });
+        let body = app.spawn_at(Vec2::new(0.5, 0.0), KinematicBundle::default());
+
+        app.step();
+        assert_eq!(app.app.world.get::<Sensor>(once).unwrap().bodies, vec![body]);
+        let events = app.app.world.resource::<Events<SensorTriggeredEvent>>();
+        assert_eq!(events.get_reader().iter(events).filter(|ev| ev.sensor == once).count(), 1);
+
+        app.step();
+        assert!(app.app.world.get::<Sensor>(once).unwrap().bodies.is_empty());
+
+        // enter the other sensor, leave and come right back
+        let enter = |app: &mut PhysicsTestApp, x: f32| {
+            app.app.world.entity_mut(body).insert(Teleport::to(Vec2::new(x, 0.0)));
+            app.step();
+            app.app.world.get::<Sensor>(cooldown).unwrap().bodies.clone()
+        };
+        assert_eq!(enter(&mut app, 20.5), vec![body]);
+        assert!(enter(&mut app, 10.0).is_empty());
+        assert!(enter(&mut app, 20.5).is_empty());
+
+        // and again once the cooldown is over
+        assert!(enter(&mut app, 10.0).is_empty());
+        app.step_n(60);
+        assert_eq!(enter(&mut app, 20.5), vec![body]);
+    }
+
+    #[test]
+    fn persistent_until_exit() {
+        let mut app = PhysicsTestApp::new();
+        // a zero budget only checks a single sensor pair each frame, so a plain sensor flickers
+        app.app.insert_resource(CollisionSettings {
+            frame_budget: Some(Duration::ZERO),
+            ..Default::default()
+        });
+
+        let persistent = app.spawn_at(Vec2::ZERO, SensorBundle {
+            sensor: Sensor::new().with_mode(SensorMode::Persistent),
+            shape: CollisionShape::Square(Square::size(Vec2::splat(10.0))),
+            ..Default::default()
+        });
+        let a = app.spawn_at(Vec2::new(-2.0, 0.0), KinematicBundle::default());
+        let b = app.spawn_at(Vec2::new(2.0, 0.0), KinematicBundle::default());
+
+        app.step_n(5);
+        for _ in 0..5 {
+            app.step();
+            let bodies = &app.app.world.get::<Sensor>(persistent).unwrap().bodies;
+            assert!(bodies.contains(&a) && bodies.contains(&b));
+        }
+
+        app.app.world.entity_mut(a).insert(Teleport::to(Vec2::new(20.0, 0.0)));
+        app.step_n(2);
+        assert_eq!(app.app.world.get::<Sensor>(persistent).unwrap().bodies, vec![b]);
+        let exits = app.app.world.resource::<Events<SensorExitedEvent>>();
+        assert!(exits.get_reader().iter(exits).any(|ev| ev.sensor == persistent && ev.body == a));
+
+        // leaves once it no longer collides with the sensor, even though it is still inside
+        app.app.world.entity_mut(b).insert(CollisionLayer::new(0b10, 0b10));
+        app.step_n(2);
+        assert!(app.app.world.get::<Sensor>(persistent).unwrap().bodies.is_empty());
+    }
+
+    #[test]
+    fn overlap_fractions() {
+        let mut app = PhysicsTestApp::new();
+
+        let sensor = app.spawn_at(Vec2::ZERO, SensorBundle {
+            shape: CollisionShape::Square(Square::new(Vec2::new(3.0, 1.0))),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(sensor).insert(SensorOverlap::new(8));
+        // half inside
+        let half = app.spawn_at(Vec2::new(3.0, 0.0), KinematicBundle::default());
+        // fully inside
+        let inside = app.spawn_at(Vec2::new(-1.0, 0.0), KinematicBundle {
+            shape: CollisionShape::Square(Square::new(Vec2::splat(0.5))),
+            ..Default::default()
+        });
+
+        app.step_n(2);
+
+        let overlap = app.app.world.get::<SensorOverlap>(sensor).unwrap();
+        assert!((overlap.fraction(half).unwrap() - 0.5).abs() < 0.001);
+        assert!((overlap.fraction(inside).unwrap() - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn reports_the_parts() {
+        let mut app = PhysicsTestApp::new();
+        // a boss made of 3 segments
+        let boss = app.spawn_at(Vec2::ZERO, KinematicBundle {
+            shape: CollisionShape::Multiple(vec![
+                CollisionShape::Circle(Circle::new(1.0).with_offset(Vec2::new(-4.0, 0.0))),
+                CollisionShape::Circle(Circle::new(1.0)),
+                CollisionShape::Circle(Circle::new(1.0).with_offset(Vec2::new(4.0, 0.0))),
+            ]),
+            ..Default::default()
+        });
+        let sword = app.spawn_at(Vec2::new(4.0, 0.5), SensorBundle {
+            sensor: Sensor::new().with_parts(),
+            shape: CollisionShape::Square(Square::new(Vec2::splat(0.5))),
+            ..Default::default()
+        });
+        let spikes = app.spawn_at(Vec2::new(-4.0, -1.5), StaticBundle {
+            shape: CollisionShape::Square(Square::new(Vec2::new(0.5, 0.75))),
+            ..Default::default()
+        });
+        app.step();
+
+        let sensor = app.app.world.get::<Sensor>(sword).unwrap();
+        assert_eq!(sensor.bodies, vec![boss]);
+        assert_eq!(sensor.parts_of(boss).collect::<Vec<_>>(), vec![2]);
+
+        let hit = app.collisions.iter().find(|c| c.other(boss) == Some(spikes)).expect("no collision with the spikes");
+        assert_eq!(hit.part_for(boss), Some(0));
+        assert_eq!(hit.part_for(spikes), None);
+    }
+}
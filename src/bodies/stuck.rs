@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+
+use crate::{contacts::ContactsPerEntity, physics_components::Transform2D};
+
+use super::PhysicsDisabled;
+
+/**
+    # StuckDetector
+
+    Add it to a body to find out when it keeps trying to move but the contacts cancel(almost) all of it,
+    like a character snagged on the seam between 2 floor tiles, or an AI walking into a wall.
+
+    Each frame the movement the body asked for(integration, joints and whatever moved it during `Update`)
+    is compared to where it ended up after the collision solve,
+    once it made less than `min_progress` of it for `frames` frames in a row a `StuckEvent` is sent
+    (and again every `frames` frames while it stays stuck).
+
+    With `jitter` the body is also nudged that far away from its contacts(and a bit to the side) whenever it gets stuck,
+    which is usually enough to get it over a seam.
+
+    Frames in which the body asked to move slower than `min_speed` don't count either way,
+    keep it above the speed gravity adds in a single frame so resting bodies aren't seen as stuck.
+*/
+#[derive(Component, Debug, Clone)]
+pub struct StuckDetector {
+    /// Frames in a row the body has to be stuck for
+    pub frames: u32,
+    /// Requested speed(units per second) under which a frame is ignored
+    pub min_speed: f32,
+    /// Fraction of the requested movement the body has to make to not count as stuck
+    pub min_progress: f32,
+    /// How far to nudge the body once it is stuck, `None` to only send the event
+    pub jitter: Option<f32>,
+    /// How many frames in a row the body has been stuck for
+    pub stuck_frames: u32,
+    last_position: Option<Vec2>,
+    requested: Vec2,
+    nudges: u32,
+}
+
+impl Default for StuckDetector {
+    fn default() -> Self {
+        StuckDetector {
+            frames: 30,
+            min_speed: 1.0,
+            min_progress: 0.1,
+            jitter: None,
+            stuck_frames: 0,
+            last_position: None,
+            requested: Vec2::ZERO,
+            nudges: 0,
+        }
+    }
+}
+
+impl StuckDetector {
+    pub fn new(frames: u32) -> Self {
+        StuckDetector {
+            frames,
+            ..Default::default()
+        }
+    }
+    pub fn with_min_speed(
+        mut self,
+        min_speed: f32,
+    ) -> Self {
+        self.min_speed = min_speed;
+        self
+    }
+    pub fn with_jitter(
+        mut self,
+        jitter: f32,
+    ) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+    /// Whether the body has been stuck for at least `frames` frames
+    pub fn is_stuck(&self) -> bool {
+        self.stuck_frames >= self.frames.max(1)
+    }
+}
+
+/// Sent when a body with a `StuckDetector` got stuck
+#[derive(Debug, Clone, Copy)]
+pub struct StuckEvent {
+    pub entity: Entity,
+    /// Frames in a row it has been stuck for
+    pub frames: u32,
+    /// The movement it asked for in the last frame
+    pub requested: Vec2,
+}
+
+/// Records how far each `StuckDetector` body asked to move this frame, runs at the end of the physics step
+pub fn stuck_request_system(mut q: Query<(&Transform2D, &mut StuckDetector), Without<PhysicsDisabled>>) {
+    for (t, mut d) in q.iter_mut() {
+        d.requested = t.translation() - d.last_position.unwrap_or_else(|| t.translation());
+    }
+}
+
+/// Compares the requested movement with the solved one, sends the `StuckEvent`s and nudges the stuck bodies,
+/// runs right after the collision solve
+pub fn stuck_system(
+    time: Res<Time>,
+    contacts: Res<ContactsPerEntity>,
+    mut q: Query<(Entity, &mut Transform2D, &mut StuckDetector), Without<PhysicsDisabled>>,
+    mut writer: EventWriter<StuckEvent>,
+) {
+    let dt = time.delta_seconds();
+
+    for (e, mut t, mut d) in q.iter_mut() {
+        let position = t.translation();
+        let last = d.last_position.replace(position);
+        let requested = d.requested;
+        let last = match last {
+            Some(l) if dt > 0.0 && requested.length() >= d.min_speed * dt => l,
+            _ => continue,
+        };
+
+        let progress = (position - last).dot(requested) / requested.length_squared();
+        if progress >= d.min_progress {
+            d.stuck_frames = 0;
+            continue;
+        }
+        d.stuck_frames += 1;
+        if d.stuck_frames % d.frames.max(1) != 0 {
+            continue;
+        }
+
+        writer.send(StuckEvent {
+            entity: e,
+            frames: d.stuck_frames,
+            requested,
+        });
+        if let Some(jitter) = d.jitter {
+            // away from the contacts, and to alternating sides of the requested movement
+            let away = contacts.get(e).iter().fold(Vec2::ZERO, |sum, c| sum + c.normal).normalize_or_zero();
+            let side = if d.nudges % 2 == 0 { 0.5 } else { -0.5 };
+            d.nudges += 1;
+
+            let nudge = (away + requested.normalize_or_zero().perp() * side).normalize_or_zero() * jitter;
+            t.add_translation(nudge);
+            d.last_position = Some(position + nudge);
+        }
+    }
+}
+
+#[cfg(test)]
+mod stuck_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn walking_into_a_wall() {
+        let mut app = PhysicsTestApp::new();
+        app.spawn_at(Vec2::new(2.0, 0.0), StaticBundle::default());
+
+        let body = |app: &mut PhysicsTestApp, position: Vec2, detector: StuckDetector| {
+            let e = app.spawn_at(position, KinematicBundle::default());
+            app.app.world.entity_mut(e).insert_bundle((Acc::default(), detector));
+            e
+        };
+        let stuck = body(&mut app, Vec2::ZERO, StuckDetector::new(5));
+        let nudged = body(&mut app, Vec2::new(0.0, -10.0), StuckDetector::new(5).with_jitter(0.5));
+        app.spawn_at(Vec2::new(2.0, -10.0), StaticBundle::default());
+        let free = body(&mut app, Vec2::new(0.0, 10.0), StuckDetector::new(5));
+
+        let mut reader = app.app.world.resource::<Events<StuckEvent>>().get_reader();
+        let mut events = Vec::new();
+        for _ in 0..12 {
+            // keep walking right(the clamp stops them every frame)
+            for e in [stuck, nudged, free] {
+                app.app.world.get_mut::<Vel>(e).unwrap().0 = Vec2::new(10.0, 0.0);
+            }
+            app.step();
+            events.extend(reader.iter(app.app.world.resource::<Events<StuckEvent>>()).map(|ev| ev.entity));
+        }
+
+        assert_eq!(events.iter().filter(|e| **e == stuck).count(), 2);
+        assert!(app.app.world.get::<StuckDetector>(stuck).unwrap().is_stuck());
+        assert!(events.contains(&nudged));
+        assert!(!events.contains(&free));
+        // nudged off the wall's face, to the side
+        assert!((app.position(nudged).y + 10.0).abs() > 0.1, "{}", app.position(nudged));
+    }
+}
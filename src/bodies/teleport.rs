@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{contacts::ContactsPerEntity, frame_budget::FrameBudget, physics_components::Transform2D};
+
+/**
+    # Teleport
+
+    Moves a body to `position`(and `rotation`, if set) at the start of the next physics step, then removes itself.
+
+    Unlike moving the `Transform` the body doesn't travel there, so nothing on the way is hit,
+    and whatever the physics remembers about the body(its contacts and the pairs carried over by the frame budget) is forgotten.
+    The velocity is kept, so it is also good for portals.
+
+    ```ignore
+    coms.entity(player).insert(Teleport::to(spawn_point));
+    ```
+*/
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Teleport {
+    pub position: Vec2,
+    pub rotation: Option<f32>,
+}
+
+impl Teleport {
+    pub fn to(position: Vec2) -> Self {
+        Teleport {
+            position,
+            rotation: None,
+        }
+    }
+    pub fn with_rotation(
+        mut self,
+        rotation: f32,
+    ) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+}
+
+/// Teleports the bodies with a `Teleport`, runs at the start of the physics step
+pub fn teleport_system(
+    mut coms: Commands,
+    mut budget: ResMut<FrameBudget>,
+    mut contacts: ResMut<ContactsPerEntity>,
+    mut q: Query<(Entity, &Teleport, &mut Transform2D)>,
+) {
+    for (e, tp, mut t) in q.iter_mut() {
+        if let Some(r) = tp.rotation {
+            t.set_rotation(r);
+        }
+        t.teleport(tp.position);
+
+        budget.forget(e);
+        contacts.0.remove(&e);
+        coms.entity(e).remove::<Teleport>();
+    }
+}
+
+#[cfg(test)]
+mod teleport_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn jumps_over_the_wall() {
+        let mut app = PhysicsTestApp::new();
+        let wall = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        let body = app.spawn_at(Vec2::new(-5.0, 0.0), KinematicBundle::default());
+        app.step();
+
+        app.app.world.entity_mut(body).insert(Teleport::to(Vec2::new(5.0, 0.0)).with_rotation(1.0));
+        app.step();
+
+        app.assert_position(body, Vec2::new(5.0, 0.0), 0.0001);
+        assert!((app.app.world.get::<Transform2D>(body).unwrap().rotation() - 1.0).abs() < 0.0001);
+        assert!(app.app.world.get::<Teleport>(body).is_none());
+        app.assert_not_collided(body, wall);
+    }
+}
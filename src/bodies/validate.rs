@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{
+    physics_components::{CollisionLayer, Vel},
+    shapes::CollisionShape,
+};
+
+use super::{KinematicBody, Sensor, StaticBody};
+
+/// Warns about bodies with ambiguous or contradictory components, checks each body once when its `CollisionShape` is added
+#[allow(clippy::type_complexity)]
+pub fn validate_bodies_system(
+    q: Query<
+        (
+            Entity,
+            Option<&Name>,
+            Option<&KinematicBody>,
+            Option<&StaticBody>,
+            Option<&Sensor>,
+            Option<&Vel>,
+            Option<&CollisionLayer>,
+        ),
+        Added<CollisionShape>,
+    >,
+) {
+    for (e, name, kin, stat, sensor, vel, layer) in q.iter() {
+        for problem in body_problems(kin.is_some(), stat.is_some(), sensor.is_some(), vel.is_some(), layer.is_some()) {
+            match name {
+                Some(n) => warn!("body {:?}({}): {}", e, n, problem),
+                None => warn!("body {:?}: {}", e, problem),
+            }
+        }
+    }
+}
+
+/// Everything wrong with a body holding the given components
+fn body_problems(
+    kinematic: bool,
+    stat: bool,
+    sensor: bool,
+    vel: bool,
+    layer: bool,
+) -> Vec<&'static str> {
+    let mut problems = Vec::new();
+
+    if stat && sensor {
+        problems.push("has both `StaticBody` and `Sensor`, it will be treated as a static body");
+    }
+    if kinematic && (stat || sensor) {
+        problems.push("has `KinematicBody` together with `StaticBody` or `Sensor`, the marker is ignored");
+    }
+    if stat && vel {
+        problems.push("has both `StaticBody` and `Vel`, static bodies are never moved by the physics so `Vel` is ignored");
+    }
+    if !(kinematic || stat || sensor || vel) {
+        problems.push("has a `CollisionShape` but no body type, it is treated as a non continuous kinematic body(add `KinematicBody::discrete()` to make it explicit)");
+    }
+    if !layer {
+        problems.push("has no `CollisionLayer`, so it will never collide with anything");
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod validate_tests {
+    use super::*;
+
+    #[test]
+    fn problems() {
+        // a normal kinematic body and static body
+        assert!(body_problems(true, false, false, true, true).is_empty());
+        assert!(body_problems(false, true, false, false, true).is_empty());
+
+        assert_eq!(body_problems(false, true, false, true, true).len(), 1);
+        assert_eq!(body_problems(false, false, false, false, true).len(), 1);
+        assert_eq!(body_problems(true, false, true, false, false).len(), 2);
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+
+use crate::{
+    physics_components::{Mass, Transform2D, Vel},
+};
+
+use super::PhysicsDisabled;
+
+/**
+    # TopDownVehicle
+
+    A simple top down car for bodies with `Vel`, facing their local +Y axis.
+
+    Set `throttle` and `steering`(both -1 to 1) from your input systems, each physics step the engine pushes the car forward,
+    the steering turns it(faster the faster it goes, and backwards when reversing),
+    and the velocity along the car and sideways is damped by `forward_friction` and `lateral_friction`.
+
+    A `lateral_friction` much higher than `forward_friction` makes the car grip the road,
+    lowering it lets the car slide sideways(drift) when turning at speed.
+*/
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TopDownVehicle {
+    /// Forward(positive) or backward(negative) input
+    pub throttle: f32,
+    /// Left(positive) or right(negative) input
+    pub steering: f32,
+    /// Force of the engine at full throttle(divided by the body's `Mass`)
+    pub engine_force: f32,
+    /// How fast the car turns at full steering(radians per second)
+    pub turn_rate: f32,
+    /// Speed at which the car turns at the full `turn_rate`, slower cars turn slower
+    pub full_turn_speed: f32,
+    /// How fast the speed along the car is lost(per second)
+    pub forward_friction: f32,
+    /// How fast the sideways speed is lost(per second)
+    pub lateral_friction: f32,
+}
+
+impl Default for TopDownVehicle {
+    fn default() -> Self {
+        TopDownVehicle {
+            throttle: 0.0,
+            steering: 0.0,
+            engine_force: 600.0,
+            turn_rate: 3.0,
+            full_turn_speed: 100.0,
+            forward_friction: 0.5,
+            lateral_friction: 8.0,
+        }
+    }
+}
+
+impl TopDownVehicle {
+    pub fn with_engine(
+        mut self,
+        force: f32,
+    ) -> Self {
+        self.engine_force = force;
+        self
+    }
+    pub fn with_steering(
+        mut self,
+        turn_rate: f32,
+        full_turn_speed: f32,
+    ) -> Self {
+        self.turn_rate = turn_rate;
+        self.full_turn_speed = full_turn_speed;
+        self
+    }
+    pub fn with_friction(
+        mut self,
+        forward: f32,
+        lateral: f32,
+    ) -> Self {
+        self.forward_friction = forward;
+        self.lateral_friction = lateral;
+        self
+    }
+}
+
+/// Drives the `TopDownVehicle`s, runs before the integration
+pub fn vehicle_system(
+    time: Res<Time>,
+    mut q: Query<(&TopDownVehicle, &mut Vel, &mut Transform2D, Option<&Mass>), Without<PhysicsDisabled>>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (car, mut vel, mut t, mass) in q.iter_mut() {
+        let forward = t.rot_matrix() * Vec2::Y;
+        let speed = vel.0.dot(forward);
+
+        // steering, reversed when going backwards
+        let grip = (speed / car.full_turn_speed.max(f32::EPSILON)).clamp(-1.0, 1.0);
+        t.add_rotation(car.steering.clamp(-1.0, 1.0) * car.turn_rate * grip * dt);
+
+        // friction along the(new) direction of the car and sideways
+        let forward = t.rot_matrix() * Vec2::Y;
+        let along = forward * vel.0.dot(forward) * (-car.forward_friction * dt).exp();
+        let sideways = (vel.0 - forward * vel.0.dot(forward)) * (-car.lateral_friction * dt).exp();
+
+        let inv_mass = mass.map(|m| m.inverse()).unwrap_or(1.0);
+        vel.0 = along + sideways + forward * car.throttle.clamp(-1.0, 1.0) * car.engine_force * inv_mass * dt;
+    }
+}
+
+#[cfg(test)]
+mod vehicle_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    /// Drives forward, then turns hard and returns the sideways speed
+    fn sideways_after_turn(lateral_friction: f32) -> f32 {
+        let mut app = PhysicsTestApp::new();
+        let car = app.spawn_at(Vec2::ZERO, KinematicBundle {
+            collision_layer: CollisionLayer::ZERO,
+            ..Default::default()
+        });
+        app.app.world.entity_mut(car).insert(TopDownVehicle {
+            throttle: 1.0,
+            ..TopDownVehicle::default().with_friction(0.5, lateral_friction)
+        });
+
+        app.step_n(30);
+        assert!(app.app.world.get::<Vel>(car).unwrap().0.y > 100.0);
+
+        app.app.world.get_mut::<TopDownVehicle>(car).unwrap().steering = 1.0;
+        app.step_n(15);
+
+        let rot = app.app.world.get::<Transform2D>(car).unwrap().rotation();
+        let vel = app.app.world.get::<Vel>(car).unwrap().0;
+        vel.dot(Mat2::from_angle(rot) * Vec2::X).abs()
+    }
+
+    #[test]
+    fn low_lateral_friction_drifts() {
+        let grip = sideways_after_turn(8.0);
+        let drift = sideways_after_turn(0.5);
+        assert!(drift > 2.0 * grip, "drift {} grip {}", drift, grip);
+    }
+}
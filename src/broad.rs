@@ -17,20 +17,34 @@ pub struct ConBroadData {
 }
 
 /// Simply pushes ObbData and ObbDataKinematic into the event system for every shape
+///
+/// Legacy, the plugin doesn't run it anymore(continuous collision is done by `ccd::ccd_system`)
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn broad_phase_1(
     time: Res<Time>,
-    kinematics: Query<(Entity, &CollisionShape, &Vel, &Transform2D, &CollisionLayer)>,
+    kinematics: Query<(Entity, &CollisionShape, &Vel, &Transform2D, &CollisionLayer, Option<&CcdMode>, Option<&KinematicBody>)>,
     statics: Query<(Entity, &CollisionShape, &Transform2D, &CollisionLayer),(With<StaticBody>, Without<Vel>, Without<Sensor>)>,
     sensors: Query<(Entity, &CollisionShape, &Transform2D, &CollisionLayer), With<Sensor>>,
     mut broad_writer: EventWriter<ConBroadData>,
+    mut scratch: Local<BroadScratch>,
 ) {
     // TODO Optimize it later, when all is done and the earth is gone
     // probably get space partition or quad trees up and running
 
     let delta = time.delta_seconds();
 
-    for (e, cs,  vel, t, layer) in kinematics.iter() {
+    // the aabbs of the static bodies and sensors, once per frame instead of once per kinematic body
+    scratch.statics.clear();
+    scratch.statics.extend(statics.iter().map(|(se, scs, st, sl)| (se, scs.aabb(st), *sl)));
+    scratch.sensors.clear();
+    scratch.sensors.extend(sensors.iter().map(|(se, scs, st, sl)| (se, scs.aabb(st), *sl)));
+
+    for (e, cs,  vel, t, layer, ccd, kin) in kinematics.iter() {
+        // slow(or opted out) bodies are left to the discrete path
+        if !CcdMode::resolve(ccd, kin, vel.0) {
+            continue;
+        }
+
         let inst_vel = vel.0 * delta;
 
         let aabb = cs.aabb(t);
@@ -39,32 +53,9 @@ pub fn broad_phase_1(
         let circle_radius_sqrd = (inst_vel.abs() + aabb.extents).length_squared();
 
         // Get all staticbodies which might collide with us
-        let mut st_en: Vec<(Entity, Aabb)> = Vec::new();
-        for (se, scs, st, sl) in statics.iter() {
-            let saabb = scs.aabb(st);
-
-            if sl.overlap(layer) && aabb_circle(
-                circle_center,
-                circle_radius_sqrd,
-                &saabb,
-            ) {
-                st_en.push((se, saabb));
-            }
-        }
+        let st_en = nearby(&scratch.statics, layer, circle_center, circle_radius_sqrd);
         // same for sensors(we do the extra calculations for sensors which do not move)
-        let mut se_en: Vec<(Entity, Aabb)> = Vec::new();
-        for (se, scs, st, sl) in sensors.iter() {
-            let saabb = scs.aabb(st);
-
-
-            if sl.overlap(layer) && aabb_circle(
-                circle_center,
-                circle_radius_sqrd,
-                &saabb,
-            ) {
-                se_en.push((se, saabb));
-            }
-        }
+        let se_en = nearby(&scratch.sensors, layer, circle_center, circle_radius_sqrd);
         // wrap it up to an event
         broad_writer.send(ConBroadData {
             entity: e,
@@ -76,6 +67,26 @@ pub fn broad_phase_1(
     }
 }
 
+/// Per frame scratch buffers of `broad_phase_1`, kept around so they are not reallocated every frame
+#[derive(Default)]
+pub struct BroadScratch {
+    statics: Vec<(Entity, Aabb, CollisionLayer)>,
+    sensors: Vec<(Entity, Aabb, CollisionLayer)>,
+}
+
+/// The bodies in `bodies` which overlap `layer` and might be inside the circle
+fn nearby(
+    bodies: &[(Entity, Aabb, CollisionLayer)],
+    layer: &CollisionLayer,
+    center: Vec2,
+    radius_sqrd: f32,
+) -> Vec<(Entity, Aabb)> {
+    bodies.iter()
+        .filter(|(_, aabb, l)| l.overlap(layer) && aabb_circle(center, radius_sqrd, aabb))
+        .map(|(e, aabb, _)| (*e, *aabb))
+        .collect()
+}
+
 fn aabb_circle(
     center: Vec2,
     radius_sqrd: f32,
@@ -0,0 +1,121 @@
+//! # Continuous collision
+//!
+//! Fast(or small) bodies can move through a thin static body within a single frame, without ever overlapping it
+//! when the collisions are found. `ccd_system` runs right after the bodies are integrated and sweeps each body
+//! which uses continuous collision this frame(see `CcdMode` and `KinematicBody::continuous`) along its motion against the static bodies,
+//! a body which would have passed through one is moved back to where it first touches it(and a bit into it),
+//! so the discrete collision solve stops it(and sends its `CollisionEvent`) like any other contact.
+//!
+//! The motion of a body is taken as `Vel * delta`(which is what the default integrator moves it by),
+//! and only static bodies are swept against - 2 fast kinematic bodies can still pass through each other.
+//!
+//! ```ignore
+//! // a bullet, which always sweeps no matter its speed
+//! coms.spawn_bundle(KinematicBundle {
+//!     shape: CollisionShape::Circle(Circle::new(2.0)),
+//!     vel: Vel(Vec2::new(3000.0, 0.0)),
+//!     ..Default::default()
+//! })
+//! .insert(CcdMode::Always);
+//! ```
+
+use bevy::prelude::*;
+
+use crate::{
+    bodies::{CcdMode, KinematicBody, PhysicsDisabled, Sensor, StaticBody},
+    physics_components::{CollisionLayer, Transform2D, Vel},
+    shapes::{toi, Aabb, CollisionShape},
+    static_index::StaticIndex,
+};
+
+/// How far into the static body a swept body is left, so the discrete solve sees the contact
+const CCD_PENETRATION: f32 = 0.01;
+
+/// Moves the bodies which would have passed through a static body this frame back to where they hit it
+#[allow(clippy::type_complexity)]
+pub fn ccd_system(
+    time: Res<Time>,
+    index: Res<StaticIndex>,
+    mut bodies: Query<
+        (&CollisionShape, &mut Transform2D, &Vel, &CollisionLayer, Option<&CcdMode>, Option<&KinematicBody>),
+        (Without<StaticBody>, Without<Sensor>, Without<PhysicsDisabled>),
+    >,
+    statics: Query<(&CollisionShape, &Transform2D), With<StaticBody>>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 || index.is_empty() {
+        return;
+    }
+
+    for (shape, mut t, vel, layer, ccd, kin) in bodies.iter_mut() {
+        if !CcdMode::resolve(ccd, kin, vel.0) {
+            continue;
+        }
+        let motion = vel.0 * dt;
+        if motion == Vec2::ZERO {
+            continue;
+        }
+
+        let start = Transform2D::new(t.translation() - motion, t.rotation(), t.scale());
+        let (start_min, start_max) = shape.aabb(&start).min_max();
+        let (end_min, end_max) = shape.aabb(&t).min_max();
+        let swept = Aabb::from_min_max(start_min.min(end_min), start_max.max(end_max));
+
+        // the first static body it touches on the way, bodies it already overlaps at the start are left to the discrete solve
+        let first = index
+            .bodies()
+            .iter()
+            .filter(|(_, aabb, l)| l.overlap(layer) && aabb.collides(&swept))
+            .filter_map(|(e, ..)| statics.get(*e).ok())
+            .filter_map(|(ss, st)| toi(shape, &start, vel.0, ss, st, Vec2::ZERO, dt))
+            .filter(|hit| *hit > 0.0)
+            .reduce(f32::min);
+
+        if let Some(hit) = first {
+            let distance = (motion.length() * hit / dt + CCD_PENETRATION).min(motion.length());
+            t.set_translation(start.translation() + motion.normalize() * distance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod ccd_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    /// Shoots a small body at a thin wall, returns where the body ended up
+    fn shoot(
+        marker: KinematicBody,
+        ccd: CcdMode,
+    ) -> Vec2 {
+        let mut app = PhysicsTestApp::new();
+        app.spawn_at(Vec2::ZERO, StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(0.2, 20.0))),
+            ..Default::default()
+        });
+        // moves 50 units a frame
+        let bullet = app.spawn_at(Vec2::new(-20.0, 0.0), KinematicBundle {
+            marker,
+            shape: CollisionShape::Circle(Circle::new(0.5)),
+            vel: Vel(Vec2::new(3000.0, 0.0)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(bullet).insert_bundle((Acc::default(), ccd));
+
+        app.step_n(5);
+        app.app.world.get::<Transform2D>(bullet).unwrap().translation()
+    }
+
+    #[test]
+    fn doesnt_tunnel() {
+        let stopped = shoot(KinematicBody::default(), CcdMode::Always);
+        assert!(stopped.x < 0.0 && stopped.x > -1.0, "{}", stopped);
+
+        // too slow for the threshold, so it goes right through
+        let tunneled = shoot(KinematicBody::default(), CcdMode::Auto { speed_threshold: 5000.0 });
+        assert!(tunneled.x > 100.0, "{}", tunneled);
+        assert!(shoot(KinematicBody::default(), CcdMode::Never).x > 100.0);
+        // the marker opts out no matter the mode
+        assert!(shoot(KinematicBody::discrete(), CcdMode::Always).x > 100.0);
+    }
+}
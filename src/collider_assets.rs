@@ -0,0 +1,411 @@
+//! # Collider assets
+//!
+//! Enabled with the `collider-assets` feature.
+//!
+//! Allows authoring static colliders outside of code, the `ColliderAssetPlugin` registers a loader for:
+//!
+//! - `*.colliders.ron` and `*.colliders.json` - a `ColliderFile` serialized as RON/JSON
+//! - `*.colliders.svg` - a (very) simple svg reader, which supports `<rect>`, `<circle>`, `<polygon>`
+//!   and `<path>`(curves are replaced by a line to their end point, so export curved paths as polygons),
+//!   polygons and paths with less than 3 points(or no area) are skipped
+//!
+//! A RON/JSON file with a polygon of less than 3 points fails to load.
+//!
+//! To spawn the colliders, spawn an entity with a `ColliderScene` component(and a `Transform`),
+//! once the asset is loaded, a `StaticBundle` entity will be spawned for each collider,
+//! positioned relative to the `ColliderScene` entity.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    math::Mat2,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bodies::{StaticBody, StaticBundle},
+    physics_components::CollisionLayer,
+    shapes::{signed_area, ShapeDesc},
+    transform_mode::TransformMode,
+};
+
+/// Registers the `ColliderFile` asset with its loader and the spawning system
+pub struct ColliderAssetPlugin;
+
+impl Plugin for ColliderAssetPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_asset::<ColliderFile>()
+            .init_asset_loader::<ColliderFileLoader>()
+            .add_system(spawn_collider_scenes);
+    }
+}
+
+/// A file describing a bunch of static colliders
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TypeUuid)]
+#[uuid = "7d8a0a4f-62f4-4a4e-9a1b-3e6f0c2d5b91"]
+pub struct ColliderFile {
+    pub colliders: Vec<ColliderDesc>,
+}
+
+/// A single static collider in a `ColliderFile`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColliderDesc {
+    /// Position relative to the `ColliderScene` entity
+    #[serde(default)]
+    pub position: Vec2,
+    /// Rotation in radians
+    #[serde(default)]
+    pub rotation: f32,
+    pub shape: ShapeDesc,
+    #[serde(default)]
+    pub layer: CollisionLayer,
+}
+
+/// Spawns the colliders of the `ColliderFile` once it is loaded
+#[derive(Debug, Clone, Default, Component)]
+pub struct ColliderScene {
+    pub file: Handle<ColliderFile>,
+    /// Whether the colliders were already spawned
+    pub spawned: bool,
+}
+impl ColliderScene {
+    pub fn new(file: Handle<ColliderFile>) -> Self {
+        ColliderScene {
+            file,
+            spawned: false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ColliderFileLoader;
+
+impl AssetLoader for ColliderFileLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let path = load_context.path().to_string_lossy().to_string();
+            let file = parse_collider_file(&path, bytes)?;
+
+            load_context.set_default_asset(LoadedAsset::new(file));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["colliders.ron", "colliders.json", "colliders.svg"]
+    }
+}
+
+fn spawn_collider_scenes(
+    mut coms: Commands,
+    trans_mode: Res<TransformMode>,
+    files: Res<Assets<ColliderFile>>,
+    mut scenes: Query<(&mut ColliderScene, &Transform)>,
+) {
+    for (mut scene, trans) in scenes.iter_mut() {
+        if scene.spawned {
+            continue;
+        }
+        let file = match files.get(&scene.file) {
+            Some(f) => f,
+            None => continue, // not loaded yet
+        };
+
+        let origin = trans_mode.get_position(trans);
+        let origin_rot = trans_mode.get_rotation(trans);
+
+        for c in file.colliders.iter() {
+            let mut t = Transform::default();
+            trans_mode.set_position(&mut t, origin + Mat2::from_angle(origin_rot) * c.position);
+            trans_mode.set_rotation(&mut t, origin_rot + c.rotation);
+
+            // the loader already rejected the shapes which can't be built
+            let shape = match c.shape.to_shape() {
+                Some(s) => s,
+                None => continue,
+            };
+            coms.spawn_bundle(TransformBundle::from_transform(t))
+                .insert_bundle(StaticBundle {
+                    marker: StaticBody,
+                    shape,
+                    coll_layer: c.layer,
+                });
+        }
+        scene.spawned = true;
+    }
+}
+
+/// Reads a `ColliderFile` by the extension of `path`(anything which isn't json or svg is read as RON)
+fn parse_collider_file(
+    path: &str,
+    bytes: &[u8],
+) -> Result<ColliderFile, anyhow::Error> {
+    if path.ends_with(".svg") {
+        return Ok(parse_svg(std::str::from_utf8(bytes)?));
+    }
+    let file = if path.ends_with(".json") {
+        serde_json::from_slice::<ColliderFile>(bytes)?
+    }
+    else {
+        ron::de::from_bytes::<ColliderFile>(bytes)?
+    };
+
+    if let Some(i) = file.colliders.iter().position(|c| c.shape.to_shape().is_none()) {
+        anyhow::bail!("collider {} in {} is a polygon without an area(it needs at least 3 points)", i, path);
+    }
+    Ok(file)
+}
+
+/// Reads the supported elements out of an svg file,
+/// svg's y axis points down, so it is flipped here
+fn parse_svg(svg: &str) -> ColliderFile {
+    let mut colliders = Vec::new();
+    let flip = |v: Vec2| Vec2::new(v.x, -v.y);
+    let polygons = |outlines: Vec<Vec<Vec2>>| {
+        outlines
+            .into_iter()
+            .filter_map(|o| polygon_desc(o.into_iter().map(flip).collect()))
+            .collect::<Vec<_>>()
+    };
+
+    for tag in svg.split('<').skip(1) {
+        let name = tag.split(|c: char| c.is_whitespace() || c == '>' || c == '/').next().unwrap_or("");
+        let attr = |a: &str| svg_attr(tag, a);
+        let num = |a: &str| attr(a).and_then(|v| v.trim().parse::<f32>().ok()).unwrap_or(0.0);
+
+        let descs = match name {
+            "rect" => {
+                let size = Vec2::new(num("width"), num("height"));
+                let min = Vec2::new(num("x"), num("y"));
+                vec![ColliderDesc {
+                    position: flip(min + size * 0.5),
+                    rotation: 0.0,
+                    shape: ShapeDesc::Square(size),
+                    layer: CollisionLayer::default(),
+                }]
+            }
+            "circle" => vec![ColliderDesc {
+                position: flip(Vec2::new(num("cx"), num("cy"))),
+                rotation: 0.0,
+                shape: ShapeDesc::Circle(num("r")),
+                layer: CollisionLayer::default(),
+            }],
+            "polygon" | "polyline" => attr("points").map(|p| polygons(vec![svg_points(&p)])).unwrap_or_default(),
+            "path" => attr("d").map(|d| polygons(svg_path(&d))).unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        colliders.extend(descs);
+    }
+    ColliderFile { colliders }
+}
+
+/// `None` for outlines which can't be a polygon(less than 3 points, or all of them on a line)
+fn polygon_desc(mut points: Vec<Vec2>) -> Option<ColliderDesc> {
+    // a closed outline may repeat its first point at the end
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 || signed_area(&points).abs() < f32::EPSILON {
+        return None;
+    }
+    Some(ColliderDesc {
+        position: Vec2::ZERO,
+        rotation: 0.0,
+        shape: ShapeDesc::Polygon(points),
+        layer: CollisionLayer::default(),
+    })
+}
+
+/// The value of the attribute `name` in `tag`(the text of the element after its `<`)
+fn svg_attr(
+    tag: &str,
+    name: &str,
+) -> Option<String> {
+    let mut from = 0;
+    while let Some(found) = tag[from..].find(name) {
+        let start = from + found;
+        from = start + name.len();
+        // a whole attribute name(`x` shouldn't match the end of `rx`), separated by any whitespace
+        if !tag[..start].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let rest = tag[from..].trim_start();
+        let rest = match rest.strip_prefix('=') {
+            Some(r) => r.trim_start(),
+            None => continue,
+        };
+        let quote = rest.chars().next().filter(|q| *q == '"' || *q == '\'')?;
+        let rest = &rest[1..];
+        let end = rest.find(quote)?;
+        return Some(rest[..end].to_string());
+    }
+    None
+}
+
+/// Splits the numbers out of a list, which svg allows to be written without separators before a sign or a second `.`(`1-2.5.5`)
+fn svg_numbers(s: &str) -> Vec<f32> {
+    let mut numbers = Vec::new();
+    let mut current = String::new();
+    let mut end = |current: &mut String| {
+        if let Ok(n) = current.parse::<f32>() {
+            numbers.push(n);
+        }
+        current.clear();
+    };
+
+    for c in s.chars() {
+        let starts_new = match c {
+            '-' | '+' => !current.is_empty() && !current.ends_with(['e', 'E']),
+            '.' => current.contains('.') && !current.contains(['e', 'E']),
+            _ => false,
+        };
+        if starts_new {
+            end(&mut current);
+        }
+        if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E') {
+            current.push(c);
+        }
+        else {
+            end(&mut current);
+        }
+    }
+    end(&mut current);
+    numbers
+}
+
+fn svg_points(s: &str) -> Vec<Vec2> {
+    svg_numbers(s)
+        .chunks_exact(2)
+        .map(|c| Vec2::new(c[0], c[1]))
+        .collect()
+}
+
+/// The outline of each sub path(started by a move command) in the path data `d`,
+/// curves and arcs only add their end point
+fn svg_path(d: &str) -> Vec<Vec<Vec2>> {
+    let mut outlines = Vec::new();
+    let mut outline: Vec<Vec2> = Vec::new();
+    let mut current = Vec2::ZERO;
+    let mut start = Vec2::ZERO;
+
+    // each command letter is followed by its numbers(which can hold any number of repeats of its arguments)
+    let mut commands = d.match_indices(|c: char| c.is_ascii_alphabetic() && !matches!(c, 'e' | 'E')).peekable();
+    while let Some((i, cmd)) = commands.next() {
+        let args_end = commands.peek().map_or(d.len(), |(j, _)| *j);
+        let args = svg_numbers(&d[i + 1..args_end]);
+        let cmd = cmd.chars().next().unwrap_or('Z');
+        let relative = cmd.is_ascii_lowercase();
+        let origin = |current: Vec2| if relative { current } else { Vec2::ZERO };
+
+        let arg_count = match cmd.to_ascii_uppercase() {
+            'M' | 'L' | 'T' => 2,
+            'H' | 'V' => 1,
+            'S' | 'Q' => 4,
+            'C' => 6,
+            'A' => 7,
+            _ => 0,
+        };
+        if arg_count == 0 {
+            // close path, the polygon is closed anyway
+            current = start;
+            continue;
+        }
+
+        for (n, a) in args.chunks_exact(arg_count).enumerate() {
+            let point = match cmd.to_ascii_uppercase() {
+                'H' => Vec2::new(origin(current).x + a[0], current.y),
+                'V' => Vec2::new(current.x, origin(current).y + a[0]),
+                _ => origin(current) + Vec2::new(a[arg_count - 2], a[arg_count - 1]),
+            };
+            // a move starts a new sub path, the pairs after its first are lines
+            if n == 0 && cmd.eq_ignore_ascii_case(&'M') {
+                if !outline.is_empty() {
+                    outlines.push(std::mem::take(&mut outline));
+                }
+                start = point;
+            }
+            outline.push(point);
+            current = point;
+        }
+    }
+    if !outline.is_empty() {
+        outlines.push(outline);
+    }
+    outlines
+}
+
+#[cfg(test)]
+mod collider_assets_tests {
+    use super::*;
+
+    #[test]
+    fn svg_elements() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+            <rect
+                x="10" y='20'	width = "30" height="40" rx="99"/>
+            <circle cx="5" cy="5" r="2.5"/>
+            <polygon points="0,0 10,0 10,10"/>
+            <polyline points="0,0 10,0"/>
+            <path d="M 0 0 L 10 10 L 20 20 Z"/>
+        </svg>"#;
+        let file = parse_svg(svg);
+        // the line and the path without an area are skipped
+        assert_eq!(file.colliders.len(), 3);
+
+        let rect = &file.colliders[0];
+        assert_eq!(rect.position, Vec2::new(25.0, -40.0));
+        assert!(matches!(rect.shape, ShapeDesc::Square(s) if s == Vec2::new(30.0, 40.0)));
+        assert!(matches!(file.colliders[1].shape, ShapeDesc::Circle(r) if r == 2.5));
+        match &file.colliders[2].shape {
+            ShapeDesc::Polygon(p) => assert_eq!(p, &vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, -10.0)]),
+            _ => panic!("expected a polygon"),
+        }
+    }
+
+    #[test]
+    fn svg_path_commands() {
+        let square = vec![Vec2::new(10.0, 10.0), Vec2::new(20.0, 10.0), Vec2::new(20.0, 20.0), Vec2::new(10.0, 20.0)];
+        assert_eq!(svg_path("M10,10 H20 V20 H10 Z"), vec![square.clone()]);
+        assert_eq!(svg_path("m10 10 h10 v10 h-10 z"), vec![square.clone()]);
+        // implicit lines after a move, numbers without separators
+        assert_eq!(svg_path("m10 10 10 0 0 10-10 0z"), vec![square.clone()]);
+        assert_eq!(svg_path("M10 10L20 10 20 20 10 20"), vec![square]);
+        // curves end where they end, sub paths are separate outlines
+        assert_eq!(
+            svg_path("M0 0 C 1 1 2 2 3 0 q 1 1 2 0 M 5 5 l 1 0"),
+            vec![vec![Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(5.0, 0.0)], vec![Vec2::new(5.0, 5.0), Vec2::new(6.0, 5.0)]]
+        );
+        assert_eq!(svg_numbers("1-2.5.5e1,3E-1"), vec![1.0, -2.5, 5.0, 0.3]);
+    }
+
+    #[test]
+    fn ron_and_json() {
+        let ron = br#"(colliders: [
+            (position: (1.0, 2.0), shape: Square((2.0, 4.0))),
+            (rotation: 0.5, shape: Polygon([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)])),
+        ])"#;
+        let file = parse_collider_file("level.colliders.ron", ron).unwrap();
+        assert_eq!(file.colliders.len(), 2);
+        assert_eq!(file.colliders[0].position, Vec2::new(1.0, 2.0));
+        assert_eq!(file.colliders[1].rotation, 0.5);
+
+        let json = br#"{ "colliders": [{ "position": [3.0, 0.0], "shape": { "Circle": 1.5 } }] }"#;
+        let file = parse_collider_file("level.colliders.json", json).unwrap();
+        assert_eq!(file.colliders[0].position, Vec2::new(3.0, 0.0));
+        assert!(matches!(file.colliders[0].shape, ShapeDesc::Circle(r) if r == 1.5));
+
+        // a polygon without an inside isn't loaded
+        let line = br#"(colliders: [(shape: Polygon([(0.0, 0.0), (1.0, 0.0)]))])"#;
+        assert!(parse_collider_file("line.colliders.ron", line).is_err());
+        assert!(parse_collider_file("bad.colliders.json", b"{").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use crate::math::Vec2;
 
 pub trait VecOp<T> {
     /// Projects the vector on the given normal
@@ -11,6 +11,11 @@ pub trait VecOp<T> {
         self,
         normal: T,
     ) -> T;
+    /// Reflects the vector on the given normal(like a ball bouncing off a wall)
+    fn reflect(
+        self,
+        normal: T,
+    ) -> T;
 }
 
 impl VecOp<Vec2> for Vec2 {
@@ -36,4 +41,15 @@ impl VecOp<Vec2> for Vec2 {
             self
         }
     }
+    fn reflect(
+        self,
+        n: Vec2,
+    ) -> Vec2 {
+        if n.is_normalized() {
+            self - 2.0 * self.project(n)
+        }
+        else {
+            self
+        }
+    }
 }
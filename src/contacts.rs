@@ -0,0 +1,218 @@
+//! # Contacts per entity
+//!
+//! `ContactsPerEntity` groups the `CollisionEvent`s of the frame by entity(after the collision solve),
+//! so systems asking "what is X touching" don't need to go over the whole event stream.
+//!
+//! ```ignore
+//! fn grounded(
+//!     contacts: Res<ContactsPerEntity>,
+//!     mut players: Query<(Entity, &mut Player)>,
+//! ) {
+//!     for (e, mut p) in players.iter_mut() {
+//!         p.grounded = contacts.on_floor(e);
+//!     }
+//! }
+//! ```
+//!
+//! Floors, walls and ceilings are told apart by the body's up - against its `LocalGravity` if it has one,
+//! against the global `Gravity` otherwise(`Vec2::Y` when there is no gravity at all).
+
+use bevy::{prelude::*, utils::HashMap};
+use smallvec::SmallVec;
+
+use crate::{
+    bodies::{Gravity, LocalGravity},
+    plugin::{CollisionEvent, CollisionSettings},
+};
+
+/// What a contact is to the body touching it, by its normal and the body's up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceKind {
+    Floor,
+    Wall,
+    Ceiling,
+}
+
+impl SurfaceKind {
+    /// Classifies a contact `normal`(pointing away from the surface) by how far it is from `up`
+    pub fn classify(
+        normal: Vec2,
+        up: Vec2,
+        floor_angle: f32,
+    ) -> SurfaceKind {
+        let cos = normal.normalize_or_zero().dot(up.normalize_or_zero());
+        let limit = floor_angle.cos();
+        if cos >= limit {
+            SurfaceKind::Floor
+        }
+        else if cos <= -limit {
+            SurfaceKind::Ceiling
+        }
+        else {
+            SurfaceKind::Wall
+        }
+    }
+}
+
+/// A single contact, from the point of view of the entity it is stored under
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactInfo {
+    pub other: Entity,
+    /// Normal of the contact, pointing away from `other`
+    pub normal: Vec2,
+    /// How much the entity penetrated `other`(before it was solved)
+    pub penetration: Vec2,
+    /// Whether `other` is a static body
+    pub is_other_static: bool,
+    /// Whether `other` is a floor, wall or ceiling for the entity(by its own up)
+    pub surface: SurfaceKind,
+}
+
+/**
+    # ContactsPerEntity
+
+    Every contact of this frame grouped by entity, both entities of a collision get a `ContactInfo`
+    (with the normal flipped for the second one).
+
+    Rebuilt in `stage::COLLISION_DETECTION` right after the collision solve,
+    so it is up to date for systems running after the physics stages.
+*/
+#[derive(Debug, Default)]
+pub struct ContactsPerEntity(pub HashMap<Entity, SmallVec<[ContactInfo; 4]>>);
+
+impl ContactsPerEntity {
+    /// Contacts of `entity` this frame(empty when it touched nothing)
+    pub fn get(
+        &self,
+        entity: Entity,
+    ) -> &[ContactInfo] {
+        self.0.get(&entity).map(|c| c.as_slice()).unwrap_or(&[])
+    }
+    /// Whether `a` and `b` touched this frame
+    pub fn touching(
+        &self,
+        a: Entity,
+        b: Entity,
+    ) -> bool {
+        self.get(a).iter().any(|c| c.other == b)
+    }
+
+    /// Whether `entity` stands on anything this frame
+    pub fn on_floor(
+        &self,
+        entity: Entity,
+    ) -> bool {
+        self.get(entity).iter().any(|c| c.surface == SurfaceKind::Floor)
+    }
+    /// Whether `entity` touches a wall this frame
+    pub fn on_wall(
+        &self,
+        entity: Entity,
+    ) -> bool {
+        self.get(entity).iter().any(|c| c.surface == SurfaceKind::Wall)
+    }
+    /// Whether `entity` bumped into a ceiling this frame
+    pub fn on_ceiling(
+        &self,
+        entity: Entity,
+    ) -> bool {
+        self.get(entity).iter().any(|c| c.surface == SurfaceKind::Ceiling)
+    }
+
+    /// Drops the contacts between `a` and `b`(from both sides)
+    pub(crate) fn forget(
+        &mut self,
+        a: Entity,
+        b: Entity,
+    ) {
+        for (e, other) in [(a, b), (b, a)] {
+            if let Some(c) = self.0.get_mut(&e) {
+                c.retain(|c| c.other != other);
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        entity: Entity,
+        contact: ContactInfo,
+    ) {
+        self.0.entry(entity).or_default().push(contact);
+    }
+}
+
+/// Rebuilds `ContactsPerEntity` from this frame's `CollisionEvent`s
+pub fn contacts_per_entity_system(
+    gravity: Res<Gravity>,
+    settings: Res<CollisionSettings>,
+    locals: Query<&LocalGravity>,
+    mut contacts: ResMut<ContactsPerEntity>,
+    mut events: EventReader<CollisionEvent>,
+) {
+    contacts.0.clear();
+    let surface = |e: Entity, normal: Vec2| {
+        let up = locals.get(e).map_or_else(|_| gravity.up(), |l| l.up());
+        SurfaceKind::classify(normal, up, settings.floor_angle)
+    };
+
+    for ev in events.iter() {
+        // `CollisionEvent::normal` points away from entity_b
+        contacts.push(ev.entity_a, ContactInfo {
+            other: ev.entity_b,
+            normal: ev.normal,
+            penetration: ev.penetration,
+            is_other_static: ev.is_b_static,
+            surface: surface(ev.entity_a, ev.normal),
+        });
+        contacts.push(ev.entity_b, ContactInfo {
+            other: ev.entity_a,
+            normal: -ev.normal,
+            penetration: -ev.penetration,
+            is_other_static: false,
+            surface: surface(ev.entity_b, -ev.normal),
+        });
+    }
+}
+
+#[cfg(test)]
+mod contacts_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn both_sides_get_the_contact() {
+        let mut app = PhysicsTestApp::new();
+
+        let floor = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        let body = app.spawn_at(Vec2::new(0.0, 1.5), KinematicBundle::default());
+        let far = app.spawn_at(Vec2::new(10.0, 0.0), KinematicBundle::default());
+
+        app.step();
+
+        let contacts = app.app.world.resource::<ContactsPerEntity>();
+        assert!(contacts.touching(body, floor) && contacts.touching(floor, body));
+        assert!(contacts.get(body)[0].normal.y > 0.9);
+        assert!(contacts.get(floor)[0].normal.y < -0.9);
+        assert!(contacts.get(far).is_empty());
+    }
+
+    #[test]
+    fn floors_by_local_up() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(Gravity(Vec2::new(0.0, -10.0)));
+
+        // both touch the right side of a wall, one of them falls to the left
+        let wall = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        app.spawn_at(Vec2::new(0.0, 10.0), StaticBundle::default());
+        let regular = app.spawn_at(Vec2::new(1.5, 10.0), KinematicBundle::default());
+        let sideways = app.spawn_at(Vec2::new(1.5, 0.0), KinematicBundle::default());
+        app.app.world.entity_mut(sideways).insert(LocalGravity(Vec2::new(-10.0, 0.0)));
+        app.step();
+
+        let contacts = app.app.world.resource::<ContactsPerEntity>();
+        assert!(contacts.on_wall(regular) && !contacts.on_floor(regular));
+        assert!(contacts.on_floor(sideways) && !contacts.on_wall(sideways));
+        assert_eq!(SurfaceKind::classify(Vec2::new(0.0, -1.0), Vec2::Y, 45f32.to_radians()), SurfaceKind::Ceiling);
+        assert_eq!(contacts.get(wall)[0].surface, SurfaceKind::Wall);
+    }
+}
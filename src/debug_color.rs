@@ -0,0 +1,229 @@
+//! # Debug coloring
+//!
+//! Tints the `Sprite` of every body by its simulation island, whether it is at rest or how many contacts it has,
+//! so it is easy to see at a glance why something is expensive or stuck.
+//! Off by default, toggled through the `DebugColors` resource:
+//!
+//! ```ignore
+//! fn toggle_debug_colors(
+//!     keys: Res<Input<KeyCode>>,
+//!     mut colors: ResMut<DebugColors>,
+//! ) {
+//!     if keys.just_pressed(KeyCode::F3) {
+//!         colors.mode = colors.mode.next();
+//!     }
+//! }
+//! ```
+//!
+//! An island is a group of kinematic bodies touching each other(directly or through other kinematic bodies),
+//! static bodies don't join islands - so a pile of boxes on the floor is a single island, and a box alone is an island of its own.
+//! The islands of the last frame are kept in `PhysicsIslands`, whether debug coloring is on or not.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{bodies::*, contacts::ContactsPerEntity, physics_components::Vel};
+
+/// What the bodies are colored by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebugColorMode {
+    /// Sprites keep their own colors
+    #[default]
+    Off,
+    /// Each island gets its own color, static bodies are gray
+    Island,
+    /// Bodies moving slower than `DebugColors::rest_speed` are blue, the rest are orange
+    ///
+    /// Bodies never sleep yet, so this is the closest thing to a sleeping state
+    Resting,
+    /// From white(no contacts) to red(`DebugColors::max_contacts` or more)
+    ContactCount,
+}
+
+impl DebugColorMode {
+    /// The next mode, for cycling through them with a single key
+    pub fn next(self) -> DebugColorMode {
+        match self {
+            DebugColorMode::Off => DebugColorMode::Island,
+            DebugColorMode::Island => DebugColorMode::Resting,
+            DebugColorMode::Resting => DebugColorMode::ContactCount,
+            DebugColorMode::ContactCount => DebugColorMode::Off,
+        }
+    }
+}
+
+/// Settings of the debug coloring, `mode` is `Off` by default
+#[derive(Debug, Clone)]
+pub struct DebugColors {
+    pub mode: DebugColorMode,
+    /// Speed under which a body counts as resting(`DebugColorMode::Resting`)
+    pub rest_speed: f32,
+    /// Number of contacts shown as fully red(`DebugColorMode::ContactCount`)
+    pub max_contacts: usize,
+}
+
+impl Default for DebugColors {
+    fn default() -> Self {
+        DebugColors {
+            mode: DebugColorMode::Off,
+            rest_speed: 1.0,
+            max_contacts: 4,
+        }
+    }
+}
+
+/// The simulation islands of the last frame, rebuilt from `ContactsPerEntity` after the collision solve
+#[derive(Debug, Default)]
+pub struct PhysicsIslands {
+    islands: HashMap<Entity, u32>,
+    count: u32,
+}
+
+impl PhysicsIslands {
+    /// Id of the island `entity` is in, `None` for static bodies and sensors
+    ///
+    /// Ids are only meaningful within a frame - the same pile can get a different id next frame
+    pub fn island(
+        &self,
+        entity: Entity,
+    ) -> Option<u32> {
+        self.islands.get(&entity).copied()
+    }
+    /// How many islands there are
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+    /// How many bodies are in each island(indexed by the island id)
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.count as usize];
+        for id in self.islands.values() {
+            sizes[*id as usize] += 1;
+        }
+        sizes
+    }
+}
+
+/// The original color of a tinted sprite, restored once the debug coloring is turned off
+#[derive(Component)]
+pub struct DebugTint(pub Color);
+
+/// Rebuilds `PhysicsIslands` by walking the kinematic contacts of this frame
+#[allow(clippy::type_complexity)]
+pub fn islands_system(
+    mut islands: ResMut<PhysicsIslands>,
+    contacts: Res<ContactsPerEntity>,
+    bodies: Query<Entity, (With<KinematicBody>, Without<StaticBody>, Without<Sensor>)>,
+    mut stack: Local<Vec<Entity>>,
+) {
+    let islands = &mut *islands;
+    islands.islands.clear();
+    islands.count = 0;
+
+    for e in bodies.iter() {
+        if islands.islands.contains_key(&e) {
+            continue;
+        }
+        let id = islands.count;
+        islands.count += 1;
+
+        islands.islands.insert(e, id);
+        stack.push(e);
+        while let Some(e) = stack.pop() {
+            for c in contacts.get(e) {
+                if !c.is_other_static && bodies.contains(c.other) && !islands.islands.contains_key(&c.other) {
+                    islands.islands.insert(c.other, id);
+                    stack.push(c.other);
+                }
+            }
+        }
+    }
+}
+
+/// Tints the sprites of the bodies by `DebugColors::mode`, and restores them when it is `Off`
+#[allow(clippy::type_complexity)]
+pub fn debug_color_system(
+    mut coms: Commands,
+    colors: Res<DebugColors>,
+    islands: Res<PhysicsIslands>,
+    contacts: Res<ContactsPerEntity>,
+    mut bodies: Query<
+        (Entity, &mut Sprite, Option<&Vel>, Option<&DebugTint>),
+        Or<(With<KinematicBody>, With<StaticBody>)>,
+    >,
+) {
+    for (e, mut sprite, vel, tint) in bodies.iter_mut() {
+        if colors.mode == DebugColorMode::Off {
+            if let Some(tint) = tint {
+                sprite.color = tint.0;
+                coms.entity(e).remove::<DebugTint>();
+            }
+            continue;
+        }
+        if tint.is_none() {
+            coms.entity(e).insert(DebugTint(sprite.color));
+        }
+
+        sprite.color = match colors.mode {
+            DebugColorMode::Island => match islands.island(e) {
+                // spread the hues by the golden angle, so neighbouring ids don't look alike
+                Some(id) => Color::hsl((id as f32 * 137.508) % 360.0, 0.7, 0.55),
+                None => Color::GRAY,
+            },
+            DebugColorMode::Resting => {
+                if vel.map_or(0.0, |v| v.0.length()) < colors.rest_speed {
+                    Color::rgb(0.2, 0.4, 1.0)
+                }
+                else {
+                    Color::ORANGE
+                }
+            },
+            DebugColorMode::ContactCount => {
+                let t = contacts.get(e).len() as f32 / colors.max_contacts.max(1) as f32;
+                let t = 1.0 - t.min(1.0);
+                Color::rgb(1.0, t, t)
+            },
+            DebugColorMode::Off => unreachable!(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod debug_color_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn islands_and_tints() {
+        let mut app = PhysicsTestApp::new();
+        let floor = app.spawn_at(Vec2::new(0.0, -1.5), StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(20.0, 1.0))),
+            ..Default::default()
+        });
+        // a and b are stacked, c rests on the floor on its own(the floor doesn't join them)
+        let a = app.spawn_at(Vec2::new(0.0, -0.4), KinematicBundle::default());
+        let b = app.spawn_at(Vec2::new(0.0, 1.2), KinematicBundle::default());
+        let c = app.spawn_at(Vec2::new(5.0, -0.4), KinematicBundle::default());
+        for e in [floor, a, b, c] {
+            app.app.world.entity_mut(e).insert(Sprite { color: Color::WHITE, ..Default::default() });
+        }
+        app.app.world.resource_mut::<DebugColors>().mode = DebugColorMode::Island;
+        app.step();
+
+        let islands = app.app.world.resource::<PhysicsIslands>();
+        assert_eq!(islands.island(a), islands.island(b));
+        assert_ne!(islands.island(a), islands.island(c));
+        assert_eq!(islands.island(floor), None);
+        assert_eq!(islands.count(), 2);
+
+        let color = |app: &PhysicsTestApp, e: Entity| app.app.world.get::<Sprite>(e).unwrap().color;
+        assert_eq!(color(&app, a), color(&app, b));
+        assert_ne!(color(&app, a), color(&app, c));
+        assert_eq!(color(&app, floor), Color::GRAY);
+
+        app.app.world.resource_mut::<DebugColors>().mode = DebugColorMode::Off;
+        app.step();
+        for e in [floor, a, b, c] {
+            assert_eq!(color(&app, e), Color::WHITE);
+            assert!(app.app.world.get::<DebugTint>(e).is_none());
+        }
+    }
+}
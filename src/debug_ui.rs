@@ -0,0 +1,299 @@
+//! # Debug UI
+//!
+//! Enabled with the `egui` feature.
+//!
+//! `PhysicsDebugUiPlugin` adds egui windows for poking at the physics while the game runs:
+//! the "Collision layers" window lists the bodies(by their `Name` when they have one),
+//! shows their layer and mask bits as checkboxes named by `LayerNames`,
+//! and toggles whether they are sensors and whether kinematic bodies use continuous collision(`KinematicBody::continuous`).
+//! The "Inspector" window shows the live state of the selected body - its `Vel`, its contacts this frame,
+//! the sensors it is in(or the bodies in it, for a sensor) and what its `RayCast` hit(and which rays hit it).
+//! Bodies are selected by clicking them(through `PhysicsPickingPlugin`) or with "Inspect" in the layers window.
+//!
+//! It draws through `bevy_egui`, whose `EguiPlugin` has to be added as well(the feature needs a `bevy_egui` made for bevy 0.8):
+//!
+//! ```ignore
+//! app.add_plugin(Physics2dPlugin::default())
+//!     .add_plugin(PhysicsPickingPlugin)
+//!     .add_plugin(EguiPlugin)
+//!     .add_plugin(PhysicsDebugUiPlugin)
+//!     .insert_resource(LayerNames::default().with_name(0, "Player").with_name(1, "Enemies"));
+//! ```
+//!
+//! Edits go through the components themselves, so a changed layer is seen by `CollisionLayerChanged`
+//! and a static body is reindexed like it would be from any other system.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+use crate::{
+    bodies::{KinematicBody, RayCast, Sensor, StaticBody},
+    contacts::ContactsPerEntity,
+    physics_components::{CollisionLayer, Vel},
+    picking::PickedEvent,
+};
+
+/// Debug windows for the physics bodies, needs `Physics2dPlugin`, `PhysicsPickingPlugin` and `bevy_egui::EguiPlugin` to be added as well
+pub struct PhysicsDebugUiPlugin;
+
+impl Plugin for PhysicsDebugUiPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<LayerNames>()
+            .init_resource::<PhysicsDebugUi>()
+            .add_system(layer_editor_system)
+            .add_system(inspector_system.after(layer_editor_system));
+    }
+}
+
+/// Names of the 8 collision layer bits(`1 << i` is named by `0[i]`), shown next to their checkboxes
+#[derive(Debug, Clone)]
+pub struct LayerNames(pub [String; 8]);
+
+impl Default for LayerNames {
+    fn default() -> Self {
+        LayerNames([0, 1, 2, 3, 4, 5, 6, 7].map(|i| format!("Layer {}", i)))
+    }
+}
+
+impl LayerNames {
+    pub fn with_name(
+        mut self,
+        bit: usize,
+        name: impl Into<String>,
+    ) -> Self {
+        self.0[bit] = name.into();
+        self
+    }
+}
+
+/// Which of the debug windows are open
+#[derive(Debug, Clone)]
+pub struct PhysicsDebugUi {
+    pub layer_editor: bool,
+    pub inspector: bool,
+    /// Only bodies whose label(name or entity) contains this are listed
+    pub filter: String,
+    /// The body shown in the inspector, left clicking a body selects it
+    pub selected: Option<Entity>,
+}
+
+impl Default for PhysicsDebugUi {
+    fn default() -> Self {
+        PhysicsDebugUi {
+            layer_editor: true,
+            inspector: true,
+            filter: String::new(),
+            selected: None,
+        }
+    }
+}
+
+/// Label of a body in the debug windows
+fn body_label(
+    e: Entity,
+    name: Option<&Name>,
+) -> String {
+    match name {
+        Some(n) => format!("{} ({:?})", n, e),
+        None => format!("{:?}", e),
+    }
+}
+
+/// Checkboxes for the bits of `bits`, returns the edited bits when one was clicked
+fn bit_checkboxes(
+    ui: &mut egui::Ui,
+    names: &LayerNames,
+    bits: u8,
+) -> Option<u8> {
+    let mut edited = None;
+    ui.horizontal_wrapped(|ui| {
+        for (i, name) in names.0.iter().enumerate() {
+            let mut on = bits & (1 << i) != 0;
+            if ui.checkbox(&mut on, name.as_str()).changed() {
+                edited = Some(bits ^ (1 << i));
+            }
+        }
+    });
+    edited
+}
+
+/// Draws the "Collision layers" window
+#[allow(clippy::type_complexity)]
+pub fn layer_editor_system(
+    mut coms: Commands,
+    mut egui_ctx: ResMut<EguiContext>,
+    mut state: ResMut<PhysicsDebugUi>,
+    names: Res<LayerNames>,
+    mut bodies: Query<(
+        Entity,
+        Option<&Name>,
+        &mut CollisionLayer,
+        Option<&Sensor>,
+        Option<&StaticBody>,
+        Option<&mut KinematicBody>,
+    )>,
+) {
+    if !state.layer_editor {
+        return;
+    }
+    let state = &mut *state;
+
+    egui::Window::new("Collision layers")
+        .open(&mut state.layer_editor)
+        .show(egui_ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Filter");
+                ui.text_edit_singleline(&mut state.filter);
+            });
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (e, name, mut layer, sensor, stat, kin) in bodies.iter_mut() {
+                    let label = body_label(e, name);
+                    if !label.contains(state.filter.as_str()) {
+                        continue;
+                    }
+                    egui::CollapsingHeader::new(label).id_source(e).show(ui, |ui| {
+                        if ui.selectable_label(state.selected == Some(e), "Inspect").clicked() {
+                            state.selected = Some(e);
+                        }
+                        ui.label("Layer");
+                        if let Some(bits) = bit_checkboxes(ui, &names, layer.layer) {
+                            layer.layer = bits;
+                        }
+                        ui.label("Mask");
+                        if let Some(bits) = bit_checkboxes(ui, &names, layer.mask) {
+                            layer.mask = bits;
+                        }
+                        ui.separator();
+
+                        // static bodies are never sensors
+                        let mut is_sensor = sensor.is_some();
+                        if ui.add_enabled(stat.is_none(), egui::Checkbox::new(&mut is_sensor, "Sensor")).changed() {
+                            if is_sensor {
+                                coms.entity(e).insert(Sensor::new());
+                            }
+                            else {
+                                coms.entity(e).remove::<Sensor>();
+                            }
+                        }
+                        // read by `ccd_system` on the next step
+                        if let Some(mut kin) = kin {
+                            let mut continuous = kin.continuous;
+                            if ui.checkbox(&mut continuous, "Continuous collision").changed() {
+                                kin.continuous = continuous;
+                            }
+                        }
+                    });
+                }
+            });
+        });
+}
+
+/// Draws the "Inspector" window for `PhysicsDebugUi::selected`, and selects the bodies clicked outside of egui's windows
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn inspector_system(
+    mut egui_ctx: ResMut<EguiContext>,
+    mut state: ResMut<PhysicsDebugUi>,
+    mut picked: EventReader<PickedEvent>,
+    contacts: Res<ContactsPerEntity>,
+    names: Query<&Name>,
+    bodies: Query<(Option<&Vel>, Option<&StaticBody>, Option<&Sensor>, Option<&RayCast>), With<CollisionLayer>>,
+    sensors: Query<(Entity, &Sensor)>,
+    rays: Query<(Entity, &RayCast)>,
+) {
+    let ctx = egui_ctx.ctx_mut();
+    // clicking a window over a body doesn't select the body
+    let over_ui = ctx.is_pointer_over_area();
+    for ev in picked.iter() {
+        if ev.button == MouseButton::Left && !over_ui {
+            state.selected = Some(ev.entity);
+        }
+    }
+    if !state.inspector {
+        return;
+    }
+    let state = &mut *state;
+    let label = |e: Entity| body_label(e, names.get(e).ok());
+
+    egui::Window::new("Inspector").open(&mut state.inspector).show(ctx, |ui| {
+        let e = match state.selected {
+            Some(e) => e,
+            None => {
+                ui.label("Click a body to inspect it");
+                return;
+            },
+        };
+        let (vel, stat, sensor, ray) = match bodies.get(e) {
+            Ok(b) => b,
+            Err(_) => {
+                // despawned, or lost its layer
+                state.selected = None;
+                return;
+            },
+        };
+        ui.heading(label(e));
+
+        match (vel, stat) {
+            (_, Some(_)) => ui.label("Static body"),
+            (Some(v), _) => ui.label(format!("Vel: ({:.2}, {:.2}), speed {:.2}", v.0.x, v.0.y, v.0.length())),
+            (None, _) => ui.label("No Vel(moved by its Transform)"),
+        };
+
+        ui.collapsing(format!("Contacts({})", contacts.get(e).len()), |ui| {
+            for c in contacts.get(e) {
+                ui.label(format!(
+                    "{} - {:?}, normal ({:.2}, {:.2}), penetration {:.3}",
+                    label(c.other),
+                    c.surface,
+                    c.normal.x,
+                    c.normal.y,
+                    c.penetration.length(),
+                ));
+            }
+        });
+
+        match sensor {
+            Some(s) => {
+                ui.collapsing(format!("Bodies in the sensor({})", s.bodies.len()), |ui| {
+                    for b in s.bodies.iter() {
+                        ui.label(label(*b));
+                    }
+                });
+            },
+            None => {
+                let inside = sensors.iter().filter(|(_, s)| s.bodies.contains(&e)).map(|(se, _)| se).collect::<Vec<_>>();
+                ui.collapsing(format!("In sensors({})", inside.len()), |ui| {
+                    for se in inside {
+                        ui.label(label(se));
+                    }
+                });
+            },
+        }
+
+        if let Some(ray) = ray {
+            match &ray.collision {
+                Some(hit) => ui.label(format!(
+                    "Ray hit: {} at ({:.2}, {:.2})",
+                    label(hit.entity),
+                    hit.collision_point.x,
+                    hit.collision_point.y
+                )),
+                None => ui.label("Ray hit: nothing"),
+            };
+        }
+        let hit_by = rays
+            .iter()
+            .filter(|(_, r)| r.collision.as_ref().map_or(false, |hit| hit.entity == e))
+            .map(|(re, _)| re)
+            .collect::<Vec<_>>();
+        if !hit_by.is_empty() {
+            ui.collapsing(format!("Hit by rays({})", hit_by.len()), |ui| {
+                for re in hit_by {
+                    ui.label(label(re));
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,258 @@
+//! # Solver fixtures
+//!
+//! Enabled with the `test-utils` feature(and always available for this crate's own tests).
+//!
+//! A `Fixture` is a small scene(usually recorded from a real game) described in RON - the bodies,
+//! their shapes and velocities, how many frames to step, and what should be true afterwards.
+//! Each fixture is run in a `PhysicsTestApp`, so solver regressions show up as a failing fixture
+//! instead of a game that feels slightly off.
+//!
+//! ```ron
+//! (
+//!     description: "a box resting on the floor stays there",
+//!     steps: 60,
+//!     gravity: (0.0, -10.0),
+//!     bodies: [
+//!         (name: "floor", kind: Static, shape: Square((20.0, 2.0))),
+//!         (name: "box", kind: Kinematic, shape: Square((2.0, 2.0)), position: (0.0, 2.0)),
+//!     ],
+//!     expect: [
+//!         Position(body: "box", position: (0.0, 2.0), tolerance: 0.05),
+//!         Collided("box", "floor"),
+//!     ],
+//! )
+//! ```
+//!
+//! This crate's own fixtures live in the `fixtures` directory, and are all run by `run_fixtures_in`.
+
+use std::path::Path;
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+use crate::{physics_components::CollisionLayer, prelude::*, test_utils::PhysicsTestApp, transform_mode::TransformMode};
+
+/// A scene to step, and what is expected of it afterwards
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    /// What the fixture checks(printed when it fails)
+    #[serde(default)]
+    pub description: String,
+    /// Delta time of each step(in seconds), defaults to 1/60
+    #[serde(default = "default_dt")]
+    pub dt: f32,
+    /// How many frames to step(the first one only propagates the transforms)
+    pub steps: usize,
+    /// Acceleration added to every kinematic body each frame
+    #[serde(default)]
+    pub gravity: Vec2,
+    pub bodies: Vec<FixtureBody>,
+    pub expect: Vec<Expectation>,
+}
+
+fn default_dt() -> f32 {
+    1.0 / 60.0
+}
+
+/// A single body in a `Fixture`
+#[derive(Debug, Clone, Deserialize)]
+pub struct FixtureBody {
+    /// Name the expectations refer to the body by
+    pub name: String,
+    pub kind: FixtureBodyKind,
+    pub shape: ShapeDesc,
+    #[serde(default)]
+    pub position: Vec2,
+    /// Rotation in radians
+    #[serde(default)]
+    pub rotation: f32,
+    /// Starting velocity(only used by kinematic bodies)
+    #[serde(default)]
+    pub vel: Vec2,
+    #[serde(default)]
+    pub layer: CollisionLayer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum FixtureBodyKind {
+    Kinematic,
+    Static,
+    Sensor,
+}
+
+/// Something which should be true after the fixture was stepped
+#[derive(Debug, Clone, Deserialize)]
+pub enum Expectation {
+    /// The body ended up within `tolerance` of `position`
+    Position { body: String, position: Vec2, tolerance: f32 },
+    /// The body ended up with a velocity within `tolerance` of `vel`
+    Velocity { body: String, vel: Vec2, tolerance: f32 },
+    /// The 2 bodies collided at least once
+    Collided(String, String),
+    /// The 2 bodies never collided
+    NotCollided(String, String),
+    /// The sensor holds the body after the last step
+    InSensor { sensor: String, body: String },
+    /// The sensor doesn't hold the body after the last step
+    NotInSensor { sensor: String, body: String },
+}
+
+impl Fixture {
+    /// Parses a fixture from RON
+    pub fn from_ron(s: &str) -> Result<Fixture, ron::Error> {
+        ron::de::from_str(s)
+    }
+
+    /// Steps the fixture, and returns every expectation which didn't hold(empty when it passed)
+    pub fn run(&self) -> Vec<String> {
+        let mut app = PhysicsTestApp::new().with_dt(self.dt);
+        let mode = *app.app.world.resource::<TransformMode>();
+
+        let mut names = HashMap::default();
+        for b in self.bodies.iter() {
+            let shape = match b.shape.to_shape() {
+                Some(s) => s,
+                None => return vec![format!("{} is a polygon without an area(it needs at least 3 points)", b.name)],
+            };
+            let e = match b.kind {
+                FixtureBodyKind::Kinematic => {
+                    let e = app.spawn_at(b.position, KinematicBundle {
+                        vel: Vel(b.vel),
+                        shape,
+                        collision_layer: b.layer,
+                        ..Default::default()
+                    });
+                    app.app.world.entity_mut(e).insert(Acc::default());
+                    e
+                },
+                FixtureBodyKind::Static => app.spawn_at(b.position, StaticBundle {
+                    shape,
+                    coll_layer: b.layer,
+                    ..Default::default()
+                }),
+                FixtureBodyKind::Sensor => app.spawn_at(b.position, SensorBundle {
+                    shape,
+                    coll_layer: b.layer,
+                    ..Default::default()
+                }),
+            };
+            if b.rotation != 0.0 {
+                let mut t = app.app.world.get_mut::<Transform>(e).unwrap();
+                mode.set_rotation(&mut t, b.rotation);
+            }
+            names.insert(b.name.clone(), (e, b.kind));
+        }
+
+        for _ in 0..self.steps {
+            for (e, kind) in names.values() {
+                if *kind == FixtureBodyKind::Kinematic {
+                    app.app.world.get_mut::<Acc>(*e).unwrap().add(self.gravity);
+                }
+            }
+            app.step();
+        }
+
+        let mut missing = Vec::new();
+        let mut entity = |name: &String| match names.get(name) {
+            Some((e, _)) => Some(*e),
+            None => {
+                missing.push(format!("no body named {:?}", name));
+                None
+            }
+        };
+        let mut failures = Vec::new();
+        for ex in self.expect.iter() {
+            let result = match ex {
+                Expectation::Position { body, position, tolerance } => entity(body).and_then(|e| {
+                    let p = app.position(e);
+                    ((p - *position).length() > *tolerance)
+                        .then(|| format!("{} is at {}, expected {} (tolerance {})", body, p, position, tolerance))
+                }),
+                Expectation::Velocity { body, vel, tolerance } => entity(body).and_then(|e| {
+                    let v = app.app.world.get::<Vel>(e).map(|v| v.0).unwrap_or_default();
+                    ((v - *vel).length() > *tolerance)
+                        .then(|| format!("{} moves at {}, expected {} (tolerance {})", body, v, vel, tolerance))
+                }),
+                Expectation::Collided(a, b) => entity(a).zip(entity(b)).and_then(|(ea, eb)| {
+                    app.collisions_between(ea, eb).next().is_none()
+                        .then(|| format!("{} and {} never collided", a, b))
+                }),
+                Expectation::NotCollided(a, b) => entity(a).zip(entity(b)).and_then(|(ea, eb)| {
+                    app.collisions_between(ea, eb).next().is_some()
+                        .then(|| format!("{} and {} collided", a, b))
+                }),
+                Expectation::InSensor { sensor, body } => entity(sensor).zip(entity(body)).and_then(|(es, eb)| {
+                    (!sensor_holds(&app, es, eb)).then(|| format!("{} is not in {}", body, sensor))
+                }),
+                Expectation::NotInSensor { sensor, body } => entity(sensor).zip(entity(body)).and_then(|(es, eb)| {
+                    sensor_holds(&app, es, eb).then(|| format!("{} is in {}", body, sensor))
+                }),
+            };
+            failures.extend(result);
+        }
+        missing.extend(failures);
+        missing
+    }
+
+    /// Steps the fixture, and panics with every expectation which didn't hold
+    pub fn assert(&self) {
+        let failures = self.run();
+        assert!(failures.is_empty(), "{}:\n  {}", self.description, failures.join("\n  "));
+    }
+}
+
+fn sensor_holds(
+    app: &PhysicsTestApp,
+    sensor: Entity,
+    body: Entity,
+) -> bool {
+    app.app.world.get::<Sensor>(sensor).map_or(false, |s| s.bodies.contains(&body))
+}
+
+/// Runs every `*.ron` fixture in `dir`, and returns the failures of each fixture which failed(by file name)
+pub fn run_fixtures_in(dir: impl AsRef<Path>) -> Vec<(String, Vec<String>)> {
+    let mut paths = std::fs::read_dir(dir.as_ref())
+        .expect("Couldn't read the fixtures directory")
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().map_or(false, |e| e == "ron"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths.into_iter()
+        .filter_map(|p| {
+            let name = p.file_name().unwrap().to_string_lossy().to_string();
+            let failures = match std::fs::read_to_string(&p).map_err(|e| e.to_string())
+                .and_then(|s| Fixture::from_ron(&s).map_err(|e| e.to_string()))
+            {
+                Ok(f) => f.run().into_iter().map(|m| format!("{}: {}", f.description, m)).collect(),
+                Err(e) => vec![format!("couldn't load: {}", e)],
+            };
+            (!failures.is_empty()).then_some((name, failures))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod fixture_tests {
+    use super::*;
+
+    #[test]
+    fn recorded_fixtures() {
+        let failed = run_fixtures_in(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+
+        let report = failed.iter()
+            .map(|(name, failures)| format!("{}\n  {}", name, failures.join("\n  ")))
+            .collect::<Vec<_>>();
+        assert!(failed.is_empty(), "fixtures failed:\n{}", report.join("\n"));
+    }
+
+    #[test]
+    fn degenerate_polygon_fails() {
+        let fixture = Fixture::from_ron(r#"(
+            steps: 2,
+            bodies: [(name: "line", kind: Static, shape: Polygon([(0.0, 0.0), (1.0, 0.0)]))],
+            expect: [],
+        )"#).unwrap();
+        assert_eq!(fixture.run(), vec!["line is a polygon without an area(it needs at least 3 points)".to_string()]);
+    }
+}
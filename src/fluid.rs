@@ -0,0 +1,331 @@
+//! # Fluids and particles
+//!
+//! Enabled with the `fluid` feature.
+//!
+//! A `ParticleSystem2D` is a bunch of particles simulated using double density relaxation(a lite version of SPH),
+//! neighbours are found using a `SpatialHash`.
+//! Particles collide with static bodies, and can optionally push kinematic bodies around.
+//!
+//! ```ignore
+//! app.add_plugin(Physics2dPlugin::default())
+//!     .add_plugin(FluidPlugin);
+//!
+//! let mut water = ParticleSystem2D::new(ParticleKind::Water, 4.0)
+//!     .with_gravity(Vec2::new(0.0, -500.0));
+//! water.add_block(Vec2::new(-100.0, 0.0), 20, 20);
+//! coms.spawn().insert(water);
+//! ```
+
+use bevy::prelude::*;
+
+use crate::{
+    bodies::{Sensor, StaticBody},
+    common::VecOp,
+    manual_step::PhysicsAppExt,
+    physics_components::{CollisionLayer, Transform2D},
+    plugin::stage,
+    shapes::{collide, push_out_circle, Circle, CollisionShape},
+    spatial_hash::SpatialHash,
+};
+
+/// Adds the particle simulation, needs `Physics2dPlugin` to be added first
+pub struct FluidPlugin;
+
+impl Plugin for FluidPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_physics_system(stage::JOINT_STEP, particle_system);
+    }
+}
+
+/// How the particles behave
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleKind {
+    /// Particles attract and repel each other to keep their density
+    Water,
+    /// Particles only repel each other, and have a lot of friction
+    Sand,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Particle {
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+/**
+    # ParticleSystem2D
+
+    A group of particles simulated together, see the module docs.
+
+    The particles are in world space, so the entity holding it doesn't need a transform.
+*/
+#[derive(Component, Debug, Clone)]
+pub struct ParticleSystem2D {
+    pub kind: ParticleKind,
+    pub particles: Vec<Particle>,
+    /// Collision radius of each particle
+    pub radius: f32,
+    /// Distance at which particles affect each other(usually about 4 times the radius)
+    pub interaction_radius: f32,
+    pub rest_density: f32,
+    pub stiffness: f32,
+    pub near_stiffness: f32,
+    /// Smooths the relative velocities of neighbouring particles
+    pub viscosity: f32,
+    pub gravity: Vec2,
+    /// How much of the tangent velocity is lost when touching a body(0.0 - 1.0)
+    pub friction: f32,
+    /// How much particles push kinematic bodies(0.0 disables it, 1.0 moves the body out instead of the particle)
+    pub push_strength: f32,
+    pub layer: CollisionLayer,
+}
+
+impl ParticleSystem2D {
+    pub fn new(
+        kind: ParticleKind,
+        radius: f32,
+    ) -> Self {
+        let (stiffness, near_stiffness, viscosity, friction) = match kind {
+            ParticleKind::Water => (0.004, 0.01, 0.1, 0.05),
+            ParticleKind::Sand => (0.0, 0.02, 0.5, 0.8),
+        };
+
+        ParticleSystem2D {
+            kind,
+            particles: Vec::new(),
+            radius,
+            interaction_radius: 4.0 * radius,
+            rest_density: 3.0,
+            stiffness,
+            near_stiffness,
+            viscosity,
+            gravity: Vec2::ZERO,
+            friction,
+            push_strength: 0.0,
+            layer: CollisionLayer::default(),
+        }
+    }
+    pub fn with_gravity(
+        mut self,
+        gravity: Vec2,
+    ) -> Self {
+        self.gravity = gravity;
+        self
+    }
+    pub fn with_push_strength(
+        mut self,
+        push_strength: f32,
+    ) -> Self {
+        self.push_strength = push_strength.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn add_particle(
+        &mut self,
+        position: Vec2,
+    ) {
+        self.particles.push(Particle { position, velocity: Vec2::ZERO });
+    }
+    /// Adds a `cols * rows` block of particles starting at `origin`(bottom left), packed by their radius
+    pub fn add_block(
+        &mut self,
+        origin: Vec2,
+        cols: usize,
+        rows: usize,
+    ) {
+        let spacing = 2.0 * self.radius;
+        for y in 0..rows {
+            for x in 0..cols {
+                self.add_particle(origin + Vec2::new(x as f32, y as f32) * spacing);
+            }
+        }
+    }
+
+    /// Advances the simulation by `dt`, colliding with the given(static) shapes
+    pub fn step(
+        &mut self,
+        dt: f32,
+        statics: &[(&CollisionShape, &Transform2D)],
+    ) {
+        let h = self.interaction_radius;
+        let previous: Vec<Vec2> = self.particles.iter().map(|p| p.position).collect();
+
+        // viscosity impulses
+        let hash = SpatialHash::from_points(h, previous.iter().copied());
+        if self.viscosity > 0.0 {
+            for i in 0..self.particles.len() {
+                for j in hash.query(previous[i], h).filter(|&j| j > i) {
+                    let d = previous[j] - previous[i];
+                    let r = d.length();
+                    if r >= h || r < f32::EPSILON {
+                        continue;
+                    }
+                    let n = d / r;
+                    let u = (self.particles[i].velocity - self.particles[j].velocity).dot(n);
+                    if u > 0.0 {
+                        let impulse = n * (dt * (1.0 - r / h) * self.viscosity * u);
+                        self.particles[i].velocity -= impulse * 0.5;
+                        self.particles[j].velocity += impulse * 0.5;
+                    }
+                }
+            }
+        }
+
+        // predict positions
+        for p in self.particles.iter_mut() {
+            p.velocity += self.gravity * dt;
+            p.position += p.velocity * dt;
+        }
+
+        // double density relaxation
+        let hash = SpatialHash::from_points(h, self.particles.iter().map(|p| p.position));
+        for i in 0..self.particles.len() {
+            let pi = self.particles[i].position;
+            let neighbours: Vec<(usize, Vec2, f32)> = hash
+                .query(pi, h)
+                .filter(|&j| j != i)
+                .filter_map(|j| {
+                    let d = self.particles[j].position - pi;
+                    let q = d.length() / h;
+                    (q < 1.0 && q > f32::EPSILON).then(|| (j, d / (q * h), q))
+                })
+                .collect();
+
+            let density: f32 = neighbours.iter().map(|(_, _, q)| (1.0 - q).powi(2)).sum();
+            let near_density: f32 = neighbours.iter().map(|(_, _, q)| (1.0 - q).powi(3)).sum();
+
+            let mut pressure = self.stiffness * (density - self.rest_density);
+            if self.kind == ParticleKind::Sand {
+                pressure = pressure.max(0.0);
+            }
+            let near_pressure = self.near_stiffness * near_density;
+
+            let mut dx = Vec2::ZERO;
+            for (j, n, q) in neighbours {
+                let d = n * (dt * dt * (pressure * (1.0 - q) + near_pressure * (1.0 - q).powi(2)) * h);
+                self.particles[j].position += d * 0.5;
+                dx -= d * 0.5;
+            }
+            self.particles[i].position += dx;
+        }
+
+        // collide and update the velocities
+        for (p, prev) in self.particles.iter_mut().zip(previous.iter()) {
+            let moved = p.position;
+            p.position = push_out_circle(moved, self.radius, statics.iter().copied());
+            p.velocity = (p.position - *prev) / dt;
+
+            let push = p.position - moved;
+            if push.length_squared() > f32::EPSILON {
+                let n = push.normalize();
+                if p.velocity.dot(n) < 0.0 {
+                    p.velocity = p.velocity.slide(n);
+                }
+                p.velocity *= 1.0 - self.friction;
+            }
+        }
+    }
+
+    /// Pushes the particles out of a body, returns how much the body should move(based on `push_strength`)
+    pub fn push_body(
+        &mut self,
+        shape: &CollisionShape,
+        transform: &Transform2D,
+    ) -> Vec2 {
+        let aabb = shape.aabb(transform);
+        let circle = CollisionShape::Circle(Circle::new(self.radius));
+        let mut body_push = Vec2::ZERO;
+        let mut count = 0;
+
+        for p in self.particles.iter_mut() {
+            let pt = Transform2D::new(p.position, 0.0, Vec2::ONE);
+            if !aabb.collides(&circle.aabb(&pt)) {
+                continue;
+            }
+            if let Some(mtv) = collide(&circle, &pt, shape, transform) {
+                p.position += mtv * (1.0 - self.push_strength);
+                if let Some(n) = mtv.try_normalize() {
+                    if p.velocity.dot(n) < 0.0 {
+                        p.velocity = p.velocity.slide(n);
+                    }
+                }
+                body_push -= mtv * self.push_strength;
+                count += 1;
+            }
+        }
+
+        if count > 0 {
+            body_push / count as f32
+        }
+        else {
+            Vec2::ZERO
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn particle_system(
+    time: Res<Time>,
+    mut systems: Query<&mut ParticleSystem2D>,
+    statics: Query<(&CollisionShape, &Transform2D, &CollisionLayer), With<StaticBody>>,
+    kinematics: Query<(Entity, &CollisionShape, &CollisionLayer), (Without<StaticBody>, Without<Sensor>)>,
+    mut transforms: Query<&mut Transform2D, Without<StaticBody>>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    for mut ps in systems.iter_mut() {
+        let layer = ps.layer;
+        let shapes: Vec<_> = statics
+            .iter()
+            .filter(|(_, _, l)| l.overlap(&layer))
+            .map(|(s, t, _)| (s, t))
+            .collect();
+
+        ps.step(dt, &shapes);
+
+        if ps.push_strength <= 0.0 {
+            continue;
+        }
+        for (e, shape, l) in kinematics.iter() {
+            if !l.overlap(&layer) {
+                continue;
+            }
+            if let Ok(mut t) = transforms.get_mut(e) {
+                let push = ps.push_body(shape, &t);
+                if push != Vec2::ZERO {
+                    t.add_translation(push);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod fluid_tests {
+    use super::*;
+    use crate::shapes::Square;
+
+    #[test]
+    fn water_settles_on_floor() {
+        let floor = CollisionShape::Square(Square::size(Vec2::new(100.0, 10.0)));
+        let floor_t = Transform2D::new(Vec2::new(0.0, -5.0), 0.0, Vec2::ONE);
+
+        let mut water = ParticleSystem2D::new(ParticleKind::Water, 1.0).with_gravity(Vec2::new(0.0, -100.0));
+        water.add_block(Vec2::new(-5.0, 5.0), 5, 5);
+
+        for _ in 0..200 {
+            water.step(1.0 / 60.0, &[(&floor, &floor_t)]);
+        }
+
+        for p in water.particles.iter() {
+            assert!(p.position.y > 0.5, "particle fell through the floor({})", p.position);
+            assert!(p.position.y < 15.0);
+        }
+    }
+}
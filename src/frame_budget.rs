@@ -0,0 +1,151 @@
+//! # Frame budget
+//!
+//! When `CollisionSettings::frame_budget` is set, the broad and narrow phases stop once they used up the budget,
+//! and whatever they didn't get to(kinematic bodies in the broad phase, pairs in the narrow phase) is carried over
+//! and handled first on the next frame.
+//!
+//! The order in which things are handled is:
+//! - whatever was carried over from the previous frame
+//! - pairs which collided on the previous frame
+//! - bodies/pairs inside `FrameBudget::focus`(usually the camera view)
+//! - everything else
+//!
+//! Bodies which are skipped for a frame are simply not solved for that frame, so this trades accuracy for a stable frame rate
+//! on low end targets, and should be set high enough that it is only hit on worst case spikes.
+//!
+//! ```ignore
+//! app.insert_resource(CollisionSettings {
+//!     frame_budget: Some(Duration::from_millis(4)),
+//!     ..Default::default()
+//! });
+//! ```
+
+use bevy::{
+    prelude::*,
+    utils::{Duration, HashSet, Instant},
+};
+
+use crate::{pair_key::PairKey, shapes::Aabb};
+
+/**
+    # FrameBudget
+
+    Bookkeeping of the frame budget, see the module docs.
+
+    Only `focus` is meant to be set by the user, the rest is for reading.
+*/
+#[derive(Debug, Default)]
+pub struct FrameBudget {
+    /// Bodies(and pairs with a body) inside this area are handled before the rest
+    pub focus: Option<Aabb>,
+    started: Option<Instant>,
+    pub(crate) deferred_bodies: Vec<Entity>,
+    pub(crate) deferred_kin: Vec<(Entity, Entity)>,
+    pub(crate) deferred_static: Vec<(Entity, Entity)>,
+    pub(crate) deferred_sensor: Vec<(Entity, Entity)>,
+    pub(crate) recent: HashSet<PairKey>,
+}
+
+impl FrameBudget {
+    /// Starts counting the frame budget, called at the start of the broad phase
+    pub(crate) fn start(&mut self) {
+        self.started = Some(Instant::now());
+    }
+
+    /// Whether the budget was used up(always `false` when there is no budget)
+    pub(crate) fn exceeded(
+        &self,
+        budget: Option<Duration>,
+    ) -> bool {
+        match (budget, self.started) {
+            (Some(b), Some(s)) => s.elapsed() >= b,
+            _ => false,
+        }
+    }
+
+    /// How many bodies and pairs were carried over to the next frame
+    pub fn deferred(&self) -> usize {
+        self.deferred_bodies.len() + self.deferred_kin.len() + self.deferred_static.len() + self.deferred_sensor.len()
+    }
+
+    /// Drops everything carried over or remembered about `entity`(when it was teleported)
+    pub(crate) fn forget(
+        &mut self,
+        entity: Entity,
+    ) {
+        self.deferred_bodies.retain(|e| *e != entity);
+        for pairs in [&mut self.deferred_kin, &mut self.deferred_static, &mut self.deferred_sensor] {
+            pairs.retain(|(e1, e2)| *e1 != entity && *e2 != entity);
+        }
+        self.recent.retain(|p| !p.contains(entity));
+    }
+
+    /// Drops the carried over pairs of `entity` with the bodies `keep` returns false for(when its layer changed)
+    pub(crate) fn forget_pairs(
+        &mut self,
+        entity: Entity,
+        keep: impl Fn(Entity) -> bool,
+    ) {
+        for pairs in [&mut self.deferred_kin, &mut self.deferred_static, &mut self.deferred_sensor] {
+            pairs.retain(|(e1, e2)| match (*e1 == entity, *e2 == entity) {
+                (true, _) => keep(*e2),
+                (_, true) => keep(*e1),
+                _ => true,
+            });
+        }
+        self.recent.retain(|p| !p.contains(entity));
+    }
+
+    /// Sorts the pairs in the order they should be handled,
+    /// the first `carried` pairs are the ones carried over from the previous frame and stay in the front
+    pub(crate) fn order_pairs(
+        &self,
+        pairs: &mut Vec<(Entity, Entity)>,
+        carried: usize,
+        in_focus: impl Fn(Entity) -> bool,
+    ) {
+        // the same pair can be both carried over and found again this frame
+        let mut seen = HashSet::default();
+        pairs.retain(|p| seen.insert(*p));
+
+        let focus = self.focus.is_some();
+        pairs[carried..].sort_by_cached_key(|(e1, e2)| {
+            (
+                !self.recent.contains(&PairKey::new(*e1, *e2)),
+                !(focus && (in_focus(*e1) || in_focus(*e2))),
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod frame_budget_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn carries_over_to_next_frame() {
+        let mut app = PhysicsTestApp::new();
+        // a zero budget only lets a single body/pair through each frame
+        app.app.insert_resource(CollisionSettings {
+            frame_budget: Some(Duration::ZERO),
+            ..Default::default()
+        });
+
+        app.spawn_at(Vec2::ZERO, StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(20.0, 1.0))),
+            ..Default::default()
+        });
+        let bodies: Vec<Entity> = (0..3)
+            .map(|i| app.spawn_at(Vec2::new(i as f32 * 4.0 - 4.0, 1.0), KinematicBundle::default()))
+            .collect();
+
+        app.step();
+        assert!(app.app.world.resource::<FrameBudget>().deferred() > 0);
+
+        app.step_n(5);
+        for b in bodies {
+            assert!(app.position(b).y > 1.499);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    bodies::Sensor,
+    physics_components::CollisionLayer,
+    plugin::CollisionEvent,
+};
+
+/**
+    # ContactDamage
+
+    Damages whatever touches the body - spikes, enemies hurting the player on contact, lava(as a `Sensor`).
+
+    Every frame a body collides with it(by `CollisionEvent`s, or for a sensor every body in `Sensor::bodies`),
+    a `DamageEvent` of `amount` is sent for that body, unless it was already damaged by this body in the last `cooldown` seconds
+    (so standing on spikes hurts once every `cooldown`, `0.0` hurts on every frame).
+    Only bodies on one of the layers in `layer_filter`(against the bits of their `CollisionLayer::layer`) are damaged.
+
+    ```ignore
+    coms.spawn_bundle(StaticBundle::default())
+        .insert(ContactDamage::new(10.0, 0.5).with_layer_filter(PLAYER_LAYER));
+
+    fn hurt(mut damage: EventReader<DamageEvent>, mut health: Query<&mut Health>) {
+        for ev in damage.iter() {
+            if let Ok(mut h) = health.get_mut(ev.target) {
+                h.0 -= ev.amount;
+            }
+        }
+    }
+    ```
+*/
+#[derive(Component, Debug, Clone)]
+pub struct ContactDamage {
+    pub amount: f32,
+    /// Seconds before the same body can be damaged again
+    pub cooldown: f32,
+    /// Layers of the bodies which are damaged
+    pub layer_filter: u8,
+    /// Time left until each recently damaged body can be damaged again
+    cooldowns: HashMap<Entity, f32>,
+}
+
+impl ContactDamage {
+    pub fn new(
+        amount: f32,
+        cooldown: f32,
+    ) -> Self {
+        ContactDamage {
+            amount,
+            cooldown,
+            layer_filter: u8::MAX,
+            cooldowns: HashMap::default(),
+        }
+    }
+    pub fn with_layer_filter(
+        mut self,
+        layer_filter: u8,
+    ) -> Self {
+        self.layer_filter = layer_filter;
+        self
+    }
+    /// Whether `target` was damaged recently enough to still be in its cooldown
+    pub fn is_cooling_down(
+        &self,
+        target: Entity,
+    ) -> bool {
+        self.cooldowns.contains_key(&target)
+    }
+}
+
+/// Sent when a `ContactDamage` body damages another body
+#[derive(Debug, Clone, Copy)]
+pub struct DamageEvent {
+    /// The body with the `ContactDamage`
+    pub source: Entity,
+    pub target: Entity,
+    pub amount: f32,
+}
+
+/// Sends the `DamageEvent`s for this frame's contacts, runs after the collisions(and sensors) of the frame
+pub fn contact_damage_system(
+    time: Res<Time>,
+    mut collisions: EventReader<CollisionEvent>,
+    mut damage: EventWriter<DamageEvent>,
+    mut sources: Query<(Entity, &mut ContactDamage, Option<&Sensor>)>,
+    layers: Query<&CollisionLayer>,
+) {
+    let dt = time.delta_seconds();
+    for (_, mut cd, _) in sources.iter_mut() {
+        if !cd.cooldowns.is_empty() {
+            cd.cooldowns.retain(|_, left| {
+                *left -= dt;
+                *left > 0.0
+            });
+        }
+    }
+
+    let mut hurt = |source: Entity, target: Entity, sources: &mut Query<(Entity, &mut ContactDamage, Option<&Sensor>)>| {
+        let mut cd = match sources.get_mut(source) {
+            Ok((_, cd, _)) => cd,
+            Err(_) => return,
+        };
+        let on_layer = layers.get(target).map_or(false, |l| l.layer & cd.layer_filter != 0);
+        if !on_layer || cd.cooldowns.contains_key(&target) {
+            return;
+        }
+        let cooldown = cd.cooldown;
+        cd.cooldowns.insert(target, cooldown);
+        damage.send(DamageEvent {
+            source,
+            target,
+            amount: cd.amount,
+        });
+    };
+
+    for ev in collisions.iter() {
+        hurt(ev.entity_a, ev.entity_b, &mut sources);
+        hurt(ev.entity_b, ev.entity_a, &mut sources);
+    }
+
+    let in_sensors = sources
+        .iter()
+        .filter_map(|(e, _, s)| s.map(|s| (e, s.bodies.clone())))
+        .collect::<Vec<_>>();
+    for (se, bodies) in in_sensors {
+        for b in bodies {
+            hurt(se, b, &mut sources);
+        }
+    }
+}
+
+#[cfg(test)]
+mod damage_tests {
+    use super::*;
+    use crate::{gameplay::GameplayPlugin, prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn damages_with_cooldown() {
+        let mut app = PhysicsTestApp::new();
+        app.app.add_plugin(GameplayPlugin);
+        let spikes = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        app.app.world.entity_mut(spikes).insert(ContactDamage::new(10.0, 0.5));
+        // lava which only burns the second layer
+        let lava = app.spawn_at(Vec2::new(0.0, 2.0), SensorBundle::default());
+        app.app.world.entity_mut(lava).insert(ContactDamage::new(1.0, 0.0).with_layer_filter(0b10));
+
+        // pressing down on the spikes for a second and a third
+        let player = app.spawn_at(Vec2::new(0.0, 2.0), KinematicBundle {
+            vel: Vel(Vec2::new(0.0, -1.0)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(player).insert(Acc::default());
+
+        let mut reader = app.app.world.resource::<Events<DamageEvent>>().get_reader();
+        let mut events = Vec::new();
+        for _ in 0..80 {
+            app.step();
+            // keeps pushing into the spikes
+            app.app.world.get_mut::<Vel>(player).unwrap().0 = Vec2::new(0.0, -1.0);
+            events.extend(reader.iter(app.app.world.resource::<Events<DamageEvent>>()).map(|ev| (ev.source, ev.target, ev.amount)));
+        }
+        // on the first contact, then half a second and a second later
+        assert_eq!(events, vec![(spikes, player, 10.0); 3]);
+        assert!(app.app.world.get::<ContactDamage>(spikes).unwrap().is_cooling_down(player));
+
+        // on the lava's layer now
+        app.app.world.get_mut::<CollisionLayer>(player).unwrap().layer = 0b11;
+        app.app.world.get_mut::<Transform>(player).unwrap().translation.y = 2.0;
+        app.step_n(3);
+        let burns = reader
+            .iter(app.app.world.resource::<Events<DamageEvent>>())
+            .filter(|ev| ev.source == lava)
+            .count();
+        assert!(burns >= 2, "{}", burns);
+    }
+}
@@ -0,0 +1,240 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::prelude::*;
+
+use crate::{
+    bodies::{PhysicsDisabled, Sensor},
+    physics_components::{CollisionLayer, Transform2D},
+    shapes::{collide, CollisionShape, Polygon},
+};
+
+/// Most the arc of a single sector polygon spans(so it stays convex)
+const MAX_SECTOR: f32 = FRAC_PI_2;
+/// Most the arc between 2 points on the sector's edge spans
+const MAX_SEGMENT: f32 = PI / 16.0;
+
+/**
+    # ArcAttack
+
+    A melee swing around a body(anything with a `Transform2D`), sweeping a sector of `radius`
+    from `facing - arc / 2` to `facing + arc / 2`(relative to the body's rotation, `facing = 0` is the body's `+X`) over `duration` seconds.
+
+    Each frame the slice of the sector swept since the last frame is checked against the bodies whose layer overlaps `layer`
+    (sensors and the attacker itself are skipped), and an `ArcHitEvent` is sent for each body the first time it is touched -
+    a body is hit at most once per swing, no matter for how many frames it stays in the arc.
+    A negative `arc` swings clockwise.
+
+    Inserting the component starts the swing, `restart` swings again(forgetting who was hit):
+
+    ```ignore
+    coms.entity(player).insert(ArcAttack::new(24.0, PI, 0.25).with_facing(aim_angle).with_layer(CollisionLayer::new(0b10, 0)));
+
+    fn on_hit(mut hits: EventReader<ArcHitEvent>, mut enemies: Query<&mut Health>) {
+        for hit in hits.iter() {
+            if let Ok(mut h) = enemies.get_mut(hit.target) {
+                h.0 -= 10;
+            }
+        }
+    }
+    ```
+*/
+#[derive(Component, Debug, Clone)]
+pub struct ArcAttack {
+    pub radius: f32,
+    /// Angle swept over the swing(in radians)
+    pub arc: f32,
+    /// Length of the swing in seconds
+    pub duration: f32,
+    /// Direction of the middle of the arc(in radians), relative to the body's rotation
+    pub facing: f32,
+    /// Offset of the arc's center from the body's center, rotates with the body
+    pub offset: Vec2,
+    /// Only bodies whose layer overlaps this are hit
+    pub layer: CollisionLayer,
+    elapsed: f32,
+    hit: Vec<Entity>,
+}
+
+impl ArcAttack {
+    pub fn new(
+        radius: f32,
+        arc: f32,
+        duration: f32,
+    ) -> Self {
+        ArcAttack {
+            radius,
+            arc,
+            duration,
+            facing: 0.0,
+            offset: Vec2::ZERO,
+            layer: CollisionLayer::default(),
+            elapsed: 0.0,
+            hit: Vec::new(),
+        }
+    }
+    pub fn with_facing(
+        mut self,
+        facing: f32,
+    ) -> Self {
+        self.facing = facing;
+        self
+    }
+    pub fn with_offset(
+        mut self,
+        offset: Vec2,
+    ) -> Self {
+        self.offset = offset;
+        self
+    }
+    pub fn with_layer(
+        mut self,
+        layer: CollisionLayer,
+    ) -> Self {
+        self.layer = layer;
+        self
+    }
+    /// How far along the swing is(0 to 1)
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        }
+        else {
+            (self.elapsed / self.duration).min(1.0)
+        }
+    }
+    pub fn is_done(&self) -> bool {
+        self.progress() >= 1.0
+    }
+    /// The bodies hit so far in this swing, in the order they were hit
+    pub fn hit(&self) -> &[Entity] {
+        &self.hit
+    }
+    /// Starts the swing over
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.hit.clear();
+    }
+    /// Angle of the swing's edge at `progress`, relative to the body's rotation
+    fn angle_at(
+        &self,
+        progress: f32,
+    ) -> f32 {
+        self.facing - self.arc * 0.5 + self.arc * progress
+    }
+}
+
+/// Sent the first time an `ArcAttack` touches a body in a swing
+#[derive(Debug, Clone, Copy)]
+pub struct ArcHitEvent {
+    pub attacker: Entity,
+    pub target: Entity,
+    /// From the arc's center towards the target's center(normalized), for knockback
+    pub direction: Vec2,
+}
+
+/// The sector between the angles `from` and `to`(in world space) as convex polygons,
+/// whose outer edge is just outside the arc so nothing touching the arc is missed
+fn sector(
+    center: Vec2,
+    radius: f32,
+    from: f32,
+    to: f32,
+) -> Vec<CollisionShape> {
+    let span = to - from;
+    let pieces = (span.abs() / MAX_SECTOR).ceil().max(1.0) as usize;
+    let segments = (span.abs() / pieces as f32 / MAX_SEGMENT).ceil().max(1.0) as usize;
+    let step = span / (pieces * segments) as f32;
+    let radius = radius / (step * 0.5).cos();
+
+    (0..pieces)
+        .map(|p| {
+            let mut verts = vec![center];
+            verts.extend((0..=segments).map(|s| {
+                let a = from + step * (p * segments + s) as f32;
+                center + Vec2::new(a.cos(), a.sin()) * radius
+            }));
+            CollisionShape::Polygon(Polygon::new(verts))
+        })
+        .collect()
+}
+
+/// Advances the swings and sends an `ArcHitEvent` for each body newly touched
+#[allow(clippy::type_complexity)]
+pub fn arc_attack_system(
+    time: Res<Time>,
+    mut hits: EventWriter<ArcHitEvent>,
+    mut attacks: Query<(Entity, &mut ArcAttack, &Transform2D), Without<PhysicsDisabled>>,
+    bodies: Query<(Entity, &CollisionShape, &Transform2D, Option<&CollisionLayer>), (Without<Sensor>, Without<PhysicsDisabled>)>,
+) {
+    let dt = time.delta_seconds();
+
+    for (ae, mut attack, t) in attacks.iter_mut() {
+        if attack.is_done() || attack.radius <= 0.0 {
+            continue;
+        }
+        let before = attack.progress();
+        attack.elapsed += dt;
+        let after = attack.progress();
+        // the first frame has no time passed in it
+        if after == before {
+            continue;
+        }
+
+        let center = t.translation() + t.rot_matrix() * attack.offset;
+        let from = t.rotation() + attack.angle_at(before);
+        let to = t.rotation() + attack.angle_at(after);
+        let slice = sector(center, attack.radius, from, to);
+        let identity = Transform2D::default();
+
+        for (be, bs, bt, bl) in bodies.iter() {
+            if be == ae || attack.hit.contains(&be) || !bl.map_or(true, |l| l.overlap(&attack.layer)) {
+                continue;
+            }
+            if slice.iter().any(|s| collide(s, &identity, bs, bt).is_some()) {
+                attack.hit.push(be);
+                hits.send(ArcHitEvent {
+                    attacker: ae,
+                    target: be,
+                    direction: (bt.translation() - center).normalize_or_zero(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod melee_tests {
+    use super::*;
+    use crate::{gameplay::GameplayPlugin, prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn hits_once_per_swing() {
+        let mut app = PhysicsTestApp::new();
+        app.app.add_plugin(GameplayPlugin);
+        let target = || KinematicBundle {
+            shape: CollisionShape::Circle(Circle::new(0.5)),
+            ..Default::default()
+        };
+        let right = app.spawn_at(Vec2::new(3.0, 0.0), target());
+        let above = app.spawn_at(Vec2::new(0.0, 3.0), target());
+        let _below = app.spawn_at(Vec2::new(0.0, -3.0), target());
+        let _far = app.spawn_at(Vec2::new(-6.0, 0.0), target());
+        // facing up, from the right side to the left side over half a second
+        let player = app.spawn_at(Vec2::ZERO, KinematicBundle::default());
+        app.app.world.entity_mut(player).insert(ArcAttack::new(4.0, PI, 0.5).with_facing(FRAC_PI_2));
+
+        let mut reader = app.app.world.resource::<Events<ArcHitEvent>>().get_reader();
+        let mut targets = Vec::new();
+        for _ in 0..40 {
+            app.step();
+            targets.extend(reader.iter(app.app.world.resource::<Events<ArcHitEvent>>()).map(|ev| (ev.attacker, ev.target)));
+        }
+        assert_eq!(targets, vec![(player, right), (player, above)]);
+        assert!(app.app.world.get::<ArcAttack>(player).unwrap().is_done());
+
+        // swinging again hits them again
+        app.app.world.get_mut::<ArcAttack>(player).unwrap().restart();
+        app.step_n(40);
+        assert_eq!(app.app.world.get::<ArcAttack>(player).unwrap().hit(), &[right, above]);
+    }
+}
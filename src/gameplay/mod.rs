@@ -0,0 +1,37 @@
+//! # Gameplay helpers
+//!
+//! Opt-in building blocks most action games end up writing on top of the physics, added with `GameplayPlugin`:
+//!
+//! - `ArcAttack` - melee swings sweeping a sector around a body, hitting each target once per swing
+//! - `ContactDamage` - damaging whatever touches a body(spikes, lava), once every cooldown per body
+//!
+//! They only use what the physics already provide(shapes, layers, the bodies' `Transform2D`s), so they can be
+//! copied and tweaked for a game which needs them to work differently.
+
+use bevy::prelude::*;
+
+use crate::{
+    manual_step::PhysicsAppExt,
+    plugin::{stage, PhysicsSystem},
+};
+
+pub mod damage;
+pub mod melee;
+
+pub use damage::{contact_damage_system, ContactDamage, DamageEvent};
+pub use melee::{arc_attack_system, ArcAttack, ArcHitEvent};
+
+/// Adds the gameplay helpers' systems and events, needs `Physics2dPlugin` to be added first
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_event::<ArcHitEvent>().add_event::<DamageEvent>();
+        // the bodies are where the collision solve left them, and the sensors are filled
+        app.add_physics_system(stage::COLLISION_DETECTION, arc_attack_system.after(PhysicsSystem::Collision));
+        app.add_physics_system(stage::COLLISION_DETECTION, contact_damage_system.after(PhysicsSystem::Collision));
+    }
+}
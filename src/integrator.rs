@@ -0,0 +1,166 @@
+//! # Integrators
+//!
+//! Integration moves bodies with both `Vel` and `Acc` based on the accumulated acceleration.
+//!
+//! The scheme is picked using the `PhysicsIntegrator` resource(`SemiImplicitEuler` by default),
+//! insert it before adding the plugin to change it:
+//!
+//! ```ignore
+//! app.insert_resource(PhysicsIntegrator::new(VelocityVerlet))
+//!     .add_plugin(Physics2dPlugin::default());
+//! ```
+//!
+//! Custom schemes can be added by implementing `Integrator`.
+
+use crate::math::Vec2;
+use crate::physics_components::Acc;
+#[cfg(feature = "ecs")]
+use bevy::prelude::*;
+#[cfg(feature = "ecs")]
+use crate::{bodies::{Frozen, PhysicsDisabled}, physics_components::{Transform2D, Vel}};
+
+/// An integration scheme
+pub trait Integrator: Send + Sync + 'static {
+    /// Advances the velocity by `dt` and returns how much the body moved
+    ///
+    /// `acc.current` is the acceleration accumulated this frame,
+    /// `acc.previous` and `acc.previous_dt` are from the last step of this body.
+    fn step(
+        &self,
+        vel: &mut Vec2,
+        acc: &Acc,
+        dt: f32,
+    ) -> Vec2;
+}
+
+/**
+    # SemiImplicitEuler
+
+    Updates the velocity first and then moves the body using the new velocity.
+
+    Cheap and stable(energy stays bounded), the default integrator.
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SemiImplicitEuler;
+
+impl Integrator for SemiImplicitEuler {
+    fn step(
+        &self,
+        vel: &mut Vec2,
+        acc: &Acc,
+        dt: f32,
+    ) -> Vec2 {
+        *vel += acc.current * dt;
+        *vel * dt
+    }
+}
+
+/**
+    # VelocityVerlet
+
+    Second order integrator, better suited for orbits and springs where energy should be conserved.
+
+    The velocity update needs the acceleration at the new position, which is only known on the next frame,
+    so `Vel` is completed at the start of the next step -
+    after a step `Vel` holds the velocity of the body at its position before the step.
+
+    Integration is exact for constant accelerations.
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VelocityVerlet;
+
+impl Integrator for VelocityVerlet {
+    fn step(
+        &self,
+        vel: &mut Vec2,
+        acc: &Acc,
+        dt: f32,
+    ) -> Vec2 {
+        // finish the last step's velocity now that we know the acceleration at the current position
+        *vel += 0.5 * (acc.previous + acc.current) * acc.previous_dt;
+
+        *vel * dt + 0.5 * acc.current * dt * dt
+    }
+}
+
+/// The integrator used by the plugin, `SemiImplicitEuler` by default
+#[cfg(feature = "ecs")]
+pub struct PhysicsIntegrator(pub Box<dyn Integrator>);
+
+#[cfg(feature = "ecs")]
+impl PhysicsIntegrator {
+    pub fn new(integrator: impl Integrator) -> Self {
+        PhysicsIntegrator(Box::new(integrator))
+    }
+}
+
+#[cfg(feature = "ecs")]
+impl Default for PhysicsIntegrator {
+    fn default() -> Self {
+        PhysicsIntegrator::new(SemiImplicitEuler)
+    }
+}
+
+/// Integrates all the bodies with `Vel` and `Acc`, and clears their accumulated acceleration
+#[cfg(feature = "ecs")]
+pub fn integrate_system(
+    time: Res<Time>,
+    integrator: Res<PhysicsIntegrator>,
+    mut q: Query<(&mut Transform2D, &mut Vel, &mut Acc, Option<&Frozen>), Without<PhysicsDisabled>>,
+    mut disabled: Query<&mut Acc, With<PhysicsDisabled>>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    // forces applied to disabled bodies are dropped, so they don't get them all at once when enabled again
+    for mut acc in disabled.iter_mut() {
+        acc.current = Vec2::ZERO;
+    }
+
+    for (mut t, mut v, mut acc, frozen) in q.iter_mut() {
+        let moved = integrator.0.step(&mut v.0, &acc, dt);
+        match frozen {
+            Some(f) => {
+                v.0 = f.mask(v.0);
+                t.add_translation(f.mask(moved));
+            },
+            None => t.add_translation(moved),
+        }
+
+        acc.previous = acc.current;
+        acc.previous_dt = dt;
+        acc.current = Vec2::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod integrator_tests {
+    use super::*;
+
+    /// Falls for 1 second under constant acceleration, returns the distance
+    fn fall(integrator: &dyn Integrator) -> f32 {
+        let dt = 0.1;
+        let mut acc = Acc::default();
+        let mut vel = Vec2::ZERO;
+        let mut pos = Vec2::ZERO;
+
+        for _ in 0..10 {
+            acc.current = Vec2::new(0.0, -10.0);
+            pos += integrator.step(&mut vel, &acc, dt);
+
+            acc.previous = acc.current;
+            acc.previous_dt = dt;
+        }
+        pos.y
+    }
+
+    #[test]
+    fn constant_acceleration() {
+        // 0.5 * a * t^2 = -5.0
+        assert!((fall(&VelocityVerlet) + 5.0).abs() < 0.0001);
+        // semi implicit euler overshoots by 0.5 * a * t * dt
+        assert!((fall(&SemiImplicitEuler) + 5.5).abs() < 0.0001);
+    }
+}
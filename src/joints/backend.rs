@@ -0,0 +1,140 @@
+use crate::math::Vec2;
+
+use super::{ConstraintPass, SolverSettings};
+
+/// A way of running the constraint phases each step, picked using the `PhysicsSolver` resource
+///
+/// Custom backends can call the phases of `ConstraintPass` in any order(and as many times) they like.
+pub trait SolverBackend: Send + Sync + 'static {
+    fn solve(
+        &self,
+        pass: &mut ConstraintPass,
+        settings: &SolverSettings,
+        dt: f32,
+    );
+}
+
+/**
+    # ImpulseSolver
+
+    Corrects the velocities first(`SolverSettings::velocity_iterations` times),
+    then fixes whatever drift is left in the positions(`SolverSettings::position_iterations` times).
+
+    The default backend.
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImpulseSolver;
+
+impl SolverBackend for ImpulseSolver {
+    fn solve(
+        &self,
+        pass: &mut ConstraintPass,
+        settings: &SolverSettings,
+        dt: f32,
+    ) {
+        pass.prepare(dt);
+        for _ in 0..settings.velocity_iterations {
+            pass.solve_velocity(dt);
+        }
+        for _ in 0..settings.position_iterations {
+            pass.solve_position(dt);
+        }
+    }
+}
+
+/**
+    # PbdSolver
+
+    Position based dynamics - projects the positions onto the constraints(`SolverSettings::position_iterations` times),
+    and derives the velocities from how much the bodies actually moved this step.
+
+    Tends to be more stable for stacks and chains of joints at low iteration counts,
+    `SolverSettings::velocity_iterations` is ignored.
+
+    `Constraint::solve_velocity` is still called once(before the projection) so forces like springs keep working.
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PbdSolver;
+
+impl SolverBackend for PbdSolver {
+    fn solve(
+        &self,
+        pass: &mut ConstraintPass,
+        settings: &SolverSettings,
+        dt: f32,
+    ) {
+        pass.prepare(dt);
+
+        // where the bodies started the step, and how fast they were going
+        let start: Vec<(Vec2, Vec2)> = pass.bodies().iter().map(|b| (b.position - b.vel * dt, b.vel)).collect();
+
+        // forces are applied through the velocities, so move them into the predicted positions as well
+        pass.solve_velocity(dt);
+        for (b, (_, vel)) in pass.bodies().iter_mut().zip(start.iter()) {
+            b.position += (b.vel - *vel) * dt;
+        }
+
+        for _ in 0..settings.position_iterations {
+            pass.solve_position(dt);
+        }
+
+        for (b, (pos, _)) in pass.bodies().iter_mut().zip(start.iter()) {
+            if b.inv_mass > 0.0 {
+                b.vel = (b.position - *pos) / dt;
+            }
+        }
+    }
+}
+
+/// The solver backend used by the joint solver, `ImpulseSolver` by default
+///
+/// Insert it before adding the plugin to change it:
+///
+/// ```ignore
+/// app.insert_resource(PhysicsSolver::new(PbdSolver))
+///     .add_plugin(Physics2dPlugin::default());
+/// ```
+pub struct PhysicsSolver(pub Box<dyn SolverBackend>);
+
+impl PhysicsSolver {
+    pub fn new(backend: impl SolverBackend) -> Self {
+        PhysicsSolver(Box::new(backend))
+    }
+}
+
+impl Default for PhysicsSolver {
+    fn default() -> Self {
+        PhysicsSolver::new(ImpulseSolver)
+    }
+}
+
+#[cfg(test)]
+mod backend_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn pbd_pendulum_keeps_length() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(PhysicsSolver::new(PbdSolver));
+
+        let bob = app.spawn_at(Vec2::new(2.0, 0.0), KinematicBundle {
+            shape: CollisionShape::Circle(Circle::new(0.1)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(bob).insert(Acc::default());
+        app.app.world.spawn().insert(DistanceJoint::new(bob, None, 2.0));
+
+        for _ in 0..60 {
+            app.app.world.get_mut::<Acc>(bob).unwrap().add(Vec2::new(0.0, -10.0));
+            app.step();
+        }
+
+        let p = app.position(bob);
+        assert!((p.length() - 2.0).abs() < 0.01);
+        assert!(p.y < -0.5);
+        // the derived velocity is along the circle
+        let v = app.app.world.get::<Vel>(bob).unwrap().0;
+        assert!(v.dot(p.normalize()).abs() < 0.5);
+    }
+}
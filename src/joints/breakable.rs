@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+
+/**
+    # Breakable
+
+    Add it next to a joint(any `Constraint`) to break it once the solver pushes on the bodies too hard,
+    the joint's entity is despawned and a `JointBrokenEvent` is sent.
+
+    The force is the impulse the joint applied this step(the bigger of its velocity or position corrections, times the body's mass)
+    divided by the step's length.
+    The torque is measured the same way from the rotation the joint corrected, times the body's moment of inertia
+    (`CollisionShape::moment_of_inertia` for its `Mass`, a body without a shape counts as having all of its mass a unit away from its center).
+
+    Whatever the joint did to its bodies in the step it breaks is undone, so a breaking joint doesn't pull on them one last time.
+*/
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Breakable {
+    pub break_force: f32,
+    pub break_torque: f32,
+}
+
+impl Breakable {
+    /// Breaks above `break_force`, never breaks because of torque
+    pub fn new(break_force: f32) -> Self {
+        Breakable {
+            break_force,
+            break_torque: f32::INFINITY,
+        }
+    }
+    pub fn with_torque(
+        mut self,
+        break_torque: f32,
+    ) -> Self {
+        self.break_torque = break_torque;
+        self
+    }
+}
+
+/// Sent when a `Breakable` joint breaks(its entity is already despawned by then)
+#[derive(Debug, Clone, Copy)]
+pub struct JointBrokenEvent {
+    pub joint: Entity,
+    /// The bodies the joint connected
+    pub bodies: (Entity, Option<Entity>),
+    /// The force which broke the joint
+    pub force: f32,
+    pub torque: f32,
+}
+
+/// What a breakable joint applied on its bodies this step
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AppliedImpulse {
+    pub limits: Breakable,
+    pub bodies: (Entity, Option<Entity>),
+    /// From the velocity corrections
+    pub impulse: Vec2,
+    /// From the position corrections
+    pub correction: Vec2,
+    pub angular: f32,
+    /// How much the joint moved, turned and sped up each of its bodies this step(position, rotation, vel), undone if it breaks
+    pub moved: [(Vec2, f32, Vec2); 2],
+}
+
+#[cfg(test)]
+mod breakable_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    /// Hangs a body from the world, returns whether the joint broke
+    fn hang(break_force: f32) -> bool {
+        let mut app = PhysicsTestApp::new();
+
+        let bob = app.spawn_at(Vec2::new(0.0, -2.0), KinematicBundle {
+            shape: CollisionShape::Circle(Circle::new(0.1)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(bob).insert(Acc::default());
+        let joint = app
+            .app
+            .world
+            .spawn()
+            .insert(DistanceJoint::new(bob, None, 2.0))
+            .insert(Breakable::new(break_force))
+            .id();
+
+        // the first frame has no delta time, so the acceleration would pile up
+        app.step();
+
+        let mut reader = app.app.world.resource::<Events<JointBrokenEvent>>().get_reader();
+        let mut broken = 0;
+        for _ in 0..10 {
+            app.app.world.get_mut::<Acc>(bob).unwrap().add(Vec2::new(0.0, -10.0));
+            app.step();
+
+            let events = app.app.world.resource::<Events<JointBrokenEvent>>();
+            broken += reader.iter(events).filter(|e| e.joint == joint).count();
+        }
+
+        assert_eq!(broken == 1, app.app.world.get_entity(joint).is_none());
+        broken == 1
+    }
+
+    #[test]
+    fn breaks_above_force() {
+        // holding the body against gravity takes a force of about 10
+        assert!(hang(5.0));
+        assert!(!hang(15.0));
+    }
+
+    #[test]
+    fn broken_joint_doesnt_pull() {
+        let mut app = PhysicsTestApp::new();
+        let bob = app.spawn_at(Vec2::new(0.0, -2.0), KinematicBundle::default());
+        app.app.world.entity_mut(bob).insert(Acc::default());
+        app.app.world.spawn().insert(DistanceJoint::new(bob, None, 2.0)).insert(Breakable::new(5.0));
+        app.step();
+
+        // the joint breaks in the first step it holds the body up, so the body falls like it was never there
+        app.app.world.get_mut::<Acc>(bob).unwrap().add(Vec2::new(0.0, -10.0));
+        app.step();
+        let vel = app.app.world.get::<Vel>(bob).unwrap().0;
+        assert!((vel.y + 10.0 * app.dt).abs() < 0.0001, "{}", vel);
+    }
+
+    #[test]
+    fn breaks_in_entity_order() {
+        let mut app = PhysicsTestApp::new();
+        let joints = (0..16)
+            .map(|i| {
+                let bob = app.spawn_at(Vec2::new(i as f32 * 3.0, -2.0), KinematicBundle::default());
+                app.app.world.entity_mut(bob).insert(Acc::default());
+                app.app.world.spawn().insert(DistanceJoint::new(bob, None, 2.0)).insert(Breakable::new(5.0)).id()
+            })
+            .collect::<Vec<_>>();
+        app.step();
+
+        // all of them break in the same step
+        let mut reader = app.app.world.resource::<Events<JointBrokenEvent>>().get_reader();
+        let mut broken = Vec::new();
+        for _ in 0..3 {
+            for j in joints.iter() {
+                let bob = app.app.world.get::<DistanceJoint>(*j).map(|j| j.a);
+                if let Some(bob) = bob {
+                    app.app.world.get_mut::<Acc>(bob).unwrap().add(Vec2::new(0.0, -10.0));
+                }
+            }
+            app.step();
+            broken.extend(reader.iter(app.app.world.resource::<Events<JointBrokenEvent>>()).map(|e| e.joint));
+        }
+        assert_eq!(broken, joints);
+    }
+
+    /// Turns a gear of `radius` with a motor, returns whether the joint broke
+    fn turn(
+        radius: f32,
+        break_torque: f32,
+    ) -> bool {
+        let mut app = PhysicsTestApp::new();
+        let motor = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        let gear = app.spawn_at(Vec2::new(3.0, 0.0), KinematicBundle {
+            shape: CollisionShape::Circle(Circle::new(radius)),
+            ..Default::default()
+        });
+        let joint = app
+            .app
+            .world
+            .spawn()
+            .insert(GearJoint::new(motor, gear, 1.0))
+            .insert(Breakable::new(f32::INFINITY).with_torque(break_torque))
+            .id();
+
+        for i in 0..=5 {
+            app.app.world.get_mut::<Transform>(motor).unwrap().rotation = Quat::from_rotation_z(0.1 * i as f32);
+            app.step();
+        }
+        app.app.world.get_entity(joint).is_none()
+    }
+
+    #[test]
+    fn torque_uses_moment_of_inertia() {
+        // turning a gear by 0.1 each step takes a torque of 0.1 / dt^2 times its moment of inertia(mr^2 / 2)
+        let torque = 0.1 * 60.0 * 60.0 * 0.5;
+        assert!(turn(2.0, torque));
+        assert!(!turn(0.5, torque));
+    }
+}
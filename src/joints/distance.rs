@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+
+use crate::math::Mat2;
+
+use super::{BodyState, Constraint};
+
+/**
+    # DistanceJoint
+
+    Keeps the anchors of 2 bodies at a fixed distance from each other.
+
+    Anchors are relative to the bodies(and rotated with them),
+    when `b` is `None` the joint is attached to the world and `anchor_b` is a world position.
+
+    A `max_only` joint acts like a rope - the bodies can get closer, but not further than `length`.
+*/
+#[derive(Component, Clone, Debug)]
+pub struct DistanceJoint {
+    pub a: Entity,
+    pub b: Option<Entity>,
+    pub anchor_a: Vec2,
+    pub anchor_b: Vec2,
+    pub length: f32,
+    /// Only limits the maximum distance
+    pub max_only: bool,
+}
+
+impl DistanceJoint {
+    /// Distance joint between the centers of the bodies
+    pub fn new(
+        a: Entity,
+        b: Option<Entity>,
+        length: f32,
+    ) -> Self {
+        DistanceJoint {
+            a,
+            b,
+            anchor_a: Vec2::ZERO,
+            anchor_b: Vec2::ZERO,
+            length,
+            max_only: false,
+        }
+    }
+    pub fn with_anchors(
+        mut self,
+        anchor_a: Vec2,
+        anchor_b: Vec2,
+    ) -> Self {
+        self.anchor_a = anchor_a;
+        self.anchor_b = anchor_b;
+        self
+    }
+    /// Only limit the maximum distance(like a rope)
+    pub fn max_only(mut self) -> Self {
+        self.max_only = true;
+        self
+    }
+
+    /// World anchors, normal from a to b and current length
+    fn axis(
+        &self,
+        a: &BodyState,
+        b: &BodyState,
+    ) -> Option<(Vec2, f32)> {
+        let pa = a.position + Mat2::from_angle(a.rotation) * self.anchor_a;
+        let pb = b.position + Mat2::from_angle(b.rotation) * self.anchor_b;
+
+        let d = pb - pa;
+        let len = d.length();
+        if len < f32::EPSILON {
+            None
+        }
+        else {
+            Some((d / len, len))
+        }
+    }
+}
+
+impl Constraint for DistanceJoint {
+    fn bodies(&self) -> (Entity, Option<Entity>) {
+        (self.a, self.b)
+    }
+
+    fn solve_velocity(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        _dt: f32,
+    ) {
+        let w = a.inv_mass + b.inv_mass;
+        let (n, len) = match self.axis(a, b) {
+            Some(x) if w > 0.0 => x,
+            _ => return,
+        };
+
+        let vr = (b.vel - a.vel).dot(n);
+        // a slack rope doesn't care, and a taut one only stops the bodies from separating
+        if self.max_only && (len < self.length || vr < 0.0) {
+            return;
+        }
+
+        let impulse = n * (-vr / w);
+        a.apply_impulse(-impulse);
+        b.apply_impulse(impulse);
+    }
+
+    fn solve_position(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        _dt: f32,
+    ) {
+        let w = a.inv_mass + b.inv_mass;
+        let (n, len) = match self.axis(a, b) {
+            Some(x) if w > 0.0 => x,
+            _ => return,
+        };
+
+        let c = len - self.length;
+        if self.max_only && c <= 0.0 {
+            return;
+        }
+
+        let correction = n * (c / w);
+        a.apply_correction(correction);
+        b.apply_correction(-correction);
+    }
+}
+
+#[cfg(test)]
+mod distance_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn pendulum_keeps_length() {
+        let mut app = PhysicsTestApp::new();
+
+        let bob = app.spawn_at(Vec2::new(2.0, 0.0), KinematicBundle {
+            shape: CollisionShape::Circle(Circle::new(0.1)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(bob).insert(Acc::default());
+        app.app.world.spawn().insert(DistanceJoint::new(bob, None, 2.0));
+
+        for _ in 0..60 {
+            app.app.world.get_mut::<Acc>(bob).unwrap().add(Vec2::new(0.0, -10.0));
+            app.step();
+        }
+
+        let p = app.position(bob);
+        assert!((p.length() - 2.0).abs() < 0.01);
+        assert!(p.y < -0.5); // actually swung down
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use super::{wrap_angle, BodyState, Constraint};
+
+/**
+    # GearJoint
+
+    Ties the rotation of 2 bodies together, like 2 meshing gears -
+    when `a` turns by some angle, `b` turns by that angle divided by `ratio` the other way.
+
+    `ratio` is the size(or number of teeth) of `b` relative to `a`, a negative ratio turns both bodies the same way(like a belt).
+    The rotations the bodies have when the joint is first solved are the starting point.
+
+    Bodies don't have angular velocity(yet), so the joint only works on the rotations(in the position phase),
+    and the body with the lower `inv_mass` turns less.
+*/
+#[derive(Component, Clone, Debug)]
+pub struct GearJoint {
+    pub a: Entity,
+    pub b: Entity,
+    pub ratio: f32,
+    /// Rotations since the joint was first solved(not wrapped) and the last rotations seen
+    turned: Option<([f32; 2], [f32; 2])>,
+}
+
+impl GearJoint {
+    pub fn new(
+        a: Entity,
+        b: Entity,
+        ratio: f32,
+    ) -> Self {
+        GearJoint {
+            a,
+            b,
+            ratio,
+            turned: None,
+        }
+    }
+}
+
+impl Constraint for GearJoint {
+    fn bodies(&self) -> (Entity, Option<Entity>) {
+        (self.a, Some(self.b))
+    }
+
+    fn solve_position(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        _dt: f32,
+    ) {
+        // rotations are wrapped, so they are followed from the last time we saw them
+        let (mut turned, last) = self.turned.unwrap_or(([0.0; 2], [a.rotation, b.rotation]));
+        turned[0] += wrap_angle(a.rotation - last[0]);
+        turned[1] += wrap_angle(b.rotation - last[1]);
+
+        let (wa, wb) = (a.inv_mass, b.inv_mass * self.ratio * self.ratio);
+        if wa + wb > 0.0 {
+            let lambda = -(turned[0] + self.ratio * turned[1]) / (wa + wb);
+            let (da, db) = (wa * lambda, b.inv_mass * self.ratio * lambda);
+
+            a.rotation += da;
+            b.rotation += db;
+            turned[0] += da;
+            turned[1] += db;
+        }
+
+        self.turned = Some((turned, [a.rotation, b.rotation]));
+    }
+}
+
+#[cfg(test)]
+mod gear_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn driven_gear_turns_back() {
+        let mut app = PhysicsTestApp::new();
+
+        let motor = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        let gear = app.spawn_at(Vec2::new(3.0, 0.0), KinematicBundle::default());
+        app.app.world.spawn().insert(GearJoint::new(motor, gear, 2.0));
+
+        for i in 0..=10 {
+            app.app.world.get_mut::<Transform>(motor).unwrap().rotation = Quat::from_rotation_z(0.1 * i as f32);
+            app.step();
+        }
+        app.step();
+
+        let rot = app.app.world.get::<Transform2D>(gear).unwrap().rotation();
+        assert!((rot + 0.5).abs() < 0.01, "rotation is {}", rot);
+    }
+}
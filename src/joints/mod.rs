@@ -0,0 +1,127 @@
+//! # Joints
+//!
+//! Joints(and any other constraint) are components implementing `Constraint`,
+//! usually living on their own entity and pointing at the bodies they connect.
+//!
+//! Every constraint type needs to be registered once using `app.add_constraint::<T>()`,
+//! after which all of them are solved together in `stage::JOINT_STEP`(after integration and before the collision detection),
+//! using the iteration counts in the `SolverSettings` resource and the backend in the `PhysicsSolver` resource
+//! (`ImpulseSolver` by default, or `PbdSolver` for position based dynamics).
+//!
+//! ```ignore
+//! app.add_plugin(Physics2dPlugin::default())
+//!     .add_constraint::<MyConstraint>();
+//! ```
+
+mod solver;
+mod backend;
+mod breakable;
+mod distance;
+mod gear;
+mod mouse;
+mod pulley;
+mod revolute;
+mod wheel;
+
+pub use solver::*;
+pub use backend::*;
+pub use breakable::*;
+pub use distance::*;
+pub use gear::*;
+pub use mouse::*;
+pub use pulley::*;
+pub use revolute::*;
+pub use wheel::*;
+
+use bevy::prelude::*;
+
+use crate::math::Vec2;
+
+/**
+    # BodyState
+
+    A copy of a body's state the constraints work on during the solver loop,
+    written back to the body's `Transform2D`(and `Vel`) once solving is done.
+
+    Bodies without `Vel`(or with `StaticBody`) have an `inv_mass` of `0.0` and are not moved by the solver,
+    constraints attached to the world(`Constraint::bodies` returned `None`) get a static body at the origin.
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BodyState {
+    pub position: Vec2,
+    pub rotation: f32,
+    pub vel: Vec2,
+    /// Inverse mass, `0.0` means the body cannot be moved
+    pub inv_mass: f32,
+}
+
+impl BodyState {
+    /// Immovable body at the origin
+    pub const STATIC: BodyState = BodyState {
+        position: Vec2::ZERO,
+        rotation: 0.0,
+        vel: Vec2::ZERO,
+        inv_mass: 0.0,
+    };
+
+    /// Applies an impulse(change in momentum) to the body
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.vel += impulse * self.inv_mass;
+    }
+    /// Moves the body by `correction` scaled by its inverse mass
+    pub fn apply_correction(&mut self, correction: Vec2) {
+        self.position += correction * self.inv_mass;
+    }
+}
+
+/**
+    # Constraint
+
+    A user defined constraint, solved by the physics solver.
+
+    Each step goes as follows:
+    - `prepare` is called once(after integration)
+    - `solve_velocity` is called `SolverSettings::velocity_iterations` times
+    - `solve_position` is called `SolverSettings::position_iterations` times
+
+    Within each iteration all the registered constraints are solved one after the other,
+    so constraints sharing a body converge together.
+
+    Body `b` is a static body at the origin when `bodies` returns `None` for it,
+    so constraints can be attached to the world as well.
+*/
+pub trait Constraint: Component {
+    /// The bodies the constraint is working on
+    fn bodies(&self) -> (Entity, Option<Entity>);
+
+    /// Called once per step before the solver loop, useful for caching anything which doesn't change between iterations
+    fn prepare(
+        &mut self,
+        _a: &BodyState,
+        _b: &BodyState,
+        _dt: f32,
+    ) {
+    }
+    /// Corrects the velocities of the bodies(usually by applying impulses)
+    fn solve_velocity(
+        &mut self,
+        _a: &mut BodyState,
+        _b: &mut BodyState,
+        _dt: f32,
+    ) {
+    }
+    /// Corrects the positions of the bodies, fixing whatever drift the velocity solving left
+    fn solve_position(
+        &mut self,
+        _a: &mut BodyState,
+        _b: &mut BodyState,
+        _dt: f32,
+    ) {
+    }
+}
+
+/// Wraps an angle into -PI..PI
+pub(crate) fn wrap_angle(a: f32) -> f32 {
+    let a = (a + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU);
+    a - std::f32::consts::PI
+}
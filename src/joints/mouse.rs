@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use crate::math::Mat2;
+
+use super::{BodyState, Constraint};
+
+/**
+    # MouseJoint
+
+    A damped spring pulling an anchor on a body towards a target point in the world,
+    mostly used for dragging bodies around with the mouse(see `MouseDragPlugin` with the `picking` feature).
+    Only bodies which are integrated(have `Vel` and `Acc`) are affected.
+
+    `anchor` is relative to the body(and rotated with it), `target` is in world space and can be moved freely every frame.
+*/
+#[derive(Component, Clone, Debug)]
+pub struct MouseJoint {
+    pub body: Entity,
+    pub anchor: Vec2,
+    pub target: Vec2,
+    /// Force per unit of distance between the anchor and the target
+    pub stiffness: f32,
+    /// Force per unit of velocity resisting the movement of the body
+    pub damping: f32,
+    /// The spring force is clamped to this
+    pub max_force: f32,
+    applied: bool,
+}
+
+impl MouseJoint {
+    /// Pulls the center of `body` towards `target`
+    pub fn new(
+        body: Entity,
+        target: Vec2,
+    ) -> Self {
+        MouseJoint {
+            body,
+            anchor: Vec2::ZERO,
+            target,
+            stiffness: 200.0,
+            damping: 28.0,
+            max_force: f32::INFINITY,
+            applied: false,
+        }
+    }
+    pub fn with_anchor(
+        mut self,
+        anchor: Vec2,
+    ) -> Self {
+        self.anchor = anchor;
+        self
+    }
+    /// Sets the stiffness and damping of the spring
+    pub fn with_spring(
+        mut self,
+        stiffness: f32,
+        damping: f32,
+    ) -> Self {
+        self.stiffness = stiffness;
+        self.damping = damping;
+        self
+    }
+    pub fn with_max_force(
+        mut self,
+        max_force: f32,
+    ) -> Self {
+        self.max_force = max_force;
+        self
+    }
+}
+
+impl Constraint for MouseJoint {
+    fn bodies(&self) -> (Entity, Option<Entity>) {
+        (self.body, None)
+    }
+
+    fn prepare(
+        &mut self,
+        _a: &BodyState,
+        _b: &BodyState,
+        _dt: f32,
+    ) {
+        self.applied = false;
+    }
+
+    fn solve_velocity(
+        &mut self,
+        a: &mut BodyState,
+        _b: &mut BodyState,
+        dt: f32,
+    ) {
+        // a spring is a force, so it is applied once per step and not once per iteration
+        if self.applied || a.inv_mass <= 0.0 {
+            return;
+        }
+        self.applied = true;
+
+        let anchor = a.position + Mat2::from_angle(a.rotation) * self.anchor;
+        let force = (self.target - anchor) * self.stiffness - a.vel * self.damping;
+        a.apply_impulse(force.clamp_length_max(self.max_force) * dt);
+    }
+}
+
+#[cfg(test)]
+mod mouse_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn drags_body_to_target() {
+        let mut app = PhysicsTestApp::new();
+
+        let body = app.spawn_at(Vec2::ZERO, KinematicBundle::default());
+        app.app.world.entity_mut(body).insert(Acc::default());
+        app.app.world.spawn().insert(MouseJoint::new(body, Vec2::new(5.0, 3.0)));
+
+        app.step_n(180);
+
+        assert!(app.position(body).distance(Vec2::new(5.0, 3.0)) < 0.05);
+    }
+}
@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::math::Mat2;
+
+use super::{BodyState, Constraint};
+
+/**
+    # PulleyJoint
+
+    Hangs 2 bodies from a rope going over 2 fixed points(`ground_a` and `ground_b`, in world space),
+    so when one body goes down the other goes up.
+
+    The rope keeps `length_a + ratio * length_b` at most `length`(it can go slack but not stretch),
+    a `ratio` above 1 makes `b` move less(like a block and tackle).
+
+    Anchors are relative to the bodies(and rotated with them),
+    a `length` of `0.0` is replaced with the length of the rope the first time the joint is solved.
+*/
+#[derive(Component, Clone, Debug)]
+pub struct PulleyJoint {
+    pub a: Entity,
+    pub b: Entity,
+    pub ground_a: Vec2,
+    pub ground_b: Vec2,
+    pub anchor_a: Vec2,
+    pub anchor_b: Vec2,
+    pub ratio: f32,
+    pub length: f32,
+}
+
+impl PulleyJoint {
+    /// Pulley between the centers of the bodies, with the rope as long as it currently is
+    pub fn new(
+        a: Entity,
+        b: Entity,
+        ground_a: Vec2,
+        ground_b: Vec2,
+    ) -> Self {
+        PulleyJoint {
+            a,
+            b,
+            ground_a,
+            ground_b,
+            anchor_a: Vec2::ZERO,
+            anchor_b: Vec2::ZERO,
+            ratio: 1.0,
+            length: 0.0,
+        }
+    }
+    pub fn with_anchors(
+        mut self,
+        anchor_a: Vec2,
+        anchor_b: Vec2,
+    ) -> Self {
+        self.anchor_a = anchor_a;
+        self.anchor_b = anchor_b;
+        self
+    }
+    pub fn with_ratio(
+        mut self,
+        ratio: f32,
+    ) -> Self {
+        self.ratio = ratio;
+        self
+    }
+    pub fn with_length(
+        mut self,
+        length: f32,
+    ) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Directions from the ground points to the anchors, and the current length of the rope
+    fn ropes(
+        &self,
+        a: &BodyState,
+        b: &BodyState,
+    ) -> Option<(Vec2, Vec2, f32)> {
+        let da = a.position + Mat2::from_angle(a.rotation) * self.anchor_a - self.ground_a;
+        let db = b.position + Mat2::from_angle(b.rotation) * self.anchor_b - self.ground_b;
+
+        let (la, lb) = (da.length(), db.length());
+        if la < f32::EPSILON || lb < f32::EPSILON {
+            None
+        }
+        else {
+            Some((da / la, db / lb, la + self.ratio * lb))
+        }
+    }
+    /// How much the rope resists moving each body
+    fn weight(
+        &self,
+        a: &BodyState,
+        b: &BodyState,
+    ) -> f32 {
+        a.inv_mass + self.ratio * self.ratio * b.inv_mass
+    }
+}
+
+impl Constraint for PulleyJoint {
+    fn bodies(&self) -> (Entity, Option<Entity>) {
+        (self.a, Some(self.b))
+    }
+
+    fn prepare(
+        &mut self,
+        a: &BodyState,
+        b: &BodyState,
+        _dt: f32,
+    ) {
+        if self.length <= 0.0 {
+            if let Some((_, _, len)) = self.ropes(a, b) {
+                self.length = len;
+            }
+        }
+    }
+
+    fn solve_velocity(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        _dt: f32,
+    ) {
+        let w = self.weight(a, b);
+        let (na, nb, len) = match self.ropes(a, b) {
+            Some(x) if w > 0.0 => x,
+            _ => return,
+        };
+
+        let vr = a.vel.dot(na) + self.ratio * b.vel.dot(nb);
+        // a slack rope doesn't care, and a taut one only stops the rope from getting longer
+        if len < self.length || vr < 0.0 {
+            return;
+        }
+
+        let lambda = -vr / w;
+        a.apply_impulse(na * lambda);
+        b.apply_impulse(nb * (self.ratio * lambda));
+    }
+
+    fn solve_position(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        _dt: f32,
+    ) {
+        let w = self.weight(a, b);
+        let (na, nb, len) = match self.ropes(a, b) {
+            Some(x) if w > 0.0 => x,
+            _ => return,
+        };
+
+        let c = len - self.length;
+        if c <= 0.0 {
+            return;
+        }
+
+        let lambda = -c / w;
+        a.apply_correction(na * lambda);
+        b.apply_correction(nb * (self.ratio * lambda));
+    }
+}
+
+#[cfg(test)]
+mod pulley_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn heavy_side_lifts_light_side() {
+        let mut app = PhysicsTestApp::new();
+
+        let heavy = app.spawn_at(Vec2::new(-2.0, 0.0), KinematicBundle::default());
+        let light = app.spawn_at(Vec2::new(2.0, 0.0), KinematicBundle::default());
+        app.app.world.entity_mut(heavy).insert(Acc::default()).insert(Mass(3.0));
+        app.app.world.entity_mut(light).insert(Acc::default());
+        app.app
+            .world
+            .spawn()
+            .insert(PulleyJoint::new(heavy, light, Vec2::new(-2.0, 5.0), Vec2::new(2.0, 5.0)));
+
+        app.step();
+        for _ in 0..60 {
+            for e in [heavy, light] {
+                app.app.world.get_mut::<Acc>(e).unwrap().add(Vec2::new(0.0, -10.0));
+            }
+            app.step();
+        }
+
+        let (h, l) = (app.position(heavy), app.position(light));
+        assert!(h.y < -0.5);
+        // the rope didn't stretch
+        assert!((h.y + l.y).abs() < 0.05, "{} {}", h.y, l.y);
+    }
+}
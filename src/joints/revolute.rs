@@ -0,0 +1,197 @@
+use bevy::prelude::*;
+
+use crate::math::Mat2;
+
+use super::{wrap_angle, BodyState, Constraint};
+
+/**
+    # RevoluteJoint
+
+    Pins an anchor on body `a` to an anchor on body `b`, like a hinge.
+
+    Bodies don't have angular velocity(yet), so when `align` is set body `b`(or `a` when `b` can't move) is rotated around the pin
+    to hang from it(its anchor pointing at the pin), which is what makes limbs and pendulums swing.
+    `limits` clamps the rotation of `b` relative to `a`(in radians).
+
+    Anchors are relative to the bodies(and rotated with them),
+    when `b` is `None` the joint is attached to the world and `anchor_b` is a world position.
+*/
+#[derive(Component, Clone, Debug)]
+pub struct RevoluteJoint {
+    pub a: Entity,
+    pub b: Option<Entity>,
+    pub anchor_a: Vec2,
+    pub anchor_b: Vec2,
+    /// Minimum and maximum rotation of `b` relative to `a`
+    pub limits: Option<(f32, f32)>,
+    /// Rotate the swinging body around the pin
+    pub align: bool,
+}
+
+impl RevoluteJoint {
+    pub fn new(
+        a: Entity,
+        b: Option<Entity>,
+        anchor_a: Vec2,
+        anchor_b: Vec2,
+    ) -> Self {
+        RevoluteJoint {
+            a,
+            b,
+            anchor_a,
+            anchor_b,
+            limits: None,
+            align: true,
+        }
+    }
+    pub fn with_limits(
+        mut self,
+        min: f32,
+        max: f32,
+    ) -> Self {
+        self.limits = Some((min, max));
+        self
+    }
+    /// Don't rotate the bodies around the pin, only keep the anchors together
+    pub fn without_align(mut self) -> Self {
+        self.align = false;
+        self
+    }
+
+    fn world_anchors(
+        &self,
+        a: &BodyState,
+        b: &BodyState,
+    ) -> (Vec2, Vec2) {
+        (
+            a.position + Mat2::from_angle(a.rotation) * self.anchor_a,
+            b.position + Mat2::from_angle(b.rotation) * self.anchor_b,
+        )
+    }
+    /// The body which turns around the pin instead of being moved as a whole,
+    /// with the pin and the body's anchor(`None` when `align` is off)
+    fn swinging<'a>(
+        &self,
+        a: &'a mut BodyState,
+        b: &'a mut BodyState,
+    ) -> Option<(&'a mut BodyState, Vec2, Vec2)> {
+        if !self.align {
+            return None;
+        }
+        let (pa, pb) = self.world_anchors(a, b);
+
+        let (body, pin, anchor) = if b.inv_mass > 0.0 {
+            (b, pa, self.anchor_b)
+        }
+        else if a.inv_mass > 0.0 {
+            (a, pb, self.anchor_a)
+        }
+        else {
+            return None;
+        };
+        // a body pinned at its center has nothing to swing with
+        (anchor.length_squared() > f32::EPSILON).then_some((body, pin, anchor))
+    }
+}
+
+impl Constraint for RevoluteJoint {
+    fn bodies(&self) -> (Entity, Option<Entity>) {
+        (self.a, self.b)
+    }
+
+    fn solve_velocity(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        _dt: f32,
+    ) {
+        let w = a.inv_mass + b.inv_mass;
+        if w <= 0.0 {
+            return;
+        }
+
+        let vr = b.vel - a.vel;
+        let impulse = match self.swinging(a, b) {
+            // the body is free to go around the pin, only the velocity along the arm is removed
+            Some((body, pin, _)) => match (body.position - pin).try_normalize() {
+                Some(n) => n * vr.dot(n),
+                None => return,
+            },
+            None => vr,
+        };
+
+        let impulse = impulse / w;
+        a.apply_impulse(impulse);
+        b.apply_impulse(-impulse);
+    }
+
+    fn solve_position(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        _dt: f32,
+    ) {
+        let w = a.inv_mass + b.inv_mass;
+        if w <= 0.0 {
+            return;
+        }
+
+        // point the anchor of the swinging body at the pin
+        if let Some((body, pin, anchor)) = self.swinging(a, b) {
+            let to_pin = pin - body.position;
+            if to_pin.length_squared() > f32::EPSILON {
+                body.rotation = to_pin.y.atan2(to_pin.x) - anchor.y.atan2(anchor.x);
+            }
+        }
+
+        if let Some((min, max)) = self.limits {
+            let relative = wrap_angle(b.rotation - a.rotation);
+            let clamped = relative.clamp(min, max);
+            if clamped != relative {
+                if b.inv_mass > 0.0 {
+                    b.rotation = a.rotation + clamped;
+                }
+                else {
+                    a.rotation = b.rotation - clamped;
+                }
+            }
+        }
+
+        let (pa, pb) = self.world_anchors(a, b);
+        let correction = (pb - pa) / w;
+        a.apply_correction(correction);
+        b.apply_correction(-correction);
+    }
+}
+
+#[cfg(test)]
+mod revolute_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn pendulum_swings_around_pin() {
+        let mut app = PhysicsTestApp::new();
+
+        // a stick hanging sideways from the origin
+        let stick = app.spawn_at(Vec2::new(1.0, 0.0), KinematicBundle {
+            shape: CollisionShape::Capsule(Capsule::new(1.0, 0.1)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(stick).insert(Acc::default());
+        app.app
+            .world
+            .spawn()
+            .insert(RevoluteJoint::new(stick, None, Vec2::new(0.0, 1.0), Vec2::ZERO));
+
+        for _ in 0..30 {
+            app.app.world.get_mut::<Acc>(stick).unwrap().add(Vec2::new(0.0, -10.0));
+            app.step();
+        }
+
+        let t = app.app.world.get::<Transform2D>(stick).unwrap();
+        let pin = t.translation() + Mat2::from_angle(t.rotation()) * Vec2::new(0.0, 1.0);
+        assert!(pin.length() < 0.01);
+        assert!(t.translation().y < -0.5);
+    }
+}
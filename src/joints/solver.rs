@@ -0,0 +1,426 @@
+use std::any::TypeId;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    bodies::{PhysicsDisabled, StaticBody},
+    physics_components::{Mass, Transform2D, Vel},
+    shapes::CollisionShape,
+};
+
+use super::{AppliedImpulse, BodyState, Breakable, Constraint, JointBrokenEvent, PhysicsSolver};
+
+/// Iteration counts of the joint solver
+#[derive(Debug, Clone, Copy)]
+pub struct SolverSettings {
+    /// How many times `Constraint::solve_velocity` is called each step
+    pub velocity_iterations: u32,
+    /// How many times `Constraint::solve_position` is called each step
+    pub position_iterations: u32,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        SolverSettings {
+            velocity_iterations: 8,
+            position_iterations: 3,
+        }
+    }
+}
+
+/// Registers constraint types to the joint solver
+pub trait ConstraintAppExt {
+    /// Registers a constraint type, so it will be solved each step(registering a type twice does nothing)
+    fn add_constraint<T: Constraint>(&mut self) -> &mut Self;
+}
+
+impl ConstraintAppExt for App {
+    fn add_constraint<T: Constraint>(&mut self) -> &mut Self {
+        self.init_resource::<ConstraintRegistry>();
+        self.world
+            .resource_mut::<ConstraintRegistry>()
+            .register::<T>();
+        self
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SolvePhase {
+    Prepare,
+    Velocity,
+    Position,
+}
+
+/// Type erased functions of a registered constraint type
+#[derive(Clone, Copy)]
+struct ConstraintFns {
+    id: TypeId,
+    collect: fn(&mut World, &mut Vec<Entity>),
+    run: fn(&mut World, &mut BodySet, SolvePhase, f32),
+}
+
+/// All the registered constraint types
+#[derive(Default)]
+pub(crate) struct ConstraintRegistry {
+    types: Vec<ConstraintFns>,
+}
+
+impl ConstraintRegistry {
+    fn register<T: Constraint>(&mut self) {
+        let id = TypeId::of::<T>();
+        if self.types.iter().any(|t| t.id == id) {
+            return;
+        }
+        self.types.push(ConstraintFns {
+            id,
+            collect: collect::<T>,
+            run: run::<T>,
+        });
+    }
+}
+
+/// The states of all the bodies used by constraints this step
+#[derive(Default)]
+struct BodySet {
+    entities: Vec<Entity>,
+    states: Vec<BodyState>,
+    /// Moment of inertia of each body in `states`(0 for the immovable ones)
+    inertia: Vec<f32>,
+    index: HashMap<Entity, usize>,
+    /// Static body given for constraints attached to the world, reset before each use
+    world: BodyState,
+    /// Impulses applied by the `Breakable` joints
+    applied: HashMap<Entity, AppliedImpulse>,
+}
+
+impl BodySet {
+    fn pair(
+        &mut self,
+        a: Entity,
+        b: Option<Entity>,
+    ) -> Option<(&mut BodyState, &mut BodyState)> {
+        let ia = *self.index.get(&a)?;
+
+        match b {
+            None => {
+                self.world = BodyState::STATIC;
+                Some((&mut self.states[ia], &mut self.world))
+            },
+            Some(b) => {
+                let ib = *self.index.get(&b)?;
+
+                if ia < ib {
+                    let (l, r) = self.states.split_at_mut(ib);
+                    Some((&mut l[ia], &mut r[0]))
+                }
+                else if ia > ib {
+                    let (l, r) = self.states.split_at_mut(ia);
+                    Some((&mut r[0], &mut l[ib]))
+                }
+                else {
+                    None // a constraint between a body and itself does nothing
+                }
+            },
+        }
+    }
+}
+
+fn collect<T: Constraint>(
+    world: &mut World,
+    out: &mut Vec<Entity>,
+) {
+    let mut q = world.query::<&T>();
+    for c in q.iter(world) {
+        let (a, b) = c.bodies();
+        out.push(a);
+        out.extend(b);
+    }
+}
+
+fn run<T: Constraint>(
+    world: &mut World,
+    bodies: &mut BodySet,
+    phase: SolvePhase,
+    dt: f32,
+) {
+    let mut q = world.query::<(Entity, &mut T, Option<&Breakable>)>();
+    for (e, mut c, breakable) in q.iter_mut(world) {
+        let (a, b) = c.bodies();
+        let inertia = |e: Option<Entity>| e.and_then(|e| bodies.index.get(&e)).map_or(0.0, |i| bodies.inertia[*i]);
+        let (ia, ib) = (inertia(Some(a)), inertia(b));
+
+        let (sa, sb) = match bodies.pair(a, b) {
+            Some(p) => p,
+            None => continue, // one of the bodies is missing(or doesn't have a `Transform2D`)
+        };
+        let before = (*sa, *sb);
+
+        match phase {
+            SolvePhase::Prepare => c.prepare(sa, sb, dt),
+            SolvePhase::Velocity => c.solve_velocity(sa, sb, dt),
+            SolvePhase::Position => c.solve_position(sa, sb, dt),
+        }
+
+        if let Some(limits) = breakable {
+            let moved = |now: &BodyState, before: BodyState| (now.position - before.position, now.rotation - before.rotation, now.vel - before.vel);
+            let moved = [moved(sa, before.0), moved(sb, before.1)];
+            // measured on whichever body can actually be moved
+            let (now, before, inertia) = if sa.inv_mass > 0.0 { (*sa, before.0, ia) } else { (*sb, before.1, ib) };
+            if now.inv_mass <= 0.0 {
+                continue;
+            }
+            let mass = 1.0 / now.inv_mass;
+
+            let applied = bodies.applied.entry(e).or_insert(AppliedImpulse {
+                limits: *limits,
+                bodies: (a, b),
+                impulse: Vec2::ZERO,
+                correction: Vec2::ZERO,
+                angular: 0.0,
+                moved: [(Vec2::ZERO, 0.0, Vec2::ZERO); 2],
+            });
+            applied.impulse += (now.vel - before.vel) * mass;
+            applied.correction += (now.position - before.position) / dt * mass;
+            applied.angular += (now.rotation - before.rotation) / dt * inertia;
+            for (sum, m) in applied.moved.iter_mut().zip(moved) {
+                sum.0 += m.0;
+                sum.1 += m.1;
+                sum.2 += m.2;
+            }
+        }
+    }
+}
+
+/**
+    # ConstraintPass
+
+    Runs the registered constraints over the bodies gathered for this step,
+    handed to the `SolverBackend` which decides how many times each phase runs.
+*/
+pub struct ConstraintPass<'a> {
+    world: &'a mut World,
+    types: &'a [ConstraintFns],
+    bodies: &'a mut BodySet,
+}
+
+impl<'a> ConstraintPass<'a> {
+    fn run(
+        &mut self,
+        phase: SolvePhase,
+        dt: f32,
+    ) {
+        for t in self.types.iter() {
+            (t.run)(self.world, self.bodies, phase, dt);
+        }
+    }
+
+    /// Calls `Constraint::prepare` on every constraint
+    pub fn prepare(
+        &mut self,
+        dt: f32,
+    ) {
+        self.run(SolvePhase::Prepare, dt);
+    }
+    /// Calls `Constraint::solve_velocity` on every constraint
+    pub fn solve_velocity(
+        &mut self,
+        dt: f32,
+    ) {
+        self.run(SolvePhase::Velocity, dt);
+    }
+    /// Calls `Constraint::solve_position` on every constraint
+    pub fn solve_position(
+        &mut self,
+        dt: f32,
+    ) {
+        self.run(SolvePhase::Position, dt);
+    }
+
+    /// The bodies the constraints are working on
+    pub fn bodies(&mut self) -> &mut [BodyState] {
+        &mut self.bodies.states
+    }
+}
+
+/// Solves all the registered constraints, runs in `stage::JOINT_STEP`
+pub fn solve_constraints(world: &mut World) {
+    let types = match world.get_resource::<ConstraintRegistry>() {
+        Some(r) if !r.types.is_empty() => r.types.clone(),
+        _ => return,
+    };
+    let settings = world.get_resource::<SolverSettings>().copied().unwrap_or_default();
+    let dt = world.resource::<Time>().delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    // Gather the bodies
+    let mut entities = Vec::new();
+    for t in types.iter() {
+        (t.collect)(world, &mut entities);
+    }
+
+    let mut bodies = BodySet::default();
+    #[allow(clippy::type_complexity)]
+    let mut q = world.query::<(
+        &Transform2D,
+        Option<&Vel>,
+        Option<&Mass>,
+        Option<&CollisionShape>,
+        Option<&StaticBody>,
+        Option<&PhysicsDisabled>,
+    )>();
+    for e in entities {
+        if bodies.index.contains_key(&e) {
+            continue;
+        }
+        let (t, v, m, shape, s, d) = match q.get(world, e) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+
+        // static and disabled bodies are immovable
+        let inv_mass = match (v, s, d) {
+            (Some(_), None, None) => m.map(|m| m.inverse()).unwrap_or(1.0),
+            _ => 0.0,
+        };
+        let inertia = match (inv_mass > 0.0, shape) {
+            (false, _) => 0.0,
+            (true, Some(shape)) => shape.moment_of_inertia(1.0 / inv_mass),
+            (true, None) => 1.0 / inv_mass,
+        };
+
+        bodies.index.insert(e, bodies.states.len());
+        bodies.inertia.push(inertia);
+        bodies.entities.push(e);
+        bodies.states.push(BodyState {
+            position: t.translation(),
+            rotation: t.rotation(),
+            vel: v.map(|v| v.0).unwrap_or(Vec2::ZERO),
+            inv_mass,
+        });
+    }
+
+    // Solver loop
+    let mut run = |world: &mut World, solver: &PhysicsSolver| {
+        let mut pass = ConstraintPass {
+            world,
+            types: &types,
+            bodies: &mut bodies,
+        };
+        solver.0.solve(&mut pass, &settings, dt);
+    };
+    if world.contains_resource::<PhysicsSolver>() {
+        world.resource_scope(|world, solver: Mut<PhysicsSolver>| run(world, &solver));
+    }
+    else {
+        run(world, &PhysicsSolver::default());
+    }
+
+    // Break the joints which were pushed too hard
+    let mut broken = bodies
+        .applied
+        .iter()
+        .filter_map(|(joint, applied)| {
+            // the position corrections fix the same error the velocity corrections did(for the next step), so they are not summed
+            let force = applied.impulse.length().max(applied.correction.length()) / dt;
+            let torque = applied.angular.abs() / dt;
+            (force > applied.limits.break_force || torque > applied.limits.break_torque).then_some((*joint, *applied, force, torque))
+        })
+        .collect::<Vec<_>>();
+    // `applied` is a hash map, so the joints break in the order of their entities for every run to send the same events
+    broken.sort_by_key(|(joint, ..)| *joint);
+
+    for (joint, applied, force, torque) in broken {
+        // the joint didn't hold, so what it did this step isn't written back
+        let (a, b) = applied.bodies;
+        for (e, (position, rotation, vel)) in [Some(a), b].into_iter().zip(applied.moved) {
+            if let Some(s) = e.and_then(|e| bodies.index.get(&e)).map(|i| &mut bodies.states[*i]) {
+                s.position -= position;
+                s.rotation -= rotation;
+                s.vel -= vel;
+            }
+        }
+
+        world.despawn(joint);
+        if let Some(mut events) = world.get_resource_mut::<Events<JointBrokenEvent>>() {
+            events.send(JointBrokenEvent {
+                joint,
+                bodies: applied.bodies,
+                force,
+                torque,
+            });
+        }
+    }
+
+    // Write back
+    let mut q = world.query::<(&mut Transform2D, Option<&mut Vel>)>();
+    for (e, s) in bodies.entities.iter().zip(bodies.states.iter()) {
+        if s.inv_mass == 0.0 {
+            continue;
+        }
+        if let Ok((mut t, v)) = q.get_mut(world, *e) {
+            // the solver already placed the body, so the rotation doesn't move it around its pivot
+            t.set_rotation(s.rotation);
+            t.set_translation(s.position);
+            if let Some(mut v) = v {
+                v.0 = s.vel;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod solver_tests {
+    use super::*;
+    use crate::{joints::ConstraintAppExt, prelude::*, test_utils::PhysicsTestApp};
+
+    /// Keeps the body on the y axis
+    #[derive(Component)]
+    struct OnYAxis(Entity);
+
+    impl Constraint for OnYAxis {
+        fn bodies(&self) -> (Entity, Option<Entity>) {
+            (self.0, None)
+        }
+
+        fn solve_velocity(
+            &mut self,
+            a: &mut BodyState,
+            _b: &mut BodyState,
+            _dt: f32,
+        ) {
+            a.vel.x = 0.0;
+        }
+
+        fn solve_position(
+            &mut self,
+            a: &mut BodyState,
+            _b: &mut BodyState,
+            _dt: f32,
+        ) {
+            a.position.x = 0.0;
+        }
+    }
+
+    #[test]
+    fn custom_constraint() {
+        let mut app = PhysicsTestApp::new();
+        app.app.add_constraint::<OnYAxis>();
+
+        let body = app.spawn_at(Vec2::new(3.0, 0.0), KinematicBundle {
+            vel: Vel(Vec2::new(10.0, 5.0)),
+            shape: CollisionShape::Circle(Circle::new(0.5)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(body).insert(Acc::default());
+        app.app.world.spawn().insert(OnYAxis(body));
+
+        app.step_n(10);
+
+        let p = app.position(body);
+        assert!(p.x.abs() < 0.0001);
+        assert!(p.y > 0.0);
+        assert_eq!(app.app.world.get::<Vel>(body).unwrap().0.x, 0.0);
+    }
+}
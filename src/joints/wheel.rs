@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+
+use crate::math::Mat2;
+
+use super::{BodyState, Constraint};
+
+/**
+    # WheelJoint
+
+    Connects a wheel to a chassis with a suspension spring along `axis`(relative to the chassis, pointing down by default),
+    the wheel can only move along that axis.
+
+    The motor drives the wheel along the chassis' forward direction(perpendicular to `axis`)
+    at `motor_speed`(radians per second, positive drives along `axis.perp()` - to the right for the default axis),
+    pushing with up to `max_motor_torque`, and rolls the wheel accordingly.
+    Bodies don't have angular velocity or friction(yet), so the wheel is pushed directly instead of rolling on the ground.
+
+    ```ignore
+    coms.spawn().insert(
+        WheelJoint::new(chassis, wheel, Vec2::new(-20.0, -10.0), 8.0)
+            .with_suspension(500.0, 30.0)
+            .with_motor(10.0, 2000.0),
+    );
+    ```
+*/
+#[derive(Component, Clone, Debug)]
+pub struct WheelJoint {
+    pub chassis: Entity,
+    pub wheel: Entity,
+    /// Where the wheel rests, relative to the chassis
+    pub anchor: Vec2,
+    /// Direction the suspension moves in, relative to the chassis
+    pub axis: Vec2,
+    pub radius: f32,
+    /// Force per unit the wheel is pushed out of its resting place
+    pub stiffness: f32,
+    /// Force per unit of velocity along the axis
+    pub damping: f32,
+    pub motor_speed: f32,
+    /// `0.0` turns the motor off
+    pub max_motor_torque: f32,
+    applied: bool,
+    rolled: bool,
+}
+
+impl WheelJoint {
+    pub fn new(
+        chassis: Entity,
+        wheel: Entity,
+        anchor: Vec2,
+        radius: f32,
+    ) -> Self {
+        WheelJoint {
+            chassis,
+            wheel,
+            anchor,
+            axis: -Vec2::Y,
+            radius,
+            stiffness: 200.0,
+            damping: 20.0,
+            motor_speed: 0.0,
+            max_motor_torque: 0.0,
+            applied: false,
+            rolled: false,
+        }
+    }
+    pub fn with_axis(
+        mut self,
+        axis: Vec2,
+    ) -> Self {
+        self.axis = axis.normalize_or_zero();
+        self
+    }
+    pub fn with_suspension(
+        mut self,
+        stiffness: f32,
+        damping: f32,
+    ) -> Self {
+        self.stiffness = stiffness;
+        self.damping = damping;
+        self
+    }
+    pub fn with_motor(
+        mut self,
+        speed: f32,
+        max_torque: f32,
+    ) -> Self {
+        self.motor_speed = speed;
+        self.max_motor_torque = max_torque;
+        self
+    }
+
+    /// World axis, forward direction and offset of the wheel from its resting place
+    fn frame(
+        &self,
+        chassis: &BodyState,
+        wheel: &BodyState,
+    ) -> (Vec2, Vec2, Vec2) {
+        let rot = Mat2::from_angle(chassis.rotation);
+        let axis = rot * self.axis;
+
+        (axis, axis.perp(), wheel.position - (chassis.position + rot * self.anchor))
+    }
+}
+
+impl Constraint for WheelJoint {
+    fn bodies(&self) -> (Entity, Option<Entity>) {
+        (self.chassis, Some(self.wheel))
+    }
+
+    fn prepare(
+        &mut self,
+        _a: &BodyState,
+        _b: &BodyState,
+        _dt: f32,
+    ) {
+        self.applied = false;
+        self.rolled = false;
+    }
+
+    fn solve_velocity(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        dt: f32,
+    ) {
+        let w = a.inv_mass + b.inv_mass;
+        if w <= 0.0 {
+            return;
+        }
+        let (axis, forward, offset) = self.frame(a, b);
+
+        // the spring and motor are forces, so they are applied once per step
+        if !self.applied {
+            self.applied = true;
+
+            let vr = (b.vel - a.vel).dot(axis);
+            let spring = axis * (-self.stiffness * offset.dot(axis) - self.damping * vr) * dt;
+            a.apply_impulse(-spring);
+            b.apply_impulse(spring);
+
+            if self.max_motor_torque > 0.0 && b.inv_mass > 0.0 && self.radius > 0.0 {
+                let target = self.motor_speed * self.radius;
+                let max = self.max_motor_torque / self.radius * dt;
+                let impulse = ((target - b.vel.dot(forward)) / b.inv_mass).clamp(-max, max);
+                b.apply_impulse(forward * impulse);
+            }
+        }
+
+        // the wheel can't move sideways relative to the chassis
+        let vr = (b.vel - a.vel).dot(forward);
+        let impulse = forward * (vr / w);
+        a.apply_impulse(impulse);
+        b.apply_impulse(-impulse);
+    }
+
+    fn solve_position(
+        &mut self,
+        a: &mut BodyState,
+        b: &mut BodyState,
+        dt: f32,
+    ) {
+        let w = a.inv_mass + b.inv_mass;
+        if w <= 0.0 {
+            return;
+        }
+        let (_, forward, offset) = self.frame(a, b);
+
+        let correction = forward * (offset.dot(forward) / w);
+        a.apply_correction(correction);
+        b.apply_correction(-correction);
+
+        // roll the wheel, only once(the position phase might run a few times)
+        if !self.rolled && self.radius > 0.0 {
+            self.rolled = true;
+            b.rotation -= b.vel.dot(forward) * dt / self.radius;
+        }
+    }
+}
+
+#[cfg(test)]
+mod wheel_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn motor_drives_the_car() {
+        let mut app = PhysicsTestApp::new();
+
+        let chassis = app.spawn_at(Vec2::ZERO, KinematicBundle::default());
+        let wheel = app.spawn_at(Vec2::new(0.0, -2.0), KinematicBundle {
+            shape: CollisionShape::Circle(Circle::new(0.5)),
+            collision_layer: CollisionLayer::ZERO,
+            ..Default::default()
+        });
+        for e in [chassis, wheel] {
+            app.app.world.entity_mut(e).insert(Acc::default());
+        }
+        app.app.world.spawn().insert(
+            WheelJoint::new(chassis, wheel, Vec2::new(0.0, -2.0), 0.5).with_motor(4.0, 100.0),
+        );
+
+        app.step_n(20);
+
+        let (c, w) = (app.position(chassis), app.position(wheel));
+        assert!(c.x > 0.2);
+        // stayed under the chassis
+        assert!((w - c - Vec2::new(0.0, -2.0)).length() < 0.01);
+        // rolled clockwise(less than half a turn so far)
+        assert!(app.app.world.get::<Transform2D>(wheel).unwrap().rotation() < 0.0);
+    }
+}
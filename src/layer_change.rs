@@ -0,0 +1,100 @@
+//! # Collision layer changes
+//!
+//! Changing a body's `CollisionLayer`(a player turning intangible, a pickup being collected) takes effect on the next collision step,
+//! but the physics still remembers things from before the change - pairs carried over by the frame budget and the contacts of the last frame.
+//!
+//! Right before the broad phase, every body whose `CollisionLayer` changed is reported with a `CollisionLayerChanged` event,
+//! and whatever is remembered about it with bodies it no longer collides with is dropped,
+//! so none of it gets solved(or shows up in `ContactsPerEntity`) after the change.
+//! The broad phase then pairs the body by its new layer as usual.
+
+use bevy::prelude::*;
+
+use crate::{contacts::ContactsPerEntity, frame_budget::FrameBudget, physics_components::CollisionLayer};
+
+/// Sent when a body's `CollisionLayer` changed(any mutable access counts, same as bevy's change detection)
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionLayerChanged {
+    pub entity: Entity,
+    pub layer: CollisionLayer,
+}
+
+/// Sends the `CollisionLayerChanged` events and drops the stale pairs and contacts, runs right before the broad phase
+pub fn layer_change_system(
+    changed: Query<(Entity, &CollisionLayer, ChangeTrackers<CollisionLayer>), Changed<CollisionLayer>>,
+    layers: Query<&CollisionLayer>,
+    mut budget: ResMut<FrameBudget>,
+    mut contacts: ResMut<ContactsPerEntity>,
+    mut events: EventWriter<CollisionLayerChanged>,
+) {
+    let overlap = |a: Entity, b: Entity| match (layers.get(a), layers.get(b)) {
+        (Ok(a), Ok(b)) => a.overlap(b),
+        _ => false,
+    };
+
+    for (e, layer, tracker) in changed.iter() {
+        // new bodies have nothing to forget
+        if tracker.is_added() {
+            continue;
+        }
+        events.send(CollisionLayerChanged {
+            entity: e,
+            layer: *layer,
+        });
+
+        budget.forget_pairs(e, |other| overlap(e, other));
+
+        let stale = contacts
+            .get(e)
+            .iter()
+            .filter(|c| !overlap(e, c.other))
+            .map(|c| c.other)
+            .collect::<Vec<_>>();
+        for other in stale {
+            contacts.forget(e, other);
+        }
+    }
+}
+
+#[cfg(test)]
+mod layer_change_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+    use bevy::utils::Duration;
+
+    #[test]
+    fn intangible_drops_carried_pairs() {
+        let mut app = PhysicsTestApp::new();
+        // a zero budget only lets a single pair through each frame, the rest are carried over
+        app.app.insert_resource(CollisionSettings {
+            frame_budget: Some(Duration::ZERO),
+            ..Default::default()
+        });
+        // the body is inside both the floor and the wall, solving one of them doesn't get it out of the other
+        let floor = app.spawn_at(Vec2::ZERO, StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(10.0, 1.0))),
+            ..Default::default()
+        });
+        let wall = app.spawn_at(Vec2::new(-1.3, 0.0), StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(1.0, 10.0))),
+            ..Default::default()
+        });
+        let body = app.spawn_at(Vec2::new(0.0, 0.9), KinematicBundle::default());
+        app.step();
+        assert!(app.app.world.resource::<FrameBudget>().deferred() > 0);
+
+        // turns intangible, the pair carried over may not push it anymore
+        let mut reader = app.app.world.resource::<Events<CollisionLayerChanged>>().get_reader();
+        app.app.world.entity_mut(body).insert(CollisionLayer::new(0b10, 0b10));
+        let before = app.position(body);
+        app.step();
+        let changed = reader.iter(app.app.world.resource::<Events<CollisionLayerChanged>>()).map(|ev| ev.entity).collect::<Vec<_>>();
+        assert_eq!(changed, vec![body]);
+
+        app.step_n(3);
+        app.assert_position(body, before, 0.0001);
+        let contacts = app.app.world.resource::<ContactsPerEntity>();
+        assert!(!contacts.touching(body, floor) && !contacts.touching(body, wall));
+        assert_eq!(app.app.world.resource::<FrameBudget>().deferred(), 0);
+    }
+}
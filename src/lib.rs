@@ -1,32 +1,171 @@
 //! # Hello!
 //! This is my lib, check out the getting start on the repo(GETTING_STARTED.md)
+//!
+//! ## Features
+//!
+//! - `ecs`(default): everything that touches bevy's ECS - the plugin, bodies and systems.
+//!   Without it you are left with the core geometry(`shapes`, `Transform2D`, `CollisionLayer`, `Vel` and `common`),
+//!   which only depends on `bevy_math`, so it can be used(and tested) headlessly, on servers, or for fuzzing.
 
+#[cfg(feature = "ecs")]
 mod broad;
+#[cfg(feature = "ecs")]
 mod narrow;
+#[cfg(feature = "ecs")]
 mod normal_coll;
 
-pub mod bodies;
+pub mod math;
 pub mod common;
 pub mod physics_components;
+pub mod shapes;
+pub mod integrator;
+pub mod spatial_hash;
+pub mod quantize;
+pub mod placement;
+#[cfg(feature = "ecs")]
+pub mod bodies;
+#[cfg(feature = "ecs")]
 pub mod plugin;
+#[cfg(feature = "ecs")]
+pub mod manual_step;
+#[cfg(feature = "ecs")]
+pub mod replay;
+#[cfg(feature = "ecs")]
+pub mod static_index;
+#[cfg(feature = "ecs")]
+pub mod joints;
+#[cfg(feature = "ecs")]
+pub mod physics_commands;
+#[cfg(feature = "ecs")]
+pub mod frame_budget;
+#[cfg(feature = "ecs")]
+pub mod lod;
+#[cfg(feature = "ecs")]
+pub mod contacts;
+#[cfg(feature = "ecs")]
+pub mod pair_key;
+#[cfg(feature = "ecs")]
+pub mod debug_color;
+#[cfg(feature = "ecs")]
+pub mod layer_change;
+#[cfg(feature = "ecs")]
+pub mod visibility;
+#[cfg(feature = "ecs")]
+pub mod navigation;
+#[cfg(feature = "ecs")]
 pub mod transform_mode;
-pub mod shapes;
+#[cfg(feature = "ecs")]
+pub mod transform_check;
+#[cfg(feature = "ecs")]
+pub mod ccd;
+#[cfg(feature = "ecs")]
+pub mod world_wrap;
+#[cfg(feature = "ecs")]
+pub mod world_bounds;
+#[cfg(feature = "ecs")]
+pub mod gameplay;
+#[cfg(feature = "collider-assets")]
+pub mod collider_assets;
+#[cfg(feature = "rapier-interop")]
+pub mod rapier_interop;
+#[cfg(feature = "soft-body")]
+pub mod soft_body;
+#[cfg(feature = "fluid")]
+pub mod fluid;
+#[cfg(feature = "picking")]
+pub mod picking;
+#[cfg(feature = "mesh")]
+pub mod shape_mesh;
+#[cfg(feature = "egui")]
+pub mod debug_ui;
+#[cfg(all(feature = "ecs", any(test, feature = "test-utils")))]
+pub mod test_utils;
+#[cfg(all(feature = "ecs", any(test, feature = "test-utils")))]
+pub mod fixture;
 
+#[cfg(feature = "ecs")]
 pub mod systems {
     //! Re-exports all the systems in the crate for ease of access
     pub use super::broad::broad_phase_1;
     pub use super::narrow::narrow_phase_system;
-    pub use super::normal_coll::{broad_phase_2, narrow_phase_2, ray_phase};
+    pub use super::normal_coll::{broad_phase_2, narrow_phase_2, ray_phase, sensor_phase};
+    pub use super::integrator::integrate_system;
+    pub use super::ccd::ccd_system;
+    pub use super::joints::solve_constraints;
+    pub use super::physics_commands::apply_physics_commands;
+    pub use super::lod::shape_lod_system;
+    pub use super::contacts::contacts_per_entity_system;
+    pub use super::debug_color::{debug_color_system, islands_system};
+    pub use super::layer_change::layer_change_system;
+    pub use super::static_index::static_index_system;
+    pub use super::visibility::visibility_polygon_system;
+    pub use super::transform_check::transform_check_system;
+    pub use super::world_wrap::world_wrap_system;
+    pub use super::world_bounds::world_bounds_system;
 }
 
 pub mod prelude {
     //! This module re-exports all the things you might need for 2d physics
     //! simulation.
     pub use crate::common::*;
-    pub use crate::plugin::{Physics2dPlugin, CollisionEvent};
     pub use crate::physics_components::*;
-    pub use crate::bodies::*;
     pub use crate::shapes::*;
+    pub use crate::integrator::{Integrator, SemiImplicitEuler, VelocityVerlet};
+    pub use crate::quantize::{QuantizedBody, Quantizer};
+    pub use crate::placement::{cast_to_surface, find_free_position};
+    #[cfg(feature = "ecs")]
+    pub use crate::placement::{snap_to_surface, SurfaceSnap};
+    #[cfg(feature = "ecs")]
+    pub use crate::integrator::PhysicsIntegrator;
+    #[cfg(feature = "ecs")]
+    pub use crate::plugin::{Physics2dPlugin, DetectionPlugin, CollisionEvent, CollisionSettings, MtvSplit, PhysicsSystem, BroadPhase};
+    #[cfg(feature = "ecs")]
+    pub use crate::physics_commands::PhysicsCommands;
+    #[cfg(feature = "ecs")]
+    pub use crate::manual_step::{step_world, PhysicsAppExt, PhysicsSchedule};
+    #[cfg(feature = "ecs")]
+    pub use crate::replay::{PhysicsRecorder, PhysicsRecording, PhysicsReplay, ReplayPlugin};
+    #[cfg(feature = "ecs")]
+    pub use crate::static_index::{StaticDirty, StaticIndex};
+    #[cfg(feature = "ecs")]
+    pub use crate::frame_budget::FrameBudget;
+    #[cfg(feature = "ecs")]
+    pub use crate::lod::{PhysicsViewer, ShapeLod, SimplifiedShape};
+    #[cfg(feature = "ecs")]
+    pub use crate::contacts::{ContactInfo, ContactsPerEntity, SurfaceKind};
+    #[cfg(feature = "ecs")]
+    pub use crate::pair_key::PairKey;
+    #[cfg(feature = "ecs")]
+    pub use crate::debug_color::{DebugColorMode, DebugColors, PhysicsIslands};
+    #[cfg(feature = "ecs")]
+    pub use crate::layer_change::CollisionLayerChanged;
+    #[cfg(feature = "ecs")]
+    pub use crate::visibility::VisibilityPolygon;
+    #[cfg(feature = "ecs")]
+    pub use crate::navigation::{LinkKind, NavSettings, OccupancyGrid, SurfaceGraph};
+    #[cfg(feature = "ecs")]
+    pub use crate::transform_check::{TransformCheck, TransformModifiedEvent};
+    #[cfg(feature = "ecs")]
+    pub use crate::world_bounds::{BoundsPolicy, OutOfBoundsEvent, WorldBounds};
+    #[cfg(feature = "ecs")]
+    pub use crate::gameplay::{ArcAttack, ArcHitEvent, ContactDamage, DamageEvent, GameplayPlugin};
+    #[cfg(feature = "ecs")]
+    pub use crate::bodies::*;
+    #[cfg(feature = "ecs")]
+    pub use crate::joints::{
+        BodyState, Breakable, Constraint, ConstraintAppExt, DistanceJoint, GearJoint, ImpulseSolver, JointBrokenEvent, MouseJoint,
+        PbdSolver, PhysicsSolver, PulleyJoint, RevoluteJoint, SolverBackend, SolverSettings, WheelJoint,
+    };
+    #[cfg(feature = "ecs")]
     pub use crate::systems;
-    pub use crate::normal_coll::collide_ray;
+    #[cfg(feature = "ecs")]
+    pub use crate::normal_coll::{collide_ray, collide_ray_all, collide_shape_sweep};
+    #[cfg(feature = "picking")]
+    pub use crate::picking::{
+        CursorWorldPosition, Hovered, MouseDragPlugin, MouseDragSettings, PhysicsPickingPlugin, PickedEvent, PickingSettings, PickingState,
+    };
+    #[cfg(feature = "mesh")]
+    pub use crate::shape_mesh::{shape_mesh, shape_mesh_with};
+    #[cfg(feature = "egui")]
+    pub use crate::debug_ui::{LayerNames, PhysicsDebugUi, PhysicsDebugUiPlugin};
 }
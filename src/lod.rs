@@ -0,0 +1,146 @@
+//! # Collision shape LOD
+//!
+//! Bodies with a `SimplifiedShape` use it instead of their `CollisionShape`(in the broad and narrow phases)
+//! while they are farther than `CollisionSettings::lod_distance` from every `PhysicsViewer`(usually the camera or the player),
+//! trading accuracy for speed in big scenes.
+//!
+//! ```ignore
+//! app.insert_resource(CollisionSettings {
+//!     lod_distance: Some(1000.0),
+//!     ..Default::default()
+//! });
+//!
+//! coms.spawn_bundle(Camera2dBundle::default()).insert(PhysicsViewer);
+//! coms.spawn_bundle(KinematicBundle { shape: complicated.clone(), ..Default::default() })
+//!     .insert(SimplifiedShape::bounding_circle(&complicated));
+//! ```
+
+use bevy::{
+    ecs::{query::QueryEntityError, system::SystemParam},
+    prelude::*,
+    utils::HashSet,
+};
+
+use crate::{
+    physics_components::Transform2D,
+    plugin::CollisionSettings,
+    shapes::{Circle, CollisionShape},
+    transform_mode::TransformMode,
+};
+
+/// Cheaper shape used instead of the `CollisionShape` when the body is far from every `PhysicsViewer`
+#[derive(Component)]
+pub struct SimplifiedShape(pub CollisionShape);
+
+impl SimplifiedShape {
+    /// A circle containing the whole shape(at any rotation)
+    pub fn bounding_circle(shape: &CollisionShape) -> Self {
+        let aabb = shape.aabb(&Transform2D::new(Vec2::ZERO, 0.0, Vec2::ONE));
+
+        SimplifiedShape(CollisionShape::Circle(
+            Circle::new(aabb.extents.length()).with_offset(aabb.position),
+        ))
+    }
+}
+
+/// Marks an entity(camera, player) around which bodies use their full `CollisionShape`
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct PhysicsViewer;
+
+/// Bodies currently using their `SimplifiedShape`, updated at the start of `stage::COLLISION_DETECTION`
+#[derive(Debug, Default)]
+pub struct ShapeLod {
+    far: HashSet<Entity>,
+}
+
+impl ShapeLod {
+    /// Whether the body is currently using its `SimplifiedShape`
+    pub fn is_simplified(
+        &self,
+        entity: Entity,
+    ) -> bool {
+        self.far.contains(&entity)
+    }
+}
+
+/// The shape each body should collide with this frame, a drop in replacement for `Query<&CollisionShape>`
+#[derive(SystemParam)]
+pub struct BodyShapes<'w, 's> {
+    shapes: Query<'w, 's, &'static CollisionShape>,
+    simplified: Query<'w, 's, &'static SimplifiedShape>,
+    lod: Res<'w, ShapeLod>,
+}
+
+impl<'w, 's> BodyShapes<'w, 's> {
+    pub fn get(
+        &self,
+        entity: Entity,
+    ) -> Result<&CollisionShape, QueryEntityError> {
+        if self.lod.is_simplified(entity) {
+            if let Ok(s) = self.simplified.get(entity) {
+                return Ok(&s.0);
+            }
+        }
+        self.shapes.get(entity)
+    }
+}
+
+/// Decides which bodies use their `SimplifiedShape` this frame
+pub fn shape_lod_system(
+    settings: Res<CollisionSettings>,
+    trans_mode: Res<TransformMode>,
+    mut lod: ResMut<ShapeLod>,
+    viewers: Query<&GlobalTransform, With<PhysicsViewer>>,
+    bodies: Query<(Entity, &Transform2D), With<SimplifiedShape>>,
+) {
+    lod.far.clear();
+
+    let distance = match settings.lod_distance {
+        Some(d) => d,
+        None => return,
+    };
+    let viewers: Vec<Vec2> = viewers.iter().map(|gt| trans_mode.get_global_position(gt)).collect();
+    // no one is looking, so nothing is "far"
+    if viewers.is_empty() {
+        return;
+    }
+
+    let dis_sqr = distance * distance;
+    for (e, t) in bodies.iter() {
+        let pos = t.translation();
+        if viewers.iter().all(|v| v.distance_squared(pos) > dis_sqr) {
+            lod.far.insert(e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod lod_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn far_bodies_use_simplified_shape() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(CollisionSettings {
+            lod_distance: Some(50.0),
+            ..Default::default()
+        });
+        app.spawn_at(Vec2::ZERO, (PhysicsViewer,));
+
+        // the bounding circle(radius ~1.41) reaches the static body, the square doesn't
+        let mut pairs = Vec::new();
+        for x in [0.0, 100.0] {
+            let wall = app.spawn_at(Vec2::new(x, 0.0), StaticBundle::default());
+            let body = app.spawn_at(Vec2::new(x + 2.2, 0.0), KinematicBundle::default());
+            let lod = SimplifiedShape::bounding_circle(&CollisionShape::default());
+            app.app.world.entity_mut(body).insert(lod);
+            pairs.push((body, wall));
+        }
+
+        app.step();
+
+        app.assert_not_collided(pairs[0].0, pairs[0].1);
+        app.assert_collided(pairs[1].0, pairs[1].1);
+    }
+}
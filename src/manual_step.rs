@@ -0,0 +1,158 @@
+//! # Manual stepping
+//!
+//! By default the physics stages run once per `App::update`, with the frame's `Time`.
+//! Dedicated servers(and tests) which want to decide when and how often the physics advance
+//! can add the plugin with `Physics2dPlugin::with_manual_step`, and call `step_world` instead:
+//!
+//! ```ignore
+//! app.add_plugin(Physics2dPlugin::default().with_manual_step());
+//!
+//! // catch up with the clients, in fixed steps
+//! for _ in 0..pending_ticks {
+//!     step_world(&mut app.world, 1.0 / 30.0);
+//! }
+//! ```
+//!
+//! The physics stages are then kept in the `PhysicsSchedule` resource instead of the app's schedule,
+//! and each `step_world` runs all of them(plus the transform propagation, so the next step starts from where this one ended)
+//! with a `Time` of its own, advanced by exactly `dt` - the app's `Time` isn't touched.
+//! Systems meant for the physics stages should be added with `PhysicsAppExt::add_physics_system`, which works in both modes.
+//!
+//! Everything outside the physics stages(like the events being cleared, or `auto_insert_system` in `CoreStage::Update`)
+//! still runs with `App::update`.
+
+use bevy::{
+    ecs::schedule::{IntoSystemDescriptor, Stage, StageLabel},
+    prelude::*,
+    transform::transform_propagate_system,
+    utils::Duration,
+};
+
+use crate::{physics_components::Transform2D, plugin::stage};
+
+/// Transform propagation at the end of a manual step
+const PROPAGATE: &str = "phy_manual_propagate";
+
+/// The physics stages, when the plugin was added with `Physics2dPlugin::with_manual_step`
+pub struct PhysicsSchedule {
+    pub schedule: Schedule,
+    time: Time,
+}
+
+impl Default for PhysicsSchedule {
+    fn default() -> Self {
+        let mut schedule = Schedule::default();
+        schedule
+            .add_stage(stage::PHYSICS_STEP, SystemStage::single_threaded())
+            .add_stage(stage::JOINT_STEP, SystemStage::single_threaded())
+            .add_stage(stage::COLLISION_DETECTION, SystemStage::single_threaded())
+            .add_stage(PROPAGATE, SystemStage::single_threaded());
+        // nothing else adds the `Transform2D`s of bodies spawned in between the steps
+        schedule.add_system_to_stage(stage::PHYSICS_STEP, Transform2D::auto_insert_system);
+        schedule.add_system_to_stage(PROPAGATE, transform_propagate_system);
+
+        let mut time = Time::default();
+        // so the first step is `dt` long as well
+        let startup = time.startup();
+        time.update_with_instant(startup);
+
+        PhysicsSchedule { schedule, time }
+    }
+}
+
+/// Advances the physics by `dt` seconds, for apps whose `Physics2dPlugin` was added with `with_manual_step`
+///
+/// Panics if the plugin wasn't added that way(there would be nothing to run)
+pub fn step_world(
+    world: &mut World,
+    dt: f32,
+) {
+    world.resource_scope(|world, mut physics: Mut<PhysicsSchedule>| {
+        let physics = &mut *physics;
+        let last = physics.time.last_update().unwrap_or_else(|| physics.time.startup());
+        physics.time.update_with_instant(last + Duration::from_secs_f32(dt.max(0.0)));
+
+        // the physics see their own time, the app's one is put back afterwards
+        let app_time = world.remove_resource::<Time>();
+        world.insert_resource(physics.time.clone());
+        physics.schedule.run(world);
+        world.remove_resource::<Time>();
+        if let Some(t) = app_time {
+            world.insert_resource(t);
+        }
+    });
+}
+
+/// Adds systems to the physics stages, wherever they are
+pub trait PhysicsAppExt {
+    /// Adds a system to one of the physics stages(`stage::PHYSICS_STEP`, `stage::JOINT_STEP` or `stage::COLLISION_DETECTION`),
+    /// in the app's schedule or in the `PhysicsSchedule` when stepping manually
+    fn add_physics_system<Params>(
+        &mut self,
+        stage: impl StageLabel,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self;
+}
+
+impl PhysicsAppExt for App {
+    fn add_physics_system<Params>(
+        &mut self,
+        stage: impl StageLabel,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self {
+        match self.world.get_resource_mut::<PhysicsSchedule>() {
+            Some(mut physics) => {
+                physics.schedule.add_system_to_stage(stage, system);
+            },
+            None => {
+                self.add_system_to_stage(stage, system);
+            },
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod manual_step_tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn steps_on_demand() {
+        let mut app = App::new();
+        app.add_plugin(bevy::core::CorePlugin)
+            .add_plugin(bevy::transform::TransformPlugin)
+            .add_plugin(bevy::hierarchy::HierarchyPlugin)
+            .insert_resource(Time::default())
+            .add_plugin(Physics2dPlugin::default().with_manual_step());
+
+        let wall = app.world.spawn()
+            .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(10.0, 0.0, 0.0)))
+            .insert_bundle(StaticBundle::default())
+            .id();
+        let body = app.world.spawn()
+            .insert_bundle(TransformBundle::default())
+            .insert_bundle(KinematicBundle {
+                vel: Vel(Vec2::new(4.0, 0.0)),
+                ..Default::default()
+            })
+            .insert(Acc::default())
+            .id();
+
+        // the app updating doesn't move anything
+        app.update();
+        assert_eq!(app.world.get::<Transform>(body).unwrap().translation.x, 0.0);
+
+        // 4 steps in a batch, each one picks up where the last one ended
+        for _ in 0..4 {
+            step_world(&mut app.world, 0.5);
+        }
+        assert!((app.world.get::<Transform>(body).unwrap().translation.x - 8.0).abs() < 0.001);
+
+        // into the wall
+        let mut reader = app.world.resource::<Events<CollisionEvent>>().get_reader();
+        step_world(&mut app.world, 0.5);
+        assert!((app.world.get::<Transform>(body).unwrap().translation.x - 8.0).abs() < 0.001);
+        assert!(reader.iter(app.world.resource::<Events<CollisionEvent>>()).any(|ev| (ev.entity_a, ev.entity_b) == (body, wall)));
+    }
+}
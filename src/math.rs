@@ -0,0 +1,4 @@
+//! Re-exports the math types used by the core geometry,
+//! which only depends on `bevy_math`(so it works without the `ecs` feature)
+
+pub use bevy_math::{Mat2, Vec2, Vec4};
@@ -26,10 +26,8 @@ pub fn narrow_phase_system(
     // Move all kinematic bodies to where they need to be moved
     // check collision pairs between kinematic bodies
 
-    // We need to transfer it into a Vec(or other iterable stuff) because the EventReader.iter is a 1 time consuming thingy
-    let broad_data = broad_data.iter().collect::<Vec<_>>();
-
-    for &broad in broad_data.iter() {
+    // the events are only gone over once, so there is no need to collect them into a Vec first
+    for broad in broad_data.iter() {
         let k_entity = broad.entity;
 
         // TODO normal error messages would be better i guess?
@@ -172,6 +170,9 @@ pub fn narrow_phase_system(
                     is_b_static: true, // we only collide with static bodies here
                     normal,
                     penetration: Vec2::ZERO,
+                    // the parts of `Multiple` shapes are only looked into by `narrow_phase_2`
+                    part_a: None,
+                    part_b: None,
                 });
             }
             else {
@@ -0,0 +1,297 @@
+//! # Navigation helpers
+//!
+//! `SurfaceGraph::build` scans static bodies for the edges an AI could walk on(edges facing up, within `NavSettings::floor_angle`),
+//! and links them into a graph - walking from one surface onto the next, jumping up to a surface within the jump envelope,
+//! or falling down to one. It is the raw data platformer pathfinding needs, the pathfinding itself is up to you.
+//!
+//! `OccupancyGrid::rasterize` turns static bodies into a grid of blocked cells instead, for flow fields and A* crates.
+//!
+//! ```ignore
+//! fn build_nav(statics: Query<(Entity, &CollisionShape, &Transform2D), With<StaticBody>>) {
+//!     let graph = SurfaceGraph::build(&NavSettings::default(), statics.iter());
+//!     for (i, s) in graph.surfaces.iter().enumerate() {
+//!         for link in graph.links[i].iter() { ... }
+//!     }
+//! }
+//! ```
+
+use bevy::prelude::*;
+
+use crate::{
+    physics_components::Transform2D,
+    shapes::{collide, Aabb, CollisionShape, Square},
+};
+
+/// Limits of the agent walking the surfaces
+#[derive(Debug, Clone, Copy)]
+pub struct NavSettings {
+    /// Steepest slope which is still a floor(in radians)
+    pub floor_angle: f32,
+    /// Ends of surfaces closer than this are walked between
+    pub merge_distance: f32,
+    /// How high up the agent can jump
+    pub jump_height: f32,
+    /// How far(horizontally) the agent can jump or fall
+    pub jump_distance: f32,
+    /// How far down the agent is willing to fall
+    pub max_fall: f32,
+}
+
+impl Default for NavSettings {
+    fn default() -> Self {
+        NavSettings {
+            floor_angle: 45f32.to_radians(),
+            merge_distance: 1.0,
+            jump_height: 100.0,
+            jump_distance: 150.0,
+            max_fall: f32::INFINITY,
+        }
+    }
+}
+
+/// An edge which can be walked on, from left to right
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WalkableSurface {
+    /// The static body the edge belongs to
+    pub entity: Entity,
+    pub left: Vec2,
+    pub right: Vec2,
+}
+
+/// How an agent gets from one surface to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// The surfaces touch
+    Walk,
+    /// The other surface is higher, but within the jump envelope
+    Jump,
+    /// The other surface is lower
+    Fall,
+}
+
+/// A link to `SurfaceGraph::surfaces[to]`, going from `from`(an end of this surface) to `at`(an end of the other one)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceLink {
+    pub to: usize,
+    pub kind: LinkKind,
+    pub from: Vec2,
+    pub at: Vec2,
+}
+
+/// The walkable surfaces and the links between them(`links[i]` are the links going out of `surfaces[i]`)
+#[derive(Debug, Clone, Default)]
+pub struct SurfaceGraph {
+    pub surfaces: Vec<WalkableSurface>,
+    pub links: Vec<Vec<SurfaceLink>>,
+}
+
+impl SurfaceGraph {
+    /// Extracts the walkable surfaces of the given static bodies and links them
+    pub fn build<'a>(
+        settings: &NavSettings,
+        statics: impl IntoIterator<Item = (Entity, &'a CollisionShape, &'a Transform2D)>,
+    ) -> Self {
+        let min_up = settings.floor_angle.cos();
+
+        let mut surfaces = Vec::new();
+        for (entity, shape, t) in statics {
+            let verts: Vec<Vec2> = shape.world_vertices(t).collect();
+            for i in 0..verts.len() {
+                let (a, b) = (verts[i], verts[(i + 1) % verts.len()]);
+                // outlines are counter-clockwise, so the outward normal is to the right of the edge
+                let normal = match Vec2::new(b.y - a.y, a.x - b.x).try_normalize() {
+                    Some(n) => n,
+                    None => continue,
+                };
+                if normal.y >= min_up {
+                    let (left, right) = if a.x <= b.x { (a, b) } else { (b, a) };
+                    surfaces.push(WalkableSurface { entity, left, right });
+                }
+            }
+        }
+
+        let links = (0..surfaces.len())
+            .map(|i| {
+                (0..surfaces.len())
+                    .filter(|j| *j != i)
+                    .filter_map(|j| Self::link(settings, &surfaces[i], &surfaces[j], j))
+                    .collect()
+            })
+            .collect();
+
+        SurfaceGraph { surfaces, links }
+    }
+
+    /// The link from `a` to `b`, from the closest pair of ends
+    fn link(
+        settings: &NavSettings,
+        a: &WalkableSurface,
+        b: &WalkableSurface,
+        to: usize,
+    ) -> Option<SurfaceLink> {
+        let (from, at) = [a.left, a.right]
+            .iter()
+            .flat_map(|f| [b.left, b.right].map(|t| (*f, t)))
+            .min_by(|x, y| x.0.distance_squared(x.1).total_cmp(&y.0.distance_squared(y.1)))?;
+        let gap = at - from;
+
+        let kind = if gap.length() <= settings.merge_distance {
+            LinkKind::Walk
+        }
+        else if gap.x.abs() > settings.jump_distance {
+            return None;
+        }
+        else if gap.y > 0.0 {
+            if gap.y > settings.jump_height {
+                return None;
+            }
+            LinkKind::Jump
+        }
+        else {
+            if -gap.y > settings.max_fall {
+                return None;
+            }
+            LinkKind::Fall
+        };
+
+        Some(SurfaceLink { to, kind, from, at })
+    }
+}
+
+/**
+    # OccupancyGrid
+
+    A grid of cells covering an area, a cell is blocked when any of the rasterized shapes overlaps it.
+
+    Cells are indexed from the bottom left corner of the area, `x` going right and `y` going up.
+*/
+#[derive(Debug, Clone)]
+pub struct OccupancyGrid {
+    /// Bottom left corner of the grid
+    pub origin: Vec2,
+    pub cell_size: f32,
+    pub width: usize,
+    pub height: usize,
+    /// Row by row(`cells[y * width + x]`), `true` is blocked
+    pub cells: Vec<bool>,
+}
+
+impl OccupancyGrid {
+    /// Rasterizes the shapes into a grid covering `area`(rounded up to whole cells)
+    pub fn rasterize<'a>(
+        area: Aabb,
+        cell_size: f32,
+        shapes: impl IntoIterator<Item = (&'a CollisionShape, &'a Transform2D)>,
+    ) -> Self {
+        let (min, max) = area.min_max();
+        let size = ((max - min) / cell_size).ceil();
+        let (width, height) = (size.x.max(0.0) as usize, size.y.max(0.0) as usize);
+
+        let mut grid = OccupancyGrid {
+            origin: min,
+            cell_size,
+            width,
+            height,
+            cells: vec![false; width * height],
+        };
+        let cell = CollisionShape::Square(Square::new(Vec2::splat(0.5 * cell_size)));
+
+        for (shape, t) in shapes {
+            // only the cells under the shape's aabb
+            let (smin, smax) = shape.aabb(t).min_max();
+            let (x0, y0) = grid.clamped_cell(smin);
+            let (x1, y1) = grid.clamped_cell(smax);
+
+            for y in y0..=y1.min(height.saturating_sub(1)) {
+                for x in x0..=x1.min(width.saturating_sub(1)) {
+                    let i = y * width + x;
+                    if grid.cells[i] {
+                        continue;
+                    }
+                    let ct = Transform2D::new(grid.cell_center(x, y), 0.0, Vec2::ONE);
+                    grid.cells[i] = shape.contains_point(t, ct.translation()) || collide(&cell, &ct, shape, t).is_some();
+                }
+            }
+        }
+        grid
+    }
+
+    /// Whether the cell is blocked(cells outside the grid are blocked)
+    pub fn is_blocked(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> bool {
+        x >= self.width || y >= self.height || self.cells[y * self.width + x]
+    }
+    /// The cell containing `point`
+    pub fn cell_at(
+        &self,
+        point: Vec2,
+    ) -> Option<(usize, usize)> {
+        let c = ((point - self.origin) / self.cell_size).floor();
+        (c.x >= 0.0 && c.y >= 0.0 && (c.x as usize) < self.width && (c.y as usize) < self.height).then_some((c.x as usize, c.y as usize))
+    }
+    /// World position of the center of a cell
+    pub fn cell_center(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> Vec2 {
+        self.origin + (Vec2::new(x as f32, y as f32) + Vec2::splat(0.5)) * self.cell_size
+    }
+
+    /// The cell containing `point`, clamped into the grid
+    fn clamped_cell(
+        &self,
+        point: Vec2,
+    ) -> (usize, usize) {
+        let c = ((point - self.origin) / self.cell_size).floor().max(Vec2::ZERO);
+        (c.x as usize, c.y as usize)
+    }
+}
+
+#[cfg(test)]
+mod navigation_tests {
+    use super::*;
+    use crate::shapes::Square;
+
+    #[test]
+    fn platforms_are_linked() {
+        let settings = NavSettings {
+            jump_height: 3.0,
+            jump_distance: 4.0,
+            ..Default::default()
+        };
+        let ground = CollisionShape::Square(Square::size(Vec2::new(10.0, 2.0)));
+        let ledge = CollisionShape::Square(Square::size(Vec2::new(8.0, 2.0)));
+        let (tg, tl) = (
+            Transform2D::new(Vec2::new(5.0, -1.0), 0.0, Vec2::ONE),
+            Transform2D::new(Vec2::new(16.0, 1.0), 0.0, Vec2::ONE),
+        );
+        let (eg, el) = (Entity::from_raw(0), Entity::from_raw(1));
+
+        let graph = SurfaceGraph::build(&settings, [(eg, &ground, &tg), (el, &ledge, &tl)]);
+
+        // only the tops
+        assert_eq!(graph.surfaces.len(), 2);
+        assert_eq!(graph.surfaces[0].left, Vec2::new(0.0, 0.0));
+        assert_eq!(graph.links[0][0].kind, LinkKind::Jump);
+        assert_eq!(graph.links[1][0].kind, LinkKind::Fall);
+        assert_eq!(graph.links[1][0].at, Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn rasterize_box() {
+        // a 2x2 box covering cells 1..=2 on both axes(and just touching no other cell)
+        let shape = CollisionShape::Square(Square::default());
+        let t = Transform2D::new(Vec2::new(2.0, 2.0), 0.0, Vec2::ONE);
+
+        let grid = OccupancyGrid::rasterize(Aabb::size(Vec2::splat(4.0), Vec2::splat(2.0)), 1.0, [(&shape, &t)]);
+
+        assert_eq!((grid.width, grid.height), (4, 4));
+        assert_eq!(grid.cells.iter().filter(|c| **c).count(), 4);
+        assert!(grid.is_blocked(1, 2) && !grid.is_blocked(0, 2) && grid.is_blocked(4, 0));
+        assert_eq!(grid.cell_at(Vec2::new(2.5, 0.5)), Some((2, 0)));
+    }
+}
@@ -1,5 +1,5 @@
-use bevy::{math::Mat2, prelude::*};
-use crate::{physics_components::Transform2D, prelude::*};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::{HashMap, HashSet}};
+use crate::{frame_budget::FrameBudget, lod::BodyShapes, pair_key::PairKey, physics_components::Transform2D, plugin::{BroadPhase, CollisionSettings, MtvSplit}, prelude::*, spatial_hash::SpatialHash, static_index::StaticIndex, world_wrap, bodies::frozen_split};
 
 pub struct CollPairKin(Entity, Entity);
 pub struct CollPairStatic(Entity, Entity);
@@ -7,22 +7,32 @@ pub struct CollPairSensor(Entity, Entity);
 
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn broad_phase_2(
-	shapes: Query<&CollisionShape>,
+	shapes: BodyShapes,
 	// bodies
-	kins: Query<(Entity, &Transform2D, &CollisionLayer),(/* Without<Vel>, */ Without<StaticBody>, Without<Sensor>)>,
+	kins: Query<(Entity, &Transform2D, &CollisionLayer),(/* Without<Vel>, */ Without<StaticBody>, Without<Sensor>, Without<PhysicsDisabled>)>,
 	// kins_con: Query<(Entity, &Transform2D, &CollisionLayer), With<Vel>>,
-	statics: Query<(Entity, &Transform2D, &CollisionLayer),With<StaticBody>>,
-	sensors: Query<(Entity, &Transform2D, &CollisionLayer), With<Sensor>>,
+	// the statics are kept between frames(`static_index_system` runs right before)
+	statics: Res<StaticIndex>,
+	sensors: Query<(Entity, &Transform2D, &CollisionLayer), (With<Sensor>, Without<PhysicsDisabled>)>,
+	settings: Res<CollisionSettings>,
+	mut budget: ResMut<FrameBudget>,
+	// a body and what it is attached to are never paired
+	attached: Query<&Attach>,
 	// event writers
 	mut pair_kin: EventWriter<CollPairKin>,
 	mut pair_static: EventWriter<CollPairStatic>,
 	mut pair_sensor: EventWriter<CollPairSensor>,
+	mut scratch: Local<BroadScratch>,
 ) {
 	// Someday this function should utilize the different algorithms and data strucs
 	// to make for a better broad phase with superiour performance
 
 	// Current imlp is for something that just works, without too much hassle
 
+	// TODO once a BVH(dynamic aabb tree) lands here, expose its tuning in `CollisionSettings`
+	// (fat aabb margin, rebalance threshold) and some diagnostics(tree depth/quality),
+	// there is nothing to tune in the brute force version below
+
 	// Kinematic_con x kinematic_con
 	// for (i, (e1, t1, l1)) in kins_con.iter().enumerate() {
 	// 	let aabb1 = match shapes.get(e1) {
@@ -44,24 +54,61 @@ pub fn broad_phase_2(
 	// 	}
 	// }
 
+	budget.start();
+
 	// Kinematic x _
-	for (i, (e1, t1, l1)) in kins.iter().enumerate() {
-		let aabb1 = match shapes.get(e1) {
-			Ok(s) => s.aabb(t1),
-			Err(_) => continue,
-		};
+	// the aabbs are found once per frame(instead of once per pair), in buffers which are reused between frames
+	let BroadScratch { kin_bodies, sensors: sensor_bodies, carried, hashed, static_hashed, candidates } = &mut *scratch;
+	kin_bodies.clear();
+	kin_bodies.extend(kins.iter().filter_map(|(e, t, l)| shapes.get(e).ok().map(|s| (e, s.aabb(t), *l))));
+	let static_bodies = statics.bodies();
+	sensor_bodies.clear();
+	sensor_bodies.extend(sensors.iter().filter_map(|(e, t, l)| shapes.get(e).ok().map(|s| (e, s.aabb(t), *l))));
+
+	// position of each body which was skipped last frame
+	carried.clear();
+	carried.extend(budget.deferred_bodies.drain(..).enumerate().map(|(i, e)| (e, i)));
+	if settings.frame_budget.is_some() {
+		// bodies which were skipped last frame go first(in the same order, so none of them starves), then the ones in focus
+		let focus = budget.focus;
+		kin_bodies.sort_by_key(|(e, aabb, _)| {
+			let in_focus = matches!(focus, Some(f) if aabb.collides(&f));
+			(carried.get(e).copied().unwrap_or(usize::MAX), !in_focus)
+		});
+	}
+
+	// the spatial hash doesn't know about the world wrapping around, so wrapping worlds always go over every body
+	// (and so does a cell size the hash can't use, which can be set on the `CollisionSettings` directly)
+	let cell_size = match settings.broad_phase {
+		BroadPhase::SpatialHash { cell_size } if settings.world_wrap.is_none() && cell_size > 0.0 => Some(cell_size),
+		_ => None,
+	};
+	if let Some(cell_size) = cell_size {
+		hashed[0].fill(cell_size, kin_bodies);
+		// the statics' hash is only rebuilt when any of them changed
+		if *static_hashed != Some((statics.version(), cell_size)) {
+			hashed[1].fill(cell_size, static_bodies);
+			*static_hashed = Some((statics.version(), cell_size));
+		}
+		hashed[2].fill(cell_size, sensor_bodies);
+	}
+	let hashed = |list: usize| cell_size.map(|_| &hashed[list]);
+	let [kin_found, static_found, sensor_found] = candidates;
+
+	for (i, (e1, aabb1, l1)) in kin_bodies.iter().enumerate() {
+		// always make some progress, even on a tiny budget
+		if i > 0 && budget.exceeded(settings.frame_budget) {
+			budget.deferred_bodies.extend(kin_bodies[i..].iter().map(|(e, ..)| *e));
+			break;
+		}
+
+		let aabb1 = Aabb::new(aabb1.extents + Vec2::splat(settings.contact_margin), aabb1.position);
 
 		// x Kinematic
-		for (e2, t2, l2) in kins.iter().skip(i + 1) {
-			if l1.overlap(l2) {
-				let aabb2 = match shapes.get(e2) {
-					Ok(s) => s.aabb(t2),
-					Err(_) => continue,
-				};
-
-				if aabb1.collides(&aabb2) {
-					pair_kin.send(CollPairKin(e1,e2));
-				}
+		gather_candidates(hashed(0), kin_bodies.len(), i + 1, &aabb1, kin_found);
+		for (e2, aabb2, l2) in kin_found.iter().map(|j| &kin_bodies[*j]) {
+			if l1.overlap(l2) && !Attach::ignores(&attached, *e1, *e2) && aabb1.collides(&world_wrap::nearest_aabb(settings.world_wrap, &aabb1, *aabb2)) {
+				pair_kin.send(CollPairKin(*e1, *e2));
 			}
 		}
 
@@ -80,145 +127,398 @@ pub fn broad_phase_2(
 		// }
 
 		// x Statics
-		for (e2, t2, l2) in statics.iter() {
-			if l1.overlap(l2) {
-				let aabb2 = match shapes.get(e2) {
-					Ok(s) => s.aabb(t2),
-					Err(_) => continue,
-				};
-
-				if aabb1.collides(&aabb2) {
-					pair_static.send(CollPairStatic(e1,e2));
-				}
+		gather_candidates(hashed(1), static_bodies.len(), 0, &aabb1, static_found);
+		for (e2, aabb2, l2) in static_found.iter().map(|j| &static_bodies[*j]) {
+			if l1.overlap(l2) && !Attach::ignores(&attached, *e1, *e2) && aabb1.collides(&world_wrap::nearest_aabb(settings.world_wrap, &aabb1, *aabb2)) {
+				pair_static.send(CollPairStatic(*e1, *e2));
 			}
 		}
 
 		// x Sensors
-		for (e2, t2, l2) in sensors.iter() {
-			if l1.overlap(l2) {
-				let aabb2 = match shapes.get(e2) {
-					Ok(s) => s.aabb(t2),
-					Err(_) => continue,
-				};
-				if aabb1.collides(&aabb2) {
-					pair_sensor.send(CollPairSensor(e1,e2));
-				}
+		gather_candidates(hashed(2), sensor_bodies.len(), 0, &aabb1, sensor_found);
+		for (e2, aabb2, l2) in sensor_found.iter().map(|j| &sensor_bodies[*j]) {
+			if l1.overlap(l2) && !Attach::ignores(&attached, *e1, *e2) && aabb1.collides(&world_wrap::nearest_aabb(settings.world_wrap, &aabb1, *aabb2)) {
+				pair_sensor.send(CollPairSensor(*e1, *e2));
 			}
 		}
 	}
 
 }
+/// Per frame buffers of `broad_phase_2`, kept in a `Local` so big scenes don't reallocate them every frame
+#[derive(Default)]
+pub struct BroadScratch {
+	kin_bodies: Vec<(Entity, Aabb, CollisionLayer)>,
+	sensors: Vec<(Entity, Aabb, CollisionLayer)>,
+	carried: HashMap<Entity, usize>,
+	/// The kinematic, static and sensor lists in spatial hashes(only with `BroadPhase::SpatialHash`)
+	hashed: [HashedBodies; 3],
+	/// `StaticIndex` version and cell size the statics' hash was filled with
+	static_hashed: Option<(u64, f32)>,
+	/// Indices of the bodies each list might collide with, for the body being checked
+	candidates: [Vec<usize>; 3],
+}
+/// A list of bodies in a spatial hash, bodies spanning too many cells are kept aside and always checked
+#[derive(Default)]
+struct HashedBodies {
+	hash: Option<SpatialHash>,
+	oversized: Vec<usize>,
+}
+impl HashedBodies {
+	/// Most cells a body can span before it is kept aside
+	const MAX_CELLS: i64 = 64;
+
+	/// Fills the hash with the bodies(by their index), remaking it when the cell size changed
+	fn fill(
+		&mut self,
+		cell_size: f32,
+		bodies: &[(Entity, Aabb, CollisionLayer)],
+	) {
+		let hash = match &mut self.hash {
+			Some(h) if h.cell_size() == cell_size => h,
+			h => h.insert(SpatialHash::new(cell_size)),
+		};
+		hash.clear();
+		self.oversized.clear();
+
+		for (i, (_, aabb, _)) in bodies.iter().enumerate() {
+			let (min, max) = aabb.min_max();
+			let (min_c, max_c) = (hash.cell(min), hash.cell(max));
+			let cells = (max_c.0 as i64 - min_c.0 as i64 + 1) * (max_c.1 as i64 - min_c.1 as i64 + 1);
+			if cells > Self::MAX_CELLS {
+				self.oversized.push(i);
+			}
+			else {
+				hash.insert_aabb(i, min, max);
+			}
+		}
+	}
+	/// Pushes the index of every body which might overlap `aabb` into `out`, in order and without repeats
+	fn candidates(
+		&self,
+		aabb: &Aabb,
+		out: &mut Vec<usize>,
+	) {
+		if let Some(hash) = &self.hash {
+			let (min, max) = aabb.min_max();
+			out.extend(hash.query_aabb(min, max));
+		}
+		out.extend(self.oversized.iter().copied());
+		out.sort_unstable();
+		out.dedup();
+	}
+}
+/// Fills `out` with the indices(from `from` on) of a list of `len` bodies which might overlap `aabb`, in order
+fn gather_candidates(
+	hashed: Option<&HashedBodies>,
+	len: usize,
+	from: usize,
+	aabb: &Aabb,
+	out: &mut Vec<usize>,
+) {
+	out.clear();
+	match hashed {
+		Some(h) => {
+			h.candidates(aabb, out);
+			out.retain(|j| *j >= from);
+		},
+		None => out.extend(from..len),
+	}
+}
+/// Per frame buffers of `narrow_phase_2`, cleared(not dropped) at the start of every frame
+#[derive(Default)]
+pub struct NarrowScratch {
+	contacts: HashMap<Entity, Vec<(Entity, Vec2)>>,
+	initial_vels: HashMap<Entity, Vec2>,
+	collided: HashSet<PairKey>,
+	supported: HashSet<Entity>,
+	kin_pairs: Vec<(Entity, Entity)>,
+	static_pairs: Vec<(Entity, Entity)>,
+	sensor_pairs: Vec<(Entity, Entity)>,
+}
 #[allow(clippy::too_many_arguments)]
 pub fn narrow_phase_2(
 	// Data we need
-	shapes: Query<&CollisionShape>,
+	shapes: BodyShapes,
 	mut transforms: Query<&mut Transform2D>,
-	mut sensors: Query<&mut Sensor>,
+	mut sensors: Query<(&mut Sensor, Option<&SensorArc>)>,
 	mut vels: Query<&mut Vel>,
+	// how the bodies in a pair are split
+	(priorities, frozen): (Query<&SolvePriority>, Query<&Frozen>),
+	masses: Query<&Mass>,
+	mut forces: Query<(Entity, &mut ContactForces)>,
+	mut stats: Query<(Entity, &mut CollisionStats)>,
+	time: Res<Time>,
+	settings: Res<CollisionSettings>,
+	mut budget: ResMut<FrameBudget>,
 	// Readers(for the entities)
 	mut pair_kin: EventReader<CollPairKin>,
 	mut pair_static: EventReader<CollPairStatic>,
 	mut pair_sensor: EventReader<CollPairSensor>,
 	// writers
 	mut coll_writer: EventWriter<CollisionEvent>,
+	mut scratch: Local<NarrowScratch>,
 ) {
-	// Solve kinematic pairs
-	for CollPairKin(e1, e2) in pair_kin.iter() {
-		let s1 = match shapes.get(*e1) {
-			Ok(s) => s,
-			Err(_) => continue,
-		};
+	let priority = |e: Entity| priorities.get(e).copied().unwrap_or_default();
+	let NarrowScratch { contacts, initial_vels, collided, supported, kin_pairs, static_pairs, sensor_pairs } = &mut *scratch;
+	// contacts of each body this frame(the other body, and the normal pointing away from it),
+	// the lists of bodies which had no contacts last frame are dropped, the rest are only cleared
+	contacts.retain(|_, c| {
+		let keep = !c.is_empty();
+		c.clear();
+		keep
+	});
+	// velocities before anything touched them, for `ContactForces`
+	initial_vels.clear();
 
-		let t1 = match transforms.get_component::<Transform2D>(*e1) {
-			Ok(t) => t,
-			Err(_) => continue,
-		};
-		
-		let s2 = match shapes.get(*e2) {
-			Ok(s) => s,
-			Err(_) => continue,
+	// pairs which collided this frame, for the frame budget(and so each pair is only reported once)
+	let budgeted = settings.frame_budget.is_some();
+	collided.clear();
+
+	// Gather the pairs(and whatever was carried over from the previous frame)
+	kin_pairs.clear();
+	static_pairs.clear();
+	sensor_pairs.clear();
+	kin_pairs.append(&mut budget.deferred_kin);
+	static_pairs.append(&mut budget.deferred_static);
+	sensor_pairs.append(&mut budget.deferred_sensor);
+	let carried = (kin_pairs.len(), static_pairs.len(), sensor_pairs.len());
+	kin_pairs.extend(pair_kin.iter().map(|CollPairKin(e1, e2)| (*e1, *e2)));
+	static_pairs.extend(pair_static.iter().map(|CollPairStatic(e1, e2)| (*e1, *e2)));
+	sensor_pairs.extend(pair_sensor.iter().map(|CollPairSensor(e1, e2)| (*e1, *e2)));
+
+	if budgeted {
+		let focus = budget.focus;
+		let in_focus = |e: Entity| match (focus, shapes.get(e), transforms.get(e)) {
+			(Some(f), Ok(s), Ok(t)) => s.aabb(t).collides(&f),
+			_ => false,
 		};
+		budget.order_pairs(kin_pairs, carried.0, in_focus);
+		budget.order_pairs(static_pairs, carried.1, in_focus);
+		budget.order_pairs(sensor_pairs, carried.2, in_focus);
+	}
 
-		let t2 = match transforms.get_component::<Transform2D>(*e2) {
-			Ok(t) => t,
-			Err(_) => continue,
+	// Solve kinematic pairs, higher priority pairs first(the sort is stable, so equal pairs keep their order)
+	kin_pairs.sort_by_key(|(e1, e2)| std::cmp::Reverse(priority(*e1).max(priority(*e2))));
+
+	// bodies resting on a static body(directly or through other bodies), in a pair with an unsupported body only the unsupported one is moved,
+	// so stacks are pushed up from the ground instead of into it
+	supported.clear();
+
+	// Solves a single pair(`e2` is static when `is_static`), returns whether they collided
+	let mut solve = |e1: Entity, e2: Entity, is_static: bool, statics: &[(Entity, Entity)], supported: &mut HashSet<Entity>| -> bool {
+		let (s1, t1, s2, t2) = match (
+			shapes.get(e1),
+			transforms.get_component::<Transform2D>(e1),
+			shapes.get(e2),
+			transforms.get_component::<Transform2D>(e2),
+		) {
+			(Ok(s1), Ok(t1), Ok(s2), Ok(t2)) => (s1, t1, s2, t2),
+			_ => return false,
 		};
 
-		let p = collide(s1,t1,s2,t2);
+		// the closest copy of the other body, when the world wraps around
+		let t2 = world_wrap::nearest_transform(settings.world_wrap, t1, t2);
+		let pen = match collide(s1,t1,s2,&t2) {
+			Some(p) => p,
+			None => return false,
+		};
+		let normal = pen.normalize();
 
-		if let Some(pen) = p {
-			let normal = pen.normalize();
+		if is_static {
+			// the face it pushes out of is shared with another static body the kinematic touches(a seam between tiles),
+			// so it is not a real face and the neighbour handles the contact(like box2d's ghost vertices)
+			if settings.smooth_seams {
+				let probe = seam_probe(&s1.aabb(t1), &s2.aabb(&t2), pen);
+				let shared = statics.iter()
+					.filter(|(k, s)| *k == e1 && *s != e2)
+					.any(|(_, s)| match (shapes.get(*s), transforms.get_component::<Transform2D>(*s)) {
+						(Ok(shape), Ok(t)) => shape.contains_point(&world_wrap::nearest_transform(settings.world_wrap, t1, t), probe),
+						_ => false,
+					});
+				if shared {
+					return false;
+				}
+			}
+		}
 
+		// each pair is reported once, even if it collided in a few iterations
+		if collided.insert(PairKey::new(e1, e2)) {
 			coll_writer.send(CollisionEvent { 
-				entity_a: *e1, 
-				entity_b: *e2, 
-				is_b_static: false, 
+				entity_a: e1, 
+				entity_b: e2, 
+				is_b_static: is_static, 
 				normal,
 				penetration: -pen,
+				part_a: s1.deepest_part(t1, s2, &t2),
+				part_b: s2.deepest_part(&t2, s1, t1),
 			});
-			// Maybe move both of them? or should i just move 1 of them?
-			// I also cannot tell which 1 is moving here, so that's a bummer
-			// for now i will move only e1
-			if let Ok(mut t) = transforms.get_mut(*e1) {
-				t.add_translation(pen);
+			contacts.entry(e1).or_default().push((e2, normal));
+			if !is_static {
+				contacts.entry(e2).or_default().push((e1, -normal));
 			}
-
-			// slide the movement of the objects
-			if let Ok(mut v) = vels.get_mut(*e1) {
-				if v.0.dot(normal) < 0.0 {
-					v.0 = v.0.slide(normal);
+			for e in [e1, e2] {
+				if let Ok(v) = vels.get(e) {
+					initial_vels.entry(e).or_insert(v.0);
 				}
 			}
-			if let Ok(mut v) = vels.get_mut(*e2) {
-				if v.0.dot(-normal) < 0.0 {
-					v.0 = v.0.slide(normal);
-				}
+		}
+
+		let pen = slop_correction(pen, settings.slop);
+		if is_static {
+			let pen = frozen.get(e1).map_or(pen, |f| f.mask(pen));
+			if let Ok(mut t) = transforms.get_mut(e1) {
+				t.add_translation(pen);
 			}
+			supported.insert(e1);
+			return true;
 		}
 
-	}
+		// Maybe move both of them? or should i just move 1 of them?
+		// I also cannot tell which 1 is moving here, so that's a bummer
+		// for now i will move only e1(unless e1 has a higher priority, is supported, or we split by area)
+		let (p1, p2) = (priority(e1), priority(e2));
+		let (sup1, sup2) = (supported.contains(&e1), supported.contains(&e2));
 
-	// Solve static pairs
-	for CollPairStatic(ek, es) in pair_static.iter() {
-		let sk = match shapes.get(*ek) {
-			Ok(s) => s,
-			Err(_) => continue,
-		};
+		let (m1, m2) = if p1 > p2 || (p1 == p2 && sup1 && !sup2) {
+			(Vec2::ZERO, -pen)
+		}
+		else if p1 < p2 || (sup2 && !sup1) || settings.mtv_split == MtvSplit::FirstBody {
+			(pen, Vec2::ZERO)
+		}
+		else {
+			let (a1, a2) = (s1.area(), s2.area());
+			let total = a1 + a2;
 
-		let tk = match transforms.get_component::<Transform2D>(*ek) {
-			Ok(t) => t,
-			Err(_) => continue,
+			if total > f32::EPSILON {
+				(pen * (a2 / total), -pen * (a1 / total))
+			}
+			else {
+				(pen * 0.5, -pen * 0.5)
+			}
 		};
+		// frozen axes don't move, the other body is pushed out the whole way there instead
+		let (m1, m2) = frozen_split(m1, m2, frozen.get(e1).ok(), frozen.get(e2).ok());
 
-		let ss = match shapes.get(*es) {
-			Ok(s) => s,
-			Err(_) => continue,
-		};
+		if let Ok(mut t) = transforms.get_mut(e1) {
+			t.add_translation(m1);
+		}
+		if let Ok(mut t) = transforms.get_mut(e2) {
+			t.add_translation(m2);
+		}
+		// whoever stayed in place holds the other one up
+		if m1 == Vec2::ZERO && sup1 {
+			supported.insert(e2);
+		}
+		else if m2 == Vec2::ZERO && sup2 {
+			supported.insert(e1);
+		}
 
-		let ts = match transforms.get_component::<Transform2D>(*es) {
-			Ok(t) => t,
-			Err(_) => continue,
-		};
+		// slide the movement of the objects
+		if let Ok(mut v) = vels.get_mut(e1) {
+			if v.0.dot(normal) < 0.0 {
+				v.0 = v.0.slide(normal);
+			}
+		}
+		if let Ok(mut v) = vels.get_mut(e2) {
+			if v.0.dot(-normal) < 0.0 {
+				v.0 = v.0.slide(normal);
+			}
+		}
+		true
+	};
 
-		let p = collide(sk,tk,ss,ts);
+	// the pairs are solved a few times, as solving one pair might push the bodies into another
+	for iteration in 0..settings.iterations.max(1) {
+		// only the first iteration is cut by the frame budget, the rest go over the same pairs
+		let first = iteration == 0;
 
-		if let Some(pen) = p {
-			coll_writer.send(CollisionEvent{
-				entity_a: *ek,
-				entity_b: *es,
-				is_b_static: true,
-				normal: pen.normalize(),
-				penetration: -pen,
-			});
+		// Static pairs first, so the bodies on the ground are known to be supported
+		for (i, (ek, es)) in static_pairs.iter().enumerate() {
+			if first && i > 0 && budget.exceeded(settings.frame_budget) {
+				budget.deferred_static.extend_from_slice(&static_pairs[i..]);
+				break;
+			}
+			solve(*ek, *es, true, static_pairs, supported);
+		}
+		if first {
+			static_pairs.truncate(static_pairs.len() - budget.deferred_static.len());
+		}
 
-			if let Ok(mut t) = transforms.get_mut(*ek) {
-				t.add_translation(pen);
+		// Spread the support up the stacks, a body is pushed up by the supported body below it, and then supports the ones above it
+		let mut spreading = true;
+		while spreading {
+			spreading = false;
+			for (e1, e2) in kin_pairs.iter() {
+				if priority(*e1) == priority(*e2)
+					&& supported.contains(e1) != supported.contains(e2)
+					&& solve(*e1, *e2, false, static_pairs, supported)
+				{
+					spreading |= supported.contains(e1) && supported.contains(e2);
+				}
+			}
+		}
+
+		// Kinematic pairs
+		for (i, (e1, e2)) in kin_pairs.iter().enumerate() {
+			if first && i > 0 && budget.exceeded(settings.frame_budget) {
+				budget.deferred_kin.extend_from_slice(&kin_pairs[i..]);
+				break;
+			}
+			solve(*e1, *e2, false, static_pairs, supported);
+		}
+		if first {
+			kin_pairs.truncate(kin_pairs.len() - budget.deferred_kin.len());
+		}
+	}
+	// and the static pairs last, so nothing is left inside a static body
+	for (ek, es) in static_pairs.iter() {
+		solve(*ek, *es, true, static_pairs, supported);
+	}
+
+	// Stop the bodies from moving back into their contacts
+	if settings.clamp_velocity {
+		for (e, normals) in contacts.iter() {
+			if let Ok(mut v) = vels.get_mut(*e) {
+				for (_, n) in normals {
+					if v.0.dot(*n) < 0.0 {
+						v.0 = v.0.slide(*n);
+					}
+				}
 			}
 		}
 	}
 
+	// Read back the contact impulses
+	let (contacts, initial_vels) = (&*contacts, &*initial_vels);
+	let impulses = |e: Entity| {
+		let mass = masses.get(e).map(|m| m.0).unwrap_or(1.0);
+		let vel = initial_vels.get(&e).copied().unwrap_or_default();
+		contacts.get(&e).into_iter().flatten().map(move |(other, normal)| {
+			let relative = vel - initial_vels.get(other).copied().unwrap_or_default();
+
+			ContactImpulse {
+				other: *other,
+				normal: *normal,
+				impulse: mass * (-relative.dot(*normal)).max(0.0),
+			}
+		})
+	};
+	for (e, mut f) in forces.iter_mut() {
+		f.clear();
+		impulses(e).for_each(|c| f.add(c));
+	}
+	let now = time.seconds_since_startup();
+	for (e, mut s) in stats.iter_mut() {
+		s.add_frame(now, impulses(e));
+	}
+
 	// "Solve" sensor pairs
-	for CollPairSensor(ek, es) in pair_sensor.iter() {
+	for (i, (ek, es)) in sensor_pairs.iter().enumerate() {
+		if i > 0 && budget.exceeded(settings.frame_budget) {
+			budget.deferred_sensor.extend_from_slice(&sensor_pairs[i..]);
+			break;
+		}
+
 		let sk = match shapes.get(*ek) {
 			Ok(s) => s,
 			Err(_) => continue,
@@ -239,62 +539,167 @@ pub fn narrow_phase_2(
 			Err(_) => continue,
 		};
 
-		let p = collide(sk,tk,ss,ts);
+		let ts = world_wrap::nearest_transform(settings.world_wrap, tk, ts);
+		let p = collide(sk,tk,ss,&ts);
 
 		if p.is_some() {
-			if let Ok(mut sen) = sensors.get_mut(*es) {
+			if let Ok((mut sen, arc)) = sensors.get_mut(*es) {
+				if matches!(arc, Some(a) if !a.contains(&ts, tk.translation())) {
+					continue;
+				}
 				if !sen.bodies.contains(ek) {
 					sen.bodies.push(*ek);
 				}
 			}
+			if budgeted {
+				collided.insert(PairKey::new(*ek, *es));
+			}
 		}
 	}
+
+	if budgeted {
+		// the old set is cleared and reused next frame
+		std::mem::swap(&mut budget.recent, collided);
+	}
 }
-#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+/// `pen` shortened by `slop`(the penetration which is left alone)
+fn slop_correction(
+	pen: Vec2,
+	slop: f32,
+) -> Vec2 {
+	let len = pen.length();
+	if len <= slop {
+		Vec2::ZERO
+	}
+	else {
+		pen * ((len - slop) / len)
+	}
+}
+/// How far past the face a contact pushes out of to look for a neighbouring static body
+const SEAM_PROBE: f32 = 0.01;
+
+/// Point just outside the face of the static body the kinematic is pushed out of(by `pen`), in the middle of their overlap
+fn seam_probe(
+	kin: &Aabb,
+	stat: &Aabb,
+	pen: Vec2,
+) -> Vec2 {
+	let (kmin, kmax) = kin.min_max();
+	let (smin, smax) = stat.min_max();
+	let center = (kmin.max(smin) + kmax.min(smax)) * 0.5;
+
+	center + pen + pen.normalize_or_zero() * SEAM_PROBE
+}
+/// Only fills the sensors(used by `DetectionPlugin` instead of `narrow_phase_2`)
+pub fn sensor_phase(
+	shapes: BodyShapes,
+	transforms: Query<&Transform2D>,
+	mut sensors: Query<(&mut Sensor, Option<&SensorArc>)>,
+	settings: Res<CollisionSettings>,
+	mut pair_sensor: EventReader<CollPairSensor>,
+) {
+	for CollPairSensor(ek, es) in pair_sensor.iter() {
+		let (sk, tk) = match (shapes.get(*ek), transforms.get(*ek)) {
+			(Ok(s), Ok(t)) => (s, t),
+			_ => continue,
+		};
+		let (ss, ts) = match (shapes.get(*es), transforms.get(*es)) {
+			(Ok(s), Ok(t)) => (s, t),
+			_ => continue,
+		};
+
+		let ts = world_wrap::nearest_transform(settings.world_wrap, tk, ts);
+		if collide(sk, tk, ss, &ts).is_some() {
+			if let Ok((mut sen, arc)) = sensors.get_mut(*es) {
+				if matches!(arc, Some(a) if !a.contains(&ts, tk.translation())) {
+					continue;
+				}
+				if !sen.bodies.contains(ek) {
+					sen.bodies.push(*ek);
+				}
+			}
+		}
+	}
+}
+/// The bodies rays can hit, shared by every system casting rays against the bodies(`ray_phase`, `LineOfSight`, `GrapplingHook`)
+#[derive(SystemParam)]
+#[allow(clippy::type_complexity)]
+pub struct RayTargets<'w, 's> {
+	trans: Query<'w, 's, &'static Transform2D>,
+	layers: Query<'w, 's, &'static CollisionLayer>,
+	ray_layers: Query<'w, 's, &'static RayLayer>,
+	kins: Query<'w, 's, (Entity, &'static CollisionShape), (Without<StaticBody>, Without<Sensor>, Without<PhysicsDisabled>)>,
+	stts: Query<'w, 's, (Entity, &'static CollisionShape), (With<StaticBody>, Without<PhysicsDisabled>)>,
+	sensors: Query<'w, 's, (Entity, &'static CollisionShape), (With<Sensor>, Without<StaticBody>, Without<PhysicsDisabled>)>,
+}
+
+impl<'w, 's> RayTargets<'w, 's> {
+	/// The layer rays hit the body on, its `RayLayer` takes precedence over its `CollisionLayer`
+	pub fn layer(
+		&self,
+		e: Entity,
+	) -> CollisionLayer {
+		match self.ray_layers.get(e) {
+			Ok(l) => l.0,
+			Err(_) => self.layers.get(e).copied().unwrap_or(CollisionLayer::ZERO),
+		}
+	}
+
+	/// The bodies a ray on `layer` can hit(kinematic bodies, and static bodies/sensors when asked for), ready for `collide_ray`
+	pub fn candidates<'a>(
+		&'a self,
+		layer: CollisionLayer,
+		with_static: bool,
+		with_sensors: bool,
+		exclude: &'a [Entity],
+	) -> impl Iterator<Item = (Entity, &'a CollisionShape, &'a Transform2D, BodyKind)> + 'a {
+		self.kins.iter().map(|(e, c)| (e, c, BodyKind::Kinematic))
+			.chain(self.stts.iter().filter(move |_| with_static).map(|(e, c)| (e, c, BodyKind::Static)))
+			.chain(self.sensors.iter().filter(move |_| with_sensors).map(|(e, c)| (e, c, BodyKind::Sensor)))
+			.filter(move |(e, ..)| !exclude.contains(e) && self.layer(*e).overlap(&layer))
+			// Make sure everyone have a transform
+			.filter_map(|(e, c, k)| self.trans.get(e).ok().map(|t| (e, c, t, k)))
+	}
+}
+
 pub fn ray_phase(
-	trans: Query<&Transform2D>,
-	layers: Query<&CollisionLayer>,
-	mut rays: Query<(Entity, &mut RayCast)>,
-	kins: Query<(Entity, &CollisionShape),(Without<StaticBody>, Without<Sensor>)>,
-	stts: Query<(Entity, &CollisionShape),With<StaticBody>>,
+	targets: RayTargets,
+	mut rays: Query<(Entity, &mut RayCast, Option<&mut Penetration>), Without<PhysicsDisabled>>,
+	penetrability: Query<&Penetrability>,
+	mut penetration_hits: EventWriter<PenetrationHit>,
 ) {
-	for (re, mut r) in rays.iter_mut() {
-		let rl = match layers.get(re) {
-			Ok(l) => l,
+	for (re, mut r, penetration) in rays.iter_mut() {
+		let rl = match targets.layers.get(re) {
+			Ok(l) => *l,
 			Err(_) => continue,
 		};
 
-		let rt = match trans.get(re) {
+		let rt = match targets.trans.get(re) {
 			Ok(t) => t,
 			Err(_) => continue,
 		};
 
-		if r.collide_with_static {
-			let bodies_iter = kins.iter()
-				.chain(stts.iter())
-				.filter(|(e, ..)| layers.get(*e).unwrap_or(&CollisionLayer::ZERO).overlap(rl))
-				// Make sure everyone have a transform
-				.filter(|(e,..)| trans.get(*e).is_ok()) 
-				.map(|(e, c)| (e, c, trans.get(e).unwrap()));
-			
-			r.collision = collide_ray(&r, rt, bodies_iter);
-		}
-		else {
-			let bodies_iter = kins.iter()
-				.filter(|(e, ..)| layers.get(*e).unwrap_or(&CollisionLayer::ZERO).overlap(rl))
-				// Make sure everyone have a transform
-				.filter(|(e,..)| trans.get(*e).is_ok()) 
-				.map(|(e, c)| (e, c, trans.get(e).unwrap()));
-			
-			r.collision = collide_ray(&r, rt, bodies_iter);
+		let bodies_iter = targets.candidates(rl, r.collide_with_static, r.collide_with_sensors, &[]);
+
+		match penetration {
+			Some(mut p) => {
+				// projectiles need every hit, not just the closest one
+				let hits = collide_ray_all(&r, rt, bodies_iter);
+				r.collision = hits.first().map(|h| h.collision);
+
+				let rot = rt.rot_matrix();
+				let events = p.penetrate(re, rt.translation() + rot * r.offset, rot * r.cast, &hits, |e| penetrability.get(e).ok().map(|p| p.0));
+				penetration_hits.send_batch(events.into_iter());
+			},
+			None => r.collision = collide_ray(&r, rt, bodies_iter),
 		}
 	}
 }
 
 /// # collide_ray
 /// 
-/// This function allows you to conjure an iterator where `Item = (Entity, &CollisionShape, &Transform2D)`
-/// eg. every iterator which returns `(Entity, &CollisionShape, &Transform2D)`
+/// This function allows you to conjure an iterator where `Item = (Entity, &CollisionShape, &Transform2D, BodyKind)`
+/// eg. every iterator which returns `(Entity, &CollisionShape, &Transform2D, BodyKind)`, the kind is only passed on to the hit
 /// 
 /// ## A Couple of notes
 /// 
@@ -313,9 +718,9 @@ pub fn collide_ray<'a,T>(
 	bodies: T,
 ) -> Option<RayCastCollision> 
 where
-	T: Iterator<Item = (Entity, &'a CollisionShape, &'a Transform2D)>
+	T: Iterator<Item = (Entity, &'a CollisionShape, &'a Transform2D, BodyKind)>
 {
-	let r_rot = Mat2::from_angle(ray_trans.rotation());
+	let r_rot = ray_trans.rot_matrix();
 	let r_cast = r_rot * ray.cast;
 	let r_origin = ray_trans.translation() + r_rot * ray.offset;
 
@@ -323,7 +728,7 @@ where
 	let mut short_entity = None;
 
 	// Collide over kins
-	for (be,bs, bt) in bodies {
+	for (be, bs, bt, bk) in bodies {
 		// TODO add aabb testing or something else first
 		
 		let c = bs.ray(bt, r_origin, r_cast);
@@ -331,14 +736,411 @@ where
 		if let Some(c) = c {
 			if c > 0.0 && c < 1.0 && c < shortest {
 				shortest = c;
-				short_entity = Some(be);
+				short_entity = Some((be, bk));
 			}
 		}
 	}
 
-	short_entity.map(|e| RayCastCollision {
+	short_entity.map(|(e, kind)| RayCastCollision {
 		collision_point: shortest * r_cast + r_origin,
 		entity: e,
-		is_static: false,
+		is_static: kind == BodyKind::Static,
+		kind,
 	})
-}
\ No newline at end of file
+}
+
+/// # collide_ray_all
+///
+/// Same as `collide_ray`, but returns every body along the ray(ordered from the closest) instead of only the closest one,
+/// each with where the ray goes into it and out of it.
+///
+/// The way out is found by casting the ray back from its end, so for concave shapes(and `CollisionShape::Multiple`)
+/// it is the last time the ray leaves the shape - the gaps in the middle count as part of the body.
+pub fn collide_ray_all<'a,T>(
+	ray: &RayCast,
+	ray_trans: &Transform2D,
+	bodies: T,
+) -> Vec<RayHit>
+where
+	T: Iterator<Item = (Entity, &'a CollisionShape, &'a Transform2D, BodyKind)>
+{
+	let r_rot = ray_trans.rot_matrix();
+	let r_cast = r_rot * ray.cast;
+	let r_origin = ray_trans.translation() + r_rot * ray.offset;
+	let r_end = r_origin + r_cast;
+
+	let mut hits = Vec::new();
+	for (be, bs, bt, bk) in bodies {
+		// starting inside a shape gives the way out of it instead of the way in
+		let inside = bs.contains_point(bt, r_origin);
+		let entry = match bs.ray(bt, r_origin, r_cast) {
+			_ if inside => 0.0,
+			Some(c) if c > 0.0 && c < 1.0 => c,
+			_ => continue,
+		};
+		let exit = if bs.contains_point(bt, r_end) {
+			1.0
+		}
+		else {
+			match bs.ray(bt, r_end, -r_cast) {
+				Some(c) if c > 0.0 && c < 1.0 => 1.0 - c,
+				_ => entry,
+			}
+		};
+
+		hits.push(RayHit {
+			collision: RayCastCollision {
+				collision_point: entry * r_cast + r_origin,
+				entity: be,
+				is_static: bk == BodyKind::Static,
+				kind: bk,
+			},
+			entry,
+			exit: exit.max(entry),
+		});
+	}
+	hits.sort_by(|a, b| a.entry.total_cmp(&b.entry));
+	hits
+}
+
+/// # collide_shape_sweep
+///
+/// Every body `shape` touches while moving by `motion` and turning by `rotation`(in radians) from `from`, ordered from the first touched,
+/// eg. everything a spinning sword hits in a frame:
+///
+/// ```ignore
+/// let hits = collide_shape_sweep(&blade, &t, Vec2::ZERO, spin * time.delta_seconds(), 8, bodies.iter().filter(|(e, ..)| *e != player));
+/// ```
+///
+/// The turn is sampled in `steps` slices, same as `toi_rotating`(which does the work for each body).
+/// Like `collide_ray`, it takes the bodies as an iterator and doesn't check their layers.
+pub fn collide_shape_sweep<'a,T>(
+	shape: &CollisionShape,
+	from: &Transform2D,
+	motion: Vec2,
+	rotation: f32,
+	steps: usize,
+	bodies: T,
+) -> Vec<SweepHit>
+where
+	T: Iterator<Item = (Entity, &'a CollisionShape, &'a Transform2D, BodyKind)>
+{
+	// whichever way it turns, the shape stays within this distance of the body's center
+	let start = shape.aabb(from);
+	let radius = (start.position - from.translation()).length() + start.extents.length();
+	let (a, b) = (from.translation(), from.translation() + motion);
+	let reach = Aabb::from_min_max(a.min(b) - Vec2::splat(radius), a.max(b) + Vec2::splat(radius));
+
+	let mut hits = bodies
+		.filter(|(_, bs, bt, _)| bs.aabb(bt).collides(&reach))
+		.filter_map(|(be, bs, bt, bk)| {
+			toi_rotating(shape, from, motion, rotation, bs, bt, Vec2::ZERO, 1.0, steps).map(|fraction| SweepHit {
+				entity: be,
+				kind: bk,
+				fraction,
+			})
+		})
+		.collect::<Vec<_>>();
+	hits.sort_by(|a, b| a.fraction.total_cmp(&b.fraction));
+	hits
+}
+#[cfg(test)]
+mod normal_coll_tests {
+	use super::*;
+	use crate::test_utils::PhysicsTestApp;
+
+	#[test]
+	fn higher_priority_doesnt_move() {
+		let mut app = PhysicsTestApp::new();
+
+		let boss = app.spawn_at(Vec2::ZERO, KinematicBundle::default());
+		app.app.world.entity_mut(boss).insert(SolvePriority(1));
+		let player = app.spawn_at(Vec2::new(1.5, 0.0), KinematicBundle::default());
+
+		app.step_n(3);
+
+		app.assert_position(boss, Vec2::ZERO, 0.001);
+		app.assert_position(player, Vec2::new(2.0, 0.0), 0.001);
+	}
+
+	#[test]
+	fn smooth_tile_seams() {
+		let mut app = PhysicsTestApp::new();
+
+		// 2 floor tiles with a seam at x = 1, the body barely pokes into the right one
+		app.spawn_at(Vec2::new(2.0, 0.0), StaticBundle::default());
+		app.spawn_at(Vec2::ZERO, StaticBundle::default());
+		let body = app.spawn_at(Vec2::new(0.04, 1.9), KinematicBundle::default());
+
+		app.step();
+
+		// pushed up by the floor, not sideways by the seam
+		app.assert_position(body, Vec2::new(0.04, 2.0), 0.001);
+	}
+
+	#[test]
+	fn area_split() {
+		let mut app = PhysicsTestApp::new();
+		app.app.insert_resource(CollisionSettings {
+			mtv_split: MtvSplit::Area,
+			..Default::default()
+		});
+
+		// the big square has 3 times the area, so it should move a quarter of the way
+		let big = app.spawn_at(Vec2::ZERO, KinematicBundle {
+			shape: CollisionShape::Square(Square::new(Vec2::new(1.0, 3.0))),
+			..Default::default()
+		});
+		let small = app.spawn_at(Vec2::new(1.6, 0.0), KinematicBundle::default());
+
+		app.step_n(3);
+
+		let gap = app.position(small).x - app.position(big).x;
+		assert!((gap - 2.0).abs() < 0.001);
+		assert!((app.position(big).x + 0.1).abs() < 0.001);
+	}
+
+	#[test]
+	fn contact_forces() {
+		let mut app = PhysicsTestApp::new();
+
+		let floor = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+		let body = app.spawn_at(Vec2::new(0.0, 1.5), KinematicBundle {
+			vel: Vel(Vec2::new(0.0, -10.0)),
+			..Default::default()
+		});
+		app.app.world.entity_mut(body).insert_bundle((Mass(2.0), ContactForces::default()));
+
+		app.step();
+
+		let f = app.app.world.get::<ContactForces>(body).unwrap();
+		let strongest = f.strongest.unwrap();
+		assert_eq!(strongest.other, floor);
+		assert!((strongest.impulse - 20.0).abs() < 0.001);
+		assert!((f.total - Vec2::new(0.0, 20.0)).length() < 0.001);
+	}
+
+	#[test]
+	fn collision_stats() {
+		let mut app = PhysicsTestApp::new();
+
+		let floor = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+		let body = app.spawn_at(Vec2::new(0.0, 1.5), KinematicBundle {
+			vel: Vel(Vec2::new(0.0, -10.0)),
+			..Default::default()
+		});
+		app.app.world.entity_mut(body).insert(CollisionStats::default());
+		app.step();
+
+		// pushed back into the floor once more, softer this time
+		app.app.world.get_mut::<Vel>(body).unwrap().0 = Vec2::new(0.0, -1.0);
+		app.app.world.entity_mut(body).insert(Teleport::to(Vec2::new(0.0, 1.5)));
+		app.step();
+		let touched = app.app.world.resource::<Time>().seconds_since_startup();
+		// and away from it
+		app.app.world.entity_mut(body).insert(Teleport::to(Vec2::new(0.0, 5.0)));
+		app.step();
+
+		let s = app.app.world.get::<CollisionStats>(body).unwrap();
+		assert_eq!(s.total_contacts, 2);
+		assert_eq!(s.frames_in_contact, 2);
+		assert_eq!(s.last_frame_contacts, 0);
+		assert_eq!(s.last_contact, Some(touched));
+		let strongest = s.strongest.unwrap();
+		assert_eq!(strongest.other, floor);
+		assert!((strongest.impulse - 10.0).abs() < 0.001);
+	}
+
+	#[test]
+	fn spatial_hash_same_as_brute_force() {
+		let run = |broad_phase: BroadPhase| {
+			let mut app = PhysicsTestApp::new();
+			app.app.insert_resource(CollisionSettings {
+				broad_phase,
+				..Default::default()
+			});
+
+			// spans way more cells than a hashed body may
+			app.spawn_at(Vec2::ZERO, StaticBundle {
+				shape: CollisionShape::Square(Square::size(Vec2::new(200.0, 2.0))),
+				..Default::default()
+			});
+			let boxes: Vec<Entity> = (0..40)
+				.map(|i| app.spawn_at(Vec2::new((i % 10) as f32 * 1.5 - 7.0, 2.0 + (i / 10) as f32 * 1.5), KinematicBundle::default()))
+				.collect();
+			for e in boxes.iter() {
+				app.app.world.entity_mut(*e).insert(Acc::default());
+			}
+			for _ in 0..60 {
+				for e in boxes.iter() {
+					app.app.world.get_mut::<Acc>(*e).unwrap().add(Vec2::new(0.0, -10.0));
+				}
+				app.step();
+			}
+			(boxes.iter().map(|e| app.position(*e)).collect::<Vec<_>>(), app.collisions.len())
+		};
+
+		let (brute, brute_collisions) = run(BroadPhase::BruteForce);
+		let (hashed, hashed_collisions) = run(BroadPhase::SpatialHash { cell_size: 2.0 });
+		assert!(brute_collisions > 0);
+		assert_eq!(brute_collisions, hashed_collisions);
+		assert_eq!(brute, hashed);
+		// a cell size the hash can't use falls back to checking every body
+		assert_eq!(run(BroadPhase::SpatialHash { cell_size: 0.0 }), (brute, brute_collisions));
+	}
+
+	#[test]
+	fn ray_hit_kinds() {
+		let mut app = PhysicsTestApp::new();
+
+		let ray = app.spawn_at(Vec2::ZERO, RayCastBundle {
+			ray: RayCast::new(Vec2::new(20.0, 0.0)).with_sensors(true),
+			..Default::default()
+		});
+		let sensor = app.spawn_at(Vec2::new(5.0, 0.0), SensorBundle::default());
+		let wall = app.spawn_at(Vec2::new(10.0, 0.0), StaticBundle::default());
+
+		let hit = |app: &mut PhysicsTestApp| {
+			app.step();
+			app.app.world.get::<RayCast>(ray).unwrap().collision.map(|c| (c.entity, c.kind, c.is_static))
+		};
+		assert_eq!(hit(&mut app), Some((sensor, BodyKind::Sensor, false)));
+
+		app.app.world.get_mut::<RayCast>(ray).unwrap().collide_with_sensors = false;
+		assert_eq!(hit(&mut app), Some((wall, BodyKind::Static, true)));
+
+		let body = app.spawn_at(Vec2::new(2.0, 0.0), KinematicBundle::default());
+		app.step();
+		assert_eq!(hit(&mut app), Some((body, BodyKind::Kinematic, false)));
+	}
+
+	#[test]
+	fn sweeps_through_the_arc() {
+		let blade = CollisionShape::Square(Square::size(Vec2::new(4.0, 0.2)).with_offset(Vec2::new(2.0, 0.0)));
+		let t = Transform2D::new(Vec2::ZERO, 0.0, Vec2::ONE);
+		let (ahead, behind, side) = (Entity::from_raw(1), Entity::from_raw(2), Entity::from_raw(3));
+		let target = CollisionShape::Circle(Circle::new(0.3));
+		let bodies = [
+			(ahead, Transform2D::new(Vec2::new(0.0, 3.0), 0.0, Vec2::ONE)),
+			(behind, Transform2D::new(Vec2::new(0.0, -3.0), 0.0, Vec2::ONE)),
+			(side, Transform2D::new(Vec2::new(-3.0, 0.1), 0.0, Vec2::ONE)),
+		];
+		let iter = || bodies.iter().map(|(e, t)| (*e, &target, t, BodyKind::Kinematic));
+
+		// without turning it only touches what it starts on
+		assert!(collide_shape_sweep(&blade, &t, Vec2::ZERO, 0.0, 8, iter()).is_empty());
+
+		// half a turn counter-clockwise goes over the one above, then ends on the one on the left
+		let hits = collide_shape_sweep(&blade, &t, Vec2::ZERO, std::f32::consts::PI, 16, iter());
+		assert_eq!(hits.iter().map(|h| h.entity).collect::<Vec<_>>(), vec![ahead, side]);
+		assert!((hits[0].fraction - 0.5).abs() < 0.1, "{}", hits[0].fraction);
+
+		// too few samples skip right over the targets
+		assert!(collide_shape_sweep(&blade, &t, Vec2::ZERO, std::f32::consts::PI, 1, iter()).len() < 2);
+	}
+
+	#[test]
+	fn inward_velocity_clamped() {
+		let mut app = PhysicsTestApp::new();
+
+		app.spawn_at(Vec2::ZERO, StaticBundle::default());
+		let body = app.spawn_at(Vec2::new(0.0, 1.5), KinematicBundle {
+			vel: Vel(Vec2::new(3.0, -10.0)),
+			..Default::default()
+		});
+
+		app.step_n(3);
+
+		assert_eq!(app.app.world.get::<Vel>(body).unwrap().0, Vec2::new(3.0, 0.0));
+	}
+
+	/// Steps 30 seconds with gravity, and checks none of the bodies moved from where they started
+	fn assert_stable(
+		app: &mut PhysicsTestApp,
+		bodies: &[Entity],
+	) {
+		for e in bodies {
+			app.app.world.entity_mut(*e).insert(Acc::default());
+		}
+		let start: Vec<Vec2> = bodies.iter().map(|e| app.position(*e)).collect();
+
+		for _ in 0..(30 * 60) {
+			for e in bodies {
+				app.app.world.get_mut::<Acc>(*e).unwrap().add(Vec2::new(0.0, -10.0));
+			}
+			app.step();
+		}
+
+		for (e, p) in bodies.iter().zip(start) {
+			app.assert_position(*e, p, 0.05);
+		}
+	}
+
+	#[test]
+	fn box_stack_stable() {
+		let mut app = PhysicsTestApp::new();
+
+		app.spawn_at(Vec2::ZERO, StaticBundle {
+			shape: CollisionShape::Square(Square::new(Vec2::new(20.0, 1.0))),
+			..Default::default()
+		});
+		let boxes: Vec<Entity> = (1..=10)
+			.map(|i| app.spawn_at(Vec2::new(0.0, 2.0 * i as f32), KinematicBundle::default()))
+			.collect();
+
+		assert_stable(&mut app, &boxes);
+	}
+
+	#[test]
+	fn pyramid_stable() {
+		let mut app = PhysicsTestApp::new();
+
+		app.spawn_at(Vec2::ZERO, StaticBundle {
+			shape: CollisionShape::Square(Square::new(Vec2::new(20.0, 1.0))),
+			..Default::default()
+		});
+		// rows of 4, 3, 2 and 1 boxes, with a small gap between the boxes of each row
+		let mut boxes = Vec::new();
+		for row in 0..4 {
+			let count = 4 - row;
+			for i in 0..count {
+				let x = (i as f32 - (count - 1) as f32 * 0.5) * 2.2;
+				boxes.push(app.spawn_at(Vec2::new(x, 2.0 * (row + 1) as f32), KinematicBundle::default()));
+			}
+		}
+
+		assert_stable(&mut app, &boxes);
+	}
+}
+
+#[cfg(test)]
+mod detection_tests {
+	use super::*;
+	use crate::plugin::DetectionPlugin;
+
+	#[test]
+	fn sensors_without_solver() {
+		let mut app = App::new();
+		app.add_plugin(bevy::core::CorePlugin)
+			.add_plugin(TransformPlugin)
+			.add_plugin(HierarchyPlugin)
+			.add_plugin(DetectionPlugin);
+
+		let sensor = app.world
+			.spawn()
+			.insert_bundle(SensorBundle::default())
+			.insert_bundle(TransformBundle::default())
+			.id();
+		let body = app.world
+			.spawn()
+			.insert_bundle(KinematicBundle::default())
+			.insert_bundle(TransformBundle::from_transform(Transform::from_xyz(0.5, 0.0, 0.0)))
+			.id();
+		app.update();
+		app.update();
+
+		assert_eq!(app.world.get::<Sensor>(sensor).unwrap().bodies, vec![body]);
+		// nothing got pushed around
+		assert_eq!(app.world.get::<Transform2D>(body).unwrap().translation(), Vec2::new(0.5, 0.0));
+	}
+}
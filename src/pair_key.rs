@@ -0,0 +1,79 @@
+//! # Pair keys
+//!
+//! `PairKey` is the key the crate uses for anything stored per pair of entities(like the pairs the frame budget remembers),
+//! it doesn't care about the order of the entities so `(a, b)` and `(b, a)` are the same pair.
+//!
+//! ```ignore
+//! // damage cooldown per pair
+//! let key = ev.pair_key();
+//! if !cooldowns.contains_key(&key) {
+//!     cooldowns.insert(key, 0.5);
+//!     // deal damage
+//! }
+//! ```
+
+use bevy::prelude::*;
+
+/// An unordered pair of entities, stored with the smaller entity first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PairKey(Entity, Entity);
+
+impl PairKey {
+    pub fn new(
+        a: Entity,
+        b: Entity,
+    ) -> Self {
+        if a <= b {
+            PairKey(a, b)
+        }
+        else {
+            PairKey(b, a)
+        }
+    }
+    /// Both entities, smaller first
+    pub fn entities(&self) -> (Entity, Entity) {
+        (self.0, self.1)
+    }
+    pub fn contains(
+        &self,
+        entity: Entity,
+    ) -> bool {
+        self.0 == entity || self.1 == entity
+    }
+    /// The other entity of the pair(`None` when `entity` isn't in the pair)
+    pub fn other(
+        &self,
+        entity: Entity,
+    ) -> Option<Entity> {
+        if self.0 == entity {
+            Some(self.1)
+        }
+        else if self.1 == entity {
+            Some(self.0)
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl From<(Entity, Entity)> for PairKey {
+    fn from((a, b): (Entity, Entity)) -> Self {
+        PairKey::new(a, b)
+    }
+}
+
+#[cfg(test)]
+mod pair_key_tests {
+    use super::*;
+
+    #[test]
+    fn order_does_not_matter() {
+        let (a, b) = (Entity::from_raw(7), Entity::from_raw(3));
+
+        assert_eq!(PairKey::new(a, b), PairKey::from((b, a)));
+        assert_eq!(PairKey::new(a, b).entities(), (b, a));
+        assert_eq!(PairKey::new(a, b).other(a), Some(b));
+        assert_eq!(PairKey::new(a, b).other(Entity::from_raw(1)), None);
+    }
+}
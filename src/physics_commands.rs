@@ -0,0 +1,143 @@
+//! # PhysicsCommands
+//!
+//! A command buffer which is applied after the collision solving,
+//! so despawning or changing bodies in reaction to collisions(for example inside a system in `stage::COLLISION_DETECTION`,
+//! or one piped into the physics systems) won't pull entities out from under the rest of the physics frame.
+//!
+//! ```ignore
+//! fn explode_on_hit(
+//!     mut events: EventReader<CollisionEvent>,
+//!     mut pcoms: ResMut<PhysicsCommands>,
+//! ) {
+//!     for c in events.iter() {
+//!         pcoms.despawn(c.entity_a);
+//!     }
+//! }
+//! ```
+
+use bevy::prelude::*;
+
+type PhysicsCommand = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// Commands buffered until the end of `stage::COLLISION_DETECTION`
+///
+/// Commands targeting entities which no longer exist are ignored.
+#[derive(Default)]
+pub struct PhysicsCommands {
+    queue: Vec<PhysicsCommand>,
+}
+
+impl PhysicsCommands {
+    /// Queues a custom command
+    pub fn add(
+        &mut self,
+        command: impl FnOnce(&mut World) + Send + Sync + 'static,
+    ) {
+        self.queue.push(Box::new(command));
+    }
+
+    pub fn despawn(
+        &mut self,
+        entity: Entity,
+    ) {
+        self.add(move |world| {
+            if world.get_entity(entity).is_some() {
+                world.despawn(entity);
+            }
+        });
+    }
+    pub fn despawn_recursive(
+        &mut self,
+        entity: Entity,
+    ) {
+        self.add(move |world| {
+            if world.get_entity(entity).is_some() {
+                despawn_with_children_recursive(world, entity);
+            }
+        });
+    }
+    pub fn insert<T: Component>(
+        &mut self,
+        entity: Entity,
+        component: T,
+    ) {
+        self.add(move |world| {
+            if let Some(mut e) = world.get_entity_mut(entity) {
+                e.insert(component);
+            }
+        });
+    }
+    pub fn insert_bundle<T: Bundle>(
+        &mut self,
+        entity: Entity,
+        bundle: T,
+    ) {
+        self.add(move |world| {
+            if let Some(mut e) = world.get_entity_mut(entity) {
+                e.insert_bundle(bundle);
+            }
+        });
+    }
+    pub fn remove<T: Component>(
+        &mut self,
+        entity: Entity,
+    ) {
+        self.add(move |world| {
+            if let Some(mut e) = world.get_entity_mut(entity) {
+                e.remove::<T>();
+            }
+        });
+    }
+
+    /// Number of queued commands
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+/// Applies the queued `PhysicsCommands`, runs at the end of `stage::COLLISION_DETECTION`
+pub fn apply_physics_commands(world: &mut World) {
+    let queue = match world.get_resource_mut::<PhysicsCommands>() {
+        Some(mut c) if !c.queue.is_empty() => std::mem::take(&mut c.queue),
+        _ => return,
+    };
+
+    for command in queue {
+        command(world);
+    }
+}
+
+#[cfg(test)]
+mod physics_commands_tests {
+    use super::*;
+    use crate::test_utils::PhysicsTestApp;
+
+    #[derive(Component)]
+    struct Marked;
+
+    fn queue_sys(
+        mut pcoms: ResMut<PhysicsCommands>,
+        q: Query<Entity, With<Marked>>,
+    ) {
+        for e in q.iter() {
+            pcoms.despawn(e);
+            // the entity is gone by the time this one is applied, so it should be ignored
+            pcoms.insert(e, Marked);
+        }
+    }
+
+    #[test]
+    fn applied_after_collision_stage() {
+        let mut app = PhysicsTestApp::new();
+        app.app.add_system(queue_sys);
+
+        let e = app.app.world.spawn().insert(Marked).id();
+        app.step();
+
+        assert!(app.app.world.get_entity(e).is_none());
+        assert!(app.app.world.resource::<PhysicsCommands>().is_empty());
+    }
+}
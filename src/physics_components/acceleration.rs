@@ -0,0 +1,33 @@
+use crate::math::Vec2;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect, Component};
+
+/**
+    # Acc
+
+    Acceleration accumulator, bodies with both `Vel` and `Acc` are integrated by the plugin
+    (using the `PhysicsIntegrator` resource) at the start of the collision step.
+
+    Add accelerations(gravity, springs, forces divided by mass...) to it during `Update` using `Acc::add`,
+    it is cleared after each integration step.
+
+    Bodies without `Acc` are not integrated, so you can keep moving those yourself.
+*/
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect, Component))]
+pub struct Acc {
+    /// Acceleration accumulated during the current frame
+    pub current: Vec2,
+    /// Acceleration used in the last integration step(needed by `VelocityVerlet`)
+    pub previous: Vec2,
+    /// Delta time of the last integration step, `0.0` if the body was never integrated
+    pub previous_dt: f32,
+}
+
+impl Acc {
+    /// Adds to the acceleration of the current frame
+    pub fn add(&mut self, acc: Vec2) {
+        self.current += acc;
+    }
+}
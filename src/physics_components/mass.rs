@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect, Component};
+
+/// Mass tuple component(access the value using `Mass.0`)
+///
+/// Used by the joint solver to split corrections between bodies,
+/// bodies with `Vel` but without `Mass` are treated as having a mass of `1.0`.
+///
+/// Default: `1.0`
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect, Component))]
+pub struct Mass(pub f32);
+
+impl Default for Mass {
+    fn default() -> Self {
+        Mass(1.0)
+    }
+}
+
+impl Mass {
+    /// `1.0 / mass`, or `0.0` for non positive or infinite masses(which are immovable)
+    pub fn inverse(&self) -> f32 {
+        if self.0 > 0.0 && self.0.is_finite() {
+            1.0 / self.0
+        }
+        else {
+            0.0
+        }
+    }
+}
@@ -1,12 +1,19 @@
 //! All the different components which describe a physical body
 
 mod velocity;
+mod acceleration;
+mod mass;
+mod solve_priority;
 mod transform2d;
 pub use transform2d::Transform2D;
 pub use velocity::Vel;
+pub use acceleration::Acc;
+pub use mass::Mass;
+pub use solve_priority::SolvePriority;
 
-use bevy::prelude::{Reflect, Component};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect, Component};
 
 /**
     # CollisionLayer
@@ -41,7 +48,8 @@ use serde::{Deserialize, Serialize};
 
     For example, if we want to add layers 2 and 3 in one go, we can do `layer = layer | 0b0000_0110`
 */
-#[derive(Debug, Clone, Copy, Reflect, Serialize, Deserialize, Component)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect, Component))]
 pub struct CollisionLayer {
     pub mask: u8,
     pub layer: u8,
@@ -71,3 +79,11 @@ impl CollisionLayer {
     }
 }
 
+
+/// Ray layer tuple component(access the value using `RayLayer.0`)
+///
+/// When present, rays check against this layer instead of the entity's `CollisionLayer`,
+/// so an entity can block rays differently than it blocks bodies(e.g. invisible glass which stops bodies but not line of sight).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect, Component))]
+pub struct RayLayer(pub CollisionLayer);
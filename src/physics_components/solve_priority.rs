@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect, Component};
+
+/// Solve priority tuple component(access the value using `SolvePriority.0`)
+///
+/// When 2 kinematic bodies collide, the one with the higher priority doesn't move and the other one is pushed out,
+/// pairs with higher priorities are also solved first.
+/// Bodies with equal priorities are solved as usual.
+///
+/// Bodies without it have a priority of `0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect, Component))]
+pub struct SolvePriority(pub i32);
@@ -1,5 +1,8 @@
+use crate::math::{Mat2, Vec2};
+#[cfg(feature = "ecs")]
 use bevy::prelude::*;
 
+#[cfg(feature = "ecs")]
 use crate::transform_mode::TransformMode;
 
 /**
@@ -10,26 +13,43 @@ use crate::transform_mode::TransformMode;
     If you are modifying the position(or rotation) of a certain component during the physics step you need to use
     `Transform2D` instead, as the physics systems use it and assume it to be the source of truth,
     thus changing `Transform` directly might cause ghost collision or missing collisions!
+    (such changes are reported with a `TransformModifiedEvent`, see `CollisionSettings::transform_check`)
     
     ### End of important
 
     The physics server syncs from `GlobalTransform into this component at the start of the physics step, keeps track of changes made and
     then syncs the changes to `Transform`, this allows us to work with 1 component type so we dont have to do some funky stuff
     (will probably stay like that for at least until the `Global/Transform` system is remade in bevy)
+
+    ## Pivot
+    By default bodies rotate around their translation, setting a `pivot`(relative to the translation, rotated with the body)
+    makes `set_rotation` and `add_rotation` rotate the body around that point instead(like a door around its hinge),
+    moving the translation as needed. The pivot is kept when syncing from `GlobalTransform`,
+    but rotating the `Transform` directly still rotates around its translation.
+
+    ## Teleporting
+    `set_translation` moves the `Transform` by the difference when syncing, so a `Transform` which was modified in the meantime
+    ends up somewhere else, `teleport` places it at the new translation instead(only for bodies without a parent).
 */
-#[derive(Clone, Debug, Reflect, Default, Component)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "ecs", derive(Reflect, Component))]
 pub struct Transform2D {
     translation: Vec2,
     rotation: f32,
+    /// `Mat2::from_angle(rotation)`, kept up to date with the rotation so the shapes don't have to recompute it
+    rot: Mat2,
     scale: Vec2,
     translation_buffer: Vec2,
     rotation_buffer: f32,
+    pivot: Vec2,
+    teleported: bool,
 }
 impl Transform2D {
     pub fn new(translation: Vec2, rotation: f32, scale: Vec2) -> Transform2D {
         Transform2D {
             translation,
             rotation,
+            rot: Mat2::from_angle(rotation),
             scale,
             ..Default::default()
         }
@@ -42,19 +62,39 @@ impl Transform2D {
     pub fn rotation(&self) -> f32 {
         self.rotation
     }
+    /// The rotation as a matrix(cached, so it is cheaper than `Mat2::from_angle(rotation)`)
+    pub fn rot_matrix(&self) -> Mat2 {
+        self.rot
+    }
     pub fn scale(&self) -> Vec2 {
         self.scale
     }
+    /// Point the body rotates around, relative to the translation(before rotating)
+    pub fn pivot(&self) -> Vec2 {
+        self.pivot
+    }
+    /// The pivot in world space
+    pub fn world_pivot(&self) -> Vec2 {
+        self.translation + self.rot * self.pivot
+    }
+    /// Sets the point the body rotates around(relative to the translation)
+    pub fn with_pivot(mut self, pivot: Vec2) -> Self {
+        self.pivot = pivot;
+        self
+    }
+    /// Sets the point the body rotates around(relative to the translation)
+    pub fn set_pivot(&mut self, pivot: Vec2) {
+        self.pivot = pivot;
+    }
     // Adders
     /// Adds to the translation
     pub fn add_translation(&mut self, amount: Vec2) {
         self.translation += amount;
         self.translation_buffer += amount;
     }
-    /// Adds to the rotation
+    /// Adds to the rotation(around the pivot)
     pub fn add_rotation(&mut self, amount: f32) {
-        self.rotation += amount;
-        self.rotation += amount;
+        self.set_rotation(self.rotation + amount);
     }
     // Setters
     /// Fully sets the translation
@@ -63,19 +103,42 @@ impl Transform2D {
         self.translation = new;
         self.translation_buffer = new - original;
     }
-    /// Fully sets the rotation
+    /// Moves the body to `translation`, which is also where its `Transform` is placed when syncing
+    /// (regardless of changes made to the `Transform` since the physics step started)
+    pub fn teleport(&mut self, translation: Vec2) {
+        self.set_translation(translation);
+        self.teleported = true;
+    }
+    /// Fully sets the rotation(around the pivot)
     pub fn set_rotation(&mut self, new: f32) {
+        let pivot = self.world_pivot();
+
         let original = self.rotation - self.rotation_buffer;
         self.rotation = new;
+        self.rot = Mat2::from_angle(new);
         self.rotation_buffer = new - original;
+
+        if self.pivot != Vec2::ZERO {
+            // keep the pivot in place
+            self.add_translation(pivot - self.world_pivot());
+        }
     }
+}
+
+#[cfg(feature = "ecs")]
+impl Transform2D {
     /// Applies the buffers to a `Transform` component.
     pub fn apply_buffers(&self, transform: &mut Transform, trans_mode: TransformMode) {
         let (tb, rb) = (self.translation_buffer, self.rotation_buffer);
 
-        let t = trans_mode.get_position(transform);
+        let t = if self.teleported {
+            self.translation
+        }
+        else {
+            trans_mode.get_position(transform) + tb
+        };
 
-        trans_mode.set_position(transform, t + tb);
+        trans_mode.set_position(transform, t);
         trans_mode.add_rotation(transform, rb);
     }
 
@@ -89,7 +152,12 @@ impl Transform2D {
 		mut query: Query<(&mut Transform2D, &GlobalTransform)>,
 	) {
 		for (mut t, gt) in query.iter_mut() {
-			*t = (gt, *trans_mode).into();
+			let mut synced: Transform2D = (gt, *trans_mode).into();
+			synced.pivot = t.pivot;
+			// only written when it differs, so `Changed<Transform2D>` means the body moved(the static index relies on it)
+			if *t != synced {
+				*t = synced;
+			}
 		}
 	}
 	/// Syncs from `Transform2D` to `Transform`
@@ -103,11 +171,11 @@ impl Transform2D {
 			t2.apply_buffers(&mut mt, *trans_mode);
 		}
 	}
-    /// Automatically inserts a Transform2D component for each new CollisionShape
+    /// Automatically inserts a Transform2D component for each new CollisionShape(which doesn't have one already)
     #[allow(clippy::type_complexity)]
     pub fn auto_insert_system(
         mut coms: Commands,
-        q: Query<Entity, Or<(Added<crate::prelude::CollisionShape>, Added<crate::prelude::RayCast>)>>,
+        q: Query<Entity, (Or<(Added<crate::prelude::CollisionShape>, Added<crate::prelude::RayCast>)>, Without<Transform2D>)>,
     ) {
         for e in q.iter() {
             coms.entity(e).insert(Transform2D::default());
@@ -116,6 +184,7 @@ impl Transform2D {
 
 }
 
+#[cfg(feature = "ecs")]
 impl From<(&GlobalTransform, TransformMode)> for Transform2D {
     fn from((trans, mode): (&GlobalTransform, TransformMode)) -> Self {
         let (s, q, t) = trans.to_scale_rotation_translation();
@@ -123,17 +192,15 @@ impl From<(&GlobalTransform, TransformMode)> for Transform2D {
         // the weird conversion is from - it actually works...
         // https://en.wikipedia.org/wiki/Conversion_between_quaternions_and_Euler_angles#Quaternion_to_Euler_angles_conversion
         // they are correct, but it really looks made up...
-        match mode {
-            TransformMode::XY => Transform2D {
-                translation: Vec2::new(t.x, t.y),
-                rotation: (2.0 * (q.w * q.z + q.x * q.y))
-                    .atan2(1.0 - 2.0 * (q.y * q.y + q.z * q.z)),
-                scale: Vec2::new(s.x, s.y),
-                ..Default::default()
-            },
-            TransformMode::XZ => Transform2D {
-                translation: Vec2::new(t.x, t.z),
-                rotation: {
+        let (translation, rotation, scale) = match mode {
+            TransformMode::XY => (
+                Vec2::new(t.x, t.y),
+                (2.0 * (q.w * q.z + q.x * q.y)).atan2(1.0 - 2.0 * (q.y * q.y + q.z * q.z)),
+                Vec2::new(s.x, s.y),
+            ),
+            TransformMode::XZ => (
+                Vec2::new(t.x, t.z),
+                {
                     let sinp = 2.0 * (q.w * q.y - q.z * q.x);
                     if sinp.abs() >= 1.0 {
                         0.5 * std::f32::consts::PI.copysign(sinp)
@@ -141,21 +208,40 @@ impl From<(&GlobalTransform, TransformMode)> for Transform2D {
                         sinp.asin()
                     }
                 },
-                scale: Vec2::new(s.x, s.z),
-                ..Default::default()
-            },
-            TransformMode::YZ => Transform2D {
-                translation: Vec2::new(t.y, t.z),
-                rotation: (2.0 * (q.w * q.x + q.y * q.z))
-                    .atan2(1.0 - 2.0 * (q.x * q.x + q.y * q.y)),
-                scale: Vec2::new(s.y, s.z),
-                ..Default::default()
-            },
-        }
+                Vec2::new(s.x, s.z),
+            ),
+            TransformMode::YZ => (
+                Vec2::new(t.y, t.z),
+                (2.0 * (q.w * q.x + q.y * q.z)).atan2(1.0 - 2.0 * (q.x * q.x + q.y * q.y)),
+                Vec2::new(s.y, s.z),
+            ),
+        };
+        Transform2D::new(translation, rotation, scale)
     }
 }
+#[cfg(feature = "ecs")]
 impl From<(TransformMode, &GlobalTransform)> for Transform2D {
     fn from(v: (TransformMode, &GlobalTransform)) -> Self {
         (v.1, v.0).into()
     }
 }
+
+#[cfg(test)]
+mod transform2d_tests {
+    use super::*;
+
+    #[test]
+    fn rotates_around_pivot() {
+        // a door hinged on its left edge
+        let mut t = Transform2D::new(Vec2::new(1.0, 0.0), 0.0, Vec2::ONE).with_pivot(Vec2::new(-1.0, 0.0));
+
+        t.set_rotation(0.5 * std::f32::consts::PI);
+        assert!((t.translation() - Vec2::new(0.0, 1.0)).length() < 0.001);
+        assert!(t.world_pivot().length() < 0.001);
+
+        t.add_rotation(0.5 * std::f32::consts::PI);
+        assert!((t.translation() - Vec2::new(-1.0, 0.0)).length() < 0.001);
+        // the cached matrix follows the rotation
+        assert!((t.rot_matrix() * Vec2::X - Vec2::new(-1.0, 0.0)).length() < 0.001);
+    }
+}
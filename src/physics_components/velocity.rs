@@ -1,12 +1,15 @@
-use bevy::prelude::*;
+use crate::math::Vec2;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect, Component};
 
 /// Linear velocity tuple component(access the value using `Vel.0`)
 ///
 /// Required for a continuous collision kinematic body 
 ///
 /// Default: `(0.0, 0.0)`
-#[derive(Clone, Default, Reflect, Serialize, Deserialize, Component)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect, Component))]
 pub struct Vel(pub Vec2);
 
 impl Vel {
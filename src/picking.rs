@@ -0,0 +1,275 @@
+//! # Picking
+//!
+//! Enabled with the `picking` feature.
+//!
+//! `PhysicsPickingPlugin` converts the cursor position through the active 2D camera into world space(`CursorWorldPosition`),
+//! finds the body under it(using `CollisionShape::contains_point`), marks it with `Hovered`
+//! and sends a `PickedEvent` when a mouse button is pressed over it.
+//!
+//! `MouseDragPlugin` builds on top of it, grabbing picked bodies with a `MouseJoint` and dragging them around with the cursor.
+//!
+//! ```ignore
+//! app.add_plugin(Physics2dPlugin::default())
+//!     .add_plugin(PhysicsPickingPlugin);
+//!
+//! fn on_pick(mut events: EventReader<PickedEvent>) {
+//!     for ev in events.iter() {
+//!         info!("picked {:?} at {}", ev.entity, ev.position);
+//!     }
+//! }
+//! ```
+
+use bevy::{
+    core_pipeline::core_2d::Camera2d,
+    input::InputSystem,
+    prelude::*,
+    render::camera::RenderTarget,
+};
+
+use crate::{
+    joints::MouseJoint,
+    physics_components::{CollisionLayer, Transform2D},
+    shapes::CollisionShape,
+    transform_mode::TransformMode,
+};
+
+/// Picking bodies with the mouse, needs `Physics2dPlugin` to be added as well
+pub struct PhysicsPickingPlugin;
+
+impl Plugin for PhysicsPickingPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<CursorWorldPosition>()
+            .init_resource::<PickingSettings>()
+            .init_resource::<PickingState>()
+            .add_event::<PickedEvent>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                cursor_world_system
+                    .label(PickingSystem::CursorWorld)
+                    .after(InputSystem),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                picking_system
+                    .label(PickingSystem::Picking)
+                    .after(PickingSystem::CursorWorld),
+            );
+    }
+}
+
+/// Labels of the picking systems(both run in `CoreStage::PreUpdate`)
+#[derive(SystemLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PickingSystem {
+    CursorWorld,
+    Picking,
+    MouseDrag,
+}
+
+/// Position of the cursor in world space(projected using `TransformMode`), `None` when the cursor is outside the window
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CursorWorldPosition(pub Option<Vec2>);
+
+/// Which bodies can be picked
+#[derive(Debug, Clone, Copy)]
+pub struct PickingSettings {
+    /// Only bodies whose `CollisionLayer` overlaps this can be picked(bodies without a layer always can)
+    pub layer: CollisionLayer,
+}
+
+impl Default for PickingSettings {
+    fn default() -> Self {
+        PickingSettings {
+            layer: CollisionLayer::new(u8::MAX, u8::MAX),
+        }
+    }
+}
+
+/// The body currently under the cursor
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PickingState {
+    pub hovered: Option<Entity>,
+}
+
+/// Marker component for the body under the cursor
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Hovered;
+
+/// Sent when a mouse button is pressed over a body
+#[derive(Debug, Clone, Copy)]
+pub struct PickedEvent {
+    pub entity: Entity,
+    pub button: MouseButton,
+    /// Cursor position in world space
+    pub position: Vec2,
+}
+
+pub fn cursor_world_system(
+    windows: Res<Windows>,
+    trans_mode: Res<TransformMode>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    mut cursor: ResMut<CursorWorldPosition>,
+) {
+    cursor.0 = None;
+
+    for (camera, gt) in cameras.iter() {
+        if !camera.is_active {
+            continue;
+        }
+        let window = match camera.target {
+            RenderTarget::Window(id) => windows.get(id),
+            _ => None,
+        };
+        let (window, screen_pos) = match window.and_then(|w| w.cursor_position().map(|p| (w, p))) {
+            Some(w) => w,
+            None => continue,
+        };
+
+        // screen -> normalized device coordinates -> world
+        let size = Vec2::new(window.width(), window.height());
+        let ndc = (screen_pos / size) * 2.0 - Vec2::ONE;
+        let ndc_to_world = gt.compute_matrix() * camera.projection_matrix().inverse();
+        let world = ndc_to_world.project_point3(ndc.extend(-1.0));
+
+        cursor.0 = Some(trans_mode.get_position(&Transform::from_translation(world)));
+        break;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn picking_system(
+    mut coms: Commands,
+    cursor: Res<CursorWorldPosition>,
+    buttons: Res<Input<MouseButton>>,
+    settings: Res<PickingSettings>,
+    mut state: ResMut<PickingState>,
+    mut picked: EventWriter<PickedEvent>,
+    bodies: Query<(Entity, &CollisionShape, &Transform2D, &GlobalTransform, Option<&CollisionLayer>)>,
+    hovered: Query<Entity, With<Hovered>>,
+) {
+    // the top most body(by z) under the cursor
+    let hit = cursor.0.and_then(|pos| {
+        bodies
+            .iter()
+            .filter(|(.., l)| l.map(|l| l.overlap(&settings.layer)).unwrap_or(true))
+            .filter(|(_, s, t, ..)| s.contains_point(t, pos))
+            .max_by(|a, b| a.3.translation().z.total_cmp(&b.3.translation().z))
+            .map(|(e, ..)| e)
+    });
+
+    if state.hovered != hit {
+        for e in hovered.iter() {
+            coms.entity(e).remove::<Hovered>();
+        }
+        if let Some(e) = hit {
+            coms.entity(e).insert(Hovered);
+        }
+        state.hovered = hit;
+    }
+
+    if let (Some(entity), Some(position)) = (hit, cursor.0) {
+        for button in buttons.get_just_pressed() {
+            picked.send(PickedEvent {
+                entity,
+                button: *button,
+                position,
+            });
+        }
+    }
+}
+
+/// Drag bodies around with the mouse(using a `MouseJoint`), needs `PhysicsPickingPlugin` to be added as well
+pub struct MouseDragPlugin;
+
+impl Plugin for MouseDragPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<MouseDragSettings>()
+            .init_resource::<MouseDragState>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                mouse_drag_system
+                    .label(PickingSystem::MouseDrag)
+                    .after(PickingSystem::Picking),
+            );
+    }
+}
+
+/// Settings of the `MouseJoint` created when grabbing a body
+#[derive(Debug, Clone, Copy)]
+pub struct MouseDragSettings {
+    pub button: MouseButton,
+    pub stiffness: f32,
+    pub damping: f32,
+    pub max_force: f32,
+}
+
+impl Default for MouseDragSettings {
+    fn default() -> Self {
+        MouseDragSettings {
+            button: MouseButton::Left,
+            stiffness: 200.0,
+            damping: 28.0,
+            max_force: f32::INFINITY,
+        }
+    }
+}
+
+/// The body currently being dragged, and the entity holding its `MouseJoint`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MouseDragState {
+    pub dragged: Option<(Entity, Entity)>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn mouse_drag_system(
+    mut coms: Commands,
+    cursor: Res<CursorWorldPosition>,
+    buttons: Res<Input<MouseButton>>,
+    settings: Res<MouseDragSettings>,
+    mut state: ResMut<MouseDragState>,
+    mut picked: EventReader<PickedEvent>,
+    bodies: Query<&Transform2D>,
+    mut joints: Query<&mut MouseJoint>,
+) {
+    // let go
+    if let Some((body, joint)) = state.dragged {
+        if !buttons.pressed(settings.button) || bodies.get(body).is_err() {
+            if joints.get(joint).is_ok() {
+                coms.entity(joint).despawn();
+            }
+            state.dragged = None;
+        }
+        else if let (Some(target), Ok(mut j)) = (cursor.0, joints.get_mut(joint)) {
+            j.target = target;
+        }
+    }
+
+    // grab
+    for ev in picked.iter() {
+        if ev.button != settings.button || state.dragged.is_some() {
+            continue;
+        }
+        let t = match bodies.get(ev.entity) {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        // the anchor is where the body was clicked
+        let anchor = t.rot_matrix().transpose() * (ev.position - t.translation());
+
+        let joint = coms
+            .spawn()
+            .insert(
+                MouseJoint::new(ev.entity, ev.position)
+                    .with_anchor(anchor)
+                    .with_spring(settings.stiffness, settings.damping)
+                    .with_max_force(settings.max_force),
+            )
+            .id();
+        state.dragged = Some((ev.entity, joint));
+    }
+}
@@ -0,0 +1,267 @@
+//! # Spawn placement
+//!
+//! `find_free_position` looks for a spot near a preferred position where a shape wouldn't overlap anything,
+//! so enemies and items don't spawn inside walls(or each other) and get shot out by the collision solve.
+//!
+//! Like `collide_ray` it takes the bodies to check against as an iterator, so it works with any query(or without bevy at all):
+//!
+//! ```ignore
+//! fn spawn_enemy(mut coms: Commands, bodies: Query<(&CollisionShape, &Transform2D, &CollisionLayer)>) {
+//!     let shape = CollisionShape::Circle(Circle::new(8.0));
+//!     if let Some(p) = find_free_position(&shape, spawn_point, 64.0, CollisionLayer::default(), bodies.iter()) {
+//!         coms.spawn_bundle(KinematicBundle { shape, ..Default::default() })
+//!             .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(p.x, p.y, 0.0)));
+//!     }
+//! }
+//! ```
+//!
+//! `cast_to_surface` moves a shape along a direction until it touches something, and `snap_to_surface` uses it to place an entity
+//! flush with the surface below it(or in any other direction), optionally turned to stand on it - for level editors and item drops:
+//!
+//! ```ignore
+//! fn drop_item(world: &mut World, item: Entity) {
+//!     snap_to_surface(world, item, Vec2::new(0.0, -1.0), 500.0, true);
+//! }
+//! ```
+
+use crate::{
+    math::Vec2,
+    physics_components::{CollisionLayer, Transform2D},
+    shapes::{collide, toi, Aabb, CollisionShape},
+};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Entity, Transform, Without, World};
+#[cfg(feature = "ecs")]
+use crate::{bodies::{PhysicsDisabled, Sensor}, transform_mode::TransformMode};
+
+/// How far past the contact `cast_to_surface` looks for the surface's normal
+const SURFACE_PROBE: f32 = 0.01;
+
+/// A spot as close as possible to `preferred`(within `search_radius` of it) where `shape` doesn't overlap any of the `bodies`,
+/// only bodies whose layer overlaps `layer` count
+///
+/// `preferred` itself is tried first, then rings around it going outwards,
+/// spaced by the shape's smallest half extent so no gap big enough for the shape is skipped.
+/// `None` when there is no free spot in the search radius.
+pub fn find_free_position<'a, T>(
+    shape: &CollisionShape,
+    preferred: Vec2,
+    search_radius: f32,
+    layer: CollisionLayer,
+    bodies: T,
+) -> Option<Vec2>
+where
+    T: Iterator<Item = (&'a CollisionShape, &'a Transform2D, &'a CollisionLayer)>,
+{
+    let extents = shape.aabb(&Transform2D::default()).extents;
+    // only the bodies the shape can reach from any of the spots tried
+    let reach = shape.aabb(&Transform2D::new(preferred, 0.0, Vec2::ONE));
+    let reach = Aabb::new(reach.extents + Vec2::splat(search_radius.max(0.0)), reach.position);
+    let near = bodies
+        .filter(|(_, _, l)| l.overlap(&layer))
+        .filter(|(s, t, _)| s.aabb(t).collides(&reach))
+        .collect::<Vec<_>>();
+
+    let free = |p: Vec2| {
+        let t = Transform2D::new(p, 0.0, Vec2::ONE);
+        !near.iter().any(|(s, bt, _)| collide(shape, &t, s, bt).is_some())
+    };
+    if free(preferred) {
+        return Some(preferred);
+    }
+
+    let step = extents.min_element().max(f32::EPSILON);
+    let rings = (search_radius / step).floor() as usize;
+    for ring in 1..=rings {
+        let r = ring as f32 * step;
+        let samples = ((std::f32::consts::TAU * r / step).ceil() as usize).max(4);
+        for i in 0..samples {
+            let a = i as f32 / samples as f32 * std::f32::consts::TAU;
+            let p = preferred + Vec2::new(a.cos(), a.sin()) * r;
+            if free(p) {
+                return Some(p);
+            }
+        }
+    }
+    None
+}
+
+/// How far `shape` moves from `from` along `direction`(up to `max_dist`) until it touches one of the `bodies`,
+/// and the normal of the surface it touches there(pointing out of it), only bodies whose layer overlaps `layer` count
+///
+/// The shape stops within a tiny distance of the surface(the tolerance of `toi`), `Some((0.0, normal))` when it starts overlapping a body.
+pub fn cast_to_surface<'a, T>(
+    shape: &CollisionShape,
+    from: &Transform2D,
+    direction: Vec2,
+    max_dist: f32,
+    layer: CollisionLayer,
+    bodies: T,
+) -> Option<(f32, Vec2)>
+where
+    T: Iterator<Item = (&'a CollisionShape, &'a Transform2D, &'a CollisionLayer)>,
+{
+    let dir = direction.try_normalize()?;
+    let start = shape.aabb(from);
+    let end = Aabb::new(start.extents, start.position + dir * max_dist.max(0.0));
+    let (min, max) = (start.min_max().0.min(end.min_max().0), start.min_max().1.max(end.min_max().1));
+    let reach = Aabb::from_min_max(min, max);
+
+    let (dist, bs, bt) = bodies
+        .filter(|(_, _, l)| l.overlap(&layer))
+        .filter(|(bs, bt, _)| bs.aabb(bt).collides(&reach))
+        .filter_map(|(bs, bt, _)| toi(shape, from, dir, bs, bt, Vec2::ZERO, max_dist).map(|d| (d, bs, bt)))
+        .min_by(|a, b| a.0.total_cmp(&b.0))?;
+
+    // a bit further in, the way out of the surface is its normal
+    let probe = Transform2D::new(from.translation() + dir * (dist + SURFACE_PROBE), from.rotation(), from.scale());
+    let normal = collide(shape, &probe, bs, bt).and_then(|mtv| mtv.try_normalize()).unwrap_or(-dir);
+    Some((dist, normal))
+}
+
+/// Where `snap_to_surface` placed the entity
+#[cfg(feature = "ecs")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceSnap {
+    pub position: Vec2,
+    pub rotation: f32,
+    /// Normal of the surface it was placed on(pointing out of it)
+    pub normal: Vec2,
+}
+
+/// Moves `entity` along `direction` until its shape touches another body(up to `max_dist`), leaving it flush with that body's surface,
+/// and with `align` turns it first so its up(`+Y`) is the surface's normal
+///
+/// Only bodies whose layer overlaps the entity's are surfaces(sensors and disabled bodies never are),
+/// taken where their `Transform2D` is(where the last physics step left them, or the last snap).
+/// The entity's `Transform` is moved(and its `Transform2D`, so snapping a few entities in a row stacks them),
+/// nothing happens when nothing was hit or the entity has no `CollisionShape`.
+#[cfg(feature = "ecs")]
+pub fn snap_to_surface(
+    world: &mut World,
+    entity: Entity,
+    direction: Vec2,
+    max_dist: f32,
+    align: bool,
+) -> Option<SurfaceSnap> {
+    let mode = world.get_resource::<TransformMode>().copied().unwrap_or(TransformMode::XY);
+    let mut bodies = world.query_filtered::<(Entity, &CollisionShape, &Transform2D, &CollisionLayer), (Without<Sensor>, Without<PhysicsDisabled>)>();
+
+    let (from, snap) = {
+        let world = &*world;
+        let shape = world.get::<CollisionShape>(entity)?;
+        let layer = world.get::<CollisionLayer>(entity).copied().unwrap_or_default();
+        let from = match (world.get::<Transform2D>(entity), world.get::<Transform>(entity)) {
+            (Some(t), _) => t.clone(),
+            (None, Some(t)) => Transform2D::new(mode.get_position(t), mode.get_rotation(t), mode.get_scale(t)),
+            (None, None) => return None,
+        };
+        let others = bodies.iter(world).filter(|(e, ..)| *e != entity).map(|(_, s, t, l)| (s, t, l)).collect::<Vec<_>>();
+
+        let (mut dist, normal) = cast_to_surface(shape, &from, direction, max_dist, layer, others.iter().copied())?;
+        let mut snap = SurfaceSnap {
+            position: from.translation(),
+            rotation: from.rotation(),
+            normal,
+        };
+        if align {
+            // turned, the shape may reach the surface sooner(or later)
+            snap.rotation = f32::atan2(-normal.x, normal.y);
+            let turned = Transform2D::new(from.translation(), snap.rotation, from.scale());
+            if let Some((d, n)) = cast_to_surface(shape, &turned, direction, max_dist, layer, others.iter().copied()) {
+                dist = d;
+                snap.normal = n;
+            }
+        }
+        snap.position += direction.normalize() * dist;
+        (from, snap)
+    };
+
+    if let Some(mut t) = world.get_mut::<Transform>(entity) {
+        mode.set_position(&mut t, snap.position);
+        if align {
+            mode.set_rotation(&mut t, snap.rotation);
+        }
+    }
+    if let Some(mut t) = world.get_mut::<Transform2D>(entity) {
+        *t = Transform2D::new(snap.position, snap.rotation, from.scale()).with_pivot(from.pivot());
+    }
+    Some(snap)
+}
+
+#[cfg(test)]
+mod placement_tests {
+    use super::*;
+    use crate::shapes::{Circle, Square};
+
+    #[test]
+    fn next_to_the_wall() {
+        let wall = (
+            CollisionShape::Square(Square::size(Vec2::new(2.0, 10.0))),
+            Transform2D::default(),
+            CollisionLayer::default(),
+        );
+        let bodies = || std::iter::once((&wall.0, &wall.1, &wall.2));
+        let shape = CollisionShape::Circle(Circle::new(1.0));
+
+        // inside the wall, moved just out of it
+        let p = find_free_position(&shape, Vec2::ZERO, 5.0, CollisionLayer::default(), bodies()).unwrap();
+        assert!(p.x.abs() >= 2.0 && p.length() <= 3.0, "{}", p);
+
+        // free already
+        let away = Vec2::new(5.0, 0.0);
+        assert_eq!(find_free_position(&shape, away, 5.0, CollisionLayer::default(), bodies()), Some(away));
+        // the wall is in another layer
+        assert_eq!(find_free_position(&shape, Vec2::ZERO, 5.0, CollisionLayer::new(0b10, 0b10), bodies()), Some(Vec2::ZERO));
+        // no room
+        assert_eq!(find_free_position(&shape, Vec2::ZERO, 1.5, CollisionLayer::default(), bodies()), None);
+    }
+
+    #[cfg(feature = "ecs")]
+    #[test]
+    fn snaps_flush() {
+        use bevy::prelude::Quat;
+        use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+        let mut app = PhysicsTestApp::new();
+        let floor = app.spawn_at(Vec2::ZERO, StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(20.0, 2.0))),
+            ..Default::default()
+        });
+        // a slope on the right
+        let slope = app.spawn_at(Vec2::new(30.0, 0.0), StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(20.0, 2.0))),
+            ..Default::default()
+        });
+        app.app.world.get_mut::<Transform>(slope).unwrap().rotation = Quat::from_rotation_z(0.3);
+        let crate_ = app.spawn_at(Vec2::new(0.0, 10.0), KinematicBundle::default());
+        let other = app.spawn_at(Vec2::new(30.0, 10.0), KinematicBundle::default());
+        app.step();
+
+        let snap = snap_to_surface(&mut app.app.world, crate_, Vec2::new(0.0, -1.0), 50.0, false).unwrap();
+        assert!((snap.position - Vec2::new(0.0, 2.0)).length() < 0.01, "{}", snap.position);
+        assert!((snap.normal - Vec2::Y).length() < 0.001, "{}", snap.normal);
+        assert!((app.position(crate_) - snap.position).length() < 0.001);
+        // stacked on top of it, in the same frame
+        let lid = app.spawn_at(Vec2::new(0.0, 20.0), KinematicBundle::default());
+        app.app.world.entity_mut(lid).insert(Transform2D::new(Vec2::new(0.0, 20.0), 0.0, Vec2::ONE));
+        let snap = snap_to_surface(&mut app.app.world, lid, Vec2::new(0.0, -1.0), 50.0, false).unwrap();
+        assert!((snap.position.y - 4.0).abs() < 0.01, "{}", snap.position);
+
+        // standing on the slope
+        let snap = snap_to_surface(&mut app.app.world, other, Vec2::new(0.0, -1.0), 50.0, true).unwrap();
+        assert!((snap.rotation - 0.3).abs() < 0.01, "{}", snap.rotation);
+        assert!((snap.normal - Vec2::new(-0.3f32.sin(), 0.3f32.cos())).length() < 0.01, "{}", snap.normal);
+        // flush with it, touching but not in it
+        let t = Transform2D::new(snap.position, snap.rotation, Vec2::ONE);
+        let shape = CollisionShape::Square(Square::default());
+        let slope_t = app.app.world.get::<Transform2D>(slope).unwrap().clone();
+        let slope_shape = app.app.world.get::<CollisionShape>(slope).unwrap();
+        assert!(collide(&shape, &t, slope_shape, &slope_t).is_none());
+        let lower = Transform2D::new(snap.position - Vec2::Y * 0.02, snap.rotation, Vec2::ONE);
+        assert!(collide(&shape, &lower, slope_shape, &slope_t).is_some());
+
+        // nothing below
+        assert!(snap_to_surface(&mut app.app.world, floor, Vec2::new(0.0, -1.0), 50.0, false).is_none());
+    }
+}
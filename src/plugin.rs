@@ -1,18 +1,112 @@
 //! # Main plugin
 //!
-//! `App.add_plugin(Physics2DPlugin)`
+//! `App.add_plugin(Physics2dPlugin::default())`
 //!
 //! Contains the plugin and stages
 
 use crate::bodies::*;
+use crate::integrator::{integrate_system, PhysicsIntegrator};
+use crate::ccd::ccd_system;
+use crate::frame_budget::FrameBudget;
+use crate::lod::{shape_lod_system, ShapeLod};
+use crate::contacts::{contacts_per_entity_system, ContactsPerEntity};
+use crate::debug_color::{debug_color_system, islands_system, DebugColors, PhysicsIslands};
+use crate::layer_change::{layer_change_system, CollisionLayerChanged};
+use crate::pair_key::PairKey;
+use crate::visibility::visibility_polygon_system;
+use crate::transform_check::{transform_check_system, TransformCheck, TransformModifiedEvent};
+use crate::world_wrap::world_wrap_system;
+use crate::world_bounds::{world_bounds_system, OutOfBoundsEvent};
+use crate::physics_commands::{apply_physics_commands, PhysicsCommands};
+use crate::joints::{
+    solve_constraints, ConstraintAppExt, ConstraintRegistry, DistanceJoint, GearJoint, JointBrokenEvent, MouseJoint, PhysicsSolver, PulleyJoint,
+    RevoluteJoint, SolverSettings, WheelJoint,
+};
 use crate::physics_components::Transform2D;
+use crate::shapes::Aabb;
 use crate::transform_mode::TransformMode;
+use crate::static_index::{static_index_system, StaticIndex};
+use crate::manual_step::{PhysicsAppExt, PhysicsSchedule};
 // use crate::{broad, narrow};
-use bevy::prelude::*;
+use bevy::{prelude::*, transform::TransformSystem, utils::Duration};
 use crate::normal_coll;
 
-/// Physics plugin for 2D physics
-pub struct Physics2dPlugin;
+/**
+    # Physics2dPlugin
+
+    Physics plugin for 2D physics, `Physics2dPlugin::default()` registers everything.
+
+    Games which don't use some of the pipelines can leave them out, and put their own systems in their place
+    using the `PhysicsSystem` labels:
+
+    ```ignore
+    app.add_plugin(Physics2dPlugin::default().without_rays().with_spatial_hash(64.0))
+        .add_system_to_stage(stage::COLLISION_DETECTION, my_ray_system.after(PhysicsSystem::Collision));
+    ```
+
+    Continuous collision(`CcdMode`) is part of the step(`systems::ccd_system`, see the `ccd` module),
+    bodies opt out of it with `CcdMode::Never`.
+
+    Servers(and tests) which advance the physics on their own can use `with_manual_step`,
+    the physics stages then only run when `manual_step::step_world` is called(see the `manual_step` module).
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Physics2dPlugin {
+    /// Cast the `RayCast`s and `LineOfSight`s(`PhysicsSystem::Rays`)
+    pub rays: bool,
+    /// Solve the joints and ropes(`PhysicsSystem::Joints`)
+    pub joints: bool,
+    /// Overrides `CollisionSettings::broad_phase`
+    pub broad_phase: Option<BroadPhase>,
+    /// Runs the physics stages only through `manual_step::step_world`, not with every `App::update`
+    pub manual_step: bool,
+}
+
+impl Default for Physics2dPlugin {
+    fn default() -> Self {
+        Physics2dPlugin {
+            rays: true,
+            joints: true,
+            broad_phase: None,
+            manual_step: false,
+        }
+    }
+}
+
+impl Physics2dPlugin {
+    /// Doesn't cast the `RayCast`s(nor the `LineOfSight`s)
+    pub fn without_rays(mut self) -> Self {
+        self.rays = false;
+        self
+    }
+    /// Doesn't solve the joints and ropes
+    pub fn without_joints(mut self) -> Self {
+        self.joints = false;
+        self
+    }
+    /// Keeps the physics stages out of the app's schedule, they run when `manual_step::step_world` is called
+    pub fn with_manual_step(mut self) -> Self {
+        self.manual_step = true;
+        self
+    }
+    pub fn with_broad_phase(
+        mut self,
+        broad_phase: BroadPhase,
+    ) -> Self {
+        if let BroadPhase::SpatialHash { cell_size } = broad_phase {
+            assert!(cell_size > 0.0, "the spatial hash's cell size must be positive, got {}", cell_size);
+        }
+        self.broad_phase = Some(broad_phase);
+        self
+    }
+    /// Finds the pairs using a spatial hash with `cell_size` cells(about the size of most bodies)
+    pub fn with_spatial_hash(
+        self,
+        cell_size: f32,
+    ) -> Self {
+        self.with_broad_phase(BroadPhase::SpatialHash { cell_size })
+    }
+}
 
 /// General collision event that happens between 2 bodies.
 #[derive(Debug, Clone)]
@@ -27,6 +121,140 @@ pub struct CollisionEvent {
     pub normal: Vec2,
     /// How much entity_a penetrated entity_b, also can be seen as the movement remainder
     pub penetration: Vec2,
+    /// When `entity_a` has a `CollisionShape::Multiple`, the index of its part which penetrated `entity_b` the deepest
+    pub part_a: Option<usize>,
+    /// Same as `part_a`, for `entity_b`
+    pub part_b: Option<usize>,
+}
+
+impl CollisionEvent {
+    /// Key of the pair, the same for both orders of the entities
+    pub fn pair_key(&self) -> PairKey {
+        PairKey::new(self.entity_a, self.entity_b)
+    }
+    /// The other entity of the collision, `None` if `entity` isn't part of it
+    pub fn other(
+        &self,
+        entity: Entity,
+    ) -> Option<Entity> {
+        if entity == self.entity_a {
+            Some(self.entity_b)
+        }
+        else if entity == self.entity_b {
+            Some(self.entity_a)
+        }
+        else {
+            None
+        }
+    }
+    /// Normal of the collision from `entity`'s perspective(pointing away from the other entity),
+    /// `Vec2::ZERO` if `entity` isn't part of it
+    pub fn normal_for(
+        &self,
+        entity: Entity,
+    ) -> Vec2 {
+        if entity == self.entity_a {
+            self.normal
+        }
+        else if entity == self.entity_b {
+            -self.normal
+        }
+        else {
+            Vec2::ZERO
+        }
+    }
+    /// The part of `entity`'s `CollisionShape::Multiple` which was hit(see `part_a`)
+    pub fn part_for(
+        &self,
+        entity: Entity,
+    ) -> Option<usize> {
+        if entity == self.entity_a {
+            self.part_a
+        }
+        else if entity == self.entity_b {
+            self.part_b
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// Settings of the collision solver
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionSettings {
+    /// How the MTV is split between 2 colliding kinematic bodies(with the same `SolvePriority`)
+    pub mtv_split: MtvSplit,
+    /// Removes the velocity going into the contacts at the end of the solve,
+    /// so bodies don't re-penetrate(and vibrate) on the next frame
+    pub clamp_velocity: bool,
+    /// How many times the kinematic and static pairs are solved each frame,
+    /// more iterations keep stacks of bodies from sinking into each other(and into the ground)
+    pub iterations: usize,
+    /// Penetration left unsolved, a bit of it keeps resting bodies from jittering
+    /// (`0.0` by default, `clamp_velocity` already keeps the stacks still)
+    pub slop: f32,
+    /// How close bodies have to be for the broad phase to pair them, a bit of margin keeps the pairs of resting bodies
+    /// (which just touch before solving) so pushing one of them into the other is still solved in the same frame
+    pub contact_margin: f32,
+    /// CPU time the broad and narrow phases may use each frame, whatever is left is carried over to the next frame
+    /// (see `frame_budget` for the details), `None` means no limit
+    pub frame_budget: Option<Duration>,
+    /// Bodies farther than this from every `PhysicsViewer` collide using their `SimplifiedShape`(if they have one),
+    /// `None` disables it
+    pub lod_distance: Option<f32>,
+    /// What to do with bodies whose `Transform` was modified outside the physics step(see `transform_check`)
+    pub transform_check: TransformCheck,
+    /// Wraps the world around at the edges of these bounds(see `world_wrap`), `None` disables it
+    pub world_wrap: Option<Aabb>,
+    /// Ignores contacts with faces shared by 2 static bodies(seams between tiles),
+    /// so bodies sliding over a tiled floor don't catch on the edges of the tiles
+    pub smooth_seams: bool,
+    /// How the broad phase finds the pairs of bodies which might collide
+    pub broad_phase: BroadPhase,
+    /// Steepest slope(in radians, from the body's up) which is still a floor in `ContactInfo::surface`,
+    /// the same angle from its down is a ceiling and everything in between is a wall
+    pub floor_angle: f32,
+}
+
+impl Default for CollisionSettings {
+    fn default() -> Self {
+        CollisionSettings {
+            mtv_split: MtvSplit::default(),
+            clamp_velocity: true,
+            iterations: 4,
+            slop: 0.0,
+            contact_margin: 0.1,
+            frame_budget: None,
+            lod_distance: None,
+            transform_check: TransformCheck::default(),
+            world_wrap: None,
+            smooth_seams: true,
+            broad_phase: BroadPhase::default(),
+            floor_angle: 45f32.to_radians(),
+        }
+    }
+}
+
+/// How to split the MTV(minimal translation vector) between 2 colliding kinematic bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MtvSplit {
+    /// Only `entity_a` of the collision moves
+    #[default]
+    FirstBody,
+    /// Both bodies move, the bigger one(by `CollisionShape::area`) moves less
+    Area,
+}
+
+/// How the broad phase finds the pairs of bodies which might collide
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BroadPhase {
+    /// Checks every kinematic body against every other body, best for small scenes
+    #[default]
+    BruteForce,
+    /// Buckets the bodies in a grid of `cell_size` cells, and only checks the bodies sharing a cell,
+    /// best for big scenes of similarly sized bodies(worlds which wrap around, and a `cell_size` which isn't positive, still check every body)
+    SpatialHash { cell_size: f32 },
 }
 
 /// labels for the physics stages(boi i am excited stageless and also am scared of it)
@@ -43,19 +271,49 @@ pub mod stage {
     pub const RAYCAST_DETECTION: &str = "phy_raycast_detection";
 }
 
+/// Labels of the physics systems
+#[derive(SystemLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhysicsSystem {
+    /// Integration and the other movement in `stage::PHYSICS_STEP`
+    Step,
+    /// The joint solver in `stage::JOINT_STEP`
+    Joints,
+    /// The collision chain in `stage::COLLISION_DETECTION`, after it the sensors are filled
+    /// and the collisions are solved(the sensors are empty while it runs)
+    Collision,
+    /// Casting the rays, in `stage::COLLISION_DETECTION` after `Collision`
+    Rays,
+}
+
 impl Plugin for Physics2dPlugin {
     fn build(
         &self,
         app: &mut App,
     ) {
         // Stage order goes as follows
-        // Joints step -> Physics step -> collision detection -> solve -> sync -> Raycast detection
+        // Physics step(integration) -> Joints step -> collision detection -> solve -> sync -> Raycast detection
 
-        app.add_stage_after(
-            CoreStage::Update,
-            stage::COLLISION_DETECTION,
-            SystemStage::single_threaded(),
-        );
+        if self.manual_step {
+            // the stages live in their own schedule, `add_physics_system` puts the systems there
+            app.init_resource::<PhysicsSchedule>();
+        }
+        else {
+            app.add_stage_after(
+                CoreStage::Update,
+                stage::PHYSICS_STEP,
+                SystemStage::single_threaded(),
+            );
+            app.add_stage_after(
+                stage::PHYSICS_STEP,
+                stage::JOINT_STEP,
+                SystemStage::single_threaded(),
+            );
+            app.add_stage_after(
+                stage::JOINT_STEP,
+                stage::COLLISION_DETECTION,
+                SystemStage::single_threaded(),
+            );
+        }
 
         // Add the event type
         // app.add_event::<broad::ConBroadData>(); // internal event for passing data
@@ -64,29 +322,190 @@ impl Plugin for Physics2dPlugin {
         app.add_event::<normal_coll::CollPairKin>();
         app.add_event::<normal_coll::CollPairStatic>();
         app.add_event::<normal_coll::CollPairSensor>();
+        app.add_event::<DestructionEvent>();
+        app.add_event::<JointBrokenEvent>();
+        app.add_event::<TransformModifiedEvent>();
+        app.add_event::<OutOfBoundsEvent>();
+        app.add_event::<SensorTriggeredEvent>();
+        app.add_event::<SensorExitedEvent>();
+        app.add_event::<StuckEvent>();
+        app.add_event::<CollisionLayerChanged>();
+        app.add_event::<PenetrationHit>();
+        app.add_event::<BouncyExhausted>();
+        app.add_event::<HookEvent>();
 
         // insert the resources
         // if `app.world().is_resource_added::<T>()` could work properly, it would be great >:( - Solved on main(so fixme on 0.6)
         app.insert_resource(TransformMode::XY);
+        // doesn't override an integrator inserted before the plugin
+        app.init_resource::<PhysicsIntegrator>();
+        app.init_resource::<Gravity>();
+        app.init_resource::<CollisionSettings>();
+        if let Some(broad_phase) = self.broad_phase {
+            app.world.resource_mut::<CollisionSettings>().broad_phase = broad_phase;
+        }
+        app.init_resource::<FrameBudget>();
+        app.init_resource::<ShapeLod>();
+        app.init_resource::<StaticIndex>();
+        app.init_resource::<ContactsPerEntity>();
+        app.init_resource::<PhysicsIslands>();
+        app.init_resource::<DebugColors>();
+        app.init_resource::<SolverSettings>();
+        // same as the integrator, a backend inserted before the plugin stays
+        app.init_resource::<PhysicsSolver>();
+        app.init_resource::<ConstraintRegistry>();
 
         // Add the systems themselves for each step
-        app.add_system_to_stage(
-            stage::COLLISION_DETECTION,
+        app.add_physics_system(
+            stage::PHYSICS_STEP,
             Transform2D::sync_from_global_transform
-                .chain(sensor_clean)
+                .chain(transform_check_system)
+                .chain(teleport_system)
+                .chain(planet_walker_system)
+                .chain(gravity_system)
+                .chain(attractor_system)
+                .chain(magnet_system)
+                .chain(drag_system)
+                .chain(vehicle_system)
+                .chain(impulse_system)
+                .chain(integrate_system)
+                .chain(ccd_system)
+                .chain(world_wrap_system)
+                .chain(world_bounds_system)
+                .chain(attach_system)
+                .chain(stuck_request_system)
+                .chain(bouncy_step_system)
+                .label(PhysicsSystem::Step),
+        );
+        // the hooks tie their ropes right before the joints are solved
+        app.add_physics_system(stage::PHYSICS_STEP, grappling_hook_system.after(PhysicsSystem::Step));
+        if self.joints {
+            app.add_physics_system(
+                stage::JOINT_STEP,
+                solve_constraints.exclusive_system().label(PhysicsSystem::Joints),
+            );
+            app.add_physics_system(stage::JOINT_STEP, rope_align_system.label(PhysicsSystem::Joints));
+            app.add_physics_system(stage::JOINT_STEP, verlet_rope_system.label(PhysicsSystem::Joints));
+        }
+        app.add_constraint::<DistanceJoint>();
+        app.add_constraint::<MouseJoint>();
+        app.add_constraint::<RevoluteJoint>();
+        app.add_constraint::<WheelJoint>();
+        app.add_constraint::<GearJoint>();
+        app.add_constraint::<PulleyJoint>();
+        app.add_physics_system(
+            stage::COLLISION_DETECTION,
+            sensor_clean
+                .chain(collider_timeline_system)
+                .chain(shape_lod_system)
+                .chain(layer_change_system)
+                .chain(static_index_system)
                 // .chain(broad::broad_phase_1)
                 // .chain(narrow::narrow_phase_system)
                 .chain(normal_coll::broad_phase_2)
                 .chain(normal_coll::narrow_phase_2)
-                .chain(normal_coll::ray_phase)
-                .chain(Transform2D::sync_to_transform),
+                .chain(sensor_mode_system)
+                .chain(sensor_overlap_system)
+                .chain(contacts_per_entity_system)
+                .chain(stuck_system)
+                .chain(bouncy_system)
+                .chain(sensor_shape_system)
+                .chain(Transform2D::sync_to_transform)
+                .label(PhysicsSystem::Collision),
+        );
+        app.add_physics_system(
+            stage::COLLISION_DETECTION,
+            islands_system
+                .chain(debug_color_system)
+                .after(PhysicsSystem::Collision),
+        );
+        if self.rays {
+            app.add_physics_system(
+                stage::COLLISION_DETECTION,
+                normal_coll::ray_phase
+                    .chain(line_of_sight_system)
+                    .label(PhysicsSystem::Rays)
+                    .after(PhysicsSystem::Collision),
+            );
+        }
+
+        // deferred commands are applied once the collisions are solved
+        app.init_resource::<PhysicsCommands>();
+        app.add_physics_system(
+            stage::COLLISION_DETECTION,
+            apply_physics_commands.exclusive_system().at_end(),
+        );
+
+        app.add_system(Transform2D::auto_insert_system);
+        app.add_system(destruction_system);
+        app.add_system(validate_bodies_system);
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            visibility_polygon_system.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/**
+    # DetectionPlugin
+
+    A lean alternative to `Physics2dPlugin` for when only overlap queries are needed(UI, board games and such),
+    it only syncs the transforms, fills the `Sensor`s and casts the `RayCast`s - nothing gets moved or solved,
+    and no `CollisionEvent`s are sent.
+
+    Should not be added together with `Physics2dPlugin`.
+*/
+pub struct DetectionPlugin;
+
+impl Plugin for DetectionPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_stage_after(
+            CoreStage::Update,
+            stage::COLLISION_DETECTION,
+            SystemStage::single_threaded(),
         );
 
+        // the broad phase still sends every pair, the kinematic and static ones are just never read
+        app.add_event::<normal_coll::CollPairKin>();
+        app.add_event::<normal_coll::CollPairStatic>();
+        app.add_event::<normal_coll::CollPairSensor>();
+        app.add_event::<SensorTriggeredEvent>();
+        app.add_event::<SensorExitedEvent>();
+        app.add_event::<PenetrationHit>();
+
+        app.insert_resource(TransformMode::XY);
+        // `SensorMode::Cooldown` needs the time, even in a headless app without the time plugin
+        app.init_resource::<Time>();
+        app.init_resource::<CollisionSettings>();
+        app.init_resource::<FrameBudget>();
+        app.init_resource::<ShapeLod>();
+        app.init_resource::<StaticIndex>();
+
+        app.add_system_to_stage(
+            stage::COLLISION_DETECTION,
+            Transform2D::sync_from_global_transform
+                .chain(sensor_clean)
+                .chain(shape_lod_system)
+                .chain(static_index_system)
+                .chain(normal_coll::broad_phase_2)
+                .chain(normal_coll::sensor_phase)
+                .chain(sensor_mode_system)
+                .chain(sensor_overlap_system)
+                .chain(sensor_shape_system)
+                .chain(normal_coll::ray_phase)
+                .chain(line_of_sight_system)
+                .label(PhysicsSystem::Collision),
+        );
         app.add_system(Transform2D::auto_insert_system);
     }
 }
 
-fn sensor_clean(mut query: Query<&mut Sensor>) {
+/// Clears the sensors before they are filled again(disabled sensors keep their bodies),
+/// `SensorMode::Persistent` sensors get back the bodies which are still inside them in `sensor_mode_system`
+fn sensor_clean(mut query: Query<&mut Sensor, Without<PhysicsDisabled>>) {
     query
         .iter_mut()
         .for_each(|mut s| s.bodies.clear());
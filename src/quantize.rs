@@ -0,0 +1,141 @@
+//! # Quantization
+//!
+//! Helpers for replicating bodies over the network, `Quantizer` packs a `Transform2D` and `Vel`
+//! into a compact fixed point `QuantizedBody`(14 bytes) and back, at a configurable precision.
+//!
+//! `checksum` hashes the quantized state of a bunch of bodies, so clients and server can compare their physics state
+//! and detect desyncs(bodies need to be given in the same order on both sides, sort them by your network id).
+//!
+//! ```ignore
+//! let q = Quantizer::default();
+//! let packet = q.quantize(&transform, &vel).to_bytes();
+//! // ... on the other side
+//! let (transform, vel) = q.dequantize(&QuantizedBody::from_bytes(packet));
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    math::Vec2,
+    physics_components::{Transform2D, Vel},
+};
+
+/// Precision of the quantized values
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Quantizer {
+    /// Smallest position step, positions are stored as `i32`s of this size
+    pub position_precision: f32,
+    /// Smallest velocity step, velocities are stored as `i16`s of this size(so they are clamped to `i16::MAX` steps)
+    pub velocity_precision: f32,
+}
+
+impl Default for Quantizer {
+    /// A hundredth of a unit for positions, a tenth for velocities
+    fn default() -> Self {
+        Quantizer {
+            position_precision: 0.01,
+            velocity_precision: 0.1,
+        }
+    }
+}
+
+/// A quantized `Transform2D`(without scale) and `Vel`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct QuantizedBody {
+    pub position: [i32; 2],
+    /// The full turn split into `u16::MAX + 1` steps
+    pub rotation: u16,
+    pub vel: [i16; 2],
+}
+
+impl QuantizedBody {
+    pub const BYTES: usize = 14;
+
+    /// Little endian bytes
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut b = [0; Self::BYTES];
+        b[0..4].copy_from_slice(&self.position[0].to_le_bytes());
+        b[4..8].copy_from_slice(&self.position[1].to_le_bytes());
+        b[8..10].copy_from_slice(&self.rotation.to_le_bytes());
+        b[10..12].copy_from_slice(&self.vel[0].to_le_bytes());
+        b[12..14].copy_from_slice(&self.vel[1].to_le_bytes());
+        b
+    }
+    pub fn from_bytes(b: [u8; Self::BYTES]) -> Self {
+        QuantizedBody {
+            position: [
+                i32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                i32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+            ],
+            rotation: u16::from_le_bytes([b[8], b[9]]),
+            vel: [i16::from_le_bytes([b[10], b[11]]), i16::from_le_bytes([b[12], b[13]])],
+        }
+    }
+}
+
+impl Quantizer {
+    pub fn quantize(
+        &self,
+        transform: &Transform2D,
+        vel: &Vel,
+    ) -> QuantizedBody {
+        let p = (transform.translation() / self.position_precision).round();
+        let v = (vel.0 / self.velocity_precision).round();
+        let turns = (transform.rotation() / std::f32::consts::TAU).rem_euclid(1.0);
+
+        QuantizedBody {
+            // `as` saturates, so huge values are clamped instead of wrapping
+            position: [p.x as i32, p.y as i32],
+            rotation: (turns * 65536.0).round() as u32 as u16,
+            vel: [v.x as i16, v.y as i16],
+        }
+    }
+
+    /// Back to a `Transform2D`(with a scale of 1 and rotation in 0..TAU) and `Vel`
+    pub fn dequantize(
+        &self,
+        body: &QuantizedBody,
+    ) -> (Transform2D, Vel) {
+        let position = Vec2::new(body.position[0] as f32, body.position[1] as f32) * self.position_precision;
+        let rotation = body.rotation as f32 / 65536.0 * std::f32::consts::TAU;
+        let vel = Vec2::new(body.vel[0] as f32, body.vel[1] as f32) * self.velocity_precision;
+
+        (Transform2D::new(position, rotation, Vec2::ONE), Vel(vel))
+    }
+}
+
+/// Hash(FNV-1a) of the quantized bodies, in the order given
+pub fn checksum(bodies: impl IntoIterator<Item = QuantizedBody>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in bodies {
+        for byte in b.to_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod quantize_tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let q = Quantizer::default();
+        let t = Transform2D::new(Vec2::new(123.456, -7.891), -1.0, Vec2::ONE);
+        let v = Vel(Vec2::new(55.53, -0.04));
+
+        let body = QuantizedBody::from_bytes(q.quantize(&t, &v).to_bytes());
+        let (t2, v2) = q.dequantize(&body);
+
+        assert!((t2.translation() - t.translation()).abs().max_element() <= 0.005);
+        assert!((t2.rotation() - (std::f32::consts::TAU - 1.0)).abs() < 0.001);
+        assert!((v2.0 - v.0).abs().max_element() <= 0.05);
+
+        // changes below the precision don't change the checksum
+        let t3 = Transform2D::new(t.translation() + Vec2::splat(0.0001), -1.0, Vec2::ONE);
+        assert_eq!(checksum([q.quantize(&t, &v)]), checksum([q.quantize(&t3, &v)]));
+        assert_ne!(checksum([q.quantize(&t, &v)]), checksum([q.quantize(&t, &Vel::ZERO)]));
+    }
+}
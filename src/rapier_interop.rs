@@ -0,0 +1,217 @@
+//! # Rapier interop
+//!
+//! Enabled with the `rapier-interop` feature.
+//!
+//! Conversion helpers between physimple's components and `bevy_rapier2d`'s,
+//! so you can prototype with physimple and move(some of) your bodies to rapier later without redoing all of your setup.
+//!
+//! Do note that rapier does not know about `TransformMode`, so the `Transform` conversions always assume `TransformMode::XY`.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{Collider, ColliderView, Velocity};
+
+use crate::{
+    physics_components::{Transform2D, Vel},
+    shapes::*,
+    transform_mode::TransformMode,
+};
+
+impl CollisionShape {
+    /// Builds a rapier `Collider` describing the same shape
+    ///
+    /// Returns `None` for custom `CollisionShape::Convex` shapes(or degenerate polygons),
+    /// as their vertices cannot be retrieved.
+    pub fn to_rapier(&self) -> Option<Collider> {
+        let mut parts = Vec::new();
+        self.rapier_parts(&mut parts);
+
+        // Offsets are expressed by wrapping the shape in a compound
+        match parts.len() {
+            0 => None,
+            1 if parts[0].0 == Vec2::ZERO => parts.pop().map(|(_, _, c)| c),
+            _ => Some(Collider::compound(parts)),
+        }
+    }
+
+    /// Pushes the rapier shape of each part along with its offset,
+    /// rapier's compounds can't be nested so the parts of a `Multiple` are flattened
+    fn rapier_parts(
+        &self,
+        parts: &mut Vec<(Vec2, f32, Collider)>,
+    ) {
+        match self {
+            CollisionShape::Square(s) => parts.push((s.offset, 0.0, Collider::cuboid(s.extents.x, s.extents.y))),
+            CollisionShape::Circle(c) => parts.push((c.offset, 0.0, Collider::ball(c.radius))),
+            CollisionShape::Capsule(c) => parts.push((c.offset, 0.0, Collider::capsule_y(c.half_height, c.radius))),
+            // the offset of triangles and polygons is a part of their vertices
+            CollisionShape::Triangle(t) => {
+                let [a, b, c] = t.verts();
+                parts.push((Vec2::ZERO, 0.0, Collider::triangle(a, b, c)));
+            },
+            CollisionShape::Polygon(p) => {
+                if let Some(c) = Collider::convex_hull(p.verts()) {
+                    parts.push((Vec2::ZERO, 0.0, c));
+                }
+            },
+            CollisionShape::Multiple(v) => v.iter().for_each(|s| s.rapier_parts(parts)),
+            CollisionShape::Convex(_) => {},
+        }
+    }
+
+    /// Builds a `CollisionShape` from a rapier `Collider`
+    ///
+    /// Supports cuboids, balls, capsules(aligned to the y axis), triangles, convex polygons
+    /// and compounds of them(as a `Multiple`, keeping the position of each part),
+    /// returns `None` for everything else.
+    pub fn from_rapier(collider: &Collider) -> Option<CollisionShape> {
+        shape_from_view(collider.as_typed_shape(), Vec2::ZERO, 0.0)
+    }
+}
+
+/// The shape of a rapier shape at `offset`, rotated by `rotation`(a part of a compound)
+fn shape_from_view(
+    view: ColliderView,
+    offset: Vec2,
+    rotation: f32,
+) -> Option<CollisionShape> {
+    let rot = Mat2::from_angle(rotation);
+    let place = |p: Vec2| offset + rot * p;
+
+    match view {
+        ColliderView::Cuboid(c) => {
+            let extents = c.half_extents();
+            if rotation.abs() < f32::EPSILON {
+                return Some(CollisionShape::Square(Square::new(extents).with_offset(offset)));
+            }
+            // squares don't rotate on their own
+            let corners = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)];
+            Some(CollisionShape::Polygon(Polygon::new(corners.iter().map(|c| place(*c * extents)).collect())))
+        },
+        ColliderView::Ball(b) => Some(CollisionShape::Circle(Circle::new(b.radius()).with_offset(offset))),
+        ColliderView::Capsule(c) => {
+            let (a, b) = (place(c.segment().a()), place(c.segment().b()));
+            // physimple capsules are always aligned to the y axis(before rotation), with some room for the rotation's rounding
+            if (a.x - b.x).abs() > 0.0001 {
+                return None;
+            }
+            let capsule = Capsule::new((a.y - b.y).abs(), c.radius()).with_offset((a + b) * 0.5);
+            Some(CollisionShape::Capsule(capsule))
+        },
+        ColliderView::Triangle(t) => Some(CollisionShape::Triangle(Triangle::new(place(t.a()), place(t.b()), place(t.c())))),
+        ColliderView::ConvexPolygon(p) => Some(CollisionShape::Polygon(Polygon::new(p.points().map(place).collect()))),
+        ColliderView::Compound(c) => {
+            let mut shapes: Vec<_> = c
+                .shapes()
+                .filter_map(|(pos, rot, view)| shape_from_view(view, place(pos), rotation + rot))
+                .collect();
+            match shapes.len() {
+                0 => None,
+                1 => shapes.pop(),
+                _ => Some(CollisionShape::Multiple(shapes)),
+            }
+        },
+        _ => None,
+    }
+}
+
+impl From<&Vel> for Velocity {
+    fn from(v: &Vel) -> Self {
+        Velocity::linear(v.0)
+    }
+}
+impl From<&Velocity> for Vel {
+    fn from(v: &Velocity) -> Self {
+        Vel(v.linvel)
+    }
+}
+
+impl From<&Transform2D> for Transform {
+    /// Converts to a `Transform`(in the XY plane), as used by rapier
+    fn from(t: &Transform2D) -> Self {
+        Transform {
+            translation: t.translation().extend(0.0),
+            rotation: Quat::from_rotation_z(t.rotation()),
+            scale: t.scale().extend(1.0),
+        }
+    }
+}
+impl From<&Transform> for Transform2D {
+    /// Converts from a `Transform`(in the XY plane), as used by rapier
+    fn from(t: &Transform) -> Self {
+        Transform2D::new(
+            t.translation.truncate(),
+            TransformMode::XY.get_rotation(t),
+            t.scale.truncate(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod rapier_interop_tests {
+    use super::*;
+
+    /// Whether both shapes cover the same area in the same place
+    fn same_shape(
+        a: &CollisionShape,
+        b: &CollisionShape,
+    ) -> bool {
+        let t = Transform2D::default();
+        let ((amin, amax), (bmin, bmax)) = (a.aabb(&t).min_max(), b.aabb(&t).min_max());
+        (amin - bmin).length() < 0.001 && (amax - bmax).length() < 0.001 && (a.area() - b.area()).abs() < 0.001
+    }
+
+    #[test]
+    fn shapes_round_trip() {
+        let shapes = vec![
+            CollisionShape::Square(Square::new(Vec2::new(2.0, 1.0))),
+            CollisionShape::Square(Square::new(Vec2::new(2.0, 1.0)).with_offset(Vec2::new(3.0, -1.0))),
+            CollisionShape::Circle(Circle::new(1.5).with_offset(Vec2::new(0.0, 4.0))),
+            CollisionShape::Capsule(Capsule::new(2.0, 0.5).with_offset(Vec2::new(-2.0, 1.0))),
+            CollisionShape::Triangle(Triangle::new(Vec2::new(5.0, 5.0), Vec2::new(7.0, 5.0), Vec2::new(5.0, 8.0))),
+            CollisionShape::Polygon(Polygon::new(vec![Vec2::new(1.0, 1.0), Vec2::new(3.0, 1.0), Vec2::new(4.0, 2.0), Vec2::new(2.0, 3.0)])),
+        ];
+        for (i, s) in shapes.iter().enumerate() {
+            let back = CollisionShape::from_rapier(&s.to_rapier().unwrap()).unwrap();
+            assert!(same_shape(s, &back), "shape {} didn't come back the same", i);
+        }
+
+        // every part keeps its place
+        let multiple = CollisionShape::Multiple(shapes);
+        match CollisionShape::from_rapier(&multiple.to_rapier().unwrap()) {
+            Some(CollisionShape::Multiple(parts)) => {
+                assert_eq!(parts.len(), 6);
+                assert!(same_shape(&multiple, &CollisionShape::Multiple(parts)));
+            },
+            _ => panic!("expected a Multiple"),
+        }
+    }
+
+    #[test]
+    fn rotated_compound_parts() {
+        let collider = Collider::compound(vec![
+            (Vec2::new(2.0, 0.0), std::f32::consts::FRAC_PI_4, Collider::cuboid(1.0, 1.0)),
+            (Vec2::new(0.0, 2.0), std::f32::consts::PI, Collider::capsule_y(1.0, 0.5)),
+        ]);
+        match CollisionShape::from_rapier(&collider) {
+            Some(CollisionShape::Multiple(parts)) => {
+                let t = Transform2D::default();
+                assert!(matches!(&parts[0], CollisionShape::Polygon(_)));
+                assert!(parts[0].contains_point(&t, Vec2::new(2.0 + 1.4, 0.0)));
+                assert!(matches!(&parts[1], CollisionShape::Capsule(c) if (c.offset - Vec2::new(0.0, 2.0)).length() < 0.001));
+            },
+            _ => panic!("expected a Multiple"),
+        }
+    }
+
+    #[test]
+    fn vel_and_transform_round_trip() {
+        let vel = Vel(Vec2::new(3.0, -2.0));
+        assert_eq!(Vel::from(&Velocity::from(&vel)).0, vel.0);
+
+        let t = Transform2D::new(Vec2::new(1.0, 2.0), 0.75, Vec2::new(2.0, 0.5));
+        let back = Transform2D::from(&Transform::from(&t));
+        assert!((back.translation() - t.translation()).length() < 0.0001);
+        assert!((back.rotation() - t.rotation()).abs() < 0.0001);
+        assert!((back.scale() - t.scale()).length() < 0.0001);
+    }
+}
@@ -0,0 +1,417 @@
+//! # Record and replay
+//!
+//! For reproducing bugs exactly, `PhysicsRecorder` logs every write done to the physics bodies from outside the physics step
+//! (spawning and despawning them, changing their `Vel`, adding to their `Acc` and moving their `Transform`), frame by frame,
+//! and `PhysicsReplay` writes them back into a fresh world at the same frames - the game's own systems aren't needed for that.
+//!
+//! ```ignore
+//! app.add_plugin(Physics2dPlugin::default()).add_plugin(ReplayPlugin);
+//! app.world.resource_mut::<PhysicsRecorder>().start();
+//! // ... play until the bug shows up
+//! let recording = app.world.resource_mut::<PhysicsRecorder>().stop();
+//! std::fs::write("bug.ron", ron::to_string(&recording)?)?;
+//!
+//! // in a test, with the same physics resources(`Gravity`, `CollisionSettings`...) and the same delta times
+//! app.insert_resource(PhysicsReplay::new(ron::from_str(&std::fs::read_to_string("bug.ron")?)?));
+//! ```
+//!
+//! Only kinematic and static bodies without a parent are recorded(sensors don't affect the simulation),
+//! with their shape, layer, `Vel` and whether they have `Acc` - other components(joints, `Frozen`, `Bouncy`...) aren't,
+//! and neither are `CollisionShape::Convex` shapes(bodies using them are skipped with a warning).
+//! Bodies which exist when the recording starts are recorded as spawned on its first frame.
+//!
+//! The writes are compared against the bodies' state at the end of the previous physics step,
+//! so `PhysicsCommands`(applied after that) are recorded as well.
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bodies::{KinematicBody, Sensor, StaticBody},
+    manual_step::PhysicsAppExt,
+    physics_components::{Acc, CollisionLayer, Transform2D, Vel},
+    plugin::{stage, PhysicsSystem},
+    shapes::{Capsule, Circle, CollisionShape, Polygon, Square, Triangle},
+};
+
+/// Adds the recording and replaying systems, needs `Physics2dPlugin` to be added first
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<PhysicsRecorder>();
+        app.add_physics_system(stage::PHYSICS_STEP, replay_system.exclusive_system().at_start());
+        app.add_physics_system(stage::PHYSICS_STEP, record_system.before(PhysicsSystem::Step));
+        app.add_physics_system(stage::COLLISION_DETECTION, record_end_system.after(PhysicsSystem::Collision));
+    }
+}
+
+/// A `CollisionShape` which can be saved(all of them but `Convex`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RecordedShape {
+    Square(Square),
+    Triangle(Triangle),
+    Circle(Circle),
+    Capsule(Capsule),
+    Polygon(Polygon),
+    Multiple(Vec<RecordedShape>),
+}
+
+impl RecordedShape {
+    /// `None` for `CollisionShape::Convex`(or a `Multiple` containing one)
+    pub fn from_shape(shape: &CollisionShape) -> Option<RecordedShape> {
+        Some(match shape {
+            CollisionShape::Square(s) => RecordedShape::Square(s.clone()),
+            CollisionShape::Triangle(t) => RecordedShape::Triangle(t.clone()),
+            CollisionShape::Circle(c) => RecordedShape::Circle(c.clone()),
+            CollisionShape::Capsule(c) => RecordedShape::Capsule(c.clone()),
+            CollisionShape::Polygon(p) => RecordedShape::Polygon(p.clone()),
+            CollisionShape::Multiple(v) => RecordedShape::Multiple(v.iter().map(RecordedShape::from_shape).collect::<Option<_>>()?),
+            CollisionShape::Convex(_) => return None,
+        })
+    }
+    pub fn to_shape(&self) -> CollisionShape {
+        match self {
+            RecordedShape::Square(s) => CollisionShape::Square(s.clone()),
+            RecordedShape::Triangle(t) => CollisionShape::Triangle(t.clone()),
+            RecordedShape::Circle(c) => CollisionShape::Circle(c.clone()),
+            RecordedShape::Capsule(c) => CollisionShape::Capsule(c.clone()),
+            RecordedShape::Polygon(p) => CollisionShape::Polygon(p.clone()),
+            RecordedShape::Multiple(v) => CollisionShape::Multiple(v.iter().map(RecordedShape::to_shape).collect()),
+        }
+    }
+}
+
+/// A `Transform` which can be saved
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecordedTransform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl From<&Transform> for RecordedTransform {
+    fn from(t: &Transform) -> Self {
+        RecordedTransform {
+            translation: t.translation,
+            rotation: t.rotation,
+            scale: t.scale,
+        }
+    }
+}
+
+impl From<RecordedTransform> for Transform {
+    fn from(t: RecordedTransform) -> Self {
+        Transform {
+            translation: t.translation,
+            rotation: t.rotation,
+            scale: t.scale,
+        }
+    }
+}
+
+/// What kind of body was spawned
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum RecordedKind {
+    /// Has a `KinematicBody` marker(`None` for kinematic bodies without one)
+    Kinematic(Option<KinematicBody>),
+    Static,
+}
+
+/// A single write to a body from outside the physics step,
+/// bodies are identified by their entity in the recorded world(`Entity::to_bits`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PhysicsWrite {
+    Spawn {
+        body: u64,
+        transform: RecordedTransform,
+        shape: RecordedShape,
+        layer: CollisionLayer,
+        kind: RecordedKind,
+        vel: Option<Vec2>,
+        acc: bool,
+    },
+    Despawn { body: u64 },
+    /// The `Vel` was set to `vel`
+    Vel { body: u64, vel: Vec2 },
+    /// `acc` was added to the `Acc`
+    Acc { body: u64, acc: Vec2 },
+    /// The `Transform` was set to `transform`
+    Moved { body: u64, transform: RecordedTransform },
+}
+
+/// The writes of a recording, by frame
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhysicsRecording {
+    /// How many physics steps were recorded
+    pub frames: u64,
+    /// The frames which had any writes(in order), with their writes
+    pub writes: Vec<(u64, Vec<PhysicsWrite>)>,
+}
+
+/// Records the writes to the bodies while `start`ed, inserted by `ReplayPlugin`
+#[derive(Default)]
+pub struct PhysicsRecorder {
+    recording: Option<PhysicsRecording>,
+    /// State of the bodies at the end of the last physics step
+    last: HashMap<Entity, (Transform, Option<Vec2>)>,
+}
+
+impl PhysicsRecorder {
+    /// Starts a new recording(dropping the current one, if any)
+    pub fn start(&mut self) {
+        self.recording = Some(PhysicsRecording::default());
+        self.last.clear();
+    }
+    /// Stops recording and returns what was recorded(empty if it wasn't recording)
+    pub fn stop(&mut self) -> PhysicsRecording {
+        self.last.clear();
+        self.recording.take().unwrap_or_default()
+    }
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+}
+
+/// Replays a `PhysicsRecording`, one recorded frame per physics step(starting with the next one)
+pub struct PhysicsReplay {
+    recording: PhysicsRecording,
+    frame: u64,
+    next: usize,
+    bodies: HashMap<u64, Entity>,
+}
+
+impl PhysicsReplay {
+    pub fn new(recording: PhysicsRecording) -> Self {
+        PhysicsReplay {
+            recording,
+            frame: 0,
+            next: 0,
+            bodies: HashMap::default(),
+        }
+    }
+    /// Whether all the recorded frames were replayed
+    pub fn is_done(&self) -> bool {
+        self.frame >= self.recording.frames
+    }
+    /// The entity replaying a recorded body(`Entity::to_bits` of the body in the recorded world)
+    pub fn entity(
+        &self,
+        recorded: u64,
+    ) -> Option<Entity> {
+        self.bodies.get(&recorded).copied()
+    }
+}
+
+/// Writes the recorded frame into the world, runs at the start of the physics step
+pub fn replay_system(world: &mut World) {
+    if !world.contains_resource::<PhysicsReplay>() {
+        return;
+    }
+    world.resource_scope(|world, mut replay: Mut<PhysicsReplay>| {
+        let replay = &mut *replay;
+        if replay.is_done() {
+            return;
+        }
+        let frame = replay.frame;
+        replay.frame += 1;
+        let writes = match replay.recording.writes.get(replay.next) {
+            Some((f, writes)) if *f == frame => writes,
+            _ => return,
+        };
+        replay.next += 1;
+
+        for w in writes {
+            match w {
+                PhysicsWrite::Spawn { body, transform, shape, layer, kind, vel, acc } => {
+                    let transform = Transform::from(*transform);
+                    let mut e = world.spawn();
+                    e.insert_bundle((transform, GlobalTransform::from(transform), Transform2D::default(), shape.to_shape(), *layer));
+                    match kind {
+                        RecordedKind::Kinematic(Some(k)) => { e.insert(*k); },
+                        RecordedKind::Kinematic(None) => {},
+                        RecordedKind::Static => { e.insert(StaticBody); },
+                    }
+                    if let Some(v) = vel {
+                        e.insert(Vel(*v));
+                    }
+                    if *acc {
+                        e.insert(Acc::default());
+                    }
+                    replay.bodies.insert(*body, e.id());
+                },
+                PhysicsWrite::Despawn { body } => {
+                    if let Some(e) = replay.bodies.remove(body) {
+                        world.despawn(e);
+                    }
+                },
+                PhysicsWrite::Vel { body, vel } => {
+                    if let Some(mut v) = replay.bodies.get(body).and_then(|e| world.get_mut::<Vel>(*e)) {
+                        v.0 = *vel;
+                    }
+                },
+                PhysicsWrite::Acc { body, acc } => {
+                    if let Some(mut a) = replay.bodies.get(body).and_then(|e| world.get_mut::<Acc>(*e)) {
+                        a.add(*acc);
+                    }
+                },
+                PhysicsWrite::Moved { body, transform } => {
+                    if let Some(mut t) = replay.bodies.get(body).and_then(|e| world.get_mut::<Transform>(*e)) {
+                        *t = (*transform).into();
+                    }
+                },
+            }
+        }
+    });
+}
+
+type RecordedBody<'a> = (
+    Entity,
+    &'a Transform,
+    &'a CollisionShape,
+    &'a CollisionLayer,
+    Option<&'a Vel>,
+    Option<&'a Acc>,
+    Option<&'a KinematicBody>,
+    Option<&'a StaticBody>,
+);
+
+/// Compares the bodies against the end of the last physics step, runs at the start of the physics step
+#[allow(clippy::type_complexity)]
+pub fn record_system(
+    mut recorder: ResMut<PhysicsRecorder>,
+    bodies: Query<RecordedBody, (Without<Sensor>, Without<Parent>)>,
+) {
+    let recorder = &mut *recorder;
+    let recording = match recorder.recording.as_mut() {
+        Some(r) => r,
+        None => return,
+    };
+
+    let mut bodies = bodies.iter().collect::<Vec<_>>();
+    // the same order for every run, whatever order the archetypes are in
+    bodies.sort_by_key(|b| b.0);
+
+    let mut writes = Vec::new();
+    for (e, transform, shape, layer, vel, acc, kinematic, stat) in bodies.iter().copied() {
+        let body = e.to_bits();
+        match recorder.last.get(&e) {
+            Some((last_transform, last_vel)) => {
+                if transform != last_transform {
+                    writes.push(PhysicsWrite::Moved {
+                        body,
+                        transform: transform.into(),
+                    });
+                }
+                if let Some(v) = vel.filter(|v| Some(v.0) != *last_vel) {
+                    writes.push(PhysicsWrite::Vel { body, vel: v.0 });
+                }
+            },
+            None => {
+                let shape = match RecordedShape::from_shape(shape) {
+                    Some(s) => s,
+                    None => {
+                        warn!("{:?} uses a `CollisionShape::Convex`, which can't be recorded", e);
+                        continue;
+                    },
+                };
+                writes.push(PhysicsWrite::Spawn {
+                    body,
+                    transform: transform.into(),
+                    shape,
+                    layer: *layer,
+                    kind: if stat.is_some() { RecordedKind::Static } else { RecordedKind::Kinematic(kinematic.copied()) },
+                    vel: vel.map(|v| v.0),
+                    acc: acc.is_some(),
+                });
+            },
+        }
+        // the step clears it, so whatever is in it was added since
+        if let Some(a) = acc.filter(|a| a.current != Vec2::ZERO) {
+            writes.push(PhysicsWrite::Acc { body, acc: a.current });
+        }
+    }
+    for e in recorder.last.keys() {
+        if bodies.binary_search_by_key(e, |b| b.0).is_err() {
+            writes.push(PhysicsWrite::Despawn { body: e.to_bits() });
+        }
+    }
+
+    if !writes.is_empty() {
+        recording.writes.push((recording.frames, writes));
+    }
+    recording.frames += 1;
+}
+
+/// Saves the state of the bodies at the end of the physics step, to compare the next step against
+#[allow(clippy::type_complexity)]
+pub fn record_end_system(
+    mut recorder: ResMut<PhysicsRecorder>,
+    bodies: Query<(Entity, &Transform, &CollisionShape, Option<&Vel>), (Without<Sensor>, Without<Parent>)>,
+) {
+    if !recorder.is_recording() {
+        return;
+    }
+    // skipped bodies(`Convex` shapes) stay out of it, so they aren't reported as despawned
+    let last = bodies
+        .iter()
+        .filter(|(e, _, s, _)| recorder.last.contains_key(e) || RecordedShape::from_shape(s).is_some())
+        .map(|(e, t, _, v)| (e, (*t, v.map(|v| v.0))))
+        .collect();
+    recorder.last = last;
+}
+
+#[cfg(test)]
+mod replay_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn replays_exactly() {
+        let mut app = PhysicsTestApp::new();
+        app.app.add_plugin(ReplayPlugin);
+        app.app.insert_resource(Gravity(Vec2::new(0.0, -20.0)));
+        app.app.world.resource_mut::<PhysicsRecorder>().start();
+
+        let floor = app.spawn_at(Vec2::new(0.0, -5.0), StaticBundle {
+            shape: CollisionShape::Square(Square::size(Vec2::new(40.0, 1.0))),
+            ..Default::default()
+        });
+        let ball = app.spawn_at(Vec2::ZERO, KinematicBundle {
+            shape: CollisionShape::Circle(Circle::new(0.5)),
+            ..Default::default()
+        });
+        app.app.world.entity_mut(ball).insert(Acc::default());
+        app.step_n(20);
+
+        app.app.world.get_mut::<Vel>(ball).unwrap().0 = Vec2::new(15.0, 10.0);
+        app.step_n(10);
+        app.app.world.get_mut::<Acc>(ball).unwrap().add(Vec2::new(0.0, 300.0));
+        app.app.world.get_mut::<Transform>(ball).unwrap().translation.x -= 3.0;
+        let crate_ = app.spawn_at(Vec2::new(2.0, 3.0), KinematicBundle::default());
+        app.app.world.entity_mut(crate_).insert(Acc::default());
+        app.step_n(20);
+        app.app.world.despawn(floor);
+        app.step_n(10);
+
+        let recording = app.app.world.resource_mut::<PhysicsRecorder>().stop();
+        assert_eq!(recording.frames, 60);
+
+        let mut replay = PhysicsTestApp::new();
+        replay.app.add_plugin(ReplayPlugin);
+        replay.app.insert_resource(Gravity(Vec2::new(0.0, -20.0)));
+        replay.app.insert_resource(PhysicsReplay::new(recording));
+        replay.step_n(60);
+
+        let replayed = replay.app.world.resource::<PhysicsReplay>();
+        assert!(replayed.is_done());
+        let (r_ball, r_crate) = (replayed.entity(ball.to_bits()).unwrap(), replayed.entity(crate_.to_bits()).unwrap());
+        assert!(replayed.entity(floor.to_bits()).is_none());
+        assert_eq!(replay.position(r_ball), app.position(ball));
+        assert_eq!(replay.position(r_crate), app.position(crate_));
+        assert_eq!(replay.app.world.get::<Vel>(r_ball).unwrap().0, app.app.world.get::<Vel>(ball).unwrap().0);
+    }
+}
@@ -0,0 +1,112 @@
+//! # Meshes from collision shapes
+//!
+//! Enabled with the `mesh` feature.
+//!
+//! `shape_mesh` builds a flat `Mesh`(in the XY plane, relative to the body) out of a `CollisionShape`,
+//! so prototypes can render exactly what the physics sees instead of sprites that might not match.
+//!
+//! ```ignore
+//! let shape = CollisionShape::Capsule(Capsule::new(40.0, 10.0));
+//!
+//! coms.spawn_bundle(MaterialMesh2dBundle {
+//!     mesh: meshes.add(shape_mesh(&shape)).into(),
+//!     material: materials.add(ColorMaterial::from(Color::ORANGE)),
+//!     ..Default::default()
+//! })
+//! .insert_bundle(KinematicBundle { shape, ..Default::default() });
+//! ```
+
+use bevy::{
+    prelude::*,
+    render::{mesh::Indices, render_resource::PrimitiveTopology},
+};
+
+use crate::{
+    physics_components::Transform2D,
+    shapes::CollisionShape,
+};
+
+/// Builds a mesh of the shape, round shapes use `CollisionShape::DEFAULT_SEGMENTS` segments
+pub fn shape_mesh(shape: &CollisionShape) -> Mesh {
+    shape_mesh_with(shape, CollisionShape::DEFAULT_SEGMENTS)
+}
+
+/// Builds a mesh of the shape, approximating circles and capsules with `segments` segments
+///
+/// Each convex part is a triangle fan around its center, UVs span the bounding box of the whole shape
+pub fn shape_mesh_with(
+    shape: &CollisionShape,
+    segments: usize,
+) -> Mesh {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+    add_fans(shape, segments, &mut positions, &mut indices);
+
+    let (min, max) = shape.aabb(&Transform2D::default()).min_max();
+    let size = (max - min).max(Vec2::splat(f32::EPSILON));
+    let uvs: Vec<[f32; 2]> = positions
+        .iter()
+        .map(|p| {
+            let uv = (Vec2::new(p[0], p[1]) - min) / size;
+            // textures go top to bottom
+            [uv.x, 1.0 - uv.y]
+        })
+        .collect();
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh
+}
+
+/// Adds a triangle fan for every convex part of the shape
+fn add_fans(
+    shape: &CollisionShape,
+    segments: usize,
+    positions: &mut Vec<[f32; 3]>,
+    indices: &mut Vec<u32>,
+) {
+    if let CollisionShape::Multiple(v) = shape {
+        for s in v {
+            add_fans(s, segments, positions, indices);
+        }
+        return;
+    }
+
+    let outline: Vec<Vec2> = shape.world_vertices_with(&Transform2D::default(), segments).collect();
+    if outline.len() < 3 {
+        return;
+    }
+    let center = outline.iter().sum::<Vec2>() / outline.len() as f32;
+
+    let first = positions.len() as u32;
+    positions.push([center.x, center.y, 0.0]);
+    positions.extend(outline.iter().map(|v| [v.x, v.y, 0.0]));
+
+    let n = outline.len() as u32;
+    for i in 0..n {
+        indices.extend([first, first + 1 + i, first + 1 + (i + 1) % n]);
+    }
+}
+
+#[cfg(test)]
+mod shape_mesh_tests {
+    use super::*;
+    use crate::shapes::{Circle, Square};
+
+    #[test]
+    fn fan_per_part() {
+        let shape = CollisionShape::Multiple(vec![
+            CollisionShape::Square(Square::default()),
+            CollisionShape::Circle(Circle::new(1.0).with_offset(Vec2::new(3.0, 0.0))),
+        ]);
+        let mesh = shape_mesh_with(&shape, 8);
+
+        // a center vertex for each part, 4 + 8 outline vertices and a triangle per outline vertex
+        assert_eq!(mesh.count_vertices(), 14);
+        assert_eq!(mesh.indices().unwrap().iter().count(), 3 * 12);
+    }
+}
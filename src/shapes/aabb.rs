@@ -1,8 +1,11 @@
-use bevy::prelude::*;
+use crate::math::Vec2;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect};
 
 /// Axis aligned bounding box
-#[derive(Debug, Default, Clone, Copy, Reflect, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect))]
 pub struct Aabb {
     pub extents: Vec2,
     pub position: Vec2,
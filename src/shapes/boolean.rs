@@ -0,0 +1,177 @@
+use crate::math::Vec2;
+
+use super::{polygon::signed_area, CollisionShape, Polygon, Transform2D};
+
+/// How many segments are used when approximating round shapes(circles and capsule caps) as polygons
+pub const ROUND_SEGMENTS: usize = 16;
+
+impl CollisionShape {
+    /// Subtracts `region`(placed at `region_trans`) from this shape(placed at `trans`)
+    ///
+    /// Returns the remains as a `CollisionShape::Multiple` of convex polygons,
+    /// with the vertices relative to `trans`(so the shape can be put back on the same entity),
+    /// or `None` if nothing was left.
+    ///
+    /// Round shapes(circles and capsules) are approximated using `ROUND_SEGMENTS` segments,
+    /// custom `CollisionShape::Convex` shapes cannot be broken into polygons and are left out of the result.
+    pub fn subtract(
+        &self,
+        trans: &Transform2D,
+        region: &CollisionShape,
+        region_trans: &Transform2D,
+    ) -> Option<CollisionShape> {
+        // Get the region in the local space of this shape
+        let inv_rot = trans.rot_matrix().transpose();
+        let region_rot = region_trans.rot_matrix();
+
+        let cutters: Vec<Vec<Vec2>> = region
+            .local_polygons(ROUND_SEGMENTS)
+            .into_iter()
+            .map(|p| {
+                p.into_iter()
+                    .map(|v| inv_rot * (region_rot * v + region_trans.translation() - trans.translation()))
+                    .collect()
+            })
+            .collect();
+
+        let mut pieces = self.local_polygons(ROUND_SEGMENTS);
+        for cutter in cutters.iter() {
+            pieces = pieces
+                .iter()
+                .flat_map(|p| subtract_convex(p, cutter))
+                .collect();
+        }
+
+        if pieces.is_empty() {
+            None
+        }
+        else {
+            Some(CollisionShape::Multiple(
+                pieces.into_iter().map(|p| CollisionShape::Polygon(Polygon::new(p))).collect()
+            ))
+        }
+    }
+
+    /// Breaks the shape into convex polygons(relative to the shape's transform)
+    pub(crate) fn local_polygons(&self, segments: usize) -> Vec<Vec<Vec2>> {
+        let segments = segments.max(3);
+
+        match self {
+            CollisionShape::Square(s) => vec![
+                [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)]
+                    .iter()
+                    .map(|v| *v * s.extents + s.offset)
+                    .collect()
+            ],
+            CollisionShape::Triangle(t) => vec![t.verts().to_vec()],
+            CollisionShape::Polygon(p) => vec![p.verts().to_vec()],
+            CollisionShape::Circle(c) => vec![arc(c.offset, c.radius, 0.0, std::f32::consts::TAU, segments)],
+            CollisionShape::Capsule(c) => {
+                let half = segments / 2;
+                let top = c.offset + Vec2::new(0.0, c.half_height);
+                let bottom = c.offset - Vec2::new(0.0, c.half_height);
+
+                let mut v = arc(top, c.radius, 0.0, std::f32::consts::PI, half);
+                v.extend(arc(bottom, c.radius, std::f32::consts::PI, std::f32::consts::PI, half));
+                vec![v]
+            },
+            CollisionShape::Multiple(v) => v.iter().flat_map(|s| s.local_polygons(segments)).collect(),
+            CollisionShape::Convex(_) => Vec::new(),
+        }
+    }
+}
+
+/// Points along an arc, `start` and `span` are in radians, includes both ends of the arc(unless it is a full circle)
+fn arc(center: Vec2, radius: f32, start: f32, span: f32, segments: usize) -> Vec<Vec2> {
+    let full = (span - std::f32::consts::TAU).abs() < f32::EPSILON;
+    let count = if full { segments } else { segments + 1 };
+
+    (0..count)
+        .map(|i| {
+            let a = start + span * i as f32 / segments as f32;
+            center + radius * Vec2::new(a.cos(), a.sin())
+        })
+        .collect()
+}
+
+/// Subtracts convex `b` from convex `a`, returning the remains as convex pieces
+///
+/// Works by cutting `a` along each edge of `b`, everything outside of an edge is a piece,
+/// and whatever is inside continues to the next edge.
+fn subtract_convex(a: &[Vec2], b: &[Vec2]) -> Vec<Vec<Vec2>> {
+    let ccw = signed_area(b) >= 0.0;
+
+    let mut pieces = Vec::new();
+    let mut rest = a.to_vec();
+
+    for i in 0..b.len() {
+        let p = b[i];
+        let e = b[(i + 1) % b.len()] - p;
+        let n = if ccw { Vec2::new(e.y, -e.x) } else { Vec2::new(-e.y, e.x) };
+
+        let outside = clip(&rest, p, n);
+        if outside.len() >= 3 && signed_area(&outside).abs() > f32::EPSILON {
+            pieces.push(outside);
+        }
+
+        rest = clip(&rest, p, -n);
+        if rest.len() < 3 {
+            break;
+        }
+    }
+    pieces
+}
+
+/// Keeps the part of the polygon where `(v - p).dot(n) >= 0`(Sutherland-Hodgman)
+fn clip(poly: &[Vec2], p: Vec2, n: Vec2) -> Vec<Vec2> {
+    let mut res = Vec::with_capacity(poly.len() + 1);
+
+    for i in 0..poly.len() {
+        let a = poly[i];
+        let b = poly[(i + 1) % poly.len()];
+        let da = (a - p).dot(n);
+        let db = (b - p).dot(n);
+
+        if da >= 0.0 {
+            res.push(a);
+        }
+        if (da >= 0.0) != (db >= 0.0) {
+            res.push(a + (b - a) * (da / (da - db)));
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod boolean_tests {
+    use super::*;
+    use crate::shapes::Square;
+
+    #[test]
+    fn subtract_corner() {
+        let s = CollisionShape::Square(Square::size(Vec2::splat(2.0)));
+        let t = Transform2D::new(Vec2::ZERO, 0.0, Vec2::ONE);
+
+        let cut = CollisionShape::Square(Square::size(Vec2::splat(2.0)));
+        let ct = Transform2D::new(Vec2::splat(1.0), 0.0, Vec2::ONE);
+
+        let res = s.subtract(&t, &cut, &ct).unwrap();
+        let area: f32 = res
+            .local_polygons(ROUND_SEGMENTS)
+            .iter()
+            .map(|p| signed_area(p))
+            .sum();
+
+        assert!((area - 3.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn subtract_everything() {
+        let s = CollisionShape::Square(Square::size(Vec2::splat(2.0)));
+        let t = Transform2D::new(Vec2::ZERO, 0.0, Vec2::ONE);
+
+        let cut = CollisionShape::Square(Square::size(Vec2::splat(4.0)));
+
+        assert!(s.subtract(&t, &cut, &t).is_none());
+    }
+}
@@ -1,6 +1,7 @@
-use bevy::math::Mat2;
-use bevy::prelude::*;
+use crate::math::{Mat2, Vec2};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect};
 
 use super::{Aabb, Transform2D};
 
@@ -10,9 +11,10 @@ use super::{Aabb, Transform2D};
     A Capsule can be defined as all points with a given length(radius) from a certain line, 
     capsule here is defined using the length of the middle line(height) and a radius.
 */
-#[derive(Clone, Debug, Serialize, Deserialize, Reflect)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect))]
 pub struct Capsule {
-    /// Offset from the `Transform` translation component
+    /// Offset from the `Transform` translation component, rotates with the body
     pub offset: Vec2,
 
     /// Distance from the center line
@@ -29,11 +31,17 @@ impl Capsule {
             half_height: 0.5 * height,
         }
     }
-    /// Offset from the `Transform` translation component
+    /// Offset from the `Transform` translation component, rotates with the body
     pub fn with_offset(mut self, offset: Vec2) -> Self {
         self.offset = offset;
         self
     }
+    /// Offset given at `rotation`(in radians), ie. where the shape should be(relative to the `Transform` translation)
+    /// while the body is rotated by `rotation` - it is stored unrotated, so it keeps rotating with the body
+    pub fn with_rotated_offset(mut self, offset: Vec2, rotation: f32) -> Self {
+        self.offset = Mat2::from_angle(-rotation) * offset;
+        self
+    }
 
     pub fn aabb(&self, t: &Transform2D) -> Aabb {
         let (a, b) = self.center_line(t);
@@ -58,14 +66,15 @@ impl Capsule {
         let minp = ap.min(bp);
         let maxp = ap.max(bp);
 
-        let rp = p.dot(ray_origin); // p.dot(rc) should be equal p.dot(ro) since we are working on the perp axis to rc
-        let rp = if rp < minp { rp - minp } else if rp > maxp { rp - maxp } else { 0.0 };
+        let ro_p = p.dot(ray_origin); // p.dot(rc) should be equal p.dot(ro) since we are working on the perp axis to rc
+        let rp = if ro_p < minp { ro_p - minp } else if ro_p > maxp { ro_p - maxp } else { 0.0 };
 
         let rc_len = n.dot(ray_cast);
 
         if rp.abs() < f32::EPSILON {
             // practically 0, do ray v line(square-ish)
-            let yp = (rp - ap) / (bp - ap); // Should be in [0,1]
+            // where the ray crosses the center line, in [0,1](the middle when the line is parallel to the ray or a point)
+            let yp = if (bp - ap).abs() > f32::EPSILON { (ro_p - ap) / (bp - ap) } else { 0.5 };
             let yn = n.dot(yp * (b - a) + a) - n.dot(ray_origin);
             let dis = if yn - self.radius < 0.0 { yn + self.radius } else { yn - self.radius };
 
@@ -114,11 +123,13 @@ impl Capsule {
         }
     }
 
+    /// The ends of the center line in world space, the offset is rotated with the body(same as every other shape)
     pub fn center_line(&self, t: &Transform2D) -> (Vec2, Vec2) {
-        let rot = Mat2::from_angle(t.rotation());
+        let rot = t.rot_matrix();
+        let center = t.translation() + rot * self.offset;
 
-        let a = rot * Vec2::new(0.0, self.half_height) + t.translation() + rot * self.offset;
-        let b = rot * Vec2::new(0.0, -self.half_height) + t.translation() + rot * self.offset;
+        let a = center + rot * Vec2::new(0.0, self.half_height);
+        let b = center + rot * Vec2::new(0.0, -self.half_height);
 
         (a, b)
     }
@@ -132,7 +143,7 @@ impl Capsule {
         let vn = n.dot(vertex);
 
         if vn > an.min(bn) && vn < an.max(bn) {
-            Mat2::from_angle(t.rotation()) * Vec2::X
+            t.rot_matrix() * Vec2::X
         }
         else {
             let a = a - vertex;
@@ -1,6 +1,7 @@
-use bevy::math::Mat2;
-use bevy::prelude::*;
+use crate::math::{Mat2, Vec2};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect};
 
 use super::{Aabb, Transform2D};
 
@@ -9,9 +10,10 @@ use super::{Aabb, Transform2D};
 
     A Circle is defined as all points with a certain length(radius) from the center point.
 */
-#[derive(Clone, Debug, Serialize, Deserialize, Reflect)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect))]
 pub struct Circle {
-    /// Offset from the `Transform` translation component
+    /// Offset from the `Transform` translation component, rotates with the body
     pub offset: Vec2,
 
     /// Circle's radius
@@ -24,7 +26,7 @@ impl Circle {
             radius,
         }
     }
-    /// Offset from the `Transform` translation component
+    /// Offset from the `Transform` translation component, rotates with the body
     pub fn with_offset(
         mut self,
         offset: Vec2,
@@ -32,12 +34,18 @@ impl Circle {
         self.offset = offset;
         self
     }
+    /// Offset given at `rotation`(in radians), ie. where the shape should be(relative to the `Transform` translation)
+    /// while the body is rotated by `rotation` - it is stored unrotated, so it keeps rotating with the body
+    pub fn with_rotated_offset(mut self, offset: Vec2, rotation: f32) -> Self {
+        self.offset = Mat2::from_angle(-rotation) * offset;
+        self
+    }
 
     pub fn aabb(
         &self,
         transform: &Transform2D,
     ) -> Aabb {
-        let rot = Mat2::from_angle(transform.rotation());
+        let rot = transform.rot_matrix();
 
         Aabb {
             extents: Vec2::splat(self.radius),
@@ -49,7 +57,7 @@ impl Circle {
         let n = ray_cast.normalize();
         let p = n.perp();
 
-        let center = trans.translation() + Mat2::from_angle(trans.rotation()) * self.offset;
+        let center = trans.translation() + trans.rot_matrix() * self.offset;
 
         let center_n = n.dot(center);
         let center_p = p.dot(center);
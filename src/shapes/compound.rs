@@ -0,0 +1,310 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use crate::math::{Mat2, Vec2};
+
+use super::{Aabb, Capsule, Circle, CollisionShape, Polygon, Square, Transform2D};
+
+/// Offsets and sizes closer than this are treated as equal when merging squares(and rotations when picking the cheaper shape)
+const MERGE_EPSILON: f32 = 0.0001;
+
+/// Why a `CompoundBuilder` couldn't build its shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompoundError {
+    /// Nothing was added
+    Empty,
+    /// The part added by the `add_*` call at this index(starting at 0) has a zero, negative or non finite size,
+    /// or a non finite offset or rotation
+    InvalidPart(usize),
+}
+
+impl std::fmt::Display for CompoundError {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            CompoundError::Empty => write!(f, "compound shape has no parts"),
+            CompoundError::InvalidPart(i) => write!(f, "part {} of the compound shape is invalid", i),
+        }
+    }
+}
+
+impl std::error::Error for CompoundError {}
+
+/**
+    # CompoundBuilder
+
+    Builds a `CollisionShape::Multiple` out of primitives placed around the body, instead of writing the `Vec` by hand:
+
+    ```ignore
+    let shape = CompoundBuilder::new()
+        .add_square(Vec2::new(50.0, 100.0), Vec2::ZERO, 0.0)
+        .add_square(Vec2::splat(50.0), Vec2::new(50.0, 25.0), 0.0)
+        .add_capsule(40.0, 10.0, Vec2::new(0.0, 60.0), FRAC_PI_2)
+        .build();
+    ```
+
+    Offsets and rotations are relative to the body(and keep rotating with it). Squares and capsules have no rotation of their own,
+    so a part rotated by a quarter turn(or half a turn) is stored as a turned square(or capsule),
+    a square rotated by anything else becomes a `Polygon`, and a capsule becomes a rotated rectangle with a circle at each end.
+
+    `build` makes sure there is at least one part and that every part's size is valid(`try_build` returns the reason instead of panicking),
+    a single part is built as is, without a `Multiple` around it.
+    `with_merged_squares` merges unrotated squares which line up side by side into one(tiles of a level, say), so there are less parts to collide with.
+*/
+#[derive(Default)]
+pub struct CompoundBuilder {
+    parts: Vec<CollisionShape>,
+    /// Number of `add_*` calls, for `CompoundError::InvalidPart`
+    added: usize,
+    invalid: Option<usize>,
+    aabb: Option<Aabb>,
+    merge_squares: bool,
+}
+
+impl CompoundBuilder {
+    pub fn new() -> Self {
+        CompoundBuilder::default()
+    }
+    /// Merges unrotated squares which share a whole side(or overlap along it) into a single square when building
+    pub fn with_merged_squares(mut self) -> Self {
+        self.merge_squares = true;
+        self
+    }
+    /// Adds a square from its absolute size(width, height), centered at `offset` and rotated by `rotation`(in radians)
+    pub fn add_square(
+        self,
+        size: Vec2,
+        offset: Vec2,
+        rotation: f32,
+    ) -> Self {
+        let valid = size.is_finite() && size.min_element() > 0.0 && offset.is_finite() && rotation.is_finite();
+        if !valid {
+            return self.invalid();
+        }
+
+        let part = match quarter_turns(rotation) {
+            Some(q) if q % 2 == 0 => CollisionShape::Square(Square::size(size).with_offset(offset)),
+            Some(_) => CollisionShape::Square(Square::size(Vec2::new(size.y, size.x)).with_offset(offset)),
+            None => {
+                let rot = Mat2::from_angle(rotation);
+                let half = size * 0.5;
+                let corners = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)];
+                CollisionShape::Polygon(Polygon::new(corners.iter().map(|c| rot * (*c * half) + offset).collect()))
+            },
+        };
+        self.push(vec![part])
+    }
+    /// Adds a circle centered at `offset`
+    pub fn add_circle(
+        self,
+        radius: f32,
+        offset: Vec2,
+    ) -> Self {
+        if !(radius.is_finite() && radius > 0.0 && offset.is_finite()) {
+            return self.invalid();
+        }
+        self.push(vec![CollisionShape::Circle(Circle::new(radius).with_offset(offset))])
+    }
+    /// Adds a capsule(same `height` and `radius` as `Capsule::new`) centered at `offset`, rotated by `rotation`(in radians)
+    pub fn add_capsule(
+        self,
+        height: f32,
+        radius: f32,
+        offset: Vec2,
+        rotation: f32,
+    ) -> Self {
+        let valid = height.is_finite() && height >= 0.0 && radius.is_finite() && radius > 0.0 && offset.is_finite() && rotation.is_finite();
+        if !valid {
+            return self.invalid();
+        }
+
+        // only a vertical capsule can be a `Capsule`, one without a middle is just a circle however it is turned
+        let vertical = height == 0.0 || quarter_turns(rotation).map_or(false, |q| q % 2 == 0);
+        let parts = if vertical {
+            vec![CollisionShape::Capsule(Capsule::new(height, radius).with_offset(offset))]
+        }
+        else {
+            let up = Mat2::from_angle(rotation) * Vec2::Y * (height * 0.5);
+            let side = Vec2::new(up.y, -up.x).normalize() * radius;
+            vec![
+                CollisionShape::Circle(Circle::new(radius).with_offset(offset + up)),
+                CollisionShape::Circle(Circle::new(radius).with_offset(offset - up)),
+                CollisionShape::Polygon(Polygon::new(vec![
+                    offset - up - side,
+                    offset - up + side,
+                    offset + up + side,
+                    offset + up - side,
+                ])),
+            ]
+        };
+        self.push(parts)
+    }
+    /// Adds any shape as is(the parts of a `Multiple` are added one by one)
+    pub fn add_shape(
+        self,
+        shape: CollisionShape,
+    ) -> Self {
+        match shape {
+            CollisionShape::Multiple(v) if v.is_empty() => self.invalid(),
+            CollisionShape::Multiple(v) => self.push(v),
+            s => self.push(vec![s]),
+        }
+    }
+    /// Number of parts added so far(a capsule which isn't vertical counts as 3), before squares are merged
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+    /// The aabb of all the parts around the body's center(unrotated), kept up to date as parts are added,
+    /// `None` when nothing was added
+    pub fn aabb(&self) -> Option<Aabb> {
+        self.aabb
+    }
+    /// Builds the shape, or the reason it can't be built
+    pub fn try_build(self) -> Result<CollisionShape, CompoundError> {
+        if let Some(i) = self.invalid {
+            return Err(CompoundError::InvalidPart(i));
+        }
+        let mut parts = self.parts;
+        if parts.is_empty() {
+            return Err(CompoundError::Empty);
+        }
+        if self.merge_squares {
+            merge_squares(&mut parts);
+        }
+
+        if parts.len() == 1 {
+            Ok(parts.remove(0))
+        }
+        else {
+            Ok(CollisionShape::Multiple(parts))
+        }
+    }
+    /// Builds the shape
+    ///
+    /// Panics if nothing was added or a part was invalid
+    pub fn build(self) -> CollisionShape {
+        self.try_build().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn invalid(mut self) -> Self {
+        self.invalid.get_or_insert(self.added);
+        self.added += 1;
+        self
+    }
+    fn push(
+        mut self,
+        parts: Vec<CollisionShape>,
+    ) -> Self {
+        let t = Transform2D::default();
+        for p in parts {
+            let (min, max) = p.aabb(&t).min_max();
+            self.aabb = Some(match self.aabb {
+                Some(a) => {
+                    let (amin, amax) = a.min_max();
+                    Aabb::from_min_max(amin.min(min), amax.max(max))
+                },
+                None => Aabb::from_min_max(min, max),
+            });
+            self.parts.push(p);
+        }
+        self.added += 1;
+        self
+    }
+}
+
+/// How many quarter turns `rotation` is(mod 4), `None` when it isn't a whole number of them
+fn quarter_turns(rotation: f32) -> Option<u32> {
+    let turns = rotation.rem_euclid(2.0 * PI) / FRAC_PI_2;
+    let whole = turns.round();
+    if (turns - whole).abs() * FRAC_PI_2 < MERGE_EPSILON {
+        Some(whole as u32 % 4)
+    }
+    else {
+        None
+    }
+}
+
+/// Merges squares with the same span on one axis whose spans on the other axis touch, until none are left
+fn merge_squares(parts: &mut Vec<CollisionShape>) {
+    let close = |a: f32, b: f32| (a - b).abs() < MERGE_EPSILON;
+    let bounds = |s: &Square| (s.offset - s.extents, s.offset + s.extents);
+
+    'merging: loop {
+        for i in 0..parts.len() {
+            for j in (i + 1)..parts.len() {
+                let (a, b) = match (&parts[i], &parts[j]) {
+                    (CollisionShape::Square(a), CollisionShape::Square(b)) => (bounds(a), bounds(b)),
+                    _ => continue,
+                };
+                let ((amin, amax), (bmin, bmax)) = (a, b);
+                let side_by_side = close(amin.y, bmin.y) && close(amax.y, bmax.y) && amin.x <= bmax.x + MERGE_EPSILON && bmin.x <= amax.x + MERGE_EPSILON;
+                let stacked = close(amin.x, bmin.x) && close(amax.x, bmax.x) && amin.y <= bmax.y + MERGE_EPSILON && bmin.y <= amax.y + MERGE_EPSILON;
+                if side_by_side || stacked {
+                    let (min, max) = (amin.min(bmin), amax.max(bmax));
+                    parts[i] = CollisionShape::Square(Square::size(max - min).with_offset((min + max) * 0.5));
+                    parts.remove(j);
+                    continue 'merging;
+                }
+            }
+        }
+        break;
+    }
+}
+
+#[cfg(test)]
+mod compound_tests {
+    use super::*;
+
+    #[test]
+    fn builds_and_validates() {
+        let t = Transform2D::default();
+
+        // a row of tiles is a single square
+        let row = (0..4)
+            .fold(CompoundBuilder::new().with_merged_squares(), |b, i| b.add_square(Vec2::splat(2.0), Vec2::new(i as f32 * 2.0, 0.0), 0.0));
+        assert_eq!(row.len(), 4);
+        let aabb = row.aabb().unwrap();
+        assert_eq!(aabb.min_max(), (Vec2::new(-1.0, -1.0), Vec2::new(7.0, 1.0)));
+        match row.build() {
+            CollisionShape::Square(s) => assert_eq!((s.offset, s.extents), (Vec2::new(3.0, 0.0), Vec2::new(4.0, 1.0))),
+            _ => panic!("expected a single square"),
+        }
+
+        // a quarter turned square is still a square, a little less than that isn't
+        let shape = CompoundBuilder::new()
+            .add_square(Vec2::new(4.0, 2.0), Vec2::ZERO, FRAC_PI_2)
+            .add_square(Vec2::new(4.0, 2.0), Vec2::new(10.0, 0.0), 0.5)
+            .add_capsule(2.0, 1.0, Vec2::new(0.0, 10.0), FRAC_PI_2)
+            .build();
+        match &shape {
+            CollisionShape::Multiple(v) => {
+                assert!(matches!(&v[0], CollisionShape::Square(s) if s.extents == Vec2::new(1.0, 2.0)));
+                assert!(matches!(&v[1], CollisionShape::Polygon(_)));
+                // the capsule lies on its side
+                assert_eq!(v.len(), 5);
+            },
+            _ => panic!("expected a Multiple"),
+        }
+        assert!(shape.contains_point(&t, Vec2::new(1.9, 10.0)));
+        assert!(!shape.contains_point(&t, Vec2::new(0.0, 11.5)));
+        assert!(shape.contains_point(&t, Vec2::new(10.0, 0.0) + Mat2::from_angle(0.5) * Vec2::new(1.9, 0.9)));
+
+        // a turned capsule without a middle is a circle, not a polygon with no width
+        let dot = CompoundBuilder::new().add_capsule(0.0, 1.0, Vec2::new(5.0, 0.0), 0.3).build();
+        assert!(matches!(&dot, CollisionShape::Capsule(c) if c.half_height == 0.0));
+        let (min, max) = dot.aabb(&t).min_max();
+        assert!(min.is_finite() && max.is_finite());
+        assert!(dot.contains_point(&t, Vec2::new(5.9, 0.0)));
+
+        assert!(matches!(CompoundBuilder::new().try_build(), Err(CompoundError::Empty)));
+        let bad = CompoundBuilder::new()
+            .add_circle(1.0, Vec2::ZERO)
+            .add_circle(-1.0, Vec2::ZERO)
+            .add_square(Vec2::ZERO, Vec2::ZERO, 0.0);
+        assert!(matches!(bad.try_build(), Err(CompoundError::InvalidPart(1))));
+    }
+}
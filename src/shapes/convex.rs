@@ -0,0 +1,135 @@
+use crate::math::{Vec2, Vec4};
+
+use super::{Aabb, Polygon, Transform2D, SAT};
+
+/**
+    # ConvexShape
+
+    A custom convex shape, for `CollisionShape::Convex`.
+
+    On top of `SAT`(normals, projections, the closest vertex, rays and the aabb), which is all the collision solver needs,
+    a custom shape tells whether it contains a point(sensors, picking) and, when it can, its exact outline(meshes, area and inertia).
+    Shapes which are just a polygon under the hood can implement `PolygonBacked` instead, and get all of it for free.
+
+    ```ignore
+    struct Hexagon(Polygon);
+
+    impl PolygonBacked for Hexagon {
+        fn polygon(&self) -> &Polygon {
+            &self.0
+        }
+    }
+
+    let shape = CollisionShape::convex(Hexagon(Polygon::new(verts)));
+    ```
+*/
+pub trait ConvexShape: SAT + Send + Sync {
+    /// Checks whether a point(in world space) is inside the shape
+    fn contains_point(&self, trans: &Transform2D, point: Vec2) -> bool {
+        // a point is inside a convex shape if it is between the shape's projections on all of its normals
+        self.get_normals(trans).all(|n| {
+            let (min, max) = self.project(trans, n);
+            let p = point.dot(n);
+            min <= p && p <= max
+        })
+    }
+
+    /// The outline of the shape in local space(counter-clockwise),
+    /// `None` when it isn't known, in which case it is found by sampling the shape's closest vertices
+    fn local_vertices(&self) -> Option<Vec<Vec2>> {
+        None
+    }
+}
+
+// forwards to the shape, so `CollisionShape::sat` can hand out `&dyn SAT` of a boxed `ConvexShape`
+impl SAT for Box<dyn ConvexShape> {
+    fn aabb(&self, trans: &Transform2D) -> Aabb {
+        self.as_ref().aabb(trans)
+    }
+    fn get_normals(&self, trans: &Transform2D) -> Box<dyn Iterator<Item = Vec2> + '_> {
+        self.as_ref().get_normals(trans)
+    }
+    fn project(&self, trans: &Transform2D, normal: Vec2) -> (f32, f32) {
+        self.as_ref().project(trans, normal)
+    }
+    fn project4(&self, trans: &Transform2D, normals: [Vec2; 4]) -> (Vec4, Vec4) {
+        self.as_ref().project4(trans, normals)
+    }
+    fn get_closest_vertex(&self, trans: &Transform2D, vertex: Vec2) -> Vec2 {
+        self.as_ref().get_closest_vertex(trans, vertex)
+    }
+    fn ray(&self, trans: &Transform2D, ray_origin: Vec2, ray_cast: Vec2) -> Option<f32> {
+        self.as_ref().ray(trans, ray_origin, ray_cast)
+    }
+}
+
+/// A custom shape which is a `Polygon` under the hood, implementing it implements `SAT` and `ConvexShape` by forwarding to the polygon
+pub trait PolygonBacked {
+    fn polygon(&self) -> &Polygon;
+}
+
+impl<T: PolygonBacked> SAT for T {
+    fn aabb(&self, trans: &Transform2D) -> Aabb {
+        self.polygon().aabb(trans)
+    }
+    fn get_normals(&self, trans: &Transform2D) -> Box<dyn Iterator<Item = Vec2> + '_> {
+        self.polygon().get_normals(trans)
+    }
+    fn project(&self, trans: &Transform2D, normal: Vec2) -> (f32, f32) {
+        self.polygon().project(trans, normal)
+    }
+    fn project4(&self, trans: &Transform2D, normals: [Vec2; 4]) -> (Vec4, Vec4) {
+        self.polygon().project4(trans, normals)
+    }
+    fn get_closest_vertex(&self, trans: &Transform2D, vertex: Vec2) -> Vec2 {
+        self.polygon().get_closest_vertex(trans, vertex)
+    }
+    fn ray(&self, trans: &Transform2D, ray_origin: Vec2, ray_cast: Vec2) -> Option<f32> {
+        self.polygon().ray(trans, ray_origin, ray_cast)
+    }
+}
+
+impl<T: PolygonBacked + Send + Sync> ConvexShape for T {
+    fn local_vertices(&self) -> Option<Vec<Vec2>> {
+        Some(self.polygon().verts().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod convex_tests {
+    use super::*;
+    use crate::shapes::{collide, CollisionShape};
+
+    struct Hexagon(Polygon);
+
+    impl PolygonBacked for Hexagon {
+        fn polygon(&self) -> &Polygon {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn polygon_backed_same_as_polygon() {
+        let verts = (0..6)
+            .map(|i| Vec2::from_angle(i as f32 * std::f32::consts::FRAC_PI_3) * 2.0)
+            .collect::<Vec<_>>();
+        let poly = CollisionShape::Polygon(Polygon::new(verts.clone()));
+        let hex = CollisionShape::convex(Hexagon(Polygon::new(verts)));
+
+        let t = Transform2D::new(Vec2::new(1.0, -1.0), 0.3, Vec2::ONE);
+        let other = CollisionShape::Square(crate::shapes::Square::default());
+        for p in [Vec2::new(3.5, -1.0), Vec2::new(1.0, 1.5), Vec2::new(6.0, 0.0)] {
+            let to = Transform2D::new(p, 0.0, Vec2::ONE);
+            assert_eq!(collide(&hex, &t, &other, &to), collide(&poly, &t, &other, &to), "square at {}", p);
+        }
+
+        assert_eq!(
+            hex.sat().unwrap().ray(&t, Vec2::new(-5.0, -1.0), Vec2::new(10.0, 0.0)),
+            poly.sat().unwrap().ray(&t, Vec2::new(-5.0, -1.0), Vec2::new(10.0, 0.0)),
+        );
+        assert!(hex.contains_point(&t, Vec2::new(2.5, -1.0)));
+        assert!(!hex.contains_point(&t, Vec2::new(3.5, -1.0)));
+        assert!((hex.area() - poly.area()).abs() < 0.001);
+        assert_eq!(hex.world_vertices(&t).count(), 6);
+    }
+}
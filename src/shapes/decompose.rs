@@ -0,0 +1,179 @@
+use crate::math::Vec2;
+
+use super::{polygon::signed_area, CollisionShape, Polygon};
+
+/// Decomposes a simple(non self intersecting) polygon outline into convex polygons
+///
+/// The outline is first triangulated by ear clipping,
+/// and then neighbouring triangles are merged as long as the result stays convex(Hertel-Mehlhorn),
+/// so the result is usually much smaller than a plain triangulation.
+///
+/// The points can be given in either clockwise or counter-clockwise order, and should not repeat the first point at the end.
+pub fn decompose(points: Vec<Vec2>) -> Vec<Polygon> {
+    let mut points = points;
+    // drop a closing point if one was given
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    if signed_area(&points) < 0.0 {
+        points.reverse();
+    }
+
+    let mut polys = triangulate(&points);
+
+    // Hertel-Mehlhorn - try to remove each diagonal(shared edge) while the merged polygon stays convex
+    let mut merged = true;
+    while merged {
+        merged = false;
+
+        'search: for i in 0..polys.len() {
+            for k in (i + 1)..polys.len() {
+                if let Some(m) = merge(&polys[i], &polys[k], &points) {
+                    polys[i] = m;
+                    polys.swap_remove(k);
+                    merged = true;
+                    break 'search;
+                }
+            }
+        }
+    }
+
+    polys
+        .into_iter()
+        .map(|p| Polygon::new(p.into_iter().map(|i| points[i]).collect()))
+        .collect()
+}
+
+impl CollisionShape {
+    /// Creates a `CollisionShape::Multiple` from a concave outline by decomposing it into convex polygons
+    ///
+    /// See `decompose` for more info
+    pub fn from_concave(points: Vec<Vec2>) -> CollisionShape {
+        CollisionShape::Multiple(
+            decompose(points)
+                .into_iter()
+                .map(CollisionShape::Polygon)
+                .collect()
+        )
+    }
+}
+
+/// Ear clipping triangulation, expects `points` to be in counter-clockwise order
+///
+/// Returns the triangles as indices into `points`
+fn triangulate(points: &[Vec2]) -> Vec<Vec<usize>> {
+    let mut idx: Vec<usize> = (0..points.len()).collect();
+    let mut tris = Vec::with_capacity(points.len() - 2);
+
+    while idx.len() > 3 {
+        let n = idx.len();
+        let mut ear = None;
+
+        for i in 0..n {
+            let (a, b, c) = (idx[(i + n - 1) % n], idx[i], idx[(i + 1) % n]);
+            let (pa, pb, pc) = (points[a], points[b], points[c]);
+
+            // reflex(or degenerate) vertices cannot be ears
+            if (pb - pa).perp_dot(pc - pb) <= f32::EPSILON {
+                continue;
+            }
+            // no other vertex may lay inside the ear
+            let blocked = idx
+                .iter()
+                .filter(|&&o| o != a && o != b && o != c)
+                .any(|&o| in_triangle(points[o], pa, pb, pc));
+
+            if !blocked {
+                ear = Some(i);
+                break;
+            }
+        }
+
+        // A degenerate outline(collinear points or self intersections) might not have a valid ear,
+        // just clip the first vertex then so we always terminate
+        let i = ear.unwrap_or(0);
+        tris.push(vec![idx[(i + n - 1) % n], idx[i], idx[(i + 1) % n]]);
+        idx.remove(i);
+    }
+    tris.push(idx);
+
+    tris
+}
+
+/// Merges 2 counter-clockwise polygons(as indices) if they share an edge and the result is convex
+fn merge(a: &[usize], b: &[usize], points: &[Vec2]) -> Option<Vec<usize>> {
+    for i in 0..a.len() {
+        let (u, v) = (a[i], a[(i + 1) % a.len()]);
+
+        // b must contain the same edge in the opposite direction
+        let j = match (0..b.len()).find(|&j| b[j] == v && b[(j + 1) % b.len()] == u) {
+            Some(j) => j,
+            None => continue,
+        };
+
+        // walk a from v around to u, then b from u around to v(without repeating u and v)
+        let mut m: Vec<usize> = (1..=a.len()).map(|k| a[(i + k) % a.len()]).collect();
+        m.extend((2..b.len()).map(|k| b[(j + k) % b.len()]));
+
+        return if is_convex(&m, points) { Some(m) } else { None };
+    }
+    None
+}
+
+fn is_convex(poly: &[usize], points: &[Vec2]) -> bool {
+    let n = poly.len();
+    (0..n).all(|i| {
+        let a = points[poly[i]];
+        let b = points[poly[(i + 1) % n]];
+        let c = points[poly[(i + 2) % n]];
+
+        (b - a).perp_dot(c - b) >= -f32::EPSILON
+    })
+}
+
+fn in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let d1 = (b - a).perp_dot(p - a);
+    let d2 = (c - b).perp_dot(p - b);
+    let d3 = (a - c).perp_dot(p - c);
+
+    d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0
+}
+
+#[cfg(test)]
+mod decompose_tests {
+    use super::*;
+
+    #[test]
+    fn convex_stays_whole() {
+        let square = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+
+        assert_eq!(decompose(square).len(), 1);
+    }
+
+    #[test]
+    fn l_shape() {
+        // clockwise L shape, should break into 2 convex pieces with the same total area
+        let l = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 0.0),
+        ];
+
+        let polys = decompose(l);
+        assert_eq!(polys.len(), 2);
+
+        let area: f32 = polys.iter().map(|p| signed_area(p.verts())).sum();
+        assert!((area - 3.0).abs() < 0.0001);
+    }
+}
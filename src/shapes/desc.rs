@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::math::Vec2;
+
+use super::{decompose, Capsule, Circle, CollisionShape, Square};
+
+/// Serializable description of a `CollisionShape`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ShapeDesc {
+    /// Square from its absolute size(width, height)
+    Square(Vec2),
+    /// Circle from its radius
+    Circle(f32),
+    /// Capsule from its height and radius(same as `Capsule::new`)
+    Capsule(f32, f32),
+    /// Outline of a polygon, which can also be concave(it will be decomposed)
+    Polygon(Vec<Vec2>),
+}
+impl ShapeDesc {
+    /// Builds the `CollisionShape` described
+    ///
+    /// `None` for a `Polygon` which decomposes to nothing(fewer than 3 points, or no area),
+    /// it would be an empty `Multiple` which panics once it collides
+    pub fn to_shape(&self) -> Option<CollisionShape> {
+        let shape = match self {
+            ShapeDesc::Square(size) => CollisionShape::Square(Square::size(*size)),
+            ShapeDesc::Circle(r) => CollisionShape::Circle(Circle::new(*r)),
+            ShapeDesc::Capsule(h, r) => CollisionShape::Capsule(Capsule::new(*h, *r)),
+            ShapeDesc::Polygon(points) => {
+                let mut polys = decompose(points.clone());
+                match polys.len() {
+                    0 => return None,
+                    1 => CollisionShape::Polygon(polys.remove(0)),
+                    _ => CollisionShape::Multiple(polys.into_iter().map(CollisionShape::Polygon).collect()),
+                }
+            }
+        };
+        Some(shape)
+    }
+}
@@ -1,17 +1,34 @@
 use crate::physics_components::Transform2D;
-use bevy::{math::Mat2, prelude::*};
+use crate::math::{Vec2, Vec4};
+#[cfg(feature = "ecs")]
+use bevy::prelude::Component;
 
 mod aabb;
 mod circle;
 mod square;
 mod capsule;
 mod triangle;
+mod polygon;
+mod decompose;
+mod boolean;
+mod toi;
+mod simd;
+mod desc;
+mod convex;
+mod compound;
 
 pub use aabb::*;
 pub use circle::*;
 pub use square::*;
 pub use capsule::*;
 pub use triangle::*;
+pub use polygon::*;
+pub use decompose::*;
+pub use boolean::*;
+pub use toi::*;
+pub use desc::*;
+pub use convex::*;
+pub use compound::*;
 
 pub trait SAT {
     /// Gets the Axis Aligned Bounding Box of the shape
@@ -38,6 +55,15 @@ pub trait SAT {
     /// (min, max)
     fn project(&self, trans: &Transform2D, normal: Vec2) -> (f32,f32);
 
+    /// Gets the projections of the shape on 4 normals at once, each lane holds the projection on the normal in the same position
+    ///
+    /// (mins, maxs)
+    fn project4(&self, trans: &Transform2D, normals: [Vec2; 4]) -> (Vec4, Vec4) {
+        let p = normals.map(|n| self.project(trans, n));
+
+        (Vec4::new(p[0].0, p[1].0, p[2].0, p[3].0), Vec4::new(p[0].1, p[1].1, p[2].1, p[3].1))
+    }
+
     /// Gets the closest vertex to the given point, used for SAT vs Special shapes(Circle and Capsule)
     fn get_closest_vertex(&self, trans: &Transform2D, vertex: Vec2) -> Vec2;
 
@@ -90,6 +116,13 @@ pub fn collide(a: &CollisionShape, trans_a: &Transform2D, b: &CollisionShape, tr
         }
     }
 
+    // unrotated squares are just aabbs, which are much cheaper than the generic SAT(tile heavy games are full of those)
+    if let (CollisionShape::Square(sa), CollisionShape::Square(sb)) = (a, b) {
+        if trans_a.rotation() == 0.0 && trans_b.rotation() == 0.0 {
+            return aabb_normal(sa, trans_a, sb, trans_b);
+        }
+    }
+
     let sat_a = a.sat();
     let sat_b = b.sat();
 
@@ -101,32 +134,95 @@ pub fn collide(a: &CollisionShape, trans_a: &Transform2D, b: &CollisionShape, tr
     }
 }
 
+/// Pushes a circle(of `radius` around `point`) out of the given shapes, returns the new center
+///
+/// Handy for particles and other point like objects(ropes, soft bodies) colliding with static geometry.
+pub fn push_out_circle<'a>(
+    point: Vec2,
+    radius: f32,
+    shapes: impl IntoIterator<Item = (&'a CollisionShape, &'a Transform2D)>,
+) -> Vec2 {
+    let circle = CollisionShape::Circle(Circle::new(radius));
+    let mut point = point;
+
+    for (shape, t) in shapes {
+        let pt = Transform2D::new(point, 0.0, Vec2::ONE);
+        if !shape.aabb(t).collides(&circle.aabb(&pt)) {
+            continue;
+        }
+        if let Some(mtv) = collide(&circle, &pt, shape, t) {
+            point += mtv;
+        }
+    }
+    point
+}
+
+/// `sat_normal` for 2 unrotated squares, gives the exact same result without projecting any vertices
+fn aabb_normal(a: &Square, ta: &Transform2D, b: &Square, tb: &Transform2D) -> Option<Vec2> {
+    // in the same order as `Square::project`, so the rounding is the same as well
+    let min_max = |s: &Square, t: &Transform2D| ((-s.extents + s.offset) + t.translation(), (s.extents + s.offset) + t.translation());
+    let (mina, maxa) = min_max(a, ta);
+    let (minb, maxb) = min_max(b, tb);
+
+    if !(mina.x < maxb.x && minb.x < maxa.x && mina.y < maxb.y && minb.y < maxa.y) {
+        return None;
+    }
+    // same as on each axis of the SAT
+    let axis = |p1: f32, p2: f32| if p1.abs() < p2.abs() { p1 } else { p2 };
+    let px = axis(maxb.x - mina.x, minb.x - maxa.x);
+    let py = axis(maxb.y - mina.y, minb.y - maxa.y);
+
+    if py.abs() < px.abs() {
+        Some(Vec2::new(0.0, py))
+    }
+    else {
+        Some(Vec2::new(px, 0.0))
+    }
+}
+
 fn sat_normal(a: &dyn SAT, ta: &Transform2D, b: &dyn SAT, tb: &Transform2D) -> Option<Vec2> {
-    let na = a.get_normals(ta);
-    let nb = b.get_normals(tb);
+    let mut normals = a.get_normals(ta).chain(b.get_normals(tb));
 
     let mut minimal_dis = f32::INFINITY;
     let mut minimal_n = Vec2::ZERO;
 
-    for n in na.chain(nb) {
-        let (mina, maxa) = a.project(ta, n);
-        let (minb, maxb) = b.project(tb, n);
+    // the normals are projected 4 at a time
+    loop {
+        let mut batch = [Vec2::ZERO; 4];
+        let mut count = 0;
+        for n in normals.by_ref().take(4) {
+            batch[count] = n;
+            count += 1;
+        }
+        if count == 0 {
+            break;
+        }
+        // pad with the first normal, its result is just ignored
+        let first = batch[0];
+        batch[count..].fill(first);
 
-        if mina < maxb && minb < maxa {
-            // collision on this axis - lets get the mtv
-            let p1 = maxb - mina;
-            let p2 = minb - maxa;
+        let (mina, maxa) = a.project4(ta, batch);
+        let (minb, maxb) = b.project4(tb, batch);
 
-            let p = if p1.abs() < p2.abs() { p1 } else { p2 };
+        for (i, n) in batch.into_iter().enumerate().take(count) {
+            let (mina, maxa, minb, maxb) = (mina[i], maxa[i], minb[i], maxb[i]);
 
-            if p.abs() < minimal_dis.abs() {
-                minimal_dis = p;
-                minimal_n = n;
+            if mina < maxb && minb < maxa {
+                // collision on this axis - lets get the mtv
+                let p1 = maxb - mina;
+                let p2 = minb - maxa;
+
+                let p = if p1.abs() < p2.abs() { p1 } else { p2 };
+
+                if p.abs() < minimal_dis.abs() {
+                    minimal_dis = p;
+                    minimal_n = n;
+                }
+            }
+            else {
+                // if we find a non colliding axis, we know they dont collide :D
+                return None;
             }
-        }
-        else {
-            // if we find a non colliding axis, we know they dont collide :D
-            return None;
         }
     }
     Some(minimal_dis * minimal_n)
@@ -134,7 +230,7 @@ fn sat_normal(a: &dyn SAT, ta: &Transform2D, b: &dyn SAT, tb: &Transform2D) -> O
 
 fn sat_special(a: &dyn SAT, ta: &Transform2D, b: &CollisionShape, tb: &Transform2D) -> Option<Vec2> {
     let na = a.get_normals(ta);
-    let b_rot = Mat2::from_angle(tb.rotation());
+    let b_rot = tb.rot_matrix();
     let nb = match b {
         CollisionShape::Circle(c) => {
             let offset = b_rot * c.offset;
@@ -191,8 +287,8 @@ fn collide_special(a: &CollisionShape, ta: &Transform2D, b: &CollisionShape, tb:
     
     match (a, b) {
         (Circle(a), Circle(b)) => {
-            let ac = ta.translation() + Mat2::from_angle(ta.rotation()) * a.offset;
-            let bc = tb.translation() + Mat2::from_angle(tb.rotation()) * b.offset;
+            let ac = ta.translation() + ta.rot_matrix() * a.offset;
+            let bc = tb.translation() + tb.rot_matrix() * b.offset;
             let d = ac - bc;
             let d_len = d.length();
 
@@ -207,8 +303,8 @@ fn collide_special(a: &CollisionShape, ta: &Transform2D, b: &CollisionShape, tb:
         (Circle(a), Capsule(b)) => collide_circle_capsule(a, ta, b, tb),
         (Capsule(a), Circle(b)) => collide_circle_capsule(b, tb, a, ta).map(|v| -v),
         (Capsule(a), Capsule(b)) => {
-            let a_rot = Mat2::from_angle(ta.rotation());
-            let b_rot = Mat2::from_angle(tb.rotation());
+            let a_rot = ta.rot_matrix();
+            let b_rot = tb.rot_matrix();
 
             // When you make 2 capsules obey SAT rules :D(they are still not fully SAT tho)
 
@@ -217,8 +313,7 @@ fn collide_special(a: &CollisionShape, ta: &Transform2D, b: &CollisionShape, tb:
 
             // get the closer vertex of b(relative to a)
             let n3 = {
-                let b1 = b_rot * Vec2::new(0.0,  b.half_height) + tb.translation() + b_rot * b.offset;
-                let b2 = b_rot * Vec2::new(0.0, -b.half_height) + tb.translation() + b_rot * b.offset;
+                let (b1, b2) = b.center_line(tb);
 
                 let v = ta.translation() + a_rot * a.offset;
 
@@ -264,12 +359,12 @@ fn collide_special(a: &CollisionShape, ta: &Transform2D, b: &CollisionShape, tb:
 }
 
 fn collide_circle_capsule(a: &Circle, ta: &Transform2D, b: &Capsule, tb: &Transform2D) -> Option<Vec2> {
-    let brot = Mat2::from_angle(tb.rotation());
+    let brot = tb.rot_matrix();
     
     // get the distance of the circle's center to the capsule's center line
     let (ba, bb) = b.center_line(tb);
 
-    let acenter = ta.translation() + Mat2::from_angle(ta.rotation()) * a.offset;
+    let acenter = ta.translation() + ta.rot_matrix() * a.offset;
 
     let n = brot * Vec2::X;
     let p = brot * Vec2::Y;
@@ -305,22 +400,24 @@ fn collide_circle_capsule(a: &Circle, ta: &Transform2D, b: &Capsule, tb: &Transf
 
     If you want to use a custom shape,
     you can do so by implementing the `SAT` trait for your shape(check the `convex` example),
-    and box it.
+    and box it, or use `CollisionShape::Polygon` for any convex polygon.
 
     Alternatively, you can build it from a vector of `CollisionShape`,
     using `CollisionShape::Multiple`(see `showcase` example)
     
     Do note that this library is using the Seperate Axis Theorem, which doesnt work for concave shapes.
-    (unless of course borken down into multiple convex shapes using `CollisionShape::Multiple`)
+    (unless of course borken down into multiple convex shapes using `CollisionShape::Multiple`,
+    which `CollisionShape::from_concave` can do for you)
 */
-#[derive(Component)]
+#[cfg_attr(feature = "ecs", derive(Component))]
 pub enum CollisionShape {
     Square(Square),
     Triangle(Triangle),
     Circle(Circle),
     Capsule(Capsule),
+    Polygon(Polygon),
     Multiple(Vec<CollisionShape>),
-    Convex(Box<dyn SAT + Send + Sync>),
+    Convex(Box<dyn ConvexShape>),
 }
 impl CollisionShape {
     pub fn sat(&self) -> Option<&dyn SAT> {
@@ -329,10 +426,15 @@ impl CollisionShape {
             CollisionShape::Triangle(t) => Some(t),
             CollisionShape::Circle(_) => None,
             CollisionShape::Capsule(_) => None,
+            CollisionShape::Polygon(p) => Some(p),
             CollisionShape::Multiple(_) => None,
-            CollisionShape::Convex(s) => Some(s.as_ref())
+            CollisionShape::Convex(s) => Some(s)
         }
     }
+    /// Boxes a custom shape into a `CollisionShape::Convex`
+    pub fn convex(shape: impl ConvexShape + 'static) -> CollisionShape {
+        CollisionShape::Convex(Box::new(shape))
+    }
 
     pub fn aabb(&self, t: &Transform2D) -> Aabb {
         if let Some(sat) = self.sat() {
@@ -361,6 +463,372 @@ impl CollisionShape {
         }
     }
 
+    /// Checks whether a point(in world space) is inside the shape
+    pub fn contains_point(&self, trans: &Transform2D, point: Vec2) -> bool {
+        if let CollisionShape::Convex(s) = self {
+            s.contains_point(trans, point)
+        }
+        else if let Some(sat) = self.sat() {
+            // a point is inside a convex shape if it is between the shape's projections on all of its normals
+            sat.get_normals(trans).all(|n| {
+                let (min, max) = sat.project(trans, n);
+                let p = point.dot(n);
+                min <= p && p <= max
+            })
+        }
+        else {
+            match self {
+                CollisionShape::Circle(c) => {
+                    let center = trans.translation() + trans.rot_matrix() * c.offset;
+                    (point - center).length_squared() <= c.radius * c.radius
+                },
+                CollisionShape::Capsule(c) => {
+                    let (a, b) = c.center_line(trans);
+                    let ab = b - a;
+                    let t = ((point - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+
+                    (point - (a + ab * t)).length_squared() <= c.radius * c.radius
+                },
+                CollisionShape::Multiple(v) => v.iter().any(|s| s.contains_point(trans, point)),
+                _ => panic!("Something is missing, please report on github(with the shape used)"),
+            }
+        }
+    }
+
+    /// Indices of the parts of a `Multiple` shape which overlap `other`(always empty for the other shapes)
+    pub fn overlapping_parts(&self, trans: &Transform2D, other: &CollisionShape, other_trans: &Transform2D) -> Vec<usize> {
+        match self {
+            CollisionShape::Multiple(v) => v
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| collide(s, trans, other, other_trans).is_some())
+                .map(|(i, _)| i)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Index of the part of a `Multiple` shape which penetrates `other` the deepest,
+    /// `None` for the other shapes(or if no part overlaps it)
+    pub fn deepest_part(&self, trans: &Transform2D, other: &CollisionShape, other_trans: &Transform2D) -> Option<usize> {
+        match self {
+            CollisionShape::Multiple(v) => v
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| collide(s, trans, other, other_trans).map(|p| (i, p.length_squared())))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i),
+            _ => None,
+        }
+    }
+
+    /// Segments used by `world_vertices` to approximate round shapes
+    pub const DEFAULT_SEGMENTS: usize = 16;
+
+    /// Vertices of the shape's outline in world space(counter-clockwise), for debug rendering, meshes and outlines
+    ///
+    /// Circles and capsules are approximated with `DEFAULT_SEGMENTS` segments, see `world_vertices_with`
+    pub fn world_vertices(&self, trans: &Transform2D) -> impl Iterator<Item = Vec2> {
+        self.world_vertices_with(trans, Self::DEFAULT_SEGMENTS)
+    }
+
+    /// Same as `world_vertices`, but with the number of segments used to approximate circles and capsules
+    ///
+    /// `Multiple` gives the outlines of its shapes one after the other,
+    /// and `Convex` shapes without `ConvexShape::local_vertices` are found by sampling `segments` directions(so their small edges might be missed)
+    pub fn world_vertices_with(&self, trans: &Transform2D, segments: usize) -> impl Iterator<Item = Vec2> {
+        let rot = trans.rot_matrix();
+        let pos = trans.translation();
+        let segments = segments.max(3);
+        // direction at angle `a`(relative to the shape)
+        let dir = |a: f32| rot * Vec2::new(a.cos(), a.sin());
+
+        let verts: Vec<Vec2> = match self {
+            CollisionShape::Square(s) => [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)]
+                .iter()
+                .map(|v| pos + rot * (s.offset + *v * s.extents))
+                .collect(),
+            CollisionShape::Triangle(t) => {
+                let mut v = t.verts();
+                if polygon::signed_area(&v) < 0.0 {
+                    v.reverse();
+                }
+                v.iter().map(|v| pos + rot * *v).collect()
+            },
+            CollisionShape::Polygon(p) => p.verts().iter().map(|v| pos + rot * *v).collect(),
+            CollisionShape::Circle(c) => {
+                let center = pos + rot * c.offset;
+                (0..segments)
+                    .map(|i| center + dir(std::f32::consts::TAU * i as f32 / segments as f32) * c.radius)
+                    .collect()
+            },
+            CollisionShape::Capsule(c) => {
+                // top half circle(around the +Y end of the center line) then the bottom half
+                let (top, bottom) = c.center_line(trans);
+                let half = (segments / 2).max(1);
+                let arc = move |center: Vec2, start: f32| {
+                    (0..=half).map(move |i| center + dir(start + std::f32::consts::PI * i as f32 / half as f32) * c.radius)
+                };
+                arc(top, 0.0).chain(arc(bottom, std::f32::consts::PI)).collect()
+            },
+            CollisionShape::Multiple(v) => v.iter().flat_map(|s| s.world_vertices_with(trans, segments)).collect(),
+            CollisionShape::Convex(s) => if let Some(v) = s.local_vertices() {
+                v.iter().map(|v| pos + rot * *v).collect()
+            }
+            else {
+                // the farthest vertex in each direction
+                let center = s.aabb(trans).position;
+                let mut verts: Vec<Vec2> = (0..segments)
+                    .map(|i| s.get_closest_vertex(trans, center + dir(std::f32::consts::TAU * i as f32 / segments as f32) * 1e6))
+                    .collect();
+                verts.dedup_by(|a, b| a.distance_squared(*b) < f32::EPSILON);
+                if verts.len() > 1 && verts[0].distance_squared(verts[verts.len() - 1]) < f32::EPSILON {
+                    verts.pop();
+                }
+                verts
+            },
+        };
+        verts.into_iter()
+    }
+
+    /// A copy of the shape grown by `radius` in every direction(the Minkowski sum with a circle),
+    /// for generous hitboxes or configuration space planning(inflating the obstacles by the agent's radius)
+    ///
+    /// Circles and capsules stay circles and capsules, the rest become polygons with their corners rounded using 4 segments,
+    /// see `inflate_with`
+    pub fn inflate(&self, radius: f32) -> CollisionShape {
+        self.inflate_with(radius, 4)
+    }
+
+    /// Same as `inflate`, with the number of segments used to round each(right angle) corner
+    pub fn inflate_with(&self, radius: f32, corner_segments: usize) -> CollisionShape {
+        let radius = radius.max(0.0);
+
+        match self {
+            CollisionShape::Circle(c) => CollisionShape::Circle(Circle::new(c.radius + radius).with_offset(c.offset)),
+            CollisionShape::Capsule(c) => {
+                CollisionShape::Capsule(Capsule::new(2.0 * c.half_height, c.radius + radius).with_offset(c.offset))
+            },
+            CollisionShape::Multiple(v) => CollisionShape::Multiple(v.iter().map(|s| s.inflate_with(radius, corner_segments)).collect()),
+            _ => {
+                let verts: Vec<Vec2> = self.world_vertices(&Transform2D::default()).collect();
+                CollisionShape::Polygon(Polygon::new(rounded_outline(&verts, radius, corner_segments.max(1))))
+            },
+        }
+    }
+
+    /// The shape between `self`(at `t = 0.0`) and `other`(at `t = 1.0`), for colliders which grow or shrink smoothly
+    /// (charging attacks, inflating balloons) - replace the body's `CollisionShape` with it every frame
+    ///
+    /// Only works between shapes of the same kind - squares, circles, capsules,
+    /// and `Multiple`s of the same length whose shapes can be morphed one by one, `None` otherwise.
+    /// The offsets are morphed as well, `t` isn't clamped.
+    pub fn lerp(&self, other: &CollisionShape, t: f32) -> Option<CollisionShape> {
+        match (self, other) {
+            (CollisionShape::Square(a), CollisionShape::Square(b)) => Some(CollisionShape::Square(Square {
+                offset: a.offset.lerp(b.offset, t),
+                extents: a.extents.lerp(b.extents, t),
+            })),
+            (CollisionShape::Circle(a), CollisionShape::Circle(b)) => Some(CollisionShape::Circle(Circle {
+                offset: a.offset.lerp(b.offset, t),
+                radius: a.radius + (b.radius - a.radius) * t,
+            })),
+            (CollisionShape::Capsule(a), CollisionShape::Capsule(b)) => Some(CollisionShape::Capsule(Capsule {
+                offset: a.offset.lerp(b.offset, t),
+                radius: a.radius + (b.radius - a.radius) * t,
+                half_height: a.half_height + (b.half_height - a.half_height) * t,
+            })),
+            (CollisionShape::Multiple(a), CollisionShape::Multiple(b)) if a.len() == b.len() => {
+                a.iter().zip(b).map(|(a, b)| a.lerp(b, t)).collect::<Option<Vec<_>>>().map(CollisionShape::Multiple)
+            },
+            _ => None,
+        }
+    }
+
+    /// Length of the shape's outline(`Multiple` sums the outlines of its shapes)
+    pub fn perimeter(&self) -> f32 {
+        match self {
+            CollisionShape::Circle(c) => std::f32::consts::TAU * c.radius,
+            CollisionShape::Capsule(c) => std::f32::consts::TAU * c.radius + 4.0 * c.half_height,
+            CollisionShape::Multiple(v) => v.iter().map(|s| s.perimeter()).sum(),
+            _ => outline_length(&self.world_vertices(&Transform2D::default()).collect::<Vec<_>>()),
+        }
+    }
+
+    /// A point(in world space) along the shape's outline, `t` goes from `0.0` to `1.0` around the whole outline
+    /// (counter-clockwise, values outside that range wrap around)
+    ///
+    /// Points are spread evenly by length, handy for particles around a shape, electric outlines and such
+    pub fn sample_perimeter(&self, trans: &Transform2D, t: f32) -> Vec2 {
+        let t = t.rem_euclid(1.0);
+        let rot = trans.rot_matrix();
+        let dir = |a: f32| rot * Vec2::new(a.cos(), a.sin());
+
+        match self {
+            CollisionShape::Circle(c) => trans.translation() + rot * c.offset + dir(std::f32::consts::TAU * t) * c.radius,
+            CollisionShape::Capsule(c) => {
+                // top half circle, left side, bottom half circle and right side
+                let (top, bottom) = c.center_line(trans);
+                let arc = std::f32::consts::PI * c.radius;
+                let side = 2.0 * c.half_height;
+                let mut d = t * (2.0 * arc + 2.0 * side);
+
+                if d < arc {
+                    return top + dir(d / c.radius) * c.radius;
+                }
+                d -= arc;
+                if d < side {
+                    return top.lerp(bottom, d / side.max(f32::EPSILON)) - dir(0.0) * c.radius;
+                }
+                d -= side;
+                if d < arc {
+                    return bottom + dir(std::f32::consts::PI + d / c.radius) * c.radius;
+                }
+                d -= arc;
+                bottom.lerp(top, d / side.max(f32::EPSILON)) + dir(0.0) * c.radius
+            },
+            CollisionShape::Multiple(v) => {
+                let mut d = t * self.perimeter();
+                for s in v {
+                    let p = s.perimeter();
+                    if d <= p {
+                        return s.sample_perimeter(trans, d / p.max(f32::EPSILON));
+                    }
+                    d -= p;
+                }
+                v.last().map(|s| s.sample_perimeter(trans, 1.0)).unwrap_or_else(|| trans.translation())
+            },
+            _ => {
+                let verts: Vec<Vec2> = self.world_vertices(trans).collect();
+                let mut d = t * outline_length(&verts);
+
+                for i in 0..verts.len() {
+                    let (a, b) = (verts[i], verts[(i + 1) % verts.len()]);
+                    let len = a.distance(b);
+                    if d <= len {
+                        return a.lerp(b, d / len.max(f32::EPSILON));
+                    }
+                    d -= len;
+                }
+                verts.first().copied().unwrap_or_else(|| trans.translation())
+            },
+        }
+    }
+
+    /// Area of the shape
+    ///
+    /// `CollisionShape::Convex` shapes without `ConvexShape::local_vertices` are approximated by their(unrotated) bounding box
+    pub fn area(&self) -> f32 {
+        match self {
+            CollisionShape::Square(s) => 4.0 * s.extents.x * s.extents.y,
+            CollisionShape::Triangle(t) => polygon::signed_area(&t.verts()).abs(),
+            CollisionShape::Circle(c) => std::f32::consts::PI * c.radius * c.radius,
+            CollisionShape::Capsule(c) => std::f32::consts::PI * c.radius * c.radius + 4.0 * c.radius * c.half_height,
+            CollisionShape::Polygon(p) => polygon::signed_area(p.verts()),
+            CollisionShape::Multiple(v) => v.iter().map(|s| s.area()).sum(),
+            CollisionShape::Convex(s) => match s.local_vertices() {
+                Some(v) => polygon::signed_area(&v).abs(),
+                None => {
+                    let aabb = s.aabb(&Transform2D::default());
+                    4.0 * aabb.extents.x * aabb.extents.y
+                },
+            },
+        }
+    }
+
+    /// Center of mass of the shape(relative to the `Transform` translation, not rotated), assuming uniform density
+    pub fn center_of_mass(&self) -> Vec2 {
+        match self {
+            CollisionShape::Square(s) => s.offset,
+            CollisionShape::Circle(c) => c.offset,
+            CollisionShape::Capsule(c) => c.offset,
+            CollisionShape::Triangle(t) => polygon::centroid_and_inertia(&t.verts()).0,
+            CollisionShape::Polygon(p) => polygon::centroid_and_inertia(p.verts()).0,
+            CollisionShape::Multiple(v) => {
+                let area: f32 = v.iter().map(|s| s.area()).sum();
+                if area <= 0.0 {
+                    return Vec2::ZERO;
+                }
+                v.iter().fold(Vec2::ZERO, |sum, s| sum + s.center_of_mass() * s.area()) / area
+            },
+            CollisionShape::Convex(_) => {
+                let verts: Vec<Vec2> = self.world_vertices(&Transform2D::default()).collect();
+                polygon::centroid_and_inertia(&verts).0
+            },
+        }
+    }
+
+    /// Moment of inertia of the shape around its `center_of_mass`, for the given mass(assuming uniform density)
+    ///
+    /// `Multiple` splits the mass between its shapes by area, `Convex` shapes use the outline from `world_vertices`
+    pub fn moment_of_inertia(&self, mass: f32) -> f32 {
+        match self {
+            CollisionShape::Square(s) => mass * (s.extents.x * s.extents.x + s.extents.y * s.extents.y) / 3.0,
+            CollisionShape::Circle(c) => 0.5 * mass * c.radius * c.radius,
+            CollisionShape::Capsule(c) => {
+                let (r, h) = (c.radius, c.half_height);
+                let area = self.area();
+                if area <= 0.0 {
+                    return 0.0;
+                }
+                let box_mass = mass * 4.0 * r * h / area;
+                let cap_mass = 0.5 * (mass - box_mass);
+                // a half disc's centroid is 4r/3PI from its flat edge
+                let cap_centroid = 4.0 * r / (3.0 * std::f32::consts::PI);
+                let cap = 0.5 * cap_mass * r * r - cap_mass * cap_centroid * cap_centroid
+                    + cap_mass * (h + cap_centroid) * (h + cap_centroid);
+
+                box_mass * (r * r + h * h) / 3.0 + 2.0 * cap
+            },
+            CollisionShape::Triangle(t) => mass * polygon::centroid_and_inertia(&t.verts()).1,
+            CollisionShape::Polygon(p) => mass * polygon::centroid_and_inertia(p.verts()).1,
+            CollisionShape::Multiple(v) => {
+                let area: f32 = v.iter().map(|s| s.area()).sum();
+                if area <= 0.0 {
+                    return 0.0;
+                }
+                let center = self.center_of_mass();
+                // parallel axis theorem for each part
+                v.iter()
+                    .map(|s| {
+                        let m = mass * s.area() / area;
+                        s.moment_of_inertia(m) + m * (s.center_of_mass() - center).length_squared()
+                    })
+                    .sum()
+            },
+            CollisionShape::Convex(_) => {
+                let verts: Vec<Vec2> = self.world_vertices(&Transform2D::default()).collect();
+                mass * polygon::centroid_and_inertia(&verts).1
+            },
+        }
+    }
+
+    /// Gets the projection of the shape on the given normal
+    ///
+    /// (min, max)
+    pub fn project(&self, trans: &Transform2D, normal: Vec2) -> (f32, f32) {
+        if let Some(sat) = self.sat() {
+            sat.project(trans, normal)
+        }
+        else {
+            match self {
+                CollisionShape::Circle(c) => {
+                    let center = (trans.translation() + trans.rot_matrix() * c.offset).dot(normal);
+                    (center - c.radius, center + c.radius)
+                },
+                CollisionShape::Capsule(c) => c.project(trans, normal),
+                CollisionShape::Multiple(v) => {
+                    assert!(!v.is_empty(), "CollisionShape::Multiple cannot be empty!");
+
+                    v.iter()
+                        .map(|s| s.project(trans, normal))
+                        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), (n, x)| (min.min(n), max.max(x)))
+                }
+                _ => panic!("Something is missing, please report on github(with the shape used)"),
+            }
+        }
+    }
+
     pub fn ray(&self, trans: &Transform2D, ray_origin: Vec2, ray_cast: Vec2) -> Option<f32> {
         if let Some(sat) = self.sat() {
             sat.ray(trans, ray_origin, ray_cast)
@@ -388,6 +856,38 @@ impl CollisionShape {
         }
     }
 }
+/// Grows a convex counter-clockwise outline by `radius`, with an arc around each corner
+fn rounded_outline(verts: &[Vec2], radius: f32, corner_segments: usize) -> Vec<Vec2> {
+    if radius <= 0.0 {
+        return verts.to_vec();
+    }
+    let n = verts.len();
+    // outward normal of the edge starting at `i`
+    let normal = |i: usize| {
+        let e = verts[(i + 1) % n] - verts[i];
+        Vec2::new(e.y, -e.x).normalize_or_zero()
+    };
+
+    let mut out = Vec::with_capacity(n * (corner_segments + 1));
+    for (i, v) in verts.iter().enumerate() {
+        let (from, to) = (normal((i + n - 1) % n), normal(i));
+        let start = from.y.atan2(from.x);
+        let turn = from.perp_dot(to).atan2(from.dot(to)).max(0.0);
+        let steps = ((turn / std::f32::consts::FRAC_PI_2) * corner_segments as f32).ceil().max(1.0) as usize;
+
+        for j in 0..=steps {
+            let a = start + turn * j as f32 / steps as f32;
+            out.push(*v + Vec2::new(a.cos(), a.sin()) * radius);
+        }
+    }
+    out
+}
+
+/// Length of a closed outline
+fn outline_length(verts: &[Vec2]) -> f32 {
+    (0..verts.len()).map(|i| verts[i].distance(verts[(i + 1) % verts.len()])).sum()
+}
+
 impl Default for CollisionShape {
     fn default() -> Self {
         CollisionShape::Square(Square::default())
@@ -403,6 +903,153 @@ mod sat_tests {
     //  around 0.0000005 miss
     const EPSILON: f32 = 0.001;
 
+    #[test]
+    fn aabb_fast_path() {
+        let a = Square::new(Vec2::new(2.0, 1.0)).with_offset(Vec2::new(0.5, 0.0));
+        let b = Square::default();
+        let ta = Transform2D::new(Vec2::ZERO, 0.0, Vec2::ONE);
+
+        for p in [Vec2::new(2.5, 0.3), Vec2::new(-1.0, 1.5), Vec2::new(0.4, -0.2), Vec2::new(4.0, 0.0), Vec2::new(3.5, 1.5)] {
+            let tb = Transform2D::new(p, 0.0, Vec2::ONE);
+            assert_eq!(aabb_normal(&a, &ta, &b, &tb), sat_normal(&a, &ta, &b, &tb), "b at {}", p);
+        }
+    }
+
+    #[test]
+    fn offset_rotates_with_body() {
+        let t = Transform2D::new(Vec2::new(1.0, 1.0), 0.5 * PI, Vec2::ONE);
+        // an offset of (3, 0) ends up at (0, 3) once rotated by 90 degrees
+        let center = Vec2::new(1.0, 4.0);
+        let probe = CollisionShape::Circle(Circle::new(0.1));
+
+        for (name, shape) in [
+            ("square", CollisionShape::Square(Square::new(Vec2::splat(0.5)).with_offset(Vec2::new(3.0, 0.0)))),
+            ("circle", CollisionShape::Circle(Circle::new(0.5).with_offset(Vec2::new(3.0, 0.0)))),
+            ("capsule", CollisionShape::Capsule(Capsule::new(0.0, 0.5).with_offset(Vec2::new(3.0, 0.0)))),
+        ] {
+            let aabb = shape.aabb(&t);
+            assert!((aabb.position - center).length() < EPSILON, "{} aabb at {}", name, aabb.position);
+            assert!(shape.contains_point(&t, center), "{}", name);
+            assert!(!shape.contains_point(&t, Vec2::new(4.0, 1.0)), "{}", name);
+            assert!(collide(&probe, &Transform2D::new(center, 0.0, Vec2::ONE), &shape, &t).is_some(), "{}", name);
+
+            let hit = shape.ray(&t, Vec2::new(-1.0, 4.0), Vec2::new(4.0, 0.0));
+            assert!(hit.map_or(false, |h| (h - 0.375).abs() < EPSILON), "{} ray hit {:?}", name, hit);
+        }
+
+        let c = Circle::new(0.5).with_rotated_offset(Vec2::new(0.0, 3.0), 0.5 * PI);
+        assert!((c.offset - Vec2::new(3.0, 0.0)).length() < EPSILON);
+        assert!((c.aabb(&t).position - center).length() < EPSILON);
+    }
+
+    #[test]
+    fn contains_point() {
+        let t = Transform2D::new(Vec2::new(1.0, 0.0), 0.25 * PI, Vec2::ONE);
+
+        let square = CollisionShape::Square(Square::default());
+        assert!(square.contains_point(&t, Vec2::new(1.0, 1.3)));
+        assert!(!square.contains_point(&t, Vec2::new(2.0, 1.0)));
+
+        let capsule = CollisionShape::Capsule(Capsule::new(2.0, 0.5));
+        assert!(capsule.contains_point(&t, Vec2::new(0.4, 0.6)));
+        assert!(!capsule.contains_point(&t, Vec2::new(1.6, 0.6)));
+    }
+
+    #[test]
+    fn world_vertices() {
+        let t = Transform2D::new(Vec2::new(1.0, 0.0), 0.5 * PI, Vec2::ONE);
+
+        let square = CollisionShape::Square(Square::new(Vec2::new(2.0, 1.0)));
+        let verts: Vec<Vec2> = square.world_vertices(&t).collect();
+        assert_eq!(verts.len(), 4);
+        // rotated a quarter turn, the first vertex(-2, -1) ends up at (1, -2) from the center
+        assert!((verts[0] - Vec2::new(2.0, -2.0)).length() < EPSILON);
+        assert!(polygon::signed_area(&verts) > 0.0);
+
+        let capsule = CollisionShape::Capsule(Capsule::new(2.0, 0.5));
+        let verts: Vec<Vec2> = capsule.world_vertices_with(&t, 8).collect();
+        assert_eq!(verts.len(), 10);
+        // every vertex is on the edge(pulled in a tiny bit towards the center)
+        assert!(verts.iter().all(|v| capsule.contains_point(&t, *v * 0.999 + t.translation() * 0.001)));
+    }
+
+    #[test]
+    fn mass_properties() {
+        let square = CollisionShape::Square(Square::new(Vec2::new(2.0, 1.0)));
+        // a box of 4x2 has m(w^2 + h^2)/12
+        assert!((square.moment_of_inertia(3.0) - 3.0 * 20.0 / 12.0).abs() < EPSILON);
+
+        // the same box as a polygon
+        let poly = CollisionShape::Polygon(Polygon::new(vec![
+            Vec2::new(1.0, 1.0), Vec2::new(5.0, 1.0), Vec2::new(5.0, 3.0), Vec2::new(1.0, 3.0),
+        ]));
+        assert!((poly.center_of_mass() - Vec2::new(3.0, 2.0)).length() < EPSILON);
+        assert!((poly.moment_of_inertia(3.0) - 3.0 * 20.0 / 12.0).abs() < EPSILON);
+
+        // 2 of those boxes side by side are a box of 8x2
+        let double = CollisionShape::Multiple(vec![
+            CollisionShape::Square(Square::new(Vec2::new(2.0, 1.0)).with_offset(Vec2::new(-2.0, 0.0))),
+            CollisionShape::Square(Square::new(Vec2::new(2.0, 1.0)).with_offset(Vec2::new(2.0, 0.0))),
+        ]);
+        assert!(double.center_of_mass().length() < EPSILON);
+        assert!((double.moment_of_inertia(6.0) - 6.0 * 68.0 / 12.0).abs() < EPSILON);
+
+        // a capsule without a middle is a circle
+        let capsule = CollisionShape::Capsule(Capsule::new(0.0, 1.0));
+        assert!((capsule.moment_of_inertia(2.0) - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn inflate() {
+        let t = Transform2D::default();
+        let square = CollisionShape::Square(Square::default()).inflate(0.5);
+
+        // the sides moved out by the radius, the corners are rounded
+        assert!((square.project(&t, Vec2::X).1 - 1.5).abs() < EPSILON);
+        assert!(square.contains_point(&t, Vec2::new(1.3, 1.3)));
+        assert!(!square.contains_point(&t, Vec2::new(1.45, 1.45)));
+
+        let circle = CollisionShape::Circle(Circle::new(1.0)).inflate(0.5);
+        assert!((circle.project(&t, Vec2::Y).1 - 1.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn lerp() {
+        let t = Transform2D::default();
+        let small = CollisionShape::Circle(Circle::new(1.0));
+        let big = CollisionShape::Circle(Circle::new(3.0).with_offset(Vec2::new(2.0, 0.0)));
+
+        let half = small.lerp(&big, 0.5).unwrap();
+        assert!((half.project(&t, Vec2::X).1 - 3.0).abs() < EPSILON);
+        assert!((half.area() - std::f32::consts::PI * 4.0).abs() < 0.001);
+
+        let squares = CollisionShape::Multiple(vec![CollisionShape::Square(Square::size(Vec2::new(2.0, 4.0)))]);
+        let wide = CollisionShape::Multiple(vec![CollisionShape::Square(Square::size(Vec2::new(6.0, 4.0)))]);
+        assert!((squares.lerp(&wide, 0.25).unwrap().project(&t, Vec2::X).1 - 1.5).abs() < EPSILON);
+
+        // different kinds(or lengths) can't be morphed
+        assert!(small.lerp(&CollisionShape::Square(Square::default()), 0.5).is_none());
+        assert!(squares.lerp(&CollisionShape::Multiple(Vec::new()), 0.5).is_none());
+    }
+
+    #[test]
+    fn sample_perimeter() {
+        let t = Transform2D::new(Vec2::new(1.0, 0.0), 0.0, Vec2::ONE);
+
+        let square = CollisionShape::Square(Square::default());
+        assert!((square.perimeter() - 8.0).abs() < EPSILON);
+        // starts at the bottom left corner and goes counter-clockwise
+        assert!((square.sample_perimeter(&t, 0.125) - Vec2::new(1.0, -1.0)).length() < EPSILON);
+        assert!((square.sample_perimeter(&t, 1.5) - Vec2::new(2.0, 1.0)).length() < EPSILON);
+
+        let capsule = CollisionShape::Capsule(Capsule::new(2.0, 0.5));
+        // half way around the top half circle is the very top
+        let top = 0.5 * PI * 0.5 / capsule.perimeter();
+        assert!((capsule.sample_perimeter(&t, top) - Vec2::new(1.0, 1.5)).length() < EPSILON);
+        // after the top and the left side
+        assert!((capsule.sample_perimeter(&t, 0.5) - Vec2::new(0.5, -1.0)).length() < EPSILON);
+    }
+
     #[test]
     fn squares() {
         let s1 = Square {
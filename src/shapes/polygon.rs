@@ -0,0 +1,154 @@
+use crate::math::{Vec2, Vec4};
+use serde::{Deserialize, Serialize};
+
+use super::{simd, Transform2D, SAT};
+
+/**
+    # Polygon
+
+    A convex polygon defined by its vertices(relative to the `Transform` translation).
+
+    The vertices are stored in counter-clockwise order, if given in clockwise order they will be flipped on creation.
+
+    Concave outlines can be broken into convex polygons using `decompose`(or `CollisionShape::from_concave`).
+*/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Polygon {
+    /// Verts of the polygon, in counter-clockwise order
+    verts: Vec<Vec2>,
+    /// Outward normals of the edges
+    ///
+    /// `normals[i] = normal between verts[i], verts[i + 1]`
+    normals: Vec<Vec2>,
+}
+impl Polygon {
+    /// Creates a new convex polygon from its vertices
+    ///
+    /// Panics if less than 3 vertices are given
+    pub fn new(verts: Vec<Vec2>) -> Polygon {
+        assert!(verts.len() >= 3, "Polygon needs at least 3 vertices, got {}", verts.len());
+
+        let mut verts = verts;
+        if signed_area(&verts) < 0.0 {
+            verts.reverse();
+        }
+
+        let normals = (0..verts.len())
+            .map(|i| {
+                let e = verts[(i + 1) % verts.len()] - verts[i];
+                // counter-clockwise winding means the outward normal is on the right side of the edge
+                Vec2::new(e.y, -e.x).normalize_or_zero()
+            })
+            .collect();
+
+        Polygon { verts, normals }
+    }
+    /// The vertices of the polygon, in counter-clockwise order
+    pub fn verts(&self) -> &[Vec2] {
+        &self.verts
+    }
+    /// Outward normals of the edges, `normals()[i]` is the normal of the edge `verts()[i] -> verts()[i + 1]`
+    pub fn normals(&self) -> &[Vec2] {
+        &self.normals
+    }
+}
+
+/// Signed area of a polygon(positive when the vertices are in counter-clockwise order)
+pub(crate) fn signed_area(verts: &[Vec2]) -> f32 {
+    let mut sum = 0.0;
+    for i in 0..verts.len() {
+        let a = verts[i];
+        let b = verts[(i + 1) % verts.len()];
+        sum += a.perp_dot(b);
+    }
+    0.5 * sum
+}
+
+/// Centroid of a polygon and its moment of inertia around the centroid per unit of mass
+pub(crate) fn centroid_and_inertia(verts: &[Vec2]) -> (Vec2, f32) {
+    let area = signed_area(verts);
+    if area.abs() < f32::EPSILON {
+        let center = verts.iter().sum::<Vec2>() / verts.len().max(1) as f32;
+        return (center, 0.0);
+    }
+
+    let mut centroid = Vec2::ZERO;
+    let mut inertia = 0.0;
+    for i in 0..verts.len() {
+        let a = verts[i];
+        let b = verts[(i + 1) % verts.len()];
+        let cross = a.perp_dot(b);
+        centroid += (a + b) * cross;
+        inertia += cross * (a.dot(a) + a.dot(b) + b.dot(b));
+    }
+    let centroid = centroid / (6.0 * area);
+    // around the origin, then moved to the centroid
+    let inertia = inertia / (12.0 * area) - centroid.length_squared();
+
+    (centroid, inertia)
+}
+
+impl SAT for Polygon {
+    fn get_normals(&self, trans: &Transform2D) -> Box<dyn Iterator<Item = Vec2> + '_> {
+        let rot = trans.rot_matrix();
+
+        Box::new(self.normals.iter().map(move |n| rot * *n))
+    }
+
+    fn project(&self, trans: &Transform2D, normal: Vec2) -> (f32,f32) {
+        let rot = trans.rot_matrix();
+
+        simd::project_verts(&self.verts, rot, trans.translation(), normal)
+    }
+
+    fn project4(&self, trans: &Transform2D, normals: [Vec2; 4]) -> (Vec4, Vec4) {
+        let rot = trans.rot_matrix();
+
+        simd::project_verts4(&self.verts, rot, trans.translation(), normals)
+    }
+
+    fn get_closest_vertex(&self, trans: &Transform2D, vertex: Vec2) -> Vec2 {
+        let rot = trans.rot_matrix();
+
+        let mut cv = Vec2::ZERO;
+        let mut cls = f32::INFINITY;
+
+        for v in self.verts.iter() {
+            let v = rot * *v + trans.translation();
+            let ls = (v - vertex).length_squared();
+
+            if ls < cls {
+                cls = ls;
+                cv = v;
+            }
+        }
+
+        cv
+    }
+
+    fn ray(&self, trans: &Transform2D, ray_origin: Vec2, ray_cast: Vec2) -> Option<f32> {
+        let rot = trans.rot_matrix();
+
+        let mut coll: Option<f32> = None;
+
+        for i in 0..self.verts.len() {
+            let es = rot * self.verts[i] + trans.translation();
+            let ee = rot * self.verts[(i + 1) % self.verts.len()] + trans.translation();
+            let edge = ee - es;
+
+            let denom = ray_cast.perp_dot(edge);
+            if denom.abs() < f32::EPSILON {
+                continue; // parallel to the edge
+            }
+
+            let d = es - ray_origin;
+            let t = d.perp_dot(edge) / denom; // position along the ray
+            let u = d.perp_dot(ray_cast) / denom; // position along the edge
+
+            if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) && t < coll.unwrap_or(f32::INFINITY) {
+                coll = Some(t);
+            }
+        }
+        coll
+    }
+}
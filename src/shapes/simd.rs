@@ -0,0 +1,85 @@
+//! Vertex projections over a few lanes at once(glam's `Vec4`, which is SIMD backed where available), used by the SAT shapes
+//!
+//! The vertices stay in the local space of the shape, the normals are rotated into it instead(one rotation instead of one per vertex).
+
+use crate::math::{Mat2, Vec2, Vec4};
+
+/// Projects the local `verts`(rotated by `rot` and moved by `translation`) on `normal`, 4 vertices at a time
+///
+/// (min, max)
+pub(crate) fn project_verts(
+    verts: &[Vec2],
+    rot: Mat2,
+    translation: Vec2,
+    normal: Vec2,
+) -> (f32, f32) {
+    let local = rot.transpose() * normal;
+
+    let mut min = Vec4::splat(f32::INFINITY);
+    let mut max = Vec4::splat(f32::NEG_INFINITY);
+
+    for chunk in verts.chunks(4) {
+        // the last chunk is padded with its first vertex, which doesn't change the min/max
+        let v = |i: usize| chunk.get(i).unwrap_or(&chunk[0]);
+        let xs = Vec4::new(v(0).x, v(1).x, v(2).x, v(3).x);
+        let ys = Vec4::new(v(0).y, v(1).y, v(2).y, v(3).y);
+
+        let proj = xs * local.x + ys * local.y;
+        min = min.min(proj);
+        max = max.max(proj);
+    }
+
+    let offset = translation.dot(normal);
+    (min.min_element() + offset, max.max_element() + offset)
+}
+
+/// Projects the local `verts`(rotated by `rot` and moved by `translation`) on 4 normals at once
+///
+/// (mins, maxs) - each lane holds the projection on the normal in the same position
+pub(crate) fn project_verts4(
+    verts: &[Vec2],
+    rot: Mat2,
+    translation: Vec2,
+    normals: [Vec2; 4],
+) -> (Vec4, Vec4) {
+    let inv = rot.transpose();
+    let local = normals.map(|n| inv * n);
+    let xs = Vec4::new(local[0].x, local[1].x, local[2].x, local[3].x);
+    let ys = Vec4::new(local[0].y, local[1].y, local[2].y, local[3].y);
+
+    let mut min = Vec4::splat(f32::INFINITY);
+    let mut max = Vec4::splat(f32::NEG_INFINITY);
+
+    for v in verts {
+        let proj = xs * v.x + ys * v.y;
+        min = min.min(proj);
+        max = max.max(proj);
+    }
+
+    let offset = Vec4::from(normals.map(|n| translation.dot(n)));
+    (min + offset, max + offset)
+}
+
+#[cfg(test)]
+mod simd_tests {
+    use super::*;
+
+    #[test]
+    fn same_as_scalar() {
+        let verts = [Vec2::new(1.0, 0.0), Vec2::new(2.0, 3.0), Vec2::new(-1.0, 2.0), Vec2::new(-2.0, -1.0), Vec2::new(0.5, -2.0)];
+        let rot = Mat2::from_angle(0.7);
+        let translation = Vec2::new(3.0, -1.0);
+        let normals = [Vec2::X, Vec2::Y, Vec2::new(0.6, 0.8), Vec2::new(-0.8, 0.6)];
+
+        let (mins, maxs) = project_verts4(&verts, rot, translation, normals);
+        for (i, n) in normals.into_iter().enumerate() {
+            let projs = verts.iter().map(|v| (rot * *v + translation).dot(n));
+            let min = projs.clone().fold(f32::INFINITY, f32::min);
+            let max = projs.fold(f32::NEG_INFINITY, f32::max);
+
+            let (smin, smax) = project_verts(&verts, rot, translation, n);
+            assert!((smin - min).abs() < 0.001 && (smax - max).abs() < 0.001);
+            assert!((mins[i] - min).abs() < 0.001 && (maxs[i] - max).abs() < 0.001);
+        }
+    }
+}
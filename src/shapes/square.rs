@@ -1,12 +1,14 @@
-use bevy::math::Mat2;
-use bevy::prelude::*;
+use crate::math::{Mat2, Vec2, Vec4};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "ecs")]
+use bevy::prelude::{Reflect};
 
-use super::Transform2D;
+use super::{simd, Transform2D};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Reflect)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ecs", derive(Reflect))]
 pub struct Square {
-    /// Offset from the `Transform` transltion component
+    /// Offset from the `Transform` translation component, rotates with the body
     pub offset: Vec2,
     /// Square's extents
     ///
@@ -30,7 +32,7 @@ impl Square {
             extents: size * 0.5,
         }
     }
-    /// Offset from the `Transform` transltion component
+    /// Offset from the `Transform` translation component, rotates with the body
     pub fn with_offset(
         mut self,
         offset: Vec2,
@@ -38,6 +40,21 @@ impl Square {
         self.offset = offset;
         self
     }
+    /// Offset given at `rotation`(in radians), ie. where the shape should be(relative to the `Transform` translation)
+    /// while the body is rotated by `rotation` - it is stored unrotated, so it keeps rotating with the body
+    pub fn with_rotated_offset(mut self, offset: Vec2, rotation: f32) -> Self {
+        self.offset = Mat2::from_angle(-rotation) * offset;
+        self
+    }
+    /// The 4 corners(offset, but not rotated or moved by the transform)
+    fn local_verts(&self) -> [Vec2; 4] {
+        [
+            Vec2::new(1.0,1.0),
+            Vec2::new(1.0,-1.0),
+            Vec2::new(-1.0,1.0),
+            Vec2::new(-1.0,-1.0),
+        ].map(|v| v * self.extents + self.offset)
+    }
 }
 impl Default for Square {
     /// Default square with `extents = Vec2::splat(1.0)`
@@ -47,39 +64,26 @@ impl Default for Square {
 }
 
 impl super::SAT for Square {
-    fn get_normals(&self, trans: &Transform2D) -> Box<(dyn Iterator<Item = bevy::prelude::Vec2> + '_)> {
-        let rot = Mat2::from_angle(trans.rotation());
+    fn get_normals(&self, trans: &Transform2D) -> Box<(dyn Iterator<Item = Vec2> + '_)> {
+        let rot = trans.rot_matrix();
 
         Box::new(Square::NORMALS.iter().map(move |n| rot * *n))
     }
 
     fn project(&self, trans: &Transform2D, normal: Vec2) -> (f32,f32) {
-        let rot = Mat2::from_angle(trans.rotation());
-        let offset = rot * self.offset;
-
-        let verts = [
-            Vec2::new(1.0,1.0),
-            Vec2::new(1.0,-1.0),
-            Vec2::new(-1.0,1.0),
-            Vec2::new(-1.0,-1.0),
-        ];
+        let rot = trans.rot_matrix();
 
-        let mut min = f32::INFINITY;
-        let mut max = f32::NEG_INFINITY;
-
-        for v in verts {
-            let v = rot * (v * self.extents) + trans.translation() + offset;
-            let proj = v.dot(normal);
+        simd::project_verts(&self.local_verts(), rot, trans.translation(), normal)
+    }
 
-            min = min.min(proj);
-            max = max.max(proj);
-        }
+    fn project4(&self, trans: &Transform2D, normals: [Vec2; 4]) -> (Vec4, Vec4) {
+        let rot = trans.rot_matrix();
 
-        (min, max)
+        simd::project_verts4(&self.local_verts(), rot, trans.translation(), normals)
     }
 
     fn get_closest_vertex(&self, trans: &Transform2D, vertex: Vec2) -> Vec2 {
-        let rot = Mat2::from_angle(trans.rotation());
+        let rot = trans.rot_matrix();
         let offset = rot * self.offset;
     
         let verts = [
@@ -106,7 +110,7 @@ impl super::SAT for Square {
     }
 
     fn ray(&self, trans: &Transform2D, ro: Vec2, rc:  Vec2) -> Option<f32> {
-        let rot = Mat2::from_angle(-trans.rotation());
+        let rot = trans.rot_matrix().transpose();
 
         // IDEA: rotate the ray (the opposite direction) and then you can do simple ray vs aabb collision
         let t = rot * (trans.translation()) + self.offset; // offset should not be rotated here
@@ -1,8 +1,7 @@
-use bevy::math::Mat2;
-use bevy::prelude::*;
+use crate::math::{Vec2, Vec4};
 use serde::{Deserialize, Serialize};
 
-use super::{Transform2D, SAT};
+use super::{simd, Transform2D, SAT};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Triangle {
     /// Verts of the triangle...
@@ -26,6 +25,10 @@ impl Triangle {
         t.validate_normals();
         t
     }
+    /// The vertices of the triangle
+    pub fn verts(&self) -> [Vec2; 3] {
+        self.verts
+    }
     /// Validates and flips(where needed) normals
     pub fn validate_normals(&mut self) {
         let v = &self.verts;
@@ -74,31 +77,26 @@ impl Triangle {
     }
 }
 impl SAT for Triangle {
-    fn get_normals(&self, trans: &Transform2D) -> Box<dyn Iterator<Item = bevy::prelude::Vec2> + '_> {
-        let rot = Mat2::from_angle(trans.rotation());
+    fn get_normals(&self, trans: &Transform2D) -> Box<dyn Iterator<Item = Vec2> + '_> {
+        let rot = trans.rot_matrix();
 
         Box::new(self.normals.iter().map(move |n| rot * *n))
     }
 
     fn project(&self, trans: &Transform2D, normal: Vec2) -> (f32,f32) {
-        let rot = Mat2::from_angle(trans.rotation());
-
-        let mut min = f32::INFINITY;
-        let mut max = f32::NEG_INFINITY;
+        let rot = trans.rot_matrix();
 
-        for v in self.verts {
-            let v = rot * v + trans.translation();
-            let proj = v.dot(normal);
+        simd::project_verts(&self.verts, rot, trans.translation(), normal)
+    }
 
-            min = min.min(proj);
-            max = max.max(proj);
-        }
+    fn project4(&self, trans: &Transform2D, normals: [Vec2; 4]) -> (Vec4, Vec4) {
+        let rot = trans.rot_matrix();
 
-        (min, max)
+        simd::project_verts4(&self.verts, rot, trans.translation(), normals)
     }
 
     fn get_closest_vertex(&self, trans: &Transform2D, vertex: Vec2) -> Vec2 {
-        let rot = Mat2::from_angle(trans.rotation());
+        let rot = trans.rot_matrix();
 
         let mut cv = Vec2::ZERO;
         let mut cls = f32::INFINITY;
@@ -121,7 +119,7 @@ impl SAT for Triangle {
         let p = n.perp();
         let r_len = ray_cast.dot(n);
 
-        let rot = Mat2::from_angle(trans.rotation());
+        let rot = trans.rot_matrix();
         let mut coll = None;
 
         for i in 0..3 {
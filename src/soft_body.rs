@@ -0,0 +1,292 @@
+//! # Soft bodies(experimental)
+//!
+//! Enabled with the `soft-body` feature.
+//!
+//! A `SoftBody2D` is a bunch of point masses connected by springs, simulated on its own
+//! (with a couple of substeps each frame) and colliding against static bodies.
+//! The points are in world space, so the entity holding it doesn't need a transform.
+//!
+//! ```ignore
+//! app.add_plugin(Physics2dPlugin::default())
+//!     .add_plugin(SoftBodyPlugin);
+//!
+//! coms.spawn().insert(
+//!     SoftBody2D::lattice(Vec2::new(-50.0, 100.0), 6, 6, 20.0, 800.0, 5.0)
+//!         .with_gravity(Vec2::new(0.0, -500.0))
+//! );
+//! ```
+//!
+//! It is still experimental - soft bodies don't collide with kinematic bodies or each other(yet).
+
+use bevy::prelude::*;
+
+use crate::{
+    bodies::StaticBody,
+    common::VecOp,
+    manual_step::PhysicsAppExt,
+    physics_components::{CollisionLayer, Transform2D},
+    plugin::stage,
+    shapes::{push_out_circle, CollisionShape},
+};
+
+/// Adds the soft body simulation, needs `Physics2dPlugin` to be added first
+pub struct SoftBodyPlugin;
+
+impl Plugin for SoftBodyPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_physics_system(stage::JOINT_STEP, soft_body_system);
+    }
+}
+
+/// A point mass of a soft body
+#[derive(Debug, Clone, Copy)]
+pub struct SoftPoint {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// Inverse mass, `0.0` pins the point in place
+    pub inv_mass: f32,
+}
+
+/// A spring between 2 points of a soft body
+#[derive(Debug, Clone, Copy)]
+pub struct Spring {
+    pub a: usize,
+    pub b: usize,
+    pub rest_length: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+/**
+    # SoftBody2D
+
+    Point masses connected by springs, see the module docs.
+*/
+#[derive(Component, Debug, Clone)]
+pub struct SoftBody2D {
+    pub points: Vec<SoftPoint>,
+    pub springs: Vec<Spring>,
+    /// Indices of the points making the outline of the body(useful for drawing it)
+    pub outline: Vec<usize>,
+    /// Collision radius of each point
+    pub radius: f32,
+    pub gravity: Vec2,
+    /// How much of the tangent velocity is lost when touching a static body(0.0 - 1.0)
+    pub friction: f32,
+    /// Only checked against static bodies
+    pub layer: CollisionLayer,
+    /// Substeps per frame, springs get unstable quickly so stiffer bodies need more
+    pub substeps: u32,
+}
+
+impl Default for SoftBody2D {
+    fn default() -> Self {
+        SoftBody2D {
+            points: Vec::new(),
+            springs: Vec::new(),
+            outline: Vec::new(),
+            radius: 2.0,
+            gravity: Vec2::ZERO,
+            friction: 0.2,
+            layer: CollisionLayer::default(),
+            substeps: 8,
+        }
+    }
+}
+
+impl SoftBody2D {
+    /// Creates a `cols * rows` lattice of points(with a mass of 1) starting at `origin`(bottom left),
+    /// connected by structural and shear springs
+    pub fn lattice(
+        origin: Vec2,
+        cols: usize,
+        rows: usize,
+        spacing: f32,
+        stiffness: f32,
+        damping: f32,
+    ) -> Self {
+        let (cols, rows) = (cols.max(2), rows.max(2));
+        let mut body = SoftBody2D::default();
+
+        for y in 0..rows {
+            for x in 0..cols {
+                body.add_point(origin + Vec2::new(x as f32, y as f32) * spacing, 1.0);
+            }
+        }
+
+        let idx = |x: usize, y: usize| y * cols + x;
+        for y in 0..rows {
+            for x in 0..cols {
+                if x + 1 < cols {
+                    body.add_spring(idx(x, y), idx(x + 1, y), stiffness, damping);
+                }
+                if y + 1 < rows {
+                    body.add_spring(idx(x, y), idx(x, y + 1), stiffness, damping);
+                }
+                if x + 1 < cols && y + 1 < rows {
+                    body.add_spring(idx(x, y), idx(x + 1, y + 1), stiffness, damping);
+                    body.add_spring(idx(x + 1, y), idx(x, y + 1), stiffness, damping);
+                }
+            }
+        }
+
+        // counter-clockwise outline
+        body.outline.extend((0..cols).map(|x| idx(x, 0)));
+        body.outline.extend((1..rows).map(|y| idx(cols - 1, y)));
+        body.outline.extend((0..cols - 1).rev().map(|x| idx(x, rows - 1)));
+        body.outline.extend((1..rows - 1).rev().map(|y| idx(0, y)));
+
+        body
+    }
+    pub fn with_gravity(
+        mut self,
+        gravity: Vec2,
+    ) -> Self {
+        self.gravity = gravity;
+        self
+    }
+    pub fn with_radius(
+        mut self,
+        radius: f32,
+    ) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Adds a point and returns its index(a non positive mass pins the point)
+    pub fn add_point(
+        &mut self,
+        position: Vec2,
+        mass: f32,
+    ) -> usize {
+        self.points.push(SoftPoint {
+            position,
+            velocity: Vec2::ZERO,
+            inv_mass: if mass > 0.0 { 1.0 / mass } else { 0.0 },
+        });
+        self.points.len() - 1
+    }
+    /// Adds a spring between 2 points, using their current distance as the rest length
+    pub fn add_spring(
+        &mut self,
+        a: usize,
+        b: usize,
+        stiffness: f32,
+        damping: f32,
+    ) {
+        let rest_length = (self.points[b].position - self.points[a].position).length();
+        self.springs.push(Spring {
+            a,
+            b,
+            rest_length,
+            stiffness,
+            damping,
+        });
+    }
+
+    /// Average position of the points
+    pub fn center(&self) -> Vec2 {
+        if self.points.is_empty() {
+            return Vec2::ZERO;
+        }
+        self.points.iter().fold(Vec2::ZERO, |sum, p| sum + p.position) / self.points.len() as f32
+    }
+
+    /// Advances the simulation by `dt`, colliding with the given shapes
+    pub fn step(
+        &mut self,
+        dt: f32,
+        shapes: &[(&CollisionShape, &Transform2D)],
+    ) {
+        let substeps = self.substeps.max(1);
+        let h = dt / substeps as f32;
+        let mut forces = vec![Vec2::ZERO; self.points.len()];
+
+        for _ in 0..substeps {
+            forces.iter_mut().for_each(|f| *f = Vec2::ZERO);
+
+            for s in self.springs.iter() {
+                let (pa, pb) = (self.points[s.a], self.points[s.b]);
+                let d = pb.position - pa.position;
+                let len = d.length();
+                if len < f32::EPSILON {
+                    continue;
+                }
+                let n = d / len;
+
+                let f = n * (s.stiffness * (len - s.rest_length) + s.damping * (pb.velocity - pa.velocity).dot(n));
+                forces[s.a] += f;
+                forces[s.b] -= f;
+            }
+
+            for (p, f) in self.points.iter_mut().zip(forces.iter()) {
+                if p.inv_mass == 0.0 {
+                    continue;
+                }
+                p.velocity += (self.gravity + *f * p.inv_mass) * h;
+                let moved = p.position + p.velocity * h;
+
+                p.position = push_out_circle(moved, self.radius, shapes.iter().copied());
+
+                // remove the velocity going into the surface, and apply some friction
+                let push = p.position - moved;
+                if push.length_squared() > f32::EPSILON {
+                    let n = push.normalize();
+                    if p.velocity.dot(n) < 0.0 {
+                        p.velocity = p.velocity.slide(n) * (1.0 - self.friction);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn soft_body_system(
+    time: Res<Time>,
+    mut bodies: Query<&mut SoftBody2D>,
+    statics: Query<(&CollisionShape, &Transform2D, &CollisionLayer), With<StaticBody>>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    for mut body in bodies.iter_mut() {
+        let layer = body.layer;
+        let shapes: Vec<_> = statics
+            .iter()
+            .filter(|(_, _, l)| l.overlap(&layer))
+            .map(|(s, t, _)| (s, t))
+            .collect();
+
+        body.step(dt, &shapes);
+    }
+}
+
+#[cfg(test)]
+mod soft_body_tests {
+    use super::*;
+    use crate::shapes::Square;
+
+    #[test]
+    fn rests_on_floor() {
+        let floor = CollisionShape::Square(Square::size(Vec2::new(100.0, 10.0)));
+        let floor_t = Transform2D::new(Vec2::new(0.0, -5.0), 0.0, Vec2::ONE);
+
+        let mut body = SoftBody2D::lattice(Vec2::new(-10.0, 20.0), 3, 3, 10.0, 500.0, 2.0)
+            .with_gravity(Vec2::new(0.0, -100.0))
+            .with_radius(1.0);
+
+        for _ in 0..300 {
+            body.step(1.0 / 60.0, &[(&floor, &floor_t)]);
+        }
+
+        // fell down, but stayed above the floor
+        let lowest = body.points.iter().map(|p| p.position.y).fold(f32::INFINITY, f32::min);
+        assert!(lowest > 0.5 && lowest < 2.0, "lowest point is at {}", lowest);
+        assert!(body.center().y < 20.0);
+    }
+}
@@ -0,0 +1,126 @@
+//! # Spatial hash
+//!
+//! Uniform grid over an unbounded world, used for finding neighbours between lots of small, similarly sized objects(particles).
+
+use std::collections::HashMap;
+
+use crate::math::Vec2;
+
+/**
+    # SpatialHash
+
+    Buckets indices by the grid cell their position falls in,
+    so neighbour queries only have to look at the cells around a point.
+
+    `cell_size` should be about the size of the queries(e.g. the interaction radius of the particles).
+*/
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "SpatialHash cell size must be positive, got {}", cell_size);
+
+        SpatialHash {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+    /// Builds a spatial hash from a list of positions, using their indices
+    pub fn from_points(
+        cell_size: f32,
+        points: impl IntoIterator<Item = Vec2>,
+    ) -> Self {
+        let mut hash = SpatialHash::new(cell_size);
+        for (i, p) in points.into_iter().enumerate() {
+            hash.insert(i, p);
+        }
+        hash
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+    /// The cell a position falls in
+    pub fn cell(
+        &self,
+        position: Vec2,
+    ) -> (i32, i32) {
+        let c = (position / self.cell_size).floor();
+        (c.x as i32, c.y as i32)
+    }
+
+    pub fn insert(
+        &mut self,
+        index: usize,
+        position: Vec2,
+    ) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push(index);
+    }
+    /// Inserts `index` into every cell the box between `min` and `max` touches
+    pub fn insert_aabb(
+        &mut self,
+        index: usize,
+        min: Vec2,
+        max: Vec2,
+    ) {
+        let (min_x, min_y) = self.cell(min);
+        let (max_x, max_y) = self.cell(max);
+
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                self.cells.entry((x, y)).or_default().push(index);
+            }
+        }
+    }
+    /// Removes everything while keeping the allocations(of the cells which were used since the last clear)
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, c| !c.is_empty());
+        self.cells.values_mut().for_each(|c| c.clear());
+    }
+
+    /// Every index in the cells touched by the circle at `position` with `radius`
+    ///
+    /// These are candidates only, the distance still needs to be checked.
+    pub fn query(
+        &self,
+        position: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.query_aabb(position - Vec2::splat(radius), position + Vec2::splat(radius))
+    }
+    /// Every index in the cells touched by the box between `min` and `max`(an index can show up more than once)
+    pub fn query_aabb(
+        &self,
+        min: Vec2,
+        max: Vec2,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let (min_x, min_y) = self.cell(min);
+        let (max_x, max_y) = self.cell(max);
+
+        (min_x..=max_x)
+            .flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+            .filter_map(move |c| self.cells.get(&c))
+            .flat_map(|c| c.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod spatial_hash_tests {
+    use super::*;
+
+    #[test]
+    fn query_neighbours() {
+        let points = [Vec2::new(0.5, 0.5), Vec2::new(1.5, 0.5), Vec2::new(10.0, 10.0), Vec2::new(-0.5, -0.5)];
+        let hash = SpatialHash::from_points(1.0, points);
+
+        let mut found: Vec<usize> = hash.query(Vec2::new(0.5, 0.5), 1.0).collect();
+        found.sort_unstable();
+
+        assert_eq!(found, vec![0, 1, 3]);
+    }
+}
@@ -0,0 +1,178 @@
+//! # Static index
+//!
+//! The broad phase keeps the aabbs of the static bodies(and their spatial hash, with `BroadPhase::SpatialHash`) between frames
+//! in the `StaticIndex`, instead of finding them again every frame, since static bodies rarely move.
+//!
+//! Static bodies which do move(a door's collider, a level editor) are reindexed right before the broad phase,
+//! found by change detection - a changed `Transform2D`(moving its `Transform` works, `Transform2D` is only written when the body actually moved),
+//! `CollisionShape` or `CollisionLayer`. Add `StaticDirty` to force reindexing a body whose change isn't seen that way
+//! (its shape modified through interior mutability and such), it is removed once the body was reindexed,
+//! or call `StaticIndex::rebuild` after moving a big batch of them.
+//!
+//! Reindexing costs an aabb per moved static body, and if any of them actually moved(or one was added or removed)
+//! the statics' spatial hash is rebuilt on that frame, so statics which move every frame are better off as kinematic bodies without `Vel`.
+//! Finding the changed bodies still goes over all the statics every frame, without computing anything for them.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    bodies::{PhysicsDisabled, StaticBody},
+    lod::{BodyShapes, SimplifiedShape},
+    physics_components::{CollisionLayer, Transform2D},
+    shapes::{Aabb, CollisionShape},
+};
+
+/// Forces a static body to be reindexed on the next physics step, removed once it was
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct StaticDirty;
+
+/// The static bodies the broad phase pairs the other bodies with, by their aabbs
+#[derive(Debug, Default)]
+pub struct StaticIndex {
+    bodies: Vec<(Entity, Aabb, CollisionLayer)>,
+    slots: HashMap<Entity, usize>,
+    /// Changed whenever a body is added, removed or moved
+    version: u64,
+    rebuild: bool,
+    reindexed: usize,
+}
+
+impl StaticIndex {
+    /// The indexed bodies, with their aabb and layer
+    pub fn bodies(&self) -> &[(Entity, Aabb, CollisionLayer)] {
+        &self.bodies
+    }
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+    /// Reindexes every static body on the next physics step
+    pub fn rebuild(&mut self) {
+        self.rebuild = true;
+    }
+    /// How many bodies were reindexed(their aabb found again) on the last physics step
+    pub fn reindexed(&self) -> usize {
+        self.reindexed
+    }
+    pub(crate) fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+type StaticChanges<'a> = (
+    Entity,
+    &'a Transform2D,
+    &'a CollisionLayer,
+    ChangeTrackers<Transform2D>,
+    ChangeTrackers<CollisionShape>,
+    ChangeTrackers<CollisionLayer>,
+    Option<&'a StaticDirty>,
+    Option<&'a SimplifiedShape>,
+);
+
+/// Reindexes the static bodies which were added, removed or changed, runs right before the broad phase
+#[allow(clippy::type_complexity)]
+pub fn static_index_system(
+    mut coms: Commands,
+    mut index: ResMut<StaticIndex>,
+    shapes: BodyShapes,
+    statics: Query<StaticChanges, (With<StaticBody>, Without<PhysicsDisabled>)>,
+) {
+    let index = &mut *index;
+    let rebuild = std::mem::take(&mut index.rebuild);
+    index.reindexed = 0;
+    let mut moved = false;
+
+    // despawned, disabled, or no longer static
+    let before = index.bodies.len();
+    index.bodies.retain(|(e, ..)| statics.contains(*e));
+    if index.bodies.len() != before {
+        moved = true;
+        index.slots.clear();
+        index.slots.extend(index.bodies.iter().enumerate().map(|(i, (e, ..))| (*e, i)));
+    }
+
+    for (e, t, l, tt, st, lt, dirty, simplified) in statics.iter() {
+        let slot = index.slots.get(&e).copied();
+        // simplified shapes come and go with the distance to the viewers, so they are always checked
+        let changed = rebuild || tt.is_changed() || st.is_changed() || lt.is_changed() || dirty.is_some() || simplified.is_some();
+        if slot.is_some() && !changed {
+            continue;
+        }
+        if dirty.is_some() {
+            coms.entity(e).remove::<StaticDirty>();
+        }
+        let aabb = match shapes.get(e) {
+            Ok(s) => s.aabb(t),
+            Err(_) => continue,
+        };
+        index.reindexed += 1;
+
+        match slot {
+            Some(i) => {
+                let (_, old_aabb, old_layer) = &mut index.bodies[i];
+                if *old_aabb != aabb || old_layer.mask != l.mask || old_layer.layer != l.layer {
+                    *old_aabb = aabb;
+                    *old_layer = *l;
+                    moved = true;
+                }
+            },
+            None => {
+                index.slots.insert(e, index.bodies.len());
+                index.bodies.push((e, aabb, *l));
+                moved = true;
+            },
+        }
+    }
+
+    if moved {
+        index.version = index.version.wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod static_index_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn reindexes_moved_statics() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(CollisionSettings {
+            broad_phase: BroadPhase::SpatialHash { cell_size: 4.0 },
+            ..Default::default()
+        });
+        let door = app.spawn_at(Vec2::new(20.0, 0.0), StaticBundle::default());
+        let _walls = (0..10)
+            .map(|i| app.spawn_at(Vec2::new(i as f32 * 3.0, 20.0), StaticBundle::default()))
+            .collect::<Vec<_>>();
+        let body = app.spawn_at(Vec2::ZERO, KinematicBundle::default());
+        app.step_n(2);
+        assert_eq!(app.app.world.resource::<StaticIndex>().len(), 11);
+        // nothing moved, nothing was reindexed
+        assert_eq!(app.app.world.resource::<StaticIndex>().reindexed(), 0);
+
+        // the door closes on the body
+        app.app.world.get_mut::<Transform>(door).unwrap().translation.x = 1.5;
+        app.step_n(2);
+        app.assert_collided(body, door);
+        // only the door(the physics see the new `Transform` on the step after it was modified)
+        assert_eq!(app.app.world.resource::<StaticIndex>().reindexed(), 1);
+        let aabb = app.app.world.resource::<StaticIndex>().bodies().iter().find(|(e, ..)| *e == door).unwrap().1;
+        assert_eq!(aabb.position, Vec2::new(1.5, 0.0));
+
+        app.step();
+        assert_eq!(app.app.world.resource::<StaticIndex>().reindexed(), 0);
+
+        app.app.world.entity_mut(door).insert(StaticDirty);
+        app.step();
+        assert_eq!(app.app.world.resource::<StaticIndex>().reindexed(), 1);
+        assert!(app.app.world.get::<StaticDirty>(door).is_none());
+
+        app.app.world.despawn(door);
+        app.step();
+        assert_eq!(app.app.world.resource::<StaticIndex>().len(), 10);
+    }
+}
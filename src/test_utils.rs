@@ -0,0 +1,219 @@
+//! # Test utilities
+//!
+//! Enabled with the `test-utils` feature(and always available for this crate's own tests).
+//!
+//! `PhysicsTestApp` is a headless bevy `App` with the physics plugin,
+//! which can be stepped frame by frame with a fixed delta time, so physics tests can be deterministic.
+//!
+//! ```ignore
+//! let mut app = PhysicsTestApp::new();
+//! let floor = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+//! let body = app.spawn_at(Vec2::new(0.0, 1.5), KinematicBundle::default());
+//!
+//! app.step_n(3);
+//!
+//! app.assert_position(body, Vec2::new(0.0, 2.0), 0.001);
+//! app.assert_collided(body, floor);
+//! ```
+
+use bevy::{
+    ecs::event::ManualEventReader,
+    prelude::*,
+    utils::Duration,
+};
+
+use crate::{
+    plugin::{CollisionEvent, Physics2dPlugin},
+    transform_mode::TransformMode,
+};
+
+/// Headless app with the physics plugin, stepped manually with a fixed delta time
+pub struct PhysicsTestApp {
+    pub app: App,
+    /// Delta time of each step(in seconds), defaults to 1/60
+    pub dt: f32,
+    /// All the collision events fired since the app was created
+    pub collisions: Vec<CollisionEvent>,
+    reader: ManualEventReader<CollisionEvent>,
+}
+
+impl PhysicsTestApp {
+    /// Creates a headless app with the physics plugin
+    ///
+    /// Only the core, transform and hierarchy plugins are added(besides the physics plugin),
+    /// and `Time` is advanced manually on each step.
+    pub fn new() -> Self {
+        let mut app = App::new();
+
+        app.add_plugin(bevy::core::CorePlugin)
+            .add_plugin(bevy::transform::TransformPlugin)
+            .add_plugin(bevy::hierarchy::HierarchyPlugin)
+            .insert_resource(Time::default())
+            .add_plugin(Physics2dPlugin::default());
+
+        PhysicsTestApp {
+            app,
+            dt: 1.0 / 60.0,
+            collisions: Vec::new(),
+            reader: ManualEventReader::default(),
+        }
+    }
+    /// Sets the delta time of each step(in seconds)
+    pub fn with_dt(
+        mut self,
+        dt: f32,
+    ) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    /// Spawns an entity at the given position(`Transform` + `GlobalTransform` are added)
+    pub fn spawn_at(
+        &mut self,
+        position: Vec2,
+        bundle: impl Bundle,
+    ) -> Entity {
+        let mode = *self.app.world.resource::<TransformMode>();
+
+        let mut t = Transform::default();
+        mode.set_position(&mut t, position);
+
+        self.app
+            .world
+            .spawn()
+            .insert_bundle(TransformBundle::from_transform(t))
+            .insert_bundle(bundle)
+            .id()
+    }
+
+    /// Advances the app by a single frame
+    pub fn step(&mut self) {
+        let dt = Duration::from_secs_f32(self.dt);
+        {
+            let mut time = self.app.world.resource_mut::<Time>();
+            let last = time.last_update().unwrap_or_else(|| time.startup());
+            time.update_with_instant(last + dt);
+        }
+        self.app.update();
+
+        let events = self.app.world.resource::<Events<CollisionEvent>>();
+        self.collisions.extend(self.reader.iter(events).cloned());
+    }
+    /// Advances the app by `n` frames
+    pub fn step_n(
+        &mut self,
+        n: usize,
+    ) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+    /// Keeps stepping until `f` returns true, or `max_steps` were taken
+    ///
+    /// Returns whether `f` returned true
+    pub fn step_until(
+        &mut self,
+        max_steps: usize,
+        mut f: impl FnMut(&mut Self) -> bool,
+    ) -> bool {
+        for _ in 0..max_steps {
+            self.step();
+            if f(self) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Current position of the entity(from its `Transform`)
+    pub fn position(
+        &self,
+        entity: Entity,
+    ) -> Vec2 {
+        let mode = *self.app.world.resource::<TransformMode>();
+        let t = self
+            .app
+            .world
+            .get::<Transform>(entity)
+            .expect("Entity doesn't have a Transform");
+
+        mode.get_position(t)
+    }
+    /// Collision events between `a` and `b`(in any order) since the app was created
+    pub fn collisions_between(
+        &self,
+        a: Entity,
+        b: Entity,
+    ) -> impl Iterator<Item = &CollisionEvent> {
+        self.collisions.iter().filter(move |c| {
+            (c.entity_a == a && c.entity_b == b) || (c.entity_a == b && c.entity_b == a)
+        })
+    }
+
+    /// Panics if the entity is further than `tolerance` from `expected`
+    pub fn assert_position(
+        &self,
+        entity: Entity,
+        expected: Vec2,
+        tolerance: f32,
+    ) {
+        let p = self.position(entity);
+        assert!(
+            (p - expected).length() <= tolerance,
+            "{:?} is at {}, expected {} (tolerance {})",
+            entity, p, expected, tolerance
+        );
+    }
+    /// Panics if no collision event occurred between `a` and `b`
+    pub fn assert_collided(
+        &self,
+        a: Entity,
+        b: Entity,
+    ) {
+        assert!(
+            self.collisions_between(a, b).next().is_some(),
+            "{:?} and {:?} never collided",
+            a, b
+        );
+    }
+    /// Panics if a collision event occurred between `a` and `b`
+    pub fn assert_not_collided(
+        &self,
+        a: Entity,
+        b: Entity,
+    ) {
+        assert!(
+            self.collisions_between(a, b).next().is_none(),
+            "{:?} and {:?} collided",
+            a, b
+        );
+    }
+}
+
+impl Default for PhysicsTestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test_app_tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn static_pushes_kinematic_out() {
+        let mut app = PhysicsTestApp::new();
+
+        let floor = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        // overlaps the floor by 0.5 on the y axis
+        let body = app.spawn_at(Vec2::new(0.0, 1.5), KinematicBundle::default());
+
+        // First frame only propagates the transforms, so give it a couple
+        app.step_n(3);
+
+        app.assert_position(body, Vec2::new(0.0, 2.0), 0.001);
+        app.assert_position(floor, Vec2::ZERO, 0.0);
+        app.assert_collided(body, floor);
+    }
+}
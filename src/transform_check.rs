@@ -0,0 +1,117 @@
+//! # Transform check
+//!
+//! The physics step syncs `Transform2D` from `GlobalTransform`, which is only propagated at the end of the frame,
+//! so a `Transform` modified directly after that(usually during `Update`) is not seen by the physics until the next frame,
+//! which causes ghost collisions(and missed ones), and the solve is then added on top of the new `Transform`.
+//!
+//! Right after syncing, bodies without a parent whose `Transform` no longer matches their `Transform2D` are reported
+//! with a `TransformModifiedEvent`, and depending on `CollisionSettings::transform_check` a warning is logged,
+//! or the `Transform2D` is reconciled(moved to where the `Transform` is) before anything else uses it.
+//!
+//! Bodies are checked from their second physics step(as their `GlobalTransform` is not propagated yet on the first one).
+//! Move bodies during the physics step using `Transform2D::set_translation`(or `Transform2D::teleport`) to avoid all that.
+
+use bevy::prelude::*;
+
+use crate::{bodies::PhysicsDisabled, physics_components::Transform2D, plugin::CollisionSettings, transform_mode::TransformMode};
+
+/// Differences smaller than this are float noise from propagating the transforms
+const TOLERANCE: f32 = 0.001;
+
+/// What to do with a body whose `Transform` was modified outside the physics step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransformCheck {
+    /// Don't check at all
+    Off,
+    /// Only send a `TransformModifiedEvent`
+    #[default]
+    Event,
+    /// Send the event and log a warning
+    Warn,
+    /// Send the event and move the `Transform2D` to the `Transform`
+    Reconcile,
+}
+
+/// Sent when a body's `Transform` was modified outside the physics step
+#[derive(Debug, Clone)]
+pub struct TransformModifiedEvent {
+    pub entity: Entity,
+    /// Translation and rotation the physics got(from the `GlobalTransform`)
+    pub expected: (Vec2, f32),
+    /// Translation and rotation of the `Transform`
+    pub found: (Vec2, f32),
+}
+
+/// Compares the `Transform2D`s against the `Transform`s, runs right after syncing from the `GlobalTransform`
+#[allow(clippy::type_complexity)]
+pub fn transform_check_system(
+    settings: Res<CollisionSettings>,
+    trans_mode: Res<TransformMode>,
+    mut q: Query<(Entity, &mut Transform2D, &Transform, ChangeTrackers<GlobalTransform>), (Without<Parent>, Without<PhysicsDisabled>)>,
+    mut events: EventWriter<TransformModifiedEvent>,
+) {
+    if settings.transform_check == TransformCheck::Off {
+        return;
+    }
+
+    for (e, mut t, transform, tracker) in q.iter_mut() {
+        // the `GlobalTransform` of a new body was not propagated yet
+        if tracker.is_added() {
+            continue;
+        }
+        let found = (trans_mode.get_position(transform), trans_mode.get_rotation(transform));
+        let expected = (t.translation(), t.rotation());
+
+        let turned = (found.1 - expected.1).sin().abs() > TOLERANCE || (found.1 - expected.1).cos() < 0.0;
+        if !turned && found.0.distance(expected.0) <= TOLERANCE * (1.0 + expected.0.length()) {
+            continue;
+        }
+
+        match settings.transform_check {
+            TransformCheck::Warn => warn!(
+                "{:?} was moved from {:?} to {:?} outside the physics step, use `Transform2D` to move bodies instead",
+                e, expected, found
+            ),
+            TransformCheck::Reconcile => {
+                let pivot = t.pivot();
+                *t = Transform2D::new(found.0, found.1, trans_mode.get_scale(transform)).with_pivot(pivot);
+            }
+            _ => {}
+        }
+        events.send(TransformModifiedEvent {
+            entity: e,
+            expected,
+            found,
+        });
+    }
+}
+
+#[cfg(test)]
+mod transform_check_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn reconciles_moved_transform() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(CollisionSettings {
+            transform_check: TransformCheck::Reconcile,
+            ..Default::default()
+        });
+        let wall = app.spawn_at(Vec2::ZERO, StaticBundle::default());
+        let body = app.spawn_at(Vec2::new(10.0, 0.0), KinematicBundle::default());
+        app.step_n(2);
+
+        // moved into the wall behind the physics' back
+        app.app.world.get_mut::<Transform>(body).unwrap().translation = Vec3::new(0.5, 0.0, 0.0);
+        app.step();
+
+        let events = app.app.world.resource::<Events<TransformModifiedEvent>>();
+        let mut reader = events.get_reader();
+        let moved = reader.iter(events).next().expect("no TransformModifiedEvent");
+        assert_eq!(moved.entity, body);
+        assert_eq!(moved.expected.0, Vec2::new(10.0, 0.0));
+        // the physics saw the new position right away
+        app.assert_collided(body, wall);
+    }
+}
@@ -0,0 +1,208 @@
+//! # Visibility polygons
+//!
+//! `VisibilityPolygon` computes what can be seen from its entity, blocked by static bodies -
+//! for stealth game vision cones, fog of war or 2D shadows.
+//!
+//! Rays are cast at every vertex of the static bodies in range(and slightly to each side of them, to peek around corners),
+//! plus evenly around the view range, and the polygon is made from where they stopped.
+//!
+//! ```ignore
+//! coms.spawn_bundle(SpatialBundle::default())
+//!     .insert(Guard)
+//!     .insert(VisibilityPolygon::new(300.0));
+//!
+//! fn can_see(guards: Query<&VisibilityPolygon, With<Guard>>, player: Query<&Transform2D, With<Player>>) { ... }
+//! ```
+
+use bevy::prelude::*;
+
+use crate::{
+    bodies::StaticBody,
+    physics_components::{CollisionLayer, Transform2D},
+    shapes::CollisionShape,
+    transform_mode::TransformMode,
+};
+
+/// How far to each side of a vertex the extra rays are cast(in radians)
+const PEEK: f32 = 0.0001;
+
+/**
+    # VisibilityPolygon
+
+    Updated every frame(in `CoreStage::PostUpdate`, once the `GlobalTransform`s are up to date)
+    with the area visible from the entity's position, up to `radius` away.
+
+    `points` goes counter-clockwise around `origin`, so the polygon is a triangle fan around it.
+*/
+#[derive(Component, Debug, Clone)]
+pub struct VisibilityPolygon {
+    /// How far can be seen
+    pub radius: f32,
+    /// Only static bodies with a `CollisionLayer::mask` overlapping this block the view
+    pub mask: u8,
+    /// Rays cast evenly around the range(on top of the ones cast at the vertices), more means a rounder polygon
+    pub segments: usize,
+    /// Where the polygon was computed from(world space)
+    pub origin: Vec2,
+    /// The visible area(world space)
+    pub points: Vec<Vec2>,
+}
+
+impl VisibilityPolygon {
+    pub fn new(radius: f32) -> Self {
+        VisibilityPolygon {
+            radius,
+            mask: u8::MAX,
+            segments: 32,
+            origin: Vec2::ZERO,
+            points: Vec::new(),
+        }
+    }
+    pub fn with_mask(
+        mut self,
+        mask: u8,
+    ) -> Self {
+        self.mask = mask;
+        self
+    }
+    pub fn with_segments(
+        mut self,
+        segments: usize,
+    ) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Whether `point` is visible(inside the polygon)
+    pub fn contains(
+        &self,
+        point: Vec2,
+    ) -> bool {
+        // the polygon is star shaped around the origin, so only the triangle the point is in needs checking
+        let n = self.points.len();
+        (0..n).any(|i| {
+            let (a, b) = (self.points[i] - self.origin, self.points[(i + 1) % n] - self.origin);
+            let p = point - self.origin;
+            a.perp_dot(p) >= 0.0 && p.perp_dot(b) >= 0.0 && (b - a).perp_dot(p - a) >= 0.0
+        })
+    }
+
+    /// A mesh of the polygon relative to `origin`(needs the `mesh` feature)
+    #[cfg(feature = "mesh")]
+    pub fn mesh(&self) -> Mesh {
+        use bevy::render::{mesh::Indices, render_resource::PrimitiveTopology};
+
+        let mut positions = vec![[0.0, 0.0, 0.0]];
+        positions.extend(self.points.iter().map(|p| [p.x - self.origin.x, p.y - self.origin.y, 0.0]));
+        let n = self.points.len() as u32;
+        let indices = (0..n).flat_map(|i| [0, 1 + i, 1 + (i + 1) % n]).collect();
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; positions.len()]);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; positions.len()]);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        mesh
+    }
+}
+
+/// Distance along the ray(`dir` is normalized) to the segment, if it hits it
+fn ray_segment(
+    origin: Vec2,
+    dir: Vec2,
+    a: Vec2,
+    b: Vec2,
+) -> Option<f32> {
+    let e = b - a;
+    let denom = dir.perp_dot(e);
+    if denom.abs() < f32::EPSILON {
+        return None;
+    }
+    let t = (a - origin).perp_dot(e) / denom;
+    let u = (a - origin).perp_dot(dir) / denom;
+
+    (t >= 0.0 && (0.0..=1.0).contains(&u)).then_some(t)
+}
+
+/// Computes the visibility polygon of `origin` against the given outlines
+pub fn visibility_polygon(
+    origin: Vec2,
+    radius: f32,
+    segments: usize,
+    outlines: &[Vec<Vec2>],
+) -> Vec<Vec2> {
+    let mut angles: Vec<f32> = (0..segments.max(3))
+        .map(|i| std::f32::consts::TAU * i as f32 / segments.max(3) as f32 - std::f32::consts::PI)
+        .collect();
+    for v in outlines.iter().flatten() {
+        let d = *v - origin;
+        if d.length_squared() <= radius * radius {
+            let a = d.y.atan2(d.x);
+            angles.extend([a - PEEK, a, a + PEEK]);
+        }
+    }
+    angles.sort_by(|a, b| a.total_cmp(b));
+
+    angles
+        .into_iter()
+        .map(|a| {
+            let dir = Vec2::new(a.cos(), a.sin());
+            let mut t = radius;
+            for outline in outlines {
+                for i in 0..outline.len() {
+                    if let Some(hit) = ray_segment(origin, dir, outline[i], outline[(i + 1) % outline.len()]) {
+                        t = t.min(hit);
+                    }
+                }
+            }
+            origin + dir * t
+        })
+        .collect()
+}
+
+/// Updates every `VisibilityPolygon`
+pub fn visibility_polygon_system(
+    trans_mode: Res<TransformMode>,
+    mut viewers: Query<(&mut VisibilityPolygon, &GlobalTransform)>,
+    statics: Query<(&CollisionShape, &GlobalTransform, Option<&CollisionLayer>), With<StaticBody>>,
+) {
+    for (mut vis, gt) in viewers.iter_mut() {
+        let origin = trans_mode.get_global_position(gt);
+        let range = crate::shapes::Aabb::new(Vec2::splat(vis.radius), origin);
+
+        let outlines: Vec<Vec<Vec2>> = statics
+            .iter()
+            .filter(|(_, _, l)| l.map_or(u8::MAX, |l| l.mask) & vis.mask != 0)
+            .filter_map(|(s, gt, _)| {
+                let t: Transform2D = (gt, *trans_mode).into();
+                s.aabb(&t).collides(&range).then(|| s.world_vertices(&t).collect())
+            })
+            .collect();
+
+        vis.points = visibility_polygon(origin, vis.radius, vis.segments, &outlines);
+        vis.origin = origin;
+    }
+}
+
+#[cfg(test)]
+mod visibility_tests {
+    use super::*;
+
+    #[test]
+    fn blocked_by_box() {
+        // a 2x2 box to the right of the origin
+        let square = vec![Vec2::new(4.0, -1.0), Vec2::new(6.0, -1.0), Vec2::new(6.0, 1.0), Vec2::new(4.0, 1.0)];
+        let points = visibility_polygon(Vec2::ZERO, 10.0, 16, &[square]);
+        let vis = VisibilityPolygon {
+            points,
+            ..VisibilityPolygon::new(10.0)
+        };
+
+        assert!(vis.contains(Vec2::new(3.9, 0.0)));
+        assert!(vis.contains(Vec2::new(-9.0, 0.0)));
+        // behind the box
+        assert!(!vis.contains(Vec2::new(8.0, 0.5)));
+        // peeking past the corner
+        assert!(vis.contains(Vec2::new(8.0, 2.5)));
+    }
+}
@@ -0,0 +1,130 @@
+//! # World bounds
+//!
+//! Insert a `WorldBounds` resource to handle bodies leaving the level(falling off the map, flying into space)
+//! right after the integration, instead of writing a cleanup system for every game.
+//!
+//! ```ignore
+//! app.insert_resource(WorldBounds::new(
+//!     Aabb::from_min_max(Vec2::new(-2000.0, -1000.0), Vec2::new(2000.0, 1000.0)),
+//!     BoundsPolicy::Despawn,
+//! ));
+//! ```
+
+use bevy::prelude::*;
+
+use crate::{
+    bodies::{PhysicsDisabled, StaticBody},
+    physics_components::{Transform2D, Vel},
+    physics_commands::PhysicsCommands,
+    shapes::Aabb,
+};
+
+/// What happens to a body outside the `WorldBounds`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundsPolicy {
+    /// Moved back to the edge, and its velocity out of the bounds is removed
+    Clamp,
+    /// Despawned(with its children) at the end of the collision stage
+    Despawn,
+    /// Left alone, only the event is sent(every step while it is outside)
+    #[default]
+    Event,
+}
+
+/**
+    # WorldBounds
+
+    Area the kinematic bodies(without a parent) are allowed in, bodies whose translation leaves it
+    are handled according to `policy`, and an `OutOfBoundsEvent` is sent for each of them.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct WorldBounds {
+    pub bounds: Aabb,
+    pub policy: BoundsPolicy,
+}
+
+impl WorldBounds {
+    pub fn new(
+        bounds: Aabb,
+        policy: BoundsPolicy,
+    ) -> Self {
+        WorldBounds { bounds, policy }
+    }
+}
+
+/// Sent when a body is outside the `WorldBounds`
+#[derive(Debug, Clone)]
+pub struct OutOfBoundsEvent {
+    pub entity: Entity,
+    /// Where the body was(before being clamped)
+    pub position: Vec2,
+}
+
+/// Applies the `WorldBounds` policy to bodies outside of it, runs after the integration
+#[allow(clippy::type_complexity)]
+pub fn world_bounds_system(
+    bounds: Option<Res<WorldBounds>>,
+    mut pcoms: ResMut<PhysicsCommands>,
+    mut q: Query<(Entity, &mut Transform2D, Option<&mut Vel>), (Without<StaticBody>, Without<Parent>, Without<PhysicsDisabled>)>,
+    mut events: EventWriter<OutOfBoundsEvent>,
+) {
+    let bounds = match bounds {
+        Some(b) => *b,
+        None => return,
+    };
+    let (min, max) = bounds.bounds.min_max();
+
+    for (e, mut t, vel) in q.iter_mut() {
+        let position = t.translation();
+        let clamped = position.clamp(min, max);
+        if clamped == position {
+            continue;
+        }
+
+        match bounds.policy {
+            BoundsPolicy::Clamp => {
+                t.teleport(clamped);
+                if let Some(mut v) = vel {
+                    // only the part going further out
+                    let out = position - clamped;
+                    if v.0.x * out.x > 0.0 {
+                        v.0.x = 0.0;
+                    }
+                    if v.0.y * out.y > 0.0 {
+                        v.0.y = 0.0;
+                    }
+                }
+            }
+            BoundsPolicy::Despawn => pcoms.despawn_recursive(e),
+            BoundsPolicy::Event => {}
+        }
+        events.send(OutOfBoundsEvent { entity: e, position });
+    }
+}
+
+#[cfg(test)]
+mod world_bounds_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn clamps_and_despawns() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(WorldBounds::new(Aabb::size(Vec2::splat(20.0), Vec2::ZERO), BoundsPolicy::Clamp));
+
+        let body = app.spawn_at(Vec2::new(9.0, 0.0), KinematicBundle {
+            collision_layer: CollisionLayer::ZERO,
+            ..Default::default()
+        });
+        app.app.world.entity_mut(body).insert_bundle((Vel(Vec2::new(120.0, 30.0)), Acc::default()));
+        app.step_n(3);
+
+        assert_eq!(app.position(body).x, 10.0);
+        assert_eq!(app.app.world.get::<Vel>(body).unwrap().0, Vec2::new(0.0, 30.0));
+
+        app.app.world.resource_mut::<WorldBounds>().policy = BoundsPolicy::Despawn;
+        app.app.world.get_mut::<Vel>(body).unwrap().0 = Vec2::new(120.0, 0.0);
+        app.step();
+        assert!(app.app.world.get_entity(body).is_none());
+    }
+}
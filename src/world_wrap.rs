@@ -0,0 +1,120 @@
+//! # World wrap
+//!
+//! When `CollisionSettings::world_wrap` is set the world wraps around at the edges of the given bounds(like in asteroids),
+//! bodies leaving on one side are teleported back in on the other side, and bodies near opposite edges collide with each other
+//! as if the edges were glued together.
+//!
+//! Only kinematic bodies(and sensors) without a parent are wrapped, static bodies stay where they are
+//! but still collide with bodies across the edges. Rays don't wrap.
+//! Each pair collides through its closest wrapped copy, so bodies bigger than half the world might miss some collisions.
+//!
+//! ```ignore
+//! app.insert_resource(CollisionSettings {
+//!     world_wrap: Some(Aabb::size(Vec2::new(1280.0, 720.0), Vec2::ZERO)),
+//!     ..Default::default()
+//! });
+//! ```
+
+use bevy::prelude::*;
+
+use crate::{
+    bodies::{PhysicsDisabled, StaticBody},
+    physics_components::Transform2D,
+    plugin::CollisionSettings,
+    shapes::Aabb,
+};
+
+/// Wraps `position` into `bounds`
+pub fn wrap_position(
+    bounds: &Aabb,
+    position: Vec2,
+) -> Vec2 {
+    let (min, _) = bounds.min_max();
+    let size = bounds.extents * 2.0;
+    let axis = |p: f32, min: f32, size: f32| if size > 0.0 { min + (p - min).rem_euclid(size) } else { p };
+
+    Vec2::new(axis(position.x, min.x, size.x), axis(position.y, min.y, size.y))
+}
+
+/// How much to move `to` so it is at its closest wrapped copy to `from`
+pub fn wrap_offset(
+    bounds: &Aabb,
+    from: Vec2,
+    to: Vec2,
+) -> Vec2 {
+    let size = bounds.extents * 2.0;
+    let d = to - from;
+    let axis = |d: f32, size: f32| if size > 0.0 { -(d / size).round() * size } else { 0.0 };
+
+    Vec2::new(axis(d.x, size.x), axis(d.y, size.y))
+}
+
+/// `other` moved to its closest wrapped copy to `from`(unchanged without a world wrap)
+pub(crate) fn nearest_aabb(
+    bounds: Option<Aabb>,
+    from: &Aabb,
+    other: Aabb,
+) -> Aabb {
+    match bounds {
+        Some(b) => Aabb::new(other.extents, other.position + wrap_offset(&b, from.position, other.position)),
+        None => other,
+    }
+}
+
+/// `other` moved to its closest wrapped copy to `from`(unchanged without a world wrap)
+pub(crate) fn nearest_transform(
+    bounds: Option<Aabb>,
+    from: &Transform2D,
+    other: &Transform2D,
+) -> Transform2D {
+    let mut t = other.clone();
+    if let Some(b) = bounds {
+        t.add_translation(wrap_offset(&b, from.translation(), other.translation()));
+    }
+    t
+}
+
+/// Teleports the bodies which left the bounds back in on the other side, runs after the integration
+#[allow(clippy::type_complexity)]
+pub fn world_wrap_system(
+    settings: Res<CollisionSettings>,
+    mut q: Query<&mut Transform2D, (Without<StaticBody>, Without<Parent>, Without<PhysicsDisabled>)>,
+) {
+    let bounds = match settings.world_wrap {
+        Some(b) => b,
+        None => return,
+    };
+
+    for mut t in q.iter_mut() {
+        let wrapped = wrap_position(&bounds, t.translation());
+        if wrapped != t.translation() {
+            t.teleport(wrapped);
+        }
+    }
+}
+
+#[cfg(test)]
+mod world_wrap_tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::PhysicsTestApp};
+
+    #[test]
+    fn collides_across_the_edge() {
+        let mut app = PhysicsTestApp::new();
+        app.app.insert_resource(CollisionSettings {
+            world_wrap: Some(Aabb::size(Vec2::splat(20.0), Vec2::ZERO)),
+            ..Default::default()
+        });
+
+        let left = app.spawn_at(Vec2::new(-9.8, 0.0), KinematicBundle::default());
+        let right = app.spawn_at(Vec2::new(9.8, 0.0), KinematicBundle::default());
+        let outside = app.spawn_at(Vec2::new(0.0, 10.5), KinematicBundle {
+            collision_layer: CollisionLayer::ZERO,
+            ..Default::default()
+        });
+        app.step();
+
+        app.assert_collided(left, right);
+        app.assert_position(outside, Vec2::new(0.0, -9.5), 0.0001);
+    }
+}